@@ -0,0 +1,8 @@
+-- ============================================
+-- DTREX - Trade Versioning (Optimistic Concurrency)
+-- Migration: 04-add-trade-version.sql
+-- ============================================
+
+-- Monotonically increasing version, bumped on every trade mutation.
+-- Clients may send it back as `expected_version` to detect concurrent edits.
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
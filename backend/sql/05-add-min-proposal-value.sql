@@ -0,0 +1,9 @@
+-- ============================================
+-- DTREX - Minimum Proposal Item Value
+-- Migration: 05-add-min-proposal-value.sql
+-- ============================================
+
+-- Floor on proposer_item_value_usd enforced when creating proposals (0 = no floor)
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('min_proposal_value_usd', '0', 'Minimum proposer item value in USD for new proposals')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,12 @@
+-- ============================================
+-- DTREX - Escrow Public Keys
+-- Migration: 06-add-escrow-keys.sql
+-- ============================================
+
+-- BLS public key (96 hex chars) used as the user's escrow signer
+ALTER TABLE users ADD COLUMN IF NOT EXISTS public_key VARCHAR(96);
+
+-- Exchange's escrow signer (third key of the 2-of-3 escrow)
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('exchange_public_key', '', 'BLS public key of the exchange escrow signer')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,8 @@
+-- ============================================
+-- DTREX - Transaction Confirmation Height
+-- Migration: 07-add-tx-confirmed-height.sql
+-- ============================================
+
+-- Block height the transaction was confirmed at, so the verifier can keep
+-- `confirmations` current (peak height - confirmed height) on every sweep.
+ALTER TABLE trade_transactions ADD COLUMN IF NOT EXISTS confirmed_height BIGINT;
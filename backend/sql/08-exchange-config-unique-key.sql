@@ -0,0 +1,26 @@
+-- ============================================
+-- DTREX - Exchange Config Unique Key
+-- Migration: 08-exchange-config-unique-key.sql
+-- ============================================
+
+-- Drop duplicate keys (if any), keeping the most recently updated row
+DELETE FROM exchange_config a
+USING exchange_config b
+WHERE a.key = b.key
+  AND (a.updated_at, a.id) < (b.updated_at, b.id);
+
+-- Ensure `key` is unique; databases created from 02 already have this
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_index i
+        JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+        WHERE i.indrelid = 'exchange_config'::regclass
+          AND i.indisunique
+          AND i.indnatts = 1
+          AND a.attname = 'key'
+    ) THEN
+        ALTER TABLE exchange_config ADD CONSTRAINT exchange_config_key_key UNIQUE (key);
+    END IF;
+END $$;
@@ -0,0 +1,18 @@
+-- ============================================
+-- DTREX - Admin Audit Log
+-- Migration: 09-add-admin-audit-log.sql
+-- ============================================
+
+-- Record of manual admin interventions (force-confirms, overrides, ...)
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    admin_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(50) NOT NULL,        -- e.g. 'confirm_transaction'
+    target_type VARCHAR(50) NOT NULL,   -- e.g. 'trade_transaction'
+    target_id VARCHAR(128) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_target ON admin_audit_log(target_type, target_id);
+CREATE INDEX IF NOT EXISTS idx_admin_audit_created ON admin_audit_log(created_at DESC);
@@ -0,0 +1,19 @@
+-- ============================================
+-- DTREX - Contacts (DB-backed, per user)
+-- Migration: 10-add-contacts.sql
+-- ============================================
+
+CREATE TABLE IF NOT EXISTS contacts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    public_key VARCHAR(96) NOT NULL,    -- compressed BLS public key (hex)
+    xch_address VARCHAR(100),
+    email VARCHAR(254),                 -- stored trimmed + lowercased
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, public_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contacts_user ON contacts(user_id);
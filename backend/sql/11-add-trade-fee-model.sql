@@ -0,0 +1,15 @@
+-- ============================================
+-- DTREX - Commitment Fee Model per Trade
+-- Migration: 11-add-trade-fee-model.sql
+-- ============================================
+
+-- Who pays the commitment fee: both parties (default), only the proposer, or only the acceptor
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS fee_model VARCHAR(20) NOT NULL DEFAULT 'both';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'trades_fee_model_check') THEN
+        ALTER TABLE trades ADD CONSTRAINT trades_fee_model_check
+            CHECK (fee_model IN ('both', 'proposer_only', 'acceptor_only'));
+    END IF;
+END $$;
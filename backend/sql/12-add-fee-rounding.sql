@@ -0,0 +1,9 @@
+-- ============================================
+-- DTREX - Commitment Fee Rounding
+-- Migration: 12-add-fee-rounding.sql
+-- ============================================
+
+-- Rounding applied when converting the USD commitment fee to mojos ('ceil' or 'nearest')
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('commitment_fee_rounding', 'ceil', 'Rounding mode for USD to mojo commitment fee conversion')
+ON CONFLICT (key) DO NOTHING;
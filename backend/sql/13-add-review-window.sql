@@ -0,0 +1,9 @@
+-- ============================================
+-- DTREX - Review Window
+-- Migration: 13-add-review-window.sql
+-- ============================================
+
+-- Reviews are accepted for this many days after a trade completes
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('review_window_days', '30', 'Days after trade completion during which reviews can be left')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,10 @@
+-- ============================================
+-- DTREX - Featured Trades
+-- Migration: 14-add-trade-featured.sql
+-- ============================================
+
+-- Admin-featured proposals are listed first on the board
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS is_featured BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_trades_proposal_board
+    ON trades (is_featured DESC, created_at DESC) WHERE status = 'proposal';
@@ -0,0 +1,16 @@
+-- ============================================
+-- DTREX - Trade Events
+-- Migration: 15-add-trade-events.sql
+-- ============================================
+
+-- Participant-visible history of a trade (proposal edits, ...)
+CREATE TABLE IF NOT EXISTS trade_events (
+    id BIGSERIAL PRIMARY KEY,
+    trade_id BIGINT NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    actor_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    event_type VARCHAR(50) NOT NULL,    -- e.g. 'proposal_edited'
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_events_trade ON trade_events(trade_id, created_at);
@@ -0,0 +1,13 @@
+-- ============================================
+-- DTREX - Trade Tags
+-- Migration: 16-add-trade-tags.sql
+-- ============================================
+
+-- Free-form, normalized (lowercase, trimmed) keywords on a proposal
+CREATE TABLE IF NOT EXISTS trade_tags (
+    trade_id BIGINT NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    tag VARCHAR(32) NOT NULL,
+    PRIMARY KEY (trade_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_tags_tag ON trade_tags(tag);
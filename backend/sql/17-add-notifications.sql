@@ -0,0 +1,18 @@
+-- ============================================
+-- DTREX - Notifications
+-- Migration: 17-add-notifications.sql
+-- ============================================
+
+-- Per-user inbox; read_at stays NULL until the user marks it read
+CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,          -- e.g. 'review_reminder'
+    trade_id BIGINT REFERENCES trades(id) ON DELETE CASCADE,
+    message TEXT NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_created ON notifications(user_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
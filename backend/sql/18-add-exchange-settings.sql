@@ -0,0 +1,26 @@
+-- ============================================
+-- DTREX - Typed Exchange Settings
+-- Migration: 18-add-exchange-settings.sql
+-- ============================================
+
+-- Single-row, typed replacement for the core exchange_config keys
+CREATE TABLE IF NOT EXISTS exchange_settings (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),   -- at most one row
+    wallet_address TEXT NOT NULL DEFAULT '',
+    commitment_fee_usd DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (commitment_fee_usd >= 0),
+    webhook_url TEXT,
+    min_confirmations INTEGER NOT NULL DEFAULT 6 CHECK (min_confirmations > 0),
+    address_hrp VARCHAR(16) NOT NULL DEFAULT 'xch',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Carry over values already stored as key/value config
+INSERT INTO exchange_settings (id, wallet_address, commitment_fee_usd)
+SELECT TRUE,
+       COALESCE((SELECT value FROM exchange_config
+                 WHERE key = 'exchange_wallet_address'
+                 ORDER BY updated_at DESC LIMIT 1), ''),
+       COALESCE((SELECT value::DOUBLE PRECISION FROM exchange_config
+                 WHERE key = 'commitment_fee_usd' AND value ~ '^[0-9]+(\.[0-9]+)?$'
+                 ORDER BY updated_at DESC LIMIT 1), 1.0)
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,12 @@
+-- ============================================
+-- DTREX - Lowball Offer Guard
+-- Migration: 19-add-lowball-guard.sql
+-- ============================================
+
+-- Proposers can switch the guard off for their own trade
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS lowball_guard BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Offers worth less than this fraction of the proposer's item are rejected (0 disables)
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('min_offer_value_ratio', '0.1', 'Minimum offer value as a fraction of the proposer item value (0 disables)')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,7 @@
+-- ============================================
+-- DTREX - Maintenance Mode
+-- Migration: 20-add-maintenance-mode.sql
+-- ============================================
+
+-- While set, new trades and commitments are refused; reads and admin RPCs keep working
+ALTER TABLE exchange_settings ADD COLUMN IF NOT EXISTS maintenance_mode BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+-- ============================================
+-- DTREX - Transaction Retry Archive
+-- Migration: 21-add-transaction-archive.sql
+-- ============================================
+
+-- A failed transaction is archived once a fresh pending row replaces it (commitment_retry)
+ALTER TABLE trade_transactions ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
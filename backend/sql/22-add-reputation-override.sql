@@ -0,0 +1,7 @@
+-- ============================================
+-- DTREX - Reputation Override
+-- Migration: 22-add-reputation-override.sql
+-- ============================================
+
+-- Set when an admin pins reputation_score (admin_adjust_reputation); new reviews then leave it alone
+ALTER TABLE users ADD COLUMN IF NOT EXISTS reputation_override BOOLEAN NOT NULL DEFAULT FALSE;
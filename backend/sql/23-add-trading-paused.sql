@@ -0,0 +1,7 @@
+-- ============================================
+-- DTREX - Trading Pause
+-- Migration: 23-add-trading-paused.sql
+-- ============================================
+
+-- Paused users keep their logins and existing trades but can't create or accept new ones
+ALTER TABLE users ADD COLUMN IF NOT EXISTS trading_paused BOOLEAN NOT NULL DEFAULT FALSE;
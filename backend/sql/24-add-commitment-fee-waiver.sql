@@ -0,0 +1,7 @@
+-- ============================================
+-- DTREX - Commitment Fee Waiver
+-- Migration: 24-add-commitment-fee-waiver.sql
+-- ============================================
+
+-- Set by an admin (promotions, dispute remedies): the trade commits without commitment fees
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS commitment_fee_waived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,10 @@
+-- ============================================
+-- DTREX - Trade Disputes
+-- Migration: 25-add-trade-disputes.sql
+-- ============================================
+
+-- A participant flags a committed/escrow trade (status 'disputed'); an admin
+-- resolves it to completed, cancelled or refunded with a note
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS dispute_reason TEXT;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS disputed_at TIMESTAMPTZ;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS dispute_resolution TEXT;
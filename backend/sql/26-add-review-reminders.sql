@@ -0,0 +1,17 @@
+-- ============================================
+-- DTREX - Review Reminders
+-- Migration: 26-add-review-reminders.sql
+-- ============================================
+
+-- Participants who haven't reviewed a completed trade get one reminder this many days after completion
+INSERT INTO exchange_config (key, value, description) VALUES
+    ('review_reminder_days', '3', 'Days after trade completion before unreviewed participants are reminded (0 disables)')
+ON CONFLICT (key) DO NOTHING;
+
+-- One row per reminder sent, so each participant is reminded once per trade
+CREATE TABLE IF NOT EXISTS review_reminders (
+    trade_id BIGINT NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (trade_id, user_id)
+);
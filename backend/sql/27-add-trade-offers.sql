@@ -0,0 +1,26 @@
+-- ============================================
+-- DTREX - Trade Offers
+-- Migration: 27-add-trade-offers.sql
+-- ============================================
+
+-- Offers on a proposal. Each bidder negotiates with the proposer in a thread of
+-- offers and counter-offers (parent_offer_id); accepting one matches the trade.
+-- status: pending, countered, accepted, rejected, withdrawn
+CREATE TABLE IF NOT EXISTS trade_offers (
+    id BIGSERIAL PRIMARY KEY,
+    trade_id BIGINT NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    bidder_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_by BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    parent_offer_id BIGINT REFERENCES trade_offers(id) ON DELETE SET NULL,
+    offer_type VARCHAR(20) NOT NULL,
+    item_title VARCHAR(256),
+    item_description TEXT,
+    item_condition VARCHAR(50),
+    item_value_usd DOUBLE PRECISION,
+    xch_amount BIGINT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_offers_trade ON trade_offers(trade_id, created_at DESC);
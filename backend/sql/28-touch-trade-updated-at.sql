@@ -0,0 +1,22 @@
+-- ============================================
+-- DTREX - Trade updated_at Trigger
+-- Migration: 28-touch-trade-updated-at.sql
+-- ============================================
+
+-- list_my_trades orders by updated_at, so every change to a trade must bump it.
+-- Updates that set updated_at themselves keep their value.
+CREATE OR REPLACE FUNCTION touch_trade_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at THEN
+        NEW.updated_at = NOW();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_touch_trade_updated_at ON trades;
+CREATE TRIGGER trg_touch_trade_updated_at
+    BEFORE UPDATE ON trades
+    FOR EACH ROW
+    EXECUTE FUNCTION touch_trade_updated_at();
@@ -0,0 +1,12 @@
+-- ============================================
+-- DTREX - Trade Photo Ordering and Cover
+-- Migration: 29-add-trade-photo-primary.sql
+-- ============================================
+
+-- trade_photos links images to trades; display_order is each image's position.
+-- is_primary marks the cover image, at most one per trade.
+ALTER TABLE trade_photos ADD COLUMN IF NOT EXISTS is_primary BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE trade_photos SET display_order = 0 WHERE display_order IS NULL;
+ALTER TABLE trade_photos ALTER COLUMN display_order SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trade_photos_one_primary ON trade_photos(trade_id) WHERE is_primary;
@@ -0,0 +1,31 @@
+-- ============================================
+-- DTREX - Trade Event Backfill
+-- Migration: 30-backfill-trade-events.sql
+-- ============================================
+
+-- The admin activity feed reads trade history from trade_events; backfill the
+-- lifecycle steps and confirmed payments recorded before they were logged there
+INSERT INTO trade_events (trade_id, actor_id, event_type, created_at)
+SELECT t.id, t.proposer_id, 'trade_created', t.created_at FROM trades t
+WHERE NOT EXISTS (SELECT 1 FROM trade_events e WHERE e.trade_id = t.id AND e.event_type = 'trade_created');
+
+INSERT INTO trade_events (trade_id, event_type, created_at)
+SELECT t.id, 'trade_committed', t.committed_at FROM trades t
+WHERE t.committed_at IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM trade_events e WHERE e.trade_id = t.id AND e.event_type = 'trade_committed');
+
+INSERT INTO trade_events (trade_id, event_type, created_at)
+SELECT t.id, 'trade_completed', t.completed_at FROM trades t
+WHERE t.completed_at IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM trade_events e WHERE e.trade_id = t.id AND e.event_type = 'trade_completed');
+
+INSERT INTO trade_events (trade_id, event_type, details, created_at)
+SELECT tx.trade_id, 'transaction_confirmed',
+       jsonb_build_object('tx_id', tx.tx_id, 'tx_type', tx.tx_type, 'amount_mojos', tx.amount_mojos),
+       tx.confirmed_at
+FROM trade_transactions tx
+WHERE tx.status = 'confirmed' AND tx.confirmed_at IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM trade_events e WHERE e.trade_id = tx.trade_id
+                  AND e.event_type = 'transaction_confirmed' AND e.details->>'tx_id' = tx.tx_id);
+
+CREATE INDEX IF NOT EXISTS idx_trade_events_created ON trade_events(created_at DESC);
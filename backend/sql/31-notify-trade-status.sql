@@ -0,0 +1,34 @@
+-- ============================================
+-- DTREX - Trade Status Notifications
+-- Migration: 31-notify-trade-status.sql
+-- ============================================
+
+-- /trades/:id/events streams every change to a trade's status or tracking,
+-- whichever code path made it. The server LISTENs on trade_status; Postgres
+-- delivers notifications on commit, so rolled-back changes are never streamed.
+CREATE OR REPLACE FUNCTION notify_trade_status()
+RETURNS TRIGGER AS $$
+DECLARE
+    kind TEXT;
+BEGIN
+    IF NEW.status IS DISTINCT FROM OLD.status THEN
+        kind := 'status';
+    ELSIF (NEW.proposer_tracking_number, NEW.proposer_tracking_carrier,
+           NEW.acceptor_tracking_number, NEW.acceptor_tracking_carrier)
+          IS DISTINCT FROM
+          (OLD.proposer_tracking_number, OLD.proposer_tracking_carrier,
+           OLD.acceptor_tracking_number, OLD.acceptor_tracking_carrier) THEN
+        kind := 'tracking';
+    ELSE
+        RETURN NULL;
+    END IF;
+    PERFORM pg_notify('trade_status', json_build_object('trade_id', NEW.id, 'status', NEW.status, 'kind', kind)::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_notify_trade_status ON trades;
+CREATE TRIGGER trg_notify_trade_status
+    AFTER UPDATE ON trades
+    FOR EACH ROW
+    EXECUTE FUNCTION notify_trade_status();
@@ -0,0 +1,238 @@
+//! Test-only helpers: a throwaway Postgres for model code and a mock Chia node.
+//!
+//! Set `TEST_DATABASE_URL` to an admin connection (e.g. `postgres://postgres@localhost/postgres`).
+//! Each call to `init_test` creates a fresh throwaway database and applies the migrations in `sql/`;
+//! the database is dropped again when the test's thread exits, whether the test passed or not.
+//! DB-backed tests are `#[ignore]`d by default; run them with
+//! `TEST_DATABASE_URL=... cargo test -- --include-ignored`, as the backend-tests CI workflow does.
+//!
+//! This lives under `src/` rather than `tests/common` because the backend is a
+//! binary crate, so integration tests cannot reach `ModelManager` or `Ctx`.
+
+use crate::ctx::Ctx;
+use crate::model::{
+    ContractForCreate, ModelManager, TradeAcceptParams, TradeBmc, TradeForCreate, TradeTransactionForCreate,
+};
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPoolOptions};
+use sqlx::{Connection, Executor, Row};
+use std::cell::RefCell;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const SQL_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/sql");
+
+/// A throwaway database, dropped with `WITH (FORCE)` so open pool connections don't block it
+struct TestDb {
+    admin_url: String,
+    name: String,
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let admin_url = self.admin_url.clone();
+        let sql = format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", self.name);
+        // The test's runtime is gone by now, so drop from a thread with its own
+        let dropped = std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+            rt.block_on(async {
+                let mut conn = PgConnection::connect(&admin_url).await?;
+                conn.execute(sql.as_str()).await?;
+                conn.close().await
+            })
+            .map_err(std::io::Error::other)
+        })
+        .join();
+        if !matches!(dropped, Ok(Ok(()))) {
+            eprintln!("Failed to drop test database {}", self.name);
+        }
+    }
+}
+
+thread_local! {
+    // Every test runs on its own thread, so these drop as the test finishes
+    static TEST_DBS: RefCell<Vec<TestDb>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Create an isolated, migrated database and return a ModelManager for it
+pub async fn init_test() -> ModelManager {
+    let admin_url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
+    let db_name = format!("dtrex_test_{}", Uuid::new_v4().simple());
+
+    let admin = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&admin_url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    admin
+        .execute(format!("CREATE DATABASE {}", db_name).as_str())
+        .await
+        .expect("Failed to create test database");
+    admin.close().await;
+    TEST_DBS.with(|dbs| dbs.borrow_mut().push(TestDb { admin_url: admin_url.clone(), name: db_name.clone() }));
+
+    let options = PgConnectOptions::from_str(&admin_url)
+        .expect("Invalid TEST_DATABASE_URL")
+        .database(&db_name);
+    let db = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(options)
+        .await
+        .expect("Failed to connect to test database");
+
+    // Apply migrations in order (00 is the dev-only drop/recreate script)
+    let mut files: Vec<_> = std::fs::read_dir(SQL_DIR)
+        .expect("Failed to read sql dir")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "sql"))
+        .filter(|path| !path.file_name().unwrap().to_string_lossy().starts_with("00"))
+        .collect();
+    files.sort();
+
+    for file in files {
+        let sql = std::fs::read_to_string(&file).expect("Failed to read migration");
+        db.execute(sql.as_str())
+            .await
+            .unwrap_or_else(|e| panic!("Migration {} failed: {}", file.display(), e));
+    }
+
+    ModelManager::new(db)
+}
+
+/// `init_test` plus two seeded users ("alice" and "bob") ready to act
+pub async fn init_test_with_users() -> (ModelManager, Ctx, Ctx) {
+    let mm = init_test().await;
+    let alice = seed_ctx(&mm, "alice").await;
+    let bob = seed_ctx(&mm, "bob").await;
+    (mm, alice, bob)
+}
+
+/// `init_test_with_users` plus a seeded admin ("admin")
+pub async fn init_test_with_admin() -> (ModelManager, Ctx, Ctx, Ctx) {
+    let (mm, alice, bob) = init_test_with_users().await;
+    let admin = Ctx::new_with_admin(seed_user(&mm, "admin").await, "admin".to_string(), true);
+    (mm, alice, bob, admin)
+}
+
+/// Seed a user and return a Ctx for them
+pub async fn seed_ctx(mm: &ModelManager, username: &str) -> Ctx {
+    let user_id = seed_user(mm, username).await;
+    Ctx::new(user_id, username.to_string())
+}
+
+/// Offer on a proposal as `bidder` and have the proposer accept it, matching the trade
+pub async fn match_trade(mm: &ModelManager, bidder: &Ctx, offer: TradeAcceptParams) -> i64 {
+    let trade_id = offer.trade_id;
+    let offer_id = TradeBmc::make_offer(bidder, mm, offer).await.expect("Failed to make offer");
+    let proposer_id: i64 = sqlx::query_scalar("SELECT proposer_id FROM trades WHERE id = $1")
+        .bind(trade_id)
+        .fetch_one(mm.db())
+        .await
+        .expect("Failed to load trade");
+    let proposer = Ctx::new(proposer_id, "proposer".to_string());
+    TradeBmc::accept_offer(&proposer, mm, offer_id, None).await.expect("Failed to accept offer");
+    offer_id
+}
+
+/// A valid proposal ("Vintage camera") worth `item_value_usd`; override fields with `..`
+pub fn proposal_with_value(item_value_usd: f64) -> TradeForCreate {
+    TradeForCreate {
+        item_title: "Vintage camera".to_string(),
+        item_description: "Works fine".to_string(),
+        item_condition: None,
+        item_value_usd,
+        item_category: None,
+        wishlist: None,
+        fee_model: None,
+        tags: None,
+        lowball_guard: None,
+    }
+}
+
+/// Create a $120 proposal as `ctx` and return its id
+pub async fn seed_proposal(mm: &ModelManager, ctx: &Ctx) -> i64 {
+    TradeBmc::create(ctx, mm, proposal_with_value(120.0)).await.expect("Failed to create proposal")
+}
+
+/// A 1 XCH offer on `trade_id`, valued at a server price of $30/XCH
+pub fn xch_offer(trade_id: i64) -> TradeAcceptParams {
+    TradeAcceptParams {
+        trade_id,
+        offer_type: "xch".to_string(),
+        item_title: None,
+        item_description: None,
+        item_condition: None,
+        item_value_usd: None,
+        xch_amount: Some(1_000_000_000_000),
+        xch_price_usd: Some(30.0),
+        expected_version: None,
+    }
+}
+
+/// An item offer ("Old radio") worth `item_value_usd` on `trade_id`
+pub fn item_offer(trade_id: i64, item_value_usd: f64) -> TradeAcceptParams {
+    TradeAcceptParams {
+        offer_type: "item".to_string(),
+        item_title: Some("Old radio".to_string()),
+        item_description: Some("Crackles a bit".to_string()),
+        item_value_usd: Some(item_value_usd),
+        xch_amount: None,
+        ..xch_offer(trade_id)
+    }
+}
+
+/// A pending commitment fee on `trade_id` with no addresses or tx_id yet
+pub fn commitment_fee(trade_id: i64, amount_mojos: i64) -> TradeTransactionForCreate {
+    TradeTransactionForCreate {
+        trade_id,
+        tx_type: "commitment_fee".to_string(),
+        tx_id: None,
+        from_address: None,
+        to_address: None,
+        amount_mojos,
+    }
+}
+
+/// A contract named `name` between two fixed, well-formed public keys
+pub fn contract(name: &str) -> ContractForCreate {
+    ContractForCreate {
+        name: name.to_string(),
+        description: None,
+        party1_public_key: "ab".repeat(48),
+        party2_public_key: "cd".repeat(48),
+        party1_xch_address: None,
+        party2_xch_address: None,
+        terms: "terms".to_string(),
+        amount: 1,
+    }
+}
+
+/// Make sure token signing works in tests (keeps any externally set secret)
+pub fn ensure_token_secret() {
+    if std::env::var("TOKEN_SECRET").is_err() {
+        std::env::set_var("TOKEN_SECRET", "dtrex-test-secret");
+    }
+}
+
+/// Serve `router` on an ephemeral local port and return its base URL
+pub async fn spawn_mock_node(router: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+/// Insert a user directly (skips Argon2 hashing, so the user cannot log in)
+pub async fn seed_user(mm: &ModelManager, username: &str) -> i64 {
+    sqlx::query(
+        "INSERT INTO users (username, pwd, pwd_salt, token_salt) VALUES ($1, '#02#seed', $2, $3) RETURNING id",
+    )
+    .bind(username)
+    .bind(Uuid::new_v4())
+    .bind(Uuid::new_v4())
+    .fetch_one(mm.db())
+    .await
+    .expect("Failed to seed user")
+    .get("id")
+}
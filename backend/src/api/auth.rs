@@ -5,7 +5,8 @@ use serde_json::{json, Value};
 use sha2::Sha256;
 
 use super::rpc::RpcError;
-use crate::model::{validate_password, ModelManager, UserBmc, UserForCreate};
+use crate::ctx::Ctx;
+use crate::model::{password_needs_rehash, validate_password, ModelManager, UserBmc, UserForCreate};
 
 // ============================================================================
 // Types
@@ -25,6 +26,35 @@ pub struct RegisterPayload {
     pwd: String,
 }
 
+const MIN_USERNAME_CHARS: usize = 3;
+const MAX_USERNAME_CHARS: usize = 32;
+const MIN_PASSWORD_CHARS: usize = 6;
+
+/// Registration checks, each with its own code so the frontend can point at the field:
+/// 4010 username empty, 4014 too short, 4011 too long, 4013 characters other than
+/// ASCII letters, digits, `_`, `-` and `.`, 4012 password too short
+fn validate_registration(username: &str, pwd: &str) -> Result<(), RpcError> {
+    let reject = |code: i32, message: String| Err(RpcError { code, message, data: None });
+    let username_chars = username.chars().count();
+
+    if username.trim().is_empty() {
+        return reject(4010, "Username cannot be empty".to_string());
+    }
+    if username_chars < MIN_USERNAME_CHARS {
+        return reject(4014, format!("Username must be at least {} characters", MIN_USERNAME_CHARS));
+    }
+    if username_chars > MAX_USERNAME_CHARS {
+        return reject(4011, format!("Username must be at most {} characters", MAX_USERNAME_CHARS));
+    }
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        return reject(4013, "Username may only contain letters, digits, '_', '-' and '.'".to_string());
+    }
+    if pwd.chars().count() < MIN_PASSWORD_CHARS {
+        return reject(4012, format!("Password must be at least {} characters", MIN_PASSWORD_CHARS));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // RPC Methods
 // ============================================================================
@@ -53,6 +83,13 @@ pub async fn rpc_login(mm: ModelManager, params: Option<Value>) -> Result<Value,
         data: None,
     })?;
 
+    // Upgrade hashes made with weaker Argon2 params while the clear password is at hand
+    if password_needs_rehash(&user.pwd) {
+        if let Err(e) = UserBmc::rehash_password(mm.db(), user.id, &params.pwd, &user.pwd_salt).await {
+            tracing::warn!("Failed to upgrade password hash for user {}: {}", user.id, e);
+        }
+    }
+
     // Generate token
     let token = generate_token(user.id, &user.token_salt.to_string())?;
 
@@ -67,7 +104,17 @@ pub async fn rpc_login(mm: ModelManager, params: Option<Value>) -> Result<Value,
     }))
 }
 
-pub async fn rpc_logout() -> Result<Value, RpcError> {
+/// Log out by rotating the caller's token salt, which invalidates the presented
+/// token and every other token issued to them so far.
+pub async fn rpc_logout(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcError> {
+    UserBmc::rotate_token_salt(mm.db(), ctx.user_id())
+        .await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Failed to end session: {}", e),
+            data: None,
+        })?;
+
     Ok(json!({
         "success": true,
         "logged_out": true,
@@ -82,15 +129,7 @@ pub async fn rpc_register(mm: ModelManager, params: Option<Value>) -> Result<Val
             data: None,
         })?;
 
-    // Validate input
-    if params.username.is_empty() || params.pwd.len() < 6 {
-        return Err(RpcError {
-            code: -32602,
-            message: "Username cannot be empty and password must be at least 6 characters"
-                .to_string(),
-            data: None,
-        });
-    }
+    validate_registration(&params.username, &params.pwd)?;
 
     // Create user
     let user_id = UserBmc::create(
@@ -133,16 +172,19 @@ pub async fn rpc_register(mm: ModelManager, params: Option<Value>) -> Result<Val
 // ============================================================================
 
 fn generate_token(user_id: i64, token_salt: &str) -> Result<String, RpcError> {
+    // Create token payload: user_id.token_salt.timestamp
+    let timestamp = chrono::Utc::now().timestamp();
+    sign_token(&format!("{}.{}.{}", user_id, token_salt, timestamp))
+}
+
+/// Sign a token payload: base64(payload).hex(hmac)
+fn sign_token(payload: &str) -> Result<String, RpcError> {
     let token_secret = std::env::var("TOKEN_SECRET").map_err(|_| RpcError {
         code: 5000,
         message: "TOKEN_SECRET not configured".to_string(),
         data: None,
     })?;
 
-    // Create token payload: user_id.token_salt.timestamp
-    let timestamp = chrono::Utc::now().timestamp();
-    let payload = format!("{}.{}.{}", user_id, token_salt, timestamp);
-
     // Create HMAC signature
     type HmacSha256 = Hmac<Sha256>;
     let mut mac = HmacSha256::new_from_slice(token_secret.as_bytes()).map_err(|_| RpcError {
@@ -158,57 +200,67 @@ fn generate_token(user_id: i64, token_salt: &str) -> Result<String, RpcError> {
     // Final token format: base64(payload).signature
     let token = format!(
         "{}.{}",
-        general_purpose::STANDARD.encode(&payload),
+        general_purpose::STANDARD.encode(payload),
         signature_hex
     );
 
     Ok(token)
 }
 
-/// Validate token and extract user_id
-pub fn validate_token(token: &str) -> Result<i64, RpcError> {
-    let parts: Vec<&str> = token.split('.').collect();
-    if parts.len() != 2 {
-        return Err(RpcError {
-            code: 4001,
-            message: "Invalid token format".to_string(),
-            data: None,
-        });
-    }
+/// Longest token `validate_token` will look at (real tokens are ~160 bytes)
+const MAX_TOKEN_LEN: usize = 512;
 
-    // Decode payload
-    let payload = general_purpose::STANDARD
-        .decode(parts[0])
-        .map_err(|_| RpcError {
-            code: 4001,
-            message: "Invalid token encoding".to_string(),
-            data: None,
-        })?;
+/// Default token lifetime; override with `TOKEN_TTL_SECS`
+const DEFAULT_TOKEN_TTL_SECS: i64 = 86_400;
+
+/// How far in the future a token's timestamp may be (clock skew between servers)
+const MAX_TOKEN_CLOCK_SKEW_SECS: i64 = 30;
 
-    let payload_str = String::from_utf8(payload).map_err(|_| RpcError {
+fn token_ttl_secs() -> i64 {
+    std::env::var("TOKEN_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|ttl: &i64| *ttl > 0)
+        .unwrap_or(DEFAULT_TOKEN_TTL_SECS)
+}
+
+fn invalid_token(message: &str) -> RpcError {
+    RpcError {
         code: 4001,
-        message: "Invalid token payload".to_string(),
+        message: message.to_string(),
         data: None,
-    })?;
+    }
+}
 
-    // Parse payload
-    let payload_parts: Vec<&str> = payload_str.split('.').collect();
-    if payload_parts.len() != 3 {
-        return Err(RpcError {
-            code: 4001,
-            message: "Invalid token payload format".to_string(),
-            data: None,
-        });
+/// What a valid token vouches for. The salt must still match the user's
+/// current `token_salt` for the token to be accepted (see `mw_ctx_resolve`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenClaims {
+    pub user_id: i64,
+    pub token_salt: String,
+}
+
+/// Validate token and extract its claims.
+/// Any malformed input yields a 4001; the signature is checked before the payload is trusted.
+/// Tokens older than `TOKEN_TTL_SECS` (default 24h) are expired.
+pub fn validate_token(token: &str) -> Result<TokenClaims, RpcError> {
+    if token.len() > MAX_TOKEN_LEN {
+        return Err(invalid_token("Invalid token format"));
     }
 
-    let user_id: i64 = payload_parts[0].parse().map_err(|_| RpcError {
-        code: 4001,
-        message: "Invalid user ID in token".to_string(),
-        data: None,
-    })?;
+    let (payload_b64, signature_hex) = token
+        .split_once('.')
+        .filter(|(_, sig)| !sig.contains('.'))
+        .ok_or_else(|| invalid_token("Invalid token format"))?;
+
+    // Decode payload
+    let payload = general_purpose::STANDARD
+        .decode(payload_b64)
+        .map_err(|_| invalid_token("Invalid token encoding"))?;
+
+    let payload_str = String::from_utf8(payload).map_err(|_| invalid_token("Invalid token payload"))?;
 
-    let _token_salt = payload_parts[1];
-    let signature_expected = parts[1];
+    let signature = hex::decode(signature_hex).map_err(|_| invalid_token("Invalid token signature"))?;
 
     // Verify signature
     let token_secret = std::env::var("TOKEN_SECRET").map_err(|_| RpcError {
@@ -225,16 +277,115 @@ pub fn validate_token(token: &str) -> Result<i64, RpcError> {
     })?;
 
     mac.update(payload_str.as_bytes());
-    let signature = mac.finalize();
-    let signature_hex = hex::encode(signature.into_bytes());
+    mac.verify_slice(&signature).map_err(|_| invalid_token("Invalid token signature"))?;
 
-    if signature_hex != signature_expected {
-        return Err(RpcError {
-            code: 4001,
-            message: "Invalid token signature".to_string(),
-            data: None,
-        });
+    // Parse payload: user_id.token_salt.timestamp
+    let payload_parts: Vec<&str> = payload_str.split('.').collect();
+    if payload_parts.len() != 3 {
+        return Err(invalid_token("Invalid token payload format"));
+    }
+
+    let user_id: i64 = payload_parts[0].parse().map_err(|_| invalid_token("Invalid user ID in token"))?;
+
+    let issued_at: i64 = payload_parts[2]
+        .parse()
+        .map_err(|_| invalid_token("Invalid timestamp in token"))?;
+    let age = chrono::Utc::now().timestamp().saturating_sub(issued_at);
+    if age < -MAX_TOKEN_CLOCK_SKEW_SECS {
+        return Err(invalid_token("Invalid timestamp in token"));
+    }
+    if age > token_ttl_secs() {
+        return Err(invalid_token("Token expired"));
+    }
+
+    Ok(TokenClaims {
+        user_id,
+        token_salt: payload_parts[1].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_token_round_trip() {
+        _dev_utils::ensure_token_secret();
+        let token = generate_token(42, "0b3f1c2e-salt").unwrap();
+        let claims = validate_token(&token).unwrap();
+        assert_eq!(claims, TokenClaims { user_id: 42, token_salt: "0b3f1c2e-salt".to_string() });
+
+        // Tampered signature, extra segment, oversized input
+        let mut tampered = token.clone();
+        tampered.replace_range(token.len() - 1.., if token.ends_with('0') { "1" } else { "0" });
+        assert_eq!(validate_token(&tampered).unwrap_err().code, 4001);
+        assert_eq!(validate_token(&format!("{}.extra", token)).unwrap_err().code, 4001);
+        assert_eq!(validate_token(&"a".repeat(MAX_TOKEN_LEN + 1)).unwrap_err().code, 4001);
+    }
+
+    #[test]
+    fn test_token_expiry() {
+        _dev_utils::ensure_token_secret();
+        let now = chrono::Utc::now().timestamp();
+        let token_at = |timestamp: &str| sign_token(&format!("42.salt.{}", timestamp)).unwrap();
+
+        // Fresh, slightly ahead of our clock, and just inside the default TTL
+        assert_eq!(validate_token(&token_at(&now.to_string())).unwrap().user_id, 42);
+        assert_eq!(validate_token(&token_at(&(now + 5).to_string())).unwrap().user_id, 42);
+        assert_eq!(validate_token(&token_at(&(now - DEFAULT_TOKEN_TTL_SECS + 60).to_string())).unwrap().user_id, 42);
+
+        let expired = validate_token(&token_at(&(now - DEFAULT_TOKEN_TTL_SECS - 1).to_string())).unwrap_err();
+        assert_eq!((expired.code, expired.message.as_str()), (4001, "Token expired"));
+
+        // Far-future, non-numeric and overflowing timestamps are invalid even when signed
+        for bad in [(now + 3600).to_string(), "soon".to_string(), String::new(), "99999999999999999999".to_string()] {
+            let err = validate_token(&token_at(&bad)).unwrap_err();
+            assert_eq!((err.code, err.message.as_str()), (4001, "Invalid timestamp in token"));
+        }
+
+        // Rewriting the timestamp of a real token breaks its signature
+        let token = generate_token(42, "salt").unwrap();
+        let signature = token.split_once('.').unwrap().1;
+        let forged = format!("{}.{}", general_purpose::STANDARD.encode(format!("42.salt.{}", now + 10 * DEFAULT_TOKEN_TTL_SECS)), signature);
+        assert_eq!(validate_token(&forged).unwrap_err().message, "Invalid token signature");
+    }
+
+    #[test]
+    fn test_registration_rejections() {
+        let code = |username: &str, pwd: &str| validate_registration(username, pwd).err().map(|e| e.code);
+        assert_eq!(code("alice", "welcome123"), None);
+        assert_eq!(code("a.b-c_9", "secret"), None);
+        assert_eq!(code(&"x".repeat(MAX_USERNAME_CHARS), "secret"), None);
+
+        assert_eq!(code("", "welcome123"), Some(4010));
+        assert_eq!(code("   ", "welcome123"), Some(4010));
+        assert_eq!(code("al", "welcome123"), Some(4014));
+        assert_eq!(code(&"x".repeat(MAX_USERNAME_CHARS + 1), "welcome123"), Some(4011));
+        assert_eq!(code("al ice", "welcome123"), Some(4013));
+        assert_eq!(code("ålice", "welcome123"), Some(4013));
+        assert_eq!(code("bob<script>", "welcome123"), Some(4013));
+        assert_eq!(code("alice", "12345"), Some(4012));
+        // Username problems are reported before the password
+        assert_eq!(code("", ""), Some(4010));
     }
 
-    Ok(user_id)
+    proptest! {
+        #[test]
+        fn test_random_tokens_rejected_cleanly(token in ".{0,600}") {
+            _dev_utils::ensure_token_secret();
+            prop_assert_eq!(validate_token(&token).unwrap_err().code, 4001);
+        }
+
+        #[test]
+        fn test_random_payloads_rejected_cleanly(
+            payload in proptest::collection::vec(any::<u8>(), 0..128),
+            signature in "[0-9a-f.]{0,80}",
+        ) {
+            _dev_utils::ensure_token_secret();
+            let token = format!("{}.{}", general_purpose::STANDARD.encode(&payload), signature);
+            prop_assert_eq!(validate_token(&token).unwrap_err().code, 4001);
+        }
+    }
 }
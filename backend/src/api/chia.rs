@@ -1,7 +1,8 @@
 /// Clear all Chia RPC and SSL state (node and wallet)
 pub async fn clear_chia_config(State(state): State<Arc<AppState>>) -> Result<Json<ChiaConfigResponse>, (StatusCode, String)> {
-    // Reset RPC URL and connection mode to defaults
-    state.set_rpc_url("http://localhost:8555".to_string()).await;
+    // Reset both RPC URLs and the connection mode to defaults
+    state.set_rpc_url_for_mode("full_node", "").await;
+    state.set_rpc_url_for_mode("wallet", "").await;
     state.set_connection_mode("full_node".to_string()).await;
     // Clear SSL paths for both node and wallet
     state.set_ssl_paths_for_mode("wallet", "".to_string(), "".to_string()).await;
@@ -46,11 +47,43 @@ pub struct ChiaNodeStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sync_mode: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub synced: Option<bool>,
+    /// Height the node is syncing towards
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_tip_height: Option<u64>,
+    /// Height the node has synced up to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_progress_height: Option<u64>,
+    /// 0-100; 100 once synced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_progress_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rpc_url: Option<String>,
 }
 
+/// Result of probing one connection mode with its own URL and SSL config
+#[derive(Debug, Serialize)]
+pub struct ChiaModeCheck {
+    pub connected: bool,
+    pub network: Option<String>,
+    pub error: Option<String>,
+    /// The normalized URL that was probed
+    pub effective_url: String,
+}
+
+/// Infer the connection mode from the RPC URL's port: 9256 is the wallet RPC,
+/// 8555 the full node. Other ports (or unparseable URLs) are ambiguous.
+pub fn infer_connection_mode(rpc_url: &str) -> Option<&'static str> {
+    let url = reqwest::Url::parse(rpc_url).ok()?;
+    match url.port()? {
+        9256 => Some("wallet"),
+        8555 => Some("full_node"),
+        _ => None,
+    }
+}
+
 /// Set Chia RPC configuration
 pub async fn set_chia_config(
     State(state): State<Arc<AppState>>,
@@ -62,62 +95,132 @@ pub async fn set_chia_config(
         payload.mode
     );
 
-    // Update the RPC URL in state
-    state.set_rpc_url(payload.rpc_url.clone()).await;
-
-    // Optionally update connection mode if needed
-    if let Some(mode) = payload.mode {
-        state.set_connection_mode(mode).await;
-    }
+    // An explicit mode wins, then the URL's port; otherwise the URL is for the current mode
+    let mode = match payload
+        .mode
+        .or_else(|| infer_connection_mode(&payload.rpc_url).map(str::to_string))
+    {
+        Some(mode) => mode,
+        None => state.connection_mode().await,
+    };
+    // Only this mode's URL changes; the other mode keeps its own
+    state.set_rpc_url_for_mode(&mode, &payload.rpc_url).await;
+    state.set_connection_mode(mode).await;
 
     Ok(Json(ChiaConfigResponse {
         success: true,
-        message: format!("Chia RPC URL set to {}", payload.rpc_url),
+        message: format!(
+            "Chia RPC URL set to {} ({} mode)",
+            payload.rpc_url,
+            state.connection_mode().await
+        ),
     }))
 }
 
+impl ChiaNodeStatus {
+    /// Status with nothing known beyond the configured URL
+    fn unknown(rpc_url: String, error: Option<String>) -> Self {
+        ChiaNodeStatus {
+            connected: false,
+            network: None,
+            peak_height: None,
+            sync_mode: None,
+            synced: None,
+            sync_tip_height: None,
+            sync_progress_height: None,
+            sync_progress_percent: None,
+            error,
+            rpc_url: Some(rpc_url),
+        }
+    }
+
+    /// Status of a reachable node from its blockchain state (full node) or sync status (wallet)
+    fn from_state(state_response: &serde_json::Value, rpc_url: String) -> Self {
+        let sync = state_response.get("sync");
+        let sync_u64 = |key: &str| sync.and_then(|s| s.get(key)).and_then(|v| v.as_u64());
+        // The wallet's get_sync_status reports `synced` at the top level
+        let synced = sync
+            .and_then(|s| s.get("synced"))
+            .or_else(|| state_response.get("synced"))
+            .and_then(|v| v.as_bool());
+        let sync_tip_height = sync_u64("sync_tip_height");
+        let sync_progress_height = sync_u64("sync_progress_height");
+
+        ChiaNodeStatus {
+            connected: true,
+            network: state_response
+                .get("network_name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            peak_height: state_response
+                .get("peak")
+                .and_then(|v| v.get("height"))
+                .and_then(|v| v.as_u64()),
+            sync_mode: sync.and_then(|s| s.get("sync_mode")).and_then(|v| v.as_bool()),
+            synced,
+            sync_tip_height,
+            sync_progress_height,
+            sync_progress_percent: sync_progress_percent(synced, sync_tip_height, sync_progress_height),
+            error: None,
+            rpc_url: Some(rpc_url),
+        }
+    }
+}
+
+/// Percentage of the way to the sync tip, to one decimal; 100 once synced, None when unknown
+pub fn sync_progress_percent(synced: Option<bool>, tip: Option<u64>, progress: Option<u64>) -> Option<f64> {
+    if synced == Some(true) {
+        return Some(100.0);
+    }
+    match (tip, progress) {
+        (Some(tip), Some(progress)) if tip > 0 => {
+            let percent = (progress as f64 / tip as f64 * 100.0).min(100.0);
+            Some((percent * 10.0).floor() / 10.0)
+        }
+        _ => None,
+    }
+}
+
+/// Probe one mode. Unlike `chia_node_status` there is no fallback to the env client,
+/// so a broken SSL setup for the mode shows up as its error.
+pub async fn check_mode(state: Arc<AppState>, mode: &str) -> ChiaModeCheck {
+    let effective_url = state.rpc_url_for_mode(mode).await;
+    let probe = match ChiaRpcClient::from_state(state, mode).await {
+        Ok(client) => client.get_blockchain_state().await,
+        Err(e) => Err(e),
+    };
+    match probe {
+        Ok(state_response) => ChiaModeCheck {
+            connected: true,
+            network: ChiaNodeStatus::from_state(&state_response, effective_url.clone()).network,
+            error: None,
+            effective_url,
+        },
+        Err(e) => ChiaModeCheck { connected: false, network: None, error: Some(e.to_string()), effective_url },
+    }
+}
+
+/// Probe the full node and the wallet concurrently: `(full_node, wallet)`
+pub async fn check_all_modes(state: Arc<AppState>) -> (ChiaModeCheck, ChiaModeCheck) {
+    tokio::join!(check_mode(state.clone(), "full_node"), check_mode(state, "wallet"))
+}
+
 // Only test connection if user requests (no auto-test on upload/config)
 use std::collections::HashMap;
 pub async fn chia_node_status(
     State(state): State<Arc<AppState>>,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<Json<ChiaNodeStatus>, (StatusCode, String)> {
-    let rpc_url = state.rpc_url().await;
     let mode = if let Some(m) = params.get("type").cloned() {
         m
     } else {
         state.connection_mode().await
     };
-
-    // Compute effective URL as used by ChiaRpcClient
-    let effective_url = {
-        let is_local = rpc_url.contains("localhost") || rpc_url.contains("127.0.0.1");
-        let has_port = rpc_url.contains(":9256") || rpc_url.contains(":8555");
-        // Force wallet mode to use 9256 if rpc_url is empty or points to 8555
-        if mode == "wallet" && (rpc_url.is_empty() || rpc_url.ends_with(":8555") || rpc_url == "http://localhost" || rpc_url == "https://localhost" || rpc_url == "http://127.0.0.1" || rpc_url == "https://127.0.0.1") {
-            "https://localhost:9256".to_string()
-        } else if rpc_url.is_empty() || rpc_url == "http://localhost" || rpc_url == "https://localhost" || rpc_url == "http://127.0.0.1" || rpc_url == "https://127.0.0.1" {
-            if mode == "wallet" {
-                "https://localhost:9256".to_string()
-            } else {
-                "https://localhost:8555".to_string()
-            }
-        } else if is_local && !has_port {
-            if mode == "wallet" {
-                rpc_url.replace("http://", "https://").replace("https://", "https://") + ":9256"
-            } else {
-                rpc_url.replace("http://", "https://").replace("https://", "https://") + ":8555"
-            }
-        } else if is_local && has_port {
-            rpc_url.replace("http://", "https://")
-        } else {
-            rpc_url.clone()
-        }
-    };
+    let rpc_url = state.rpc_url_for_mode(&mode).await;
 
     tracing::info!(
         "Checking Chia node status: url={}, mode={}",
-        effective_url,
+        rpc_url,
         mode
     );
 
@@ -127,58 +230,160 @@ pub async fn chia_node_status(
             Ok(c) => c,
             Err(e) => {
                 tracing::warn!("Falling back to env client: {}", e);
-                ChiaRpcClient::from_env(effective_url.clone())
+                ChiaRpcClient::from_env(rpc_url.clone())
             }
         };
 
         // Try to get blockchain state to verify connection
         match client.get_blockchain_state().await {
-            Ok(state_response) => {
-                let network: Option<String> = state_response
-                    .get("network_name")
-                    .and_then(|v: &serde_json::Value| v.as_str())
-                    .map(|s: &str| s.to_string());
-
-                let peak_height: Option<u64> = state_response
-                    .get("peak")
-                    .and_then(|v: &serde_json::Value| v.get("height"))
-                    .and_then(|v: &serde_json::Value| v.as_u64());
-
-                let sync_mode: Option<bool> = state_response
-                    .get("sync")
-                    .and_then(|v: &serde_json::Value| v.get("sync_mode"))
-                    .and_then(|v: &serde_json::Value| v.as_bool());
-
-                Ok(Json(ChiaNodeStatus {
-                    connected: true,
-                    network,
-                    peak_height,
-                    sync_mode,
-                    error: None,
-                    rpc_url: Some(effective_url),
-                }))
-            }
+            Ok(state_response) => Ok(Json(ChiaNodeStatus::from_state(&state_response, rpc_url))),
             Err(e) => {
                 tracing::warn!("Failed to connect to Chia node: {}", e);
-                Ok(Json(ChiaNodeStatus {
-                    connected: false,
-                    network: None,
-                    peak_height: None,
-                    sync_mode: None,
-                    error: Some(format!("Failed to connect: {}", e)),
-                    rpc_url: Some(rpc_url),
-                }))
+                Ok(Json(ChiaNodeStatus::unknown(rpc_url, Some(format!("Failed to connect: {}", e)))))
             }
         }
     } else {
         // If not testing, just return config info (no connection attempt)
-        Ok(Json(ChiaNodeStatus {
-            connected: false,
-            network: None,
-            peak_height: None,
-            sync_mode: None,
-            error: None,
-            rpc_url: Some(rpc_url),
-        }))
+        Ok(Json(ChiaNodeStatus::unknown(rpc_url, None)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_connection_mode() {
+        assert_eq!(infer_connection_mode("https://localhost:9256"), Some("wallet"));
+        assert_eq!(infer_connection_mode("https://127.0.0.1:8555"), Some("full_node"));
+        assert_eq!(infer_connection_mode("http://node.example.com:8555/"), Some("full_node"));
+        assert_eq!(infer_connection_mode("https://localhost"), None);
+        assert_eq!(infer_connection_mode("https://localhost:1234"), None);
+        assert_eq!(infer_connection_mode("not a url"), None);
+    }
+
+    #[test]
+    fn test_status_from_syncing_state() {
+        let state = serde_json::json!({
+            "network_name": "mainnet",
+            "peak": { "height": 3_100_000u64 },
+            "sync": {
+                "sync_mode": true,
+                "synced": false,
+                "sync_tip_height": 5_200_000u64,
+                "sync_progress_height": 3_100_000u64
+            }
+        });
+        let status = ChiaNodeStatus::from_state(&state, "https://localhost:8555".to_string());
+        assert!(status.connected);
+        assert_eq!(status.network.as_deref(), Some("mainnet"));
+        assert_eq!(status.peak_height, Some(3_100_000));
+        assert_eq!(status.sync_mode, Some(true));
+        assert_eq!(status.synced, Some(false));
+        assert_eq!(status.sync_tip_height, Some(5_200_000));
+        assert_eq!(status.sync_progress_height, Some(3_100_000));
+        assert_eq!(status.sync_progress_percent, Some(59.6));
+
+        // Synced nodes report 0 heights; wallets only report `synced`
+        let synced = serde_json::json!({
+            "sync": { "sync_mode": false, "synced": true, "sync_tip_height": 0, "sync_progress_height": 0 }
+        });
+        let status = ChiaNodeStatus::from_state(&synced, String::new());
+        assert_eq!(status.sync_progress_percent, Some(100.0));
+        let wallet = serde_json::json!({ "synced": false, "syncing": true, "genesis_initialized": true });
+        let status = ChiaNodeStatus::from_state(&wallet, String::new());
+        assert_eq!((status.synced, status.sync_progress_percent), (Some(false), None));
+
+        assert_eq!(sync_progress_percent(Some(false), Some(0), Some(0)), None);
+        assert_eq!(sync_progress_percent(None, Some(100), Some(120)), Some(100.0));
+    }
+
+    async fn set(state: &Arc<AppState>, rpc_url: &str, mode: Option<&str>) {
+        let req = ChiaConfigRequest { rpc_url: rpc_url.to_string(), mode: mode.map(str::to_string) };
+        set_chia_config(State(state.clone()), Json(req)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_chia_config_stores_consistent_mode() {
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+
+        set(&state, "https://localhost:9256", None).await;
+        assert_eq!(state.connection_mode().await, "wallet");
+
+        set(&state, "https://localhost:8555", None).await;
+        assert_eq!(state.connection_mode().await, "full_node");
+
+        // Ambiguous URL keeps the stored mode; an explicit mode always wins
+        set(&state, "https://localhost:9256", None).await;
+        set(&state, "https://node.example.com", None).await;
+        assert_eq!(state.connection_mode().await, "wallet");
+        set(&state, "https://localhost:9256", Some("full_node")).await;
+        assert_eq!(state.connection_mode().await, "full_node");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_urls_are_kept_per_mode() {
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://localhost:8555");
+        assert_eq!(state.rpc_url_for_mode("wallet").await, "https://localhost:9256");
+
+        set(&state, "https://node.example.com:8555", Some("full_node")).await;
+        set(&state, "https://wallet.example.com:9256", Some("wallet")).await;
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://node.example.com:8555");
+        assert_eq!(state.rpc_url_for_mode("wallet").await, "https://wallet.example.com:9256");
+
+        // Changing the node URL leaves the wallet URL alone, and vice versa
+        set(&state, "http://127.0.0.1", Some("full_node")).await;
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://127.0.0.1:8555");
+        assert_eq!(state.rpc_url_for_mode("wallet").await, "https://wallet.example.com:9256");
+        set(&state, "https://localhost:9256", None).await;
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://127.0.0.1:8555");
+        assert_eq!(state.rpc_url().await, "https://localhost:9256");
+
+        clear_chia_config(State(state.clone())).await.unwrap();
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://localhost:8555");
+        assert_eq!(state.rpc_url_for_mode("wallet").await, "https://localhost:9256");
+    }
+
+    #[tokio::test]
+    async fn test_check_all_modes_reports_each_mode() {
+        let router = axum::Router::new().route(
+            "/get_blockchain_state",
+            axum::routing::post(|| async {
+                Json(serde_json::json!({ "blockchain_state": { "network_name": "testnet11", "peak": { "height": 42 } } }))
+            }),
+        );
+        // The normalizer forces https for localhost URLs; 0.0.0.0 reaches the plain-http mock node
+        let node_url = crate::_dev_utils::spawn_mock_node(router).await.replace("127.0.0.1", "0.0.0.0");
+        let state = Arc::new(AppState::new(node_url.clone()));
+        state.set_rpc_url_for_mode("wallet", "http://0.0.0.0:1").await;
+
+        let (full_node, wallet) = check_all_modes(state).await;
+        assert!(full_node.connected);
+        assert_eq!(full_node.network.as_deref(), Some("testnet11"));
+        assert_eq!((full_node.error, full_node.effective_url), (None, node_url));
+        assert!(!wallet.connected);
+        assert!(wallet.network.is_none());
+        assert!(wallet.error.is_some());
+        assert_eq!(wallet.effective_url, "http://0.0.0.0:1");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_single_url_fills_its_mode() {
+        // A wallet URL in CHIA_RPC_URL now lands in the wallet slot and selects wallet mode
+        let state = AppState::new("https://10.0.0.5:9256".to_string());
+        assert_eq!(state.connection_mode().await, "wallet");
+        assert_eq!(state.rpc_url_for_mode("wallet").await, "https://10.0.0.5:9256");
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://localhost:8555");
+
+        let state = AppState::new("https://node.example.com".to_string());
+        assert_eq!(state.connection_mode().await, "full_node");
+        assert_eq!(state.rpc_url().await, "https://node.example.com");
+
+        // Set without a mode: the port decides, else the current mode
+        state.set_rpc_url("https://localhost:9256".to_string()).await;
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://node.example.com");
+        state.set_rpc_url("https://other.example.com".to_string()).await;
+        assert_eq!(state.rpc_url_for_mode("full_node").await, "https://other.example.com");
     }
 }
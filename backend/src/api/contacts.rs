@@ -8,6 +8,7 @@ use uuid::Uuid;
 
 use crate::api::contracts::AppError;
 use crate::storage::contacts::{self, Contact};
+use crate::util::{email, text};
 
 #[derive(Debug, Deserialize)]
 pub struct CreateContactRequest {
@@ -46,30 +47,41 @@ fn validate_contact_fields(name: &str, public_key: &str) -> Result<(), AppError>
     Ok(())
 }
 
+/// Trim/lowercase an optional email; blank means "no email", anything else must validate
+fn normalize_email(raw: Option<&str>) -> Result<Option<String>, AppError> {
+    let clean = match raw.map(email::normalize) {
+        Some(v) if !v.is_empty() => v,
+        _ => return Ok(None),
+    };
+
+    if !email::validate(&clean) {
+        return Err(AppError::BadRequest(format!("email '{}' is not a valid address", clean)));
+    }
+
+    Ok(Some(clean))
+}
+
 pub async fn create_contact(
     Json(payload): Json<CreateContactRequest>,
 ) -> Result<Json<Contact>, AppError> {
     validate_contact_fields(&payload.name, &payload.public_key)?;
+    let email = normalize_email(payload.email.as_deref())?;
 
     let now = chrono::Utc::now().to_rfc3339();
     let contact = Contact {
         id: Uuid::new_v4().to_string(),
-        name: payload.name.trim().to_string(),
+        name: text::escape(&text::strip_controls(payload.name.trim())),
         public_key: payload.public_key.trim().to_string(),
         xch_address: payload
             .xch_address
             .as_ref()
             .map(|v| v.trim().to_string())
             .filter(|v| !v.is_empty()),
-        email: payload
-            .email
-            .as_ref()
-            .map(|v| v.trim().to_string())
-            .filter(|v| !v.is_empty()),
+        email,
         note: payload
             .note
             .as_ref()
-            .map(|v| v.trim().to_string())
+            .map(|v| text::escape(&text::strip_controls(v.trim())))
             .filter(|v| !v.is_empty()),
         created_at: now.clone(),
         updated_at: now,
@@ -104,7 +116,7 @@ pub async fn update_contact(
         if name.trim().is_empty() {
             return Err(AppError::BadRequest("name cannot be empty".to_string()));
         }
-        contact.name = name.trim().to_string();
+        contact.name = text::escape(&text::strip_controls(name.trim()));
     }
 
     if let Some(key) = payload.public_key {
@@ -122,12 +134,7 @@ pub async fn update_contact(
     }
 
     if let Some(email) = payload.email {
-        let clean = email.trim();
-        contact.email = if clean.is_empty() {
-            None
-        } else {
-            Some(clean.to_string())
-        };
+        contact.email = normalize_email(Some(&email))?;
     }
 
     if let Some(note) = payload.note {
@@ -135,7 +142,7 @@ pub async fn update_contact(
         contact.note = if clean.is_empty() {
             None
         } else {
-            Some(clean.to_string())
+            Some(text::escape(&text::strip_controls(clean)))
         };
     }
 
@@ -153,3 +160,25 @@ pub async fn delete_contact(Path(id): Path<String>) -> Result<impl IntoResponse,
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email_valid() {
+        let email = normalize_email(Some("  Alice@Example.com ")).unwrap();
+        assert_eq!(email.as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn test_normalize_email_invalid() {
+        assert!(matches!(normalize_email(Some("not-an-email")), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_normalize_email_empty_optional() {
+        assert_eq!(normalize_email(None).unwrap(), None);
+        assert_eq!(normalize_email(Some("   ")).unwrap(), None);
+    }
+}
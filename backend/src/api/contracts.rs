@@ -7,13 +7,18 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::app_state::AppState;
-use crate::blockchain::puzzles;
+use crate::ctx::Ctx;
+use crate::blockchain::hash::{CoinId, PuzzleHash};
+use crate::blockchain::{clvm, puzzles};
 use crate::rpc::client::ChiaRpcClient;
 use crate::storage::files;
-use crate::util::hashing;
+use crate::util::{hashing, text};
 use axum::extract::Path;
 use axum::extract::State;
 
+/// Default cap on `terms_text`; override with `CONTRACT_TERMS_MAX_BYTES`
+const DEFAULT_TERMS_MAX_BYTES: usize = 1024 * 1024;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateContractRequest {
     pub title: String,
@@ -57,19 +62,19 @@ pub struct CompileContractResponse {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeployContractRequest {
-    pub puzzle_hash: String,
+    pub puzzle_hash: PuzzleHash,
     pub amount: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeployContractResponse {
-    pub coin_id: String,
+    pub coin_id: CoinId,
     pub status: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SpendContractRequest {
-    pub coin_id: String,
+    pub coin_id: CoinId,
     pub signatures: Vec<String>,
     pub solution: String,
 }
@@ -82,6 +87,7 @@ pub struct SpendContractResponse {
 
 // Create a new contract
 pub async fn create_contract(
+    ctx: Ctx,
     Json(payload): Json<CreateContractRequest>,
 ) -> Result<Json<CreateContractResponse>, AppError> {
     tracing::info!("Creating contract: {}", payload.title);
@@ -106,6 +112,10 @@ pub async fn create_contract(
         ));
     }
 
+    if let Some(content) = &payload.terms_text {
+        validate_terms_text(content, terms_max_bytes())?;
+    }
+
     // Hash the contract terms
     let terms_hash = if let Some(ref path) = payload.file_path {
         hashing::hash_contract_file(path)
@@ -124,7 +134,7 @@ pub async fn create_contract(
         &terms_hash,
         payload.required_signatures,
     )
-    .map_err(|e| AppError::InternalError(format!("Failed to generate puzzle: {}", e)))?;
+    .map_err(|e| AppError::BadRequest(format!("Failed to generate puzzle: {}", e)))?;
 
     // Store contract terms if provided as text
     if let Some(content) = &payload.terms_text {
@@ -137,6 +147,7 @@ pub async fn create_contract(
     // Store contract metadata
     let metadata = serde_json::json!({
         "contract_id": contract_id,
+        "created_by": ctx.user_id(),
         "title": payload.title,
         "participants": payload.participants,
         "required_signatures": payload.required_signatures,
@@ -159,6 +170,35 @@ pub async fn create_contract(
     }))
 }
 
+/// Max `terms_text` size in bytes (`CONTRACT_TERMS_MAX_BYTES`, default 1 MiB)
+fn terms_max_bytes() -> usize {
+    std::env::var("CONTRACT_TERMS_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TERMS_MAX_BYTES)
+}
+
+/// Contract terms must be non-blank text within `max_bytes`. Control characters
+/// other than tabs and line breaks mean binary data, not terms.
+fn validate_terms_text(content: &str, max_bytes: usize) -> Result<(), AppError> {
+    if content.len() > max_bytes {
+        return Err(AppError::BadRequest(format!(
+            "terms_text is {} bytes; the limit is {}",
+            content.len(),
+            max_bytes
+        )));
+    }
+    if content.trim().is_empty() {
+        return Err(AppError::BadRequest("terms_text must not be empty".to_string()));
+    }
+    if text::has_control_chars(content) {
+        return Err(AppError::BadRequest(
+            "terms_text must be plain text (no control characters)".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 // Hash a contract file or content
 pub async fn hash_contract(
     Json(payload): Json<HashContractRequest>,
@@ -198,16 +238,16 @@ pub async fn compile_contract(
         ));
     }
 
-    let puzzle_hash = puzzles::generate_contract_puzzle_hash(
+    let puzzle = clvm::m_of_n_puzzle(
         &payload.participants,
-        &payload.terms_hash,
         payload.required_signatures,
+        &payload.terms_hash,
     )
-    .map_err(|e| AppError::InternalError(format!("Failed to compile puzzle: {}", e)))?;
+    .map_err(|e| AppError::BadRequest(format!("Failed to compile puzzle: {}", e)))?;
 
     Ok(Json(CompileContractResponse {
-        puzzle_hash: puzzle_hash.clone(),
-        puzzle_reveal: format!("0x{}", puzzle_hash), // Simplified for now
+        puzzle_hash: hex::encode(puzzle.tree_hash),
+        puzzle_reveal: format!("0x{}", hex::encode(puzzle.program)),
     }))
 }
 
@@ -223,7 +263,7 @@ pub async fn deploy_contract(
     // This would interact with Chia RPC to create the initial coin
     // For now, return a mock response
     Ok(Json(DeployContractResponse {
-        coin_id: format!("0x{}", hex::encode(&[0u8; 32])),
+        coin_id: CoinId::from([0u8; 32]),
         status: "pending".to_string(),
     }))
 }
@@ -291,7 +331,10 @@ pub async fn set_chia_config(
         return Err(AppError::BadRequest("rpc_url cannot be empty".to_string()));
     }
 
-    state.set_rpc_url(payload.rpc_url.clone()).await;
+    match payload.mode.as_deref() {
+        Some(mode) => state.set_rpc_url_for_mode(mode, &payload.rpc_url).await,
+        None => state.set_rpc_url(payload.rpc_url.clone()).await,
+    }
 
     Ok(Json(serde_json::json!({
         "saved": true,
@@ -324,28 +367,13 @@ pub async fn spend_contract(
     }))
 }
 
-// List all contracts
-pub async fn list_contracts() -> Result<Json<Vec<serde_json::Value>>, AppError> {
-    tracing::info!("Listing all contracts");
-
-    // Get all metadata files
-    let storage_dir = "storage/metadata";
-    let mut contracts = Vec::new();
-
-    if let Ok(entries) = std::fs::read_dir(storage_dir) {
-        for entry in entries.flatten() {
-            if entry.path().extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Some(contract_id) = entry.path().file_stem().and_then(|s| s.to_str()) {
-                    match files::load_contract_metadata(contract_id) {
-                        Ok(metadata) => contracts.push(metadata),
-                        Err(e) => {
-                            tracing::warn!("Failed to load metadata for {}: {}", contract_id, e)
-                        }
-                    }
-                }
-            }
-        }
-    }
+// List the caller's contracts (disk metadata, scoped by `created_by`)
+#[deprecated(note = "contracts live in Postgres; use the `contract_list` RPC (ContractBmc::list)")]
+pub async fn list_contracts(ctx: Ctx) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    tracing::info!("Listing contracts for user {}", ctx.user_id());
+
+    let contracts = files::list_contract_metadata(ctx.user_id())
+        .map_err(|e| AppError::InternalError(format!("Failed to list contracts: {}", e)))?;
 
     tracing::info!("Found {} contracts", contracts.len());
     Ok(Json(contracts))
@@ -353,38 +381,53 @@ pub async fn list_contracts() -> Result<Json<Vec<serde_json::Value>>, AppError>
 
 // Get a single contract by ID
 pub async fn get_contract(
+    ctx: Ctx,
     axum::extract::Path(contract_id): axum::extract::Path<String>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     tracing::info!("Getting contract: {}", contract_id);
 
-    let metadata = files::load_contract_metadata(&contract_id)
-        .map_err(|e| AppError::BadRequest(format!("Contract not found: {}", e)))?;
+    let metadata = load_owned_metadata(&ctx, &contract_id)?;
 
     Ok(Json(metadata))
 }
 
+// Load metadata, treating another user's contract as not found
+fn load_owned_metadata(ctx: &Ctx, contract_id: &str) -> Result<serde_json::Value, AppError> {
+    let metadata = files::load_contract_metadata(contract_id)
+        .map_err(|e| AppError::BadRequest(format!("Contract not found: {}", e)))?;
+
+    if !files::metadata_owned_by(&metadata, ctx.user_id()) {
+        return Err(AppError::BadRequest(format!("Contract not found: {}", contract_id)));
+    }
+
+    Ok(metadata)
+}
+
 // Validate contract presence on-chain by puzzle hash
 pub async fn validate_contract(
+    ctx: Ctx,
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let metadata = files::load_contract_metadata(&contract_id)
-        .map_err(|e| AppError::BadRequest(format!("Contract not found: {}", e)))?;
+    let metadata = load_owned_metadata(&ctx, &contract_id)?;
 
-    let puzzle_hash = metadata
+    let puzzle_hash: PuzzleHash = metadata
         .get("puzzle_hash")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| AppError::InternalError("Missing puzzle_hash in metadata".to_string()))?;
+        .ok_or_else(|| AppError::InternalError("Missing puzzle_hash in metadata".to_string()))?
+        .parse()
+        .map_err(|e| AppError::InternalError(format!("Invalid puzzle_hash in metadata: {}", e)))?;
 
     let base_url = state.rpc_url().await;
     let client = ChiaRpcClient::from_env(base_url.clone());
 
-    match client.get_coin_records_by_puzzle_hash(puzzle_hash).await {
+    match client.get_coin_records_by_puzzle_hash(&puzzle_hash).await {
         Ok(records) => {
-            let validated = !records.is_empty();
+            // Only coins locked to this contract's puzzle count
+            let matching = records.iter().filter(|r| r.puzzle_hash == puzzle_hash).count();
             Ok(Json(serde_json::json!({
-                "validated": validated,
-                "records": records.len(),
+                "validated": matching > 0,
+                "records": matching,
                 "rpc_url": base_url,
             })))
         }
@@ -413,3 +456,41 @@ impl IntoResponse for AppError {
         (status, message).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(terms_text: &str) -> CreateContractRequest {
+        CreateContractRequest {
+            title: "Terms test".to_string(),
+            participants: vec!["aa".repeat(48)],
+            required_signatures: 1,
+            file_path: None,
+            terms_text: Some(terms_text.to_string()),
+            attached_files: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_terms_rejected() {
+        let ctx = Ctx::new(1, "alice".to_string());
+        let oversized = "a".repeat(DEFAULT_TERMS_MAX_BYTES + 1);
+        let res = create_contract(ctx, Json(request(&oversized))).await;
+        assert!(matches!(res, Err(AppError::BadRequest(msg)) if msg.contains("limit")));
+
+        assert!(validate_terms_text(&"a".repeat(16), 16).is_ok());
+        assert!(validate_terms_text(&"a".repeat(17), 16).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_terms_rejected() {
+        let ctx = Ctx::new(1, "alice".to_string());
+        for blank in ["", "  \n\t "] {
+            let res = create_contract(ctx.clone(), Json(request(blank))).await;
+            assert!(matches!(res, Err(AppError::BadRequest(msg)) if msg.contains("empty")));
+        }
+        assert!(validate_terms_text("binary\0data", 64).is_err());
+        assert!(validate_terms_text("Line one\r\n\tLine two", 64).is_ok());
+    }
+}
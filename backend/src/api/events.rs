@@ -0,0 +1,226 @@
+//! Server-Sent Events for trade status changes (`GET /trades/:id/events`).
+//!
+//! Participants subscribe instead of polling `trade_get`. A trigger on `trades`
+//! (sql/31-notify-trade-status.sql) notifies Postgres channel `trade_status` on every
+//! committed status or tracking change, and `start_trade_status_listener` forwards those
+//! to `AppState`'s per-trade channels. The verifier adds payment `confirmation` events.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use futures_util::Stream;
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tower_cookies::CookieManagerLayer;
+
+use crate::api::mw_auth::mw_ctx_resolve;
+use crate::api::rpc::RpcState;
+use crate::app_state::{AppState, TradeStatusEvent};
+use crate::ctx::Ctx;
+use crate::model::{ModelManager, TradeBmc};
+
+/// SSE event name of every `TradeStatusEvent`
+pub const TRADE_STATUS_EVENT: &str = "trade_status";
+
+/// Postgres channel the `trades` trigger notifies on
+pub const TRADE_STATUS_CHANNEL: &str = "trade_status";
+
+/// Payload of a `trade_status` notification
+#[derive(Deserialize)]
+struct TradeStatusNotice {
+    trade_id: i64,
+    status: String,
+    kind: String,
+}
+
+pub fn routes(mm: ModelManager, app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/trades/:id/events", get(trade_events))
+        .layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
+        .layer(CookieManagerLayer::new())
+        .with_state(RpcState(mm, app_state))
+}
+
+/// Stream a trade's status events to one of its participants; anyone else gets 404
+async fn trade_events(
+    State(mm): State<ModelManager>,
+    State(app_state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(trade_id): Path<i64>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, StatusCode> {
+    TradeBmc::get(&ctx, &mm, trade_id).await.map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let rx = app_state.subscribe_trade(trade_id);
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => return Some((Event::default().event(TRADE_STATUS_EVENT).json_data(&event), rx)),
+                // A slow client only needs the latest status
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Listen for the `trades` trigger's notifications and publish them to the trade's
+/// subscribers. Returns once listening, so changes committed afterwards are streamed.
+pub async fn start_trade_status_listener(
+    mm: &ModelManager,
+    app_state: Arc<AppState>,
+) -> Result<JoinHandle<()>, sqlx::Error> {
+    let mut listener = PgListener::connect_with(mm.db()).await?;
+    listener.listen(TRADE_STATUS_CHANNEL).await?;
+
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str::<TradeStatusNotice>(notification.payload()) {
+                    Ok(notice) => app_state.publish_trade_event(TradeStatusEvent {
+                        trade_id: notice.trade_id,
+                        status: notice.status,
+                        kind: notice.kind,
+                        at: chrono::Utc::now(),
+                    }),
+                    Err(e) => tracing::warn!("Ignoring malformed trade status notification: {}", e),
+                },
+                // The listener reconnects on the next recv; changes in between are missed
+                Err(e) => {
+                    tracing::warn!("Trade status listener lost its connection: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }))
+}
+
+/// Publish a trade's current status to its subscribers, if it has any. Best effort.
+pub async fn publish_trade_status(mm: &ModelManager, app_state: &AppState, trade_id: i64, kind: &str) {
+    if !app_state.has_trade_subscribers(trade_id) {
+        return;
+    }
+    match TradeBmc::statuses_for(mm, &[trade_id]).await {
+        Ok(mut statuses) => {
+            if let Some(status) = statuses.remove(&trade_id) {
+                app_state.publish_trade_event(TradeStatusEvent {
+                    trade_id,
+                    status,
+                    kind: kind.to_string(),
+                    at: chrono::Utc::now(),
+                });
+            }
+        }
+        Err(e) => tracing::warn!("Failed to publish status event for trade {}: {:?}", trade_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, proposal_with_value, xch_offer};
+    use crate::model::TransactionBmc;
+    use axum::{routing::post, Extension};
+    use serde_json::json;
+    use std::time::Duration;
+
+    /// Serve the events and RPC routes as `ctx`
+    async fn serve_as(mm: &ModelManager, state: &Arc<AppState>, ctx: &Ctx) -> String {
+        let rpc = Router::new()
+            .route("/api/rpc", post(crate::api::rpc::rpc_handler))
+            .with_state(RpcState(mm.clone(), state.clone()));
+        let router = routes(mm.clone(), state.clone()).merge(rpc).layer(Extension(ctx.clone()));
+        _dev_utils::spawn_mock_node(router).await
+    }
+
+    /// Reads `trade_status` events off an open stream; one chunk may carry several
+    struct EventReader {
+        res: reqwest::Response,
+        buf: String,
+    }
+
+    impl EventReader {
+        async fn next(&mut self) -> serde_json::Value {
+            while !self.buf.contains("\n\n") {
+                let chunk = tokio::time::timeout(Duration::from_secs(5), self.res.chunk()).await.unwrap().unwrap().unwrap();
+                self.buf.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+            let end = self.buf.find("\n\n").unwrap();
+            let text: String = self.buf.drain(..end + 2).collect();
+            assert!(text.starts_with("event: trade_status\n"), "{}", text);
+            let data = text.lines().find_map(|l| l.strip_prefix("data: ")).unwrap();
+            serde_json::from_str(data).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_status_update_streams_event() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        start_trade_status_listener(&mm, state.clone()).await.unwrap();
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(150.0)).await.unwrap();
+        let offer = xch_offer(trade_id);
+        _dev_utils::match_trade(&mm, &bob, offer).await;
+        let client = reqwest::Client::new();
+
+        // Non-participants can't listen in
+        let carol = _dev_utils::seed_ctx(&mm, "carol_events").await;
+        let url = serve_as(&mm, &state, &carol).await;
+        let res = client.get(format!("{}/trades/{}/events", url, trade_id)).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let bob_url = serve_as(&mm, &state, &bob).await;
+        let res = client.get(format!("{}/trades/{}/events", bob_url, trade_id)).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        assert_eq!(res.headers()["content-type"], "text/event-stream");
+        let mut events = EventReader { res, buf: String::new() };
+
+        // The proposer cancels through the RPC layer
+        let alice_url = serve_as(&mm, &state, &alice).await;
+        let body = json!({ "id": 1, "method": "trade_cancel", "params": { "trade_id": trade_id } });
+        let rpc_res: serde_json::Value =
+            client.post(format!("{}/api/rpc", alice_url)).json(&body).send().await.unwrap().json().await.unwrap();
+        assert!(rpc_res["error"].is_null(), "{}", rpc_res);
+
+        let event = events.next().await;
+        assert_eq!(event["trade_id"], trade_id);
+        assert_eq!(event["status"], "cancelled");
+        assert_eq!(event["kind"], "status");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_model_layer_transitions_stream_events() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        start_trade_status_listener(&mm, state.clone()).await.unwrap();
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(150.0)).await.unwrap();
+        _dev_utils::match_trade(&mm, &bob, xch_offer(trade_id)).await;
+
+        let url = serve_as(&mm, &state, &bob).await;
+        let res = reqwest::get(format!("{}/trades/{}/events", url, trade_id)).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        let mut events = EventReader { res, buf: String::new() };
+
+        // Tracking saved through the model, not an RPC
+        TradeBmc::add_tracking(&bob, &mm, trade_id, "1Z999", "UPS", None).await.unwrap();
+        let event = events.next().await;
+        assert_eq!((event["status"].as_str(), event["kind"].as_str()), (Some("matched"), Some("tracking")));
+
+        // Waiving the fee commits the trade and starts escrow inside the transaction model
+        let admin = _dev_utils::seed_ctx(&mm, "admin_events").await;
+        TransactionBmc::admin_set_fee_waived(&admin, &mm, trade_id, true, None).await.unwrap();
+        let statuses = [events.next().await["status"].clone(), events.next().await["status"].clone()];
+        assert_eq!(statuses, [json!("committed"), json!("escrow")]);
+    }
+}
@@ -1,15 +1,90 @@
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
 use axum::{
-    extract::{Json, Multipart, Path, State},
-    http::StatusCode,
+    body::Body,
+    extract::{DefaultBodyLimit, Json, Multipart, Path, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware,
     response::{IntoResponse, Response},
+    routing::{get, MethodRouter},
+    Router,
 };
 use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use tokio::io::AsyncWrite;
+use tokio_util::compat::FuturesAsyncWriteCompatExt;
+use tokio_util::io::ReaderStream;
+use tower_cookies::CookieManagerLayer;
+use tower_http::cors::CorsLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 use uuid::Uuid;
 
 use crate::api::contracts::AppError;
+use crate::api::mw_auth::mw_ctx_resolve;
 use crate::ctx::Ctx;
-use crate::model::{FileBmc, FileForCreate, ModelManager};
+use crate::model::{ContractFile, FileBmc, FileForCreate, ModelManager};
 use crate::storage::files;
+use crate::util::hashing::hash_bytes;
+
+/// Default request size cap for `POST /files`; override with `FILE_UPLOAD_MAX_BYTES`
+pub const DEFAULT_FILE_UPLOAD_MAX_BYTES: usize = 15 * 1024 * 1024;
+
+/// Body limit and CORS for the file routes, kept apart from the JSON-RPC routes
+#[derive(Debug, Clone)]
+pub struct FileRoutesConfig {
+    pub upload_max_bytes: usize,
+    /// Origins allowed to call the file routes cross-origin; empty allows any
+    pub cors_origins: Vec<String>,
+}
+
+impl FileRoutesConfig {
+    /// `FILE_UPLOAD_MAX_BYTES` and comma-separated `FILE_CORS_ORIGINS`
+    pub fn from_env() -> Self {
+        Self {
+            upload_max_bytes: std::env::var("FILE_UPLOAD_MAX_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_FILE_UPLOAD_MAX_BYTES),
+            cors_origins: std::env::var("FILE_CORS_ORIGINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    fn cors(&self) -> CorsLayer {
+        if self.cors_origins.is_empty() {
+            return CorsLayer::permissive();
+        }
+        let origins: Vec<HeaderValue> = self.cors_origins.iter().filter_map(|o| o.parse().ok()).collect();
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods([Method::GET, Method::POST, Method::DELETE])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+    }
+}
+
+/// Authenticated file upload/download routes (REST - binary data doesn't work well with JSON-RPC).
+/// Only `POST /files` gets the upload limit.
+pub fn routes(mm: ModelManager, config: &FileRoutesConfig) -> Router {
+    let upload: MethodRouter<ModelManager> = axum::routing::post(upload_file)
+        .layer::<_, Infallible>(DefaultBodyLimit::disable())
+        .layer::<_, Infallible>(RequestBodyLimitLayer::new(config.upload_max_bytes));
+
+    Router::new()
+        .route("/files", get(list_files).merge(upload))
+        .route("/files/:id", get(get_file).head(head_file).delete(delete_file))
+        .route("/contracts/:id/files.zip", get(download_contract_zip))
+        .layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
+        .layer(CookieManagerLayer::new())
+        .layer(config.cors())
+        .with_state(mm)
+}
 
 #[derive(Debug, Serialize)]
 pub struct UploadFileResponse {
@@ -30,51 +105,120 @@ pub struct FileMetadata {
     pub uploaded_at: String,
 }
 
+/// Largest single file accepted by `POST /files`; the whole request is capped by `upload_max_bytes`
+const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+/// One `file` field read from the multipart body
+struct UploadedPart {
+    filename: String,
+    content_type: String,
+    data: Vec<u8>,
+}
+
+/// Store every `file` field of the multipart body, attached to the contract in the optional
+/// `contract_id` field. All files are checked before any is stored, so one bad file rejects
+/// the whole request.
 pub async fn upload_file(
     ctx: Ctx,
     State(mm): State<ModelManager>,
     mut multipart: Multipart,
-) -> Result<Json<UploadFileResponse>, AppError> {
-    let mut file_data: Option<Vec<u8>> = None;
-    let mut filename: Option<String> = None;
-    let mut content_type: Option<String> = None;
+) -> Result<Json<Vec<UploadFileResponse>>, AppError> {
+    let mut parts: Vec<UploadedPart> = Vec::new();
+    let mut contract_id = 0; // Will be set by client or update later
 
     while let Some(field) = multipart
         .next_field()
         .await
         .map_err(|e| AppError::BadRequest(format!("Invalid multipart data: {}", e)))?
     {
-        let field_name = field.name().unwrap_or("").to_string();
+        match field.name() {
+            Some("file") => {}
+            Some("contract_id") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Invalid multipart data: {}", e)))?;
+                contract_id = text
+                    .trim()
+                    .parse()
+                    .map_err(|_| AppError::BadRequest("Invalid contract_id".to_string()))?;
+                continue;
+            }
+            _ => continue,
+        }
+        let filename = field
+            .file_name()
+            .map(|s| s.to_string())
+            .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?;
+        let content_type = field
+            .content_type()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "application/octet-stream".to_string());
 
-        if field_name == "file" {
-            filename = field.file_name().map(|s| s.to_string());
-            content_type = field.content_type().map(|s| s.to_string());
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read file data: {}", e)))?;
 
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::BadRequest(format!("Failed to read file data: {}", e)))?;
-
-            file_data = Some(data.to_vec());
+        if data.is_empty() {
+            return Err(AppError::BadRequest(format!("File \"{}\" is empty", filename)));
+        }
+        if data.len() > MAX_FILE_SIZE {
+            return Err(AppError::BadRequest(format!("File \"{}\" too large (max 10MB)", filename)));
         }
+        parts.push(UploadedPart { filename, content_type, data: data.to_vec() });
     }
 
-    let data = file_data.ok_or_else(|| AppError::BadRequest("No file provided".to_string()))?;
-    let filename =
-        filename.ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?;
-    let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    if parts.is_empty() {
+        return Err(AppError::BadRequest("No file provided".to_string()));
+    }
 
-    if data.is_empty() {
-        return Err(AppError::BadRequest("File is empty".to_string()));
+    // Every record goes in one transaction; if any fails, the files already written are removed
+    let mut tx = mm
+        .db()
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to create file record: {}", e)))?;
+    let mut stored_paths = Vec::with_capacity(parts.len());
+    let mut uploaded = Vec::with_capacity(parts.len());
+    let mut result = Ok(());
+    for part in parts {
+        match store_upload(&ctx, &mut tx, contract_id, part, &mut stored_paths).await {
+            Ok(file) => uploaded.push(file),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    if result.is_ok() {
+        result = tx
+            .commit()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to create file record: {}", e)));
+    }
+    if let Err(e) = result {
+        for path in &stored_paths {
+            let _ = std::fs::remove_file(path);
+        }
+        return Err(e);
     }
 
-    // Validate file size (10MB limit)
-    const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
-    if data.len() > MAX_FILE_SIZE {
-        return Err(AppError::BadRequest(
-            "File too large (max 10MB)".to_string(),
-        ));
+    for file in &uploaded {
+        tracing::info!("File uploaded by user {}: {} ({} bytes)", ctx.user_id(), file.filename, file.size);
     }
+    Ok(Json(uploaded))
+}
+
+/// Write one part to disk (recording its path in `stored_paths`) and insert its record
+async fn store_upload(
+    ctx: &Ctx,
+    conn: &mut PgConnection,
+    contract_id: i64,
+    part: UploadedPart,
+    stored_paths: &mut Vec<String>,
+) -> Result<UploadFileResponse, AppError> {
+    let UploadedPart { filename, content_type, data } = part;
 
     // Determine file extension
     let ext = std::path::Path::new(&filename)
@@ -87,36 +231,30 @@ pub async fn upload_file(
     let file_path = format!("storage/contracts/{}", stored_filename);
 
     // Store file on disk
-    files::store_contract_file(&data, &stored_filename)
+    let stored_path = files::store_contract_file(&data, &stored_filename)
         .map_err(|e| AppError::InternalError(format!("Failed to store file: {}", e)))?;
+    stored_paths.push(stored_path);
 
     // Create database record
     let file_data = FileForCreate {
-        contract_id: 0, // Will be set by client or update later
+        contract_id,
         filename: filename.clone(),
         file_path: file_path.clone(),
         file_size: data.len() as i64,
         mime_type: Some(content_type.clone()),
     };
 
-    let file_id = FileBmc::create(&ctx, mm.db(), file_data)
+    let file_id = FileBmc::create(ctx, conn, file_data)
         .await
         .map_err(|e| AppError::InternalError(format!("Failed to create file record: {}", e)))?;
 
-    tracing::info!(
-        "File uploaded by user {}: {} ({} bytes)",
-        ctx.user_id(),
-        filename,
-        data.len()
-    );
-
-    Ok(Json(UploadFileResponse {
+    Ok(UploadFileResponse {
         file_id: file_id.to_string(),
         filename,
         content_type,
         size: data.len(),
         hash: String::new(), // Can add hash if needed
-    }))
+    })
 }
 
 pub async fn get_file(
@@ -124,8 +262,24 @@ pub async fn get_file(
     State(mm): State<ModelManager>,
     Path(file_id): Path<i64>,
 ) -> Result<Response, AppError> {
+    let (headers, file_data) = load_file(&ctx, &mm, file_id).await?;
+    Ok((StatusCode::OK, headers, file_data).into_response())
+}
+
+/// `GET /files/:id` without the body: same headers, including the full `Content-Length`
+pub async fn head_file(
+    ctx: Ctx,
+    State(mm): State<ModelManager>,
+    Path(file_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let (headers, _) = load_file(&ctx, &mm, file_id).await?;
+    Ok((StatusCode::OK, headers).into_response())
+}
+
+/// Read a file the caller owns, with the response headers describing it
+async fn load_file(ctx: &Ctx, mm: &ModelManager, file_id: i64) -> Result<(HeaderMap, Vec<u8>), AppError> {
     // Get file record from database
-    let file = FileBmc::get(&ctx, mm.db(), file_id)
+    let file = FileBmc::get(ctx, mm.db(), file_id)
         .await
         .map_err(|_| AppError::BadRequest("File not found".to_string()))?;
 
@@ -133,24 +287,111 @@ pub async fn get_file(
     let file_data = files::load_contract_file(&file.file_path)
         .map_err(|_| AppError::BadRequest("File not found on disk".to_string()))?;
 
-    let content_type = file
-        .mime_type
-        .unwrap_or_else(|| "application/octet-stream".to_string());
+    Ok((file_headers(&file, &file_data), file_data))
+}
+
+/// Content type, disposition, length and a content-hash `ETag` for a file
+fn file_headers(file: &ContractFile, data: &[u8]) -> HeaderMap {
+    let content_type = file.mime_type.as_deref().unwrap_or("application/octet-stream");
+    let disposition = format!("inline; filename=\"{}\"", file.filename);
+    let etag = format!("\"{}\"", hash_bytes(data));
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+        (header::CONTENT_LENGTH, data.len().to_string()),
+        (header::ETAG, etag),
+    ] {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// Bytes buffered between the zip writer task and the response body
+const ZIP_STREAM_BUFFER: usize = 64 * 1024;
+
+/// Download every file of a contract the caller owns as one zip, streamed as it is built
+pub async fn download_contract_zip(
+    ctx: Ctx,
+    State(mm): State<ModelManager>,
+    Path(contract_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let contract_files = FileBmc::list_by_contract(&ctx, mm.db(), contract_id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::BadRequest("Contract not found".to_string()),
+            e => AppError::InternalError(format!("Failed to list contract files: {}", e)),
+        })?;
+
+    let (writer, reader) = tokio::io::duplex(ZIP_STREAM_BUFFER);
+    tokio::spawn(async move {
+        // A failure here truncates the stream; the client sees a broken download
+        if let Err(e) = write_contract_zip(&contract_files, writer).await {
+            tracing::error!("Failed to stream zip for contract {}: {}", contract_id, e);
+        }
+    });
 
     Ok((
         StatusCode::OK,
         [
-            ("Content-Type".to_string(), content_type),
+            ("Content-Type".to_string(), "application/zip".to_string()),
             (
                 "Content-Disposition".to_string(),
-                format!("inline; filename=\"{}\"", file.filename),
+                format!("attachment; filename=\"contract-{}.zip\"", contract_id),
             ),
         ],
-        file_data,
+        Body::from_stream(ReaderStream::new(reader)),
     )
         .into_response())
 }
 
+/// Write `contract_files` into a zip on `writer`, one entry per file named by its original filename
+async fn write_contract_zip<W>(
+    contract_files: &[ContractFile],
+    writer: W,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut zip = ZipFileWriter::with_tokio(writer);
+    let mut used_names = HashSet::new();
+
+    for file in contract_files {
+        let name = zip_entry_name(&file.filename, &mut used_names);
+        let entry = ZipEntryBuilder::new(name.into(), Compression::Deflate);
+        let mut source = tokio::fs::File::open(&file.file_path).await?;
+        let mut entry_writer = zip.write_entry_stream(entry).await?.compat_write();
+        tokio::io::copy(&mut source, &mut entry_writer).await?;
+        entry_writer.into_inner().close().await?;
+    }
+
+    zip.close().await?;
+    Ok(())
+}
+
+/// Entry name for an uploaded filename: directory parts dropped, duplicates numbered
+fn zip_entry_name(filename: &str, used_names: &mut HashSet<String>) -> String {
+    let base = std::path::Path::new(filename)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("file");
+
+    let mut name = base.to_string();
+    let mut n = 1;
+    while !used_names.insert(name.clone()) {
+        n += 1;
+        name = match base.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => format!("{} ({}).{}", stem, n, ext),
+            _ => format!("{} ({})", base, n),
+        };
+    }
+    name
+}
+
 pub async fn list_files(
     _ctx: Ctx,
     State(_mm): State<ModelManager>,
@@ -184,3 +425,272 @@ pub async fn delete_file(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_zip::base::read::mem::ZipFileReader;
+
+    fn contract_file(id: i64, filename: &str, file_path: &std::path::Path) -> ContractFile {
+        ContractFile {
+            id,
+            contract_id: 1,
+            user_id: 1,
+            filename: filename.to_string(),
+            file_path: file_path.to_string_lossy().to_string(),
+            file_size: 0,
+            mime_type: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zip_contains_every_contract_file() {
+        let dir = std::env::temp_dir().join(format!("dtrex-zip-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.pdf"), b"terms of the deal").unwrap();
+        std::fs::write(dir.join("b.png"), vec![7u8; 100_000]).unwrap();
+        let contract_files = vec![
+            contract_file(1, "terms.pdf", &dir.join("a.pdf")),
+            contract_file(2, "photo.png", &dir.join("b.png")),
+        ];
+
+        let mut zip_bytes = Vec::new();
+        write_contract_zip(&contract_files, &mut zip_bytes).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let reader = ZipFileReader::new(zip_bytes).await.unwrap();
+        let names: Vec<String> = reader
+            .file()
+            .entries()
+            .iter()
+            .map(|e| e.filename().as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["terms.pdf", "photo.png"]);
+
+        let mut contents = Vec::new();
+        let mut entry = reader.reader_with_entry(1).await.unwrap();
+        entry.read_to_end_checked(&mut contents).await.unwrap();
+        assert_eq!(contents, vec![7u8; 100_000]);
+    }
+
+    #[test]
+    fn test_zip_entry_names_are_flat_and_unique() {
+        let mut used = HashSet::new();
+        assert_eq!(zip_entry_name("../../etc/passwd", &mut used), "passwd");
+        assert_eq!(zip_entry_name("deal.pdf", &mut used), "deal.pdf");
+        assert_eq!(zip_entry_name("deal.pdf", &mut used), "deal (2).pdf");
+        assert_eq!(zip_entry_name("..", &mut used), "file");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_head_matches_get_without_body() {
+        use crate::model::ContractBmc;
+
+        let (mm, alice, _bob) = crate::_dev_utils::init_test_with_users().await;
+        let contract_id = ContractBmc::create(&alice, &mm, crate::_dev_utils::contract("Lease")).await.unwrap();
+        let path = std::env::temp_dir().join(format!("dtrex-head-{}.pdf", Uuid::new_v4()));
+        std::fs::write(&path, vec![9u8; 12_345]).unwrap();
+        let file_c = FileForCreate {
+            contract_id,
+            filename: "lease.pdf".to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            file_size: 12_345,
+            mime_type: Some("application/pdf".to_string()),
+        };
+        let file_id = FileBmc::create(&alice, &mut mm.db().acquire().await.unwrap(), file_c).await.unwrap();
+
+        let app = Router::new()
+            .route("/files/:id", get(get_file).head(head_file))
+            .layer(axum::Extension(alice))
+            .with_state(mm);
+        let base = crate::_dev_utils::spawn_mock_node(app).await;
+        let client = reqwest::Client::new();
+        let url = format!("{}/files/{}", base, file_id);
+
+        let got = client.get(&url).send().await.unwrap();
+        let head = client.head(&url).send().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(head.status(), reqwest::StatusCode::OK);
+        for name in ["content-type", "content-length", "etag", "content-disposition"] {
+            assert_eq!(head.headers().get(name), got.headers().get(name), "{}", name);
+        }
+        assert_eq!(head.headers()["content-length"], "12345");
+        assert_eq!(head.headers()["content-type"], "application/pdf");
+        assert!(head.bytes().await.unwrap().is_empty());
+        assert_eq!(got.bytes().await.unwrap().len(), 12_345);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_zip_download_error_codes() {
+        use crate::model::ContractBmc;
+
+        let (mm, alice, bob) = crate::_dev_utils::init_test_with_users().await;
+        let contract_id = ContractBmc::create(&alice, &mm, crate::_dev_utils::contract("Zip")).await.unwrap();
+        let app = Router::new()
+            .route("/contracts/:id/files.zip", get(download_contract_zip))
+            .layer(axum::Extension(bob))
+            .with_state(mm.clone());
+        let url = format!("{}/contracts/{}/files.zip", crate::_dev_utils::spawn_mock_node(app).await, contract_id);
+
+        // Someone else's contract is not found
+        let res = reqwest::get(&url).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert_eq!(res.text().await.unwrap(), "Contract not found");
+
+        // A database failure is a server error, not a missing contract
+        mm.db().close().await;
+        let res = reqwest::get(&url).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.text().await.unwrap().starts_with("Failed to list contract files"));
+    }
+
+    /// A multipart/form-data body with boundary `x`
+    fn multipart_body(contract_id: i64, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut body = format!(
+            "--x\r\nContent-Disposition: form-data; name=\"contract_id\"\r\n\r\n{}\r\n",
+            contract_id
+        )
+        .into_bytes();
+        for (name, data) in files {
+            body.extend(
+                format!(
+                    "--x\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+                     Content-Type: text/plain\r\n\r\n",
+                    name
+                )
+                .bytes(),
+            );
+            body.extend_from_slice(data);
+            body.extend(b"\r\n");
+        }
+        body.extend(b"--x--\r\n");
+        body
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_upload_several_files_in_one_request() {
+        use crate::model::ContractBmc;
+
+        let (mm, alice, _bob) = crate::_dev_utils::init_test_with_users().await;
+        let contract_id = ContractBmc::create(&alice, &mm, crate::_dev_utils::contract("Bundle")).await.unwrap();
+        let app = Router::new()
+            .route("/files", axum::routing::post(upload_file))
+            .layer(axum::Extension(alice))
+            .with_state(mm.clone());
+        let base = crate::_dev_utils::spawn_mock_node(app).await;
+        let upload = |body: Vec<u8>| {
+            reqwest::Client::new()
+                .post(format!("{}/files", base))
+                .header("Content-Type", "multipart/form-data; boundary=x")
+                .body(body)
+                .send()
+        };
+        let records = || async {
+            sqlx::query_as::<_, (String,)>("SELECT file_path FROM contract_files WHERE contract_id = $1")
+                .bind(contract_id)
+                .fetch_all(mm.db())
+                .await
+                .unwrap()
+        };
+
+        // One empty file rejects the whole batch before anything is stored
+        let bad = multipart_body(contract_id, &[("a.txt", b"first"), ("empty.txt", b"")]);
+        let res = upload(bad).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert!(records().await.is_empty());
+
+        // A record failing mid-batch (name too long for its column) rolls back the rest, on disk too
+        let marker: &[u8] = b"rolled back with its batch";
+        let long_name = format!("{}.txt", "n".repeat(300));
+        let res = upload(multipart_body(contract_id, &[("a.txt", marker), (&long_name, b"second")])).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(records().await.is_empty());
+        let leftovers = std::fs::read_dir("storage/contracts")
+            .unwrap()
+            .filter(|entry| std::fs::read(entry.as_ref().unwrap().path()).is_ok_and(|data| data == marker))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let files: [(&str, &[u8]); 3] = [("a.txt", b"first"), ("b.txt", b"second!"), ("c.txt", b"third")];
+        let res = upload(multipart_body(contract_id, &files)).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        let uploaded: serde_json::Value = res.json().await.unwrap();
+        let uploaded = uploaded.as_array().unwrap();
+        let names: Vec<&str> = uploaded.iter().map(|f| f["filename"].as_str().unwrap()).collect();
+        assert_eq!(names, ["a.txt", "b.txt", "c.txt"]);
+        assert_eq!(uploaded[1]["size"], 7);
+
+        let stored = records().await;
+        for (path,) in &stored {
+            std::fs::remove_file(path).unwrap();
+        }
+        assert_eq!(stored.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_upload_limit_and_cors_scoped_to_file_routes() {
+        use crate::app_state::AppState;
+        use std::sync::Arc;
+
+        // None of these requests reach the database
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .unwrap();
+        let mm = ModelManager::new(db);
+        let config = FileRoutesConfig {
+            upload_max_bytes: 1024,
+            cors_origins: vec!["https://app.example".to_string()],
+        };
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let body_max_bytes = 3 * 1024 * 1024;
+        let app = crate::app(mm, state, crate::api::metrics::InFlight::new(), &config, body_max_bytes);
+        let base = crate::_dev_utils::spawn_mock_node(app).await;
+        let client = reqwest::Client::new();
+
+        // 4 KiB is over the file limit...
+        let res = client
+            .post(format!("{}/files", base))
+            .header("Content-Type", "multipart/form-data; boundary=x")
+            .body(vec![b'a'; 4096])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+        // ...but the other routes take bodies up to their own limit, past axum's 2 MB default
+        let rpc_call = |pad: usize| serde_json::json!({ "id": 1, "method": "user_me", "params": { "pad": "a".repeat(pad) } });
+        let res = client.post(format!("{}/api/rpc", base)).json(&rpc_call(2_500_000)).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        let res: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(res["error"]["code"], 4001);
+        let res = client.post(format!("{}/api/rpc", base)).json(&rpc_call(body_max_bytes)).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+        let res = client
+            .post(format!("{}/ssl/upload", base))
+            .header("Content-Type", "multipart/form-data; boundary=x")
+            .body(vec![b'a'; body_max_bytes + 1])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+        // File routes only answer preflights from configured origins; RPC stays permissive
+        let preflight = |path: &str, origin: &str| {
+            client
+                .request(reqwest::Method::OPTIONS, format!("{}{}", base, path))
+                .header("Origin", origin)
+                .header("Access-Control-Request-Method", "POST")
+                .send()
+        };
+        let allowed = preflight("/files", "https://app.example").await.unwrap();
+        assert_eq!(allowed.headers()["access-control-allow-origin"], "https://app.example");
+        let denied = preflight("/files", "https://evil.example").await.unwrap();
+        assert!(denied.headers().get("access-control-allow-origin").is_none());
+        let rpc = preflight("/api/rpc", "https://evil.example").await.unwrap();
+        assert_eq!(rpc.headers()["access-control-allow-origin"], "*");
+    }
+}
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Extension, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::verify::verifier_health;
+use crate::app_state::AppState;
+use crate::model::{ModelManager, SettingsBmc};
+
+/// How long the readiness probe waits for the database
+const DB_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Liveness probe - 200 whenever the process is running
+pub async fn livez() -> &'static str {
+    "OK"
+}
+
+/// Readiness probe - 200 only if the database answers and required config is present,
+/// otherwise 503 with the failing checks. `/health` is an alias of this.
+/// The verifier is reported but does not gate readiness: a stalled verifier
+/// delays confirmations, it does not stop the API from serving.
+pub async fn readyz(State(mm): State<ModelManager>, Extension(app_state): Extension<Arc<AppState>>) -> Response {
+    let database = match tokio::time::timeout(DB_CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(mm.db())).await {
+        Ok(Ok(_)) => "ok".to_string(),
+        Ok(Err(e)) => format!("error: {}", e),
+        Err(_) => "error: timed out".to_string(),
+    };
+    let token_secret = match std::env::var("TOKEN_SECRET") {
+        Ok(v) if !v.is_empty() => "ok".to_string(),
+        _ => "error: TOKEN_SECRET not set".to_string(),
+    };
+
+    let (verifier_status, verifier_ok) = verifier_health(&app_state).await;
+    let verifier = if verifier_ok {
+        "ok".to_string()
+    } else {
+        format!("stalled: last run {:?}", verifier_status.last_run_at.map(|t| t.to_rfc3339()))
+    };
+
+    // Maintenance is reported but does not gate readiness: reads keep being served
+    let maintenance = database == "ok"
+        && SettingsBmc::get(&mm).await.map(|s| s.maintenance_mode).unwrap_or(false);
+
+    let ready = database == "ok" && token_secret == "ok";
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    let mut headers = HeaderMap::new();
+    if std::env::var("CHIA_ALLOW_INSECURE").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false) {
+        headers.insert("X-Chia-Insecure", "true".parse().unwrap());
+    }
+
+    let body = json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "checks": { "database": database, "token_secret": token_secret, "verifier": verifier },
+        "maintenance": maintenance,
+    });
+
+    (status, headers, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn app_state() -> Extension<Arc<AppState>> {
+        Extension(Arc::new(AppState::new("http://localhost:8555".to_string())))
+    }
+
+    #[tokio::test]
+    async fn test_livez_ok() {
+        assert_eq!(livez().await, "OK");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_db_down() {
+        // Nothing listens on port 1, so the pool can never connect
+        let db = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(500))
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .unwrap();
+
+        let res = readyz(State(ModelManager::new(db)), app_state()).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_readyz_db_up() {
+        _dev_utils::ensure_token_secret();
+        let mm = _dev_utils::init_test().await;
+
+        let res = readyz(State(mm), app_state()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
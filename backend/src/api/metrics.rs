@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Extension, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::verify::verifier_health;
+use crate::app_state::AppState;
+
+/// Shared counter of requests currently being handled
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no requests are in flight, giving up after `timeout`.
+    /// Returns true if everything drained.
+    pub async fn wait_for_drain(&self, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.count() > 0 {
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+        true
+    }
+}
+
+/// Decrements the counter when dropped, so cancelled or panicking requests are released too
+struct InFlightGuard(InFlight);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        (self.0).0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// IN-FLIGHT middleware - counts requests for the duration of the handler
+pub async fn mw_track_in_flight(
+    State(in_flight): State<InFlight>,
+    req: Request,
+    next: Next,
+) -> Response {
+    in_flight.0.fetch_add(1, Ordering::SeqCst);
+    let _guard = InFlightGuard(in_flight);
+    next.run(req).await
+}
+
+/// Prometheus-style text metrics. The scrape itself counts as one in-flight request.
+pub async fn metrics(State(in_flight): State<InFlight>, Extension(app_state): Extension<Arc<AppState>>) -> Response {
+    let (verifier, verifier_ok) = verifier_health(&app_state).await;
+    let body = format!(
+        "# HELP dtrex_http_requests_in_flight Requests currently being handled\n\
+         # TYPE dtrex_http_requests_in_flight gauge\n\
+         dtrex_http_requests_in_flight {}\n\
+         # HELP dtrex_verifier_healthy 1 if the transaction verifier ran within 3x its interval\n\
+         # TYPE dtrex_verifier_healthy gauge\n\
+         dtrex_verifier_healthy {}\n\
+         # HELP dtrex_verifier_last_run_timestamp_seconds Unix time of the verifier's last run\n\
+         # TYPE dtrex_verifier_last_run_timestamp_seconds gauge\n\
+         dtrex_verifier_last_run_timestamp_seconds {}\n\
+         # HELP dtrex_verifier_last_processed Pending transactions processed by the last run\n\
+         # TYPE dtrex_verifier_last_processed gauge\n\
+         dtrex_verifier_last_processed {}\n",
+        in_flight.count(),
+        verifier_ok as u8,
+        verifier.last_run_at.map(|t| t.timestamp()).unwrap_or(0),
+        verifier.last_processed,
+    );
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
@@ -2,11 +2,16 @@ pub mod auth;
 pub mod chia;
 pub mod contacts;
 pub mod contracts;
+pub mod events;
 pub mod files;
+pub mod health;
+pub mod metrics;
 pub mod mw_auth;
+pub mod mw_panic;
 pub mod rpc;
+pub mod shutdown;
 pub mod signing;
 pub mod ssl;
 pub mod wallet_rpc;
-pub mod node_rpc;
+pub mod retention;
 pub mod verify;
@@ -25,18 +25,25 @@ pub async fn mw_ctx_resolve(
 
     // If token exists, validate and create Ctx
     if let Some(token) = token {
-        if let Ok(user_id) = validate_token(token) {
-            // Get user from database
-            if let Ok(user) = UserBmc::first_by_id_for_auth(mm.db(), user_id).await {
-                let ctx = Ctx::new_with_admin(user.id, user.username, user.is_admin);
-                req.extensions_mut().insert(ctx);
-            }
+        if let Some(ctx) = resolve_ctx(&mm, token).await {
+            req.extensions_mut().insert(ctx);
         }
     }
 
     next.run(req).await
 }
 
+/// Ctx for a bearer token, if it is valid and its salt is still the user's
+/// current one (rotating the salt revokes older tokens)
+pub async fn resolve_ctx(mm: &ModelManager, token: &str) -> Option<Ctx> {
+    let claims = validate_token(token).ok()?;
+    let user = UserBmc::first_by_id_for_auth(mm.db(), claims.user_id).await.ok()?;
+    if claims.token_salt != user.token_salt.to_string() {
+        return None;
+    }
+    Some(Ctx::new_with_admin(user.id, user.username, user.is_admin))
+}
+
 /// AUTH-REQUIRE middleware - requires Ctx to be present
 /// Returns 401 if no Ctx found
 pub async fn mw_ctx_require(
@@ -67,3 +74,36 @@ where
             .ok_or(StatusCode::UNAUTHORIZED)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use serde_json::json;
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_rotated_salt_rejects_old_tokens() {
+        _dev_utils::ensure_token_secret();
+        let mm = _dev_utils::init_test().await;
+        let creds = json!({ "username": "dana", "pwd": "welcome123" });
+        crate::api::auth::rpc_register(mm.clone(), Some(creds.clone())).await.unwrap();
+        let login = |creds| crate::api::auth::rpc_login(mm.clone(), Some(creds));
+
+        let old = login(creds.clone()).await.unwrap()["token"].as_str().unwrap().to_string();
+        let ctx = resolve_ctx(&mm, &old).await.expect("fresh token resolves");
+        assert_eq!(ctx.username(), "dana");
+
+        // Logging out rotates the salt: the old token no longer resolves
+        crate::api::auth::rpc_logout(mm.clone(), ctx.clone()).await.unwrap();
+        assert!(resolve_ctx(&mm, &old).await.is_none());
+        // The signature alone still checks out; only the salt check rejects it
+        assert!(validate_token(&old).is_ok());
+
+        // A new login works, until the salt is rotated again
+        let new = login(creds).await.unwrap()["token"].as_str().unwrap().to_string();
+        assert!(resolve_ctx(&mm, &new).await.is_some());
+        UserBmc::rotate_token_salt(mm.db(), ctx.user_id()).await.unwrap();
+        assert!(resolve_ctx(&mm, &new).await.is_none());
+    }
+}
@@ -0,0 +1,103 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::any::Any;
+use tower_http::catch_panic::CatchPanicLayer;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The request's id, in request extensions for handlers that report it (see `mw_request_id`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    /// Id of the request being handled, for log lines that have no access to the request
+    static REQUEST_ID: String;
+}
+
+/// REQUEST-ID middleware - reuses the caller's `x-request-id` or assigns a new one,
+/// and echoes it on the response. The id is also put in request extensions, and every
+/// log line written while handling the request carries it through a `request` span.
+pub async fn mw_request_id(mut req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(request_id.clone()));
+    let span = tracing::info_span!("request", request_id = %request_id);
+    let mut res = REQUEST_ID.scope(request_id.clone(), next.run(req)).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    res
+}
+
+/// Turns a handler panic into a JSON 500 instead of dropping the connection.
+/// Must sit inside `mw_request_id` so the panic is logged with the request id.
+pub fn catch_panic_layer() -> CatchPanicLayer<fn(Box<dyn Any + Send + 'static>) -> Response<Body>> {
+    CatchPanicLayer::custom(panic_response as fn(Box<dyn Any + Send + 'static>) -> Response<Body>)
+}
+
+fn panic_response(err: Box<dyn Any + Send + 'static>) -> Response<Body> {
+    let message = err
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| err.downcast_ref::<&str>().copied())
+        .unwrap_or("<non-string panic payload>");
+    let request_id = REQUEST_ID.try_with(Clone::clone).ok();
+    tracing::error!(
+        "Handler panicked (request_id={}): {}",
+        request_id.as_deref().unwrap_or("-"),
+        message
+    );
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": "Internal server error", "request_id": request_id })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+
+    #[tokio::test]
+    async fn test_panicking_handler_returns_json_500() {
+        async fn boom() -> &'static str {
+            panic!("handler exploded")
+        }
+        let app = Router::new()
+            .route("/boom", get(boom))
+            .route("/ok", get(|| async { "fine" }))
+            .layer(catch_panic_layer())
+            .layer(middleware::from_fn(mw_request_id));
+        let base = crate::_dev_utils::spawn_mock_node(app).await;
+        let client = reqwest::Client::new();
+
+        let res = client.get(format!("{}/boom", base)).header(REQUEST_ID_HEADER, "req-42").send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()[REQUEST_ID_HEADER], "req-42");
+        assert_eq!(res.headers()["content-type"], "application/json");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body, json!({ "error": "Internal server error", "request_id": "req-42" }));
+
+        // The server keeps serving after the panic, and assigns ids when none are sent
+        let res = client.get(format!("{}/ok", base)).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        assert!(Uuid::parse_str(res.headers()[REQUEST_ID_HEADER].to_str().unwrap()).is_ok());
+    }
+}
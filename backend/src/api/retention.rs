@@ -0,0 +1,145 @@
+//! Opt-in retention sweep, run by the verification service on its slow cadence.
+//!
+//! Hard-deletes failed transactions and read notifications past their windows, in bounded batches so one sweep never holds long locks.
+//! Trades are deleted outright (`trade_delete`), so there are no soft-deleted trades to prune.
+
+use crate::error::Result;
+use crate::model::{ModelManager, NotificationBmc, TransactionBmc};
+
+const DEFAULT_FAILED_TX_DAYS: i64 = 90;
+const DEFAULT_READ_NOTIFICATION_DAYS: i64 = 30;
+const DEFAULT_BATCH_SIZE: i64 = 500;
+
+/// Retention windows (`RETENTION_FAILED_TX_DAYS`, `RETENTION_READ_NOTIFICATION_DAYS`)
+/// and rows per DELETE (`RETENTION_BATCH_SIZE`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionConfig {
+    pub failed_tx_days: i64,
+    pub read_notification_days: i64,
+    pub batch_size: i64,
+}
+
+/// Rows removed by one sweep
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RetentionReport {
+    pub failed_transactions: u64,
+    pub read_notifications: u64,
+}
+
+impl RetentionConfig {
+    /// `None` (no pruning) unless `RETENTION_ENABLED` is `1` or `true`.
+    /// Windows and batch size must be at least 1; anything else uses the default.
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("RETENTION_ENABLED")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let env = |key: &str, default: i64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|n| *n >= 1)
+                .unwrap_or(default)
+        };
+        Some(Self {
+            failed_tx_days: env("RETENTION_FAILED_TX_DAYS", DEFAULT_FAILED_TX_DAYS),
+            read_notification_days: env("RETENTION_READ_NOTIFICATION_DAYS", DEFAULT_READ_NOTIFICATION_DAYS),
+            batch_size: env("RETENTION_BATCH_SIZE", DEFAULT_BATCH_SIZE),
+        })
+    }
+}
+
+/// Prune everything past its window, one batch at a time until a batch comes back short
+pub async fn run_retention_sweep(mm: &ModelManager, config: &RetentionConfig) -> Result<RetentionReport> {
+    let mut report = RetentionReport::default();
+    loop {
+        let deleted = TransactionBmc::prune_failed(mm, config.failed_tx_days, config.batch_size).await?;
+        report.failed_transactions += deleted;
+        if deleted < config.batch_size as u64 {
+            break;
+        }
+    }
+    loop {
+        let deleted = NotificationBmc::prune_read(mm, config.read_notification_days, config.batch_size).await?;
+        report.read_notifications += deleted;
+        if deleted < config.batch_size as u64 {
+            break;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, commitment_fee, proposal_with_value};
+    use crate::model::TradeBmc;
+
+    async fn exists(mm: &ModelManager, table: &str, id: i64) -> bool {
+        sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = $1)", table))
+            .bind(id)
+            .fetch_one(mm.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_sweep_prunes_only_old_rows() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(80.0)).await.unwrap();
+
+        // Failed 100 days ago (archived by a retry and not), failed 5 days ago, and pending since 100 days ago
+        let mut txs = Vec::new();
+        for (status, age, archived) in
+            [("failed", 100, true), ("failed", 100, false), ("failed", 5, false), ("pending", 100, false)]
+        {
+            let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+            sqlx::query(
+                "UPDATE trade_transactions SET status = $2, created_at = NOW() - make_interval(days => $3),
+                 archived_at = CASE WHEN $4 THEN NOW() END WHERE id = $1",
+            )
+            .bind(id)
+            .bind(status)
+            .bind(age)
+            .bind(archived)
+            .execute(mm.pool())
+            .await
+            .unwrap();
+            txs.push(id);
+        }
+
+        // Notifications read 40 days ago, read 2 days ago, and unread since 40 days ago
+        let mut notes = Vec::new();
+        for read in [Some(40), Some(2), None] {
+            let id = NotificationBmc::create(&mm, alice.user_id(), "test", None, "retention").await.unwrap();
+            sqlx::query(
+                "UPDATE notifications SET created_at = NOW() - INTERVAL '40 days',
+                 read_at = NOW() - make_interval(days => $2) WHERE id = $1",
+            )
+            .bind(id)
+            .bind(read)
+            .execute(mm.pool())
+            .await
+            .unwrap();
+            notes.push(id);
+        }
+
+        // Batches of one still get through everything
+        let config = RetentionConfig { failed_tx_days: 90, read_notification_days: 30, batch_size: 1 };
+        let report = run_retention_sweep(&mm, &config).await.unwrap();
+        assert_eq!(report, RetentionReport { failed_transactions: 2, read_notifications: 1 });
+
+        let remaining: Vec<i64> = sqlx::query_scalar("SELECT id FROM trade_transactions WHERE trade_id = $1 ORDER BY id")
+            .bind(trade_id)
+            .fetch_all(mm.pool())
+            .await
+            .unwrap();
+        assert_eq!(remaining, txs[2..]);
+        assert!(!exists(&mm, "notifications", notes[0]).await);
+        assert!(exists(&mm, "notifications", notes[1]).await);
+        assert!(exists(&mm, "notifications", notes[2]).await);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::ctx::OptionCtx;
 use axum::extract::State;
+use axum::Extension;
 use axum::{response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -7,11 +8,17 @@ use std::sync::Arc;
 
 use crate::ctx::Ctx;
 use crate::model::{
-    ContractBmc, ContractForCreate, ContractForUpdate, ModelManager,
-    TradeBmc, TradeForCreate, TradeAcceptParams, ReviewBmc, ReviewForCreate,
-    TransactionBmc, TradeTransactionForCreate, UserBmc,
+    commitment_memo, ActivityBmc, AuditBmc, NotificationBmc, ExchangeSettingsForUpdate, SettingsBmc, ContactBmc, ContactForCreate, ContractBmc, ContractForCreate, ContractForUpdate, ModelManager,
+    TradeBmc, TradeEventBmc, TradeForCreate, TradeForUpdate, TradeAcceptParams, TradeCounterParams, TradeSearchCriteria, TradeSort, ReviewBmc, ReviewForCreate, ReputationAdjustment,
+    TransactionBmc, TransactionListFilter, WishlistItem, FeeRounding, MOJOS_PER_XCH, TradeTransactionForCreate, UserBmc, UserPublicInfo,
 };
+use crate::api::mw_panic::RequestId;
 use crate::app_state::AppState;
+use crate::error::Error;
+use crate::rpc::client::ChiaRpcClient;
+use crate::rpc::ChiaError;
+use crate::util::address::{decode_puzzle_hash, validate_xch_address};
+use crate::util::price::XchPriceProvider;
 
 #[derive(Deserialize)]
 pub struct RpcRequest {
@@ -29,7 +36,7 @@ pub struct RpcResponse {
     pub error: Option<RpcError>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Debug, Serialize, Clone)]
 pub struct RpcError {
     pub code: i32,
     pub message: String,
@@ -37,6 +44,51 @@ pub struct RpcError {
     pub data: Option<Value>,
 }
 
+/// Node/wallet failures keep their kind: 5001 rejected by node, 5002 unreachable,
+/// 5003 not configured, 5004 timeout, 5000 unexpected response
+impl From<ChiaError> for RpcError {
+    fn from(e: ChiaError) -> Self {
+        let (code, kind, status) = match &e {
+            ChiaError::NodeRejected { status, .. } => (5001, "node_rejected", Some(*status)),
+            ChiaError::Network(_) => (5002, "network", None),
+            ChiaError::NotConfigured(_) => (5003, "not_configured", None),
+            ChiaError::Timeout => (5004, "timeout", None),
+            ChiaError::Parse(_) => (5000, "parse", None),
+        };
+        RpcError {
+            code,
+            message: e.to_string(),
+            data: Some(json!({ "kind": kind, "status": status })),
+        }
+    }
+}
+
+/// Map a model error to its RPC code (auth -> 4001, missing -> 4004, stale `expected_version` -> 4009,
+/// rejected input or state -> 4000, trading paused -> 4006, anything else -> 5000)
+impl From<Error> for RpcError {
+    fn from(e: Error) -> Self {
+        let (code, message) = match e {
+            Error::Auth(msg) => (4001, msg),
+            Error::NotFoundMsg(msg) => (4004, msg),
+            Error::NotFound => (4004, "Resource not found".to_string()),
+            Error::EntityNotFound { entity, id } => (4004, format!("{} with id {} not found", entity, id)),
+            Error::Conflict(msg) => (4009, msg),
+            Error::InvalidState(msg) => (4000, msg),
+            Error::BadRequest => (4000, "Bad request".to_string()),
+            Error::TradingPaused => (4006, "Trading paused".to_string()),
+            Error::Validation(errs) => {
+                return RpcError {
+                    code: 4000,
+                    message: format!("Validation failed: {}", errs),
+                    data: Some(json!({ "fields": errs.fields })),
+                };
+            }
+            e => (5000, e.to_string()),
+        };
+        RpcError { code, message, data: None }
+    }
+}
+
 #[derive(Clone)]
 pub struct RpcState(pub ModelManager, pub Arc<AppState>);
 
@@ -54,27 +106,111 @@ pub async fn rpc_handler(
     State(mm): State<ModelManager>,
     State(app_state): State<Arc<AppState>>,
     OptionCtx(ctx): OptionCtx,
-    Json(rpc_req): Json<RpcRequest>,
+    request_id: Option<Extension<RequestId>>,
+    Json(body): Json<Value>,
 ) -> impl IntoResponse {
+    let request_id = request_id.map(|Extension(RequestId(id))| id);
+    
+    // A JSON array is a batch: one response per entry, in order, sharing the caller's Ctx
+    let Value::Array(entries) = body else {
+        let rpc_response = match serde_json::from_value::<RpcRequest>(body) {
+            Ok(rpc_req) => dispatch(mm, app_state, ctx, rpc_req).await,
+            Err(e) => invalid_request(format!("Invalid request: {}", e)),
+        };
+        return Json(with_request_id(rpc_response, request_id.as_deref())).into_response();
+    };
+    
+    if entries.is_empty() {
+        let rpc_response = invalid_request("Empty batch".to_string());
+        return Json(with_request_id(rpc_response, request_id.as_deref())).into_response();
+    }
+    if entries.len() > MAX_BATCH_LEN {
+        let rpc_response = invalid_request(format!("Batch too large (max {} requests)", MAX_BATCH_LEN));
+        return Json(with_request_id(rpc_response, request_id.as_deref())).into_response();
+    }
+    
+    let mut responses = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let rpc_response = match serde_json::from_value::<RpcRequest>(entry) {
+            Ok(rpc_req) => dispatch(mm.clone(), app_state.clone(), ctx.clone(), rpc_req).await,
+            Err(e) => invalid_request(format!("Invalid request: {}", e)),
+        };
+        responses.push(with_request_id(rpc_response, request_id.as_deref()));
+    }
+    
+    Json(responses).into_response()
+}
+
+/// Put the request id into a failed response's `error.data`, so clients can quote it in bug reports
+fn with_request_id(mut rpc_response: RpcResponse, request_id: Option<&str>) -> RpcResponse {
+    if let (Some(error), Some(request_id)) = (rpc_response.error.as_mut(), request_id) {
+        match &mut error.data {
+            Some(Value::Object(data)) => {
+                data.insert("request_id".to_string(), json!(request_id));
+            }
+            None => error.data = Some(json!({ "request_id": request_id })),
+            // Leave non-object data as the method returned it
+            Some(_) => {}
+        }
+    }
+    rpc_response
+}
+
+/// Most requests accepted in one batch
+const MAX_BATCH_LEN: usize = 50;
+
+/// -32600 response for a body that is not a usable JSON-RPC request
+fn invalid_request(message: String) -> RpcResponse {
+    RpcResponse { id: None, result: None, error: Some(RpcError { code: -32600, message, data: None }) }
+}
+
+/// Run one JSON-RPC request. Errors are returned in the response, never raised,
+/// so one failing entry cannot abort a batch.
+async fn dispatch(mm: ModelManager, app_state: Arc<AppState>, ctx: Option<Ctx>, rpc_req: RpcRequest) -> RpcResponse {
     let rpc_id = rpc_req.id.clone();
     
+    // Operator-disabled methods look like unknown ones, whatever the caller's auth
+    if app_state.disabled_rpc_methods().contains(&rpc_req.method) {
+        let error = RpcError { code: -32601, message: "Method not found".to_string(), data: None };
+        return RpcResponse { id: rpc_id, result: None, error: Some(error) };
+    }
+    
+    if MAINTENANCE_BLOCKED_METHODS.contains(&rpc_req.method.as_str()) {
+        let maintenance = SettingsBmc::get(&mm).await.map(|s| s.maintenance_mode).unwrap_or(false);
+        if maintenance {
+            let error = RpcError { code: 4503, message: "Maintenance in progress".to_string(), data: None };
+            return RpcResponse { id: rpc_id, result: None, error: Some(error) };
+        }
+    }
+    
     let result = match rpc_req.method.as_str() {
         // ============================================
         // Authentication
         // ============================================
         "login" => crate::api::auth::rpc_login(mm, rpc_req.params).await,
-        "logout" => crate::api::auth::rpc_logout().await,
+        "logout" => {
+            if let Some(ctx) = ctx { crate::api::auth::rpc_logout(mm, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
         "register" => crate::api::auth::rpc_register(mm, rpc_req.params).await,
         "user_me" => {
             if let Some(ctx) = ctx { rpc_user_me(ctx).await }
             else { Err(unauthorized_error()) }
         }
+        "user_set_public_key" => {
+            if let Some(ctx) = ctx { rpc_user_set_public_key(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
 
         // ============================================
         // Trade Proposals (Public)
         // ============================================
         "trade_list_proposals" => rpc_trade_list_proposals(mm, rpc_req.params).await,
+        "trade_list_categories" => rpc_trade_list_categories(mm).await,
+        "trade_search" => rpc_trade_search(mm, rpc_req.params).await,
         "trade_get_public" => rpc_trade_get_public(mm, rpc_req.params).await,
+        "trade_statuses" => rpc_trade_statuses(mm, rpc_req.params).await,
+        "trade_list_images" => rpc_trade_list_images(mm, rpc_req.params).await,
 
         // ============================================
         // Trade Management (Authenticated)
@@ -87,18 +223,54 @@ pub async fn rpc_handler(
             if let Some(ctx) = ctx { rpc_trade_my_trades(mm, ctx).await }
             else { Err(unauthorized_error()) }
         }
+        "user_trade_counts" => {
+            if let Some(ctx) = ctx { rpc_user_trade_counts(mm, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
         "trade_get" => {
             if let Some(ctx) = ctx { rpc_trade_get(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "trade_timeline" => {
+            if let Some(ctx) = ctx { rpc_trade_timeline(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_update_proposal" => {
+            if let Some(ctx) = ctx { rpc_trade_update_proposal(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "trade_accept" => {
-            if let Some(ctx) = ctx { rpc_trade_accept(mm, ctx, rpc_req.params).await }
+            if let Some(ctx) = ctx { rpc_trade_accept(mm, app_state, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_counter_offer" => {
+            if let Some(ctx) = ctx { rpc_trade_counter_offer(mm, app_state, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_accept_offer" => {
+            if let Some(ctx) = ctx { rpc_trade_accept_offer(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_list_offers" => {
+            if let Some(ctx) = ctx { rpc_trade_list_offers(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_set_primary_image" => {
+            if let Some(ctx) = ctx { rpc_trade_set_primary_image(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "trade_reorder_images" => {
+            if let Some(ctx) = ctx { rpc_trade_reorder_images(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
         "trade_commit" => {
             if let Some(ctx) = ctx { rpc_trade_commit(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "trade_can_commit" => {
+            if let Some(ctx) = ctx { rpc_trade_can_commit(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "trade_add_tracking" => {
             if let Some(ctx) = ctx { rpc_trade_add_tracking(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
@@ -111,6 +283,10 @@ pub async fn rpc_handler(
             if let Some(ctx) = ctx { rpc_trade_cancel(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "trade_raise_dispute" => {
+            if let Some(ctx) = ctx { rpc_trade_raise_dispute(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "trade_delete" => {
             if let Some(ctx) = ctx { rpc_trade_delete(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
@@ -124,6 +300,11 @@ pub async fn rpc_handler(
             else { Err(unauthorized_error()) }
         }
         "user_reviews" => rpc_user_reviews(mm, rpc_req.params).await,
+        "user_authored_reviews" => {
+            if let Some(ctx) = ctx { rpc_user_authored_reviews(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "user_profile" => rpc_user_profile(mm, rpc_req.params).await,
 
         // ============================================
         // Commitment & Transactions
@@ -133,17 +314,33 @@ pub async fn rpc_handler(
             else { Err(unauthorized_error()) }
         }
         "commitment_create_pending" => {
-            if let Some(ctx) = ctx { rpc_commitment_create_pending(mm, ctx, rpc_req.params).await }
+            if let Some(ctx) = ctx { rpc_commitment_create_pending(mm, app_state, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
         "commitment_submit_tx" => {
             if let Some(ctx) = ctx { rpc_commitment_submit_tx(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "commitment_retry" => {
+            if let Some(ctx) = ctx { rpc_commitment_retry(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "commitment_cancel_pending" => {
+            if let Some(ctx) = ctx { rpc_commitment_cancel_pending(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "commitment_create_escrow_deposit" => {
+            if let Some(ctx) = ctx { rpc_commitment_create_escrow_deposit(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "commitment_list_transactions" => {
             if let Some(ctx) = ctx { rpc_commitment_list_transactions(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "affordability_check" => {
+            if let Some(ctx) = ctx { rpc_affordability_check(mm, app_state, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "config_set_exchange_wallet" => {
             if let Some(ctx) = ctx { rpc_config_set_exchange_wallet(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
@@ -152,12 +349,48 @@ pub async fn rpc_handler(
             if let Some(ctx) = ctx { rpc_config_get_exchange_wallet(mm, ctx).await }
             else { Err(unauthorized_error()) }
         }
+        "config_get_settings" => {
+            if let Some(ctx) = ctx { rpc_config_get_settings(mm, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
+        "config_update_settings" => {
+            if let Some(ctx) = ctx { rpc_config_update_settings(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_set_maintenance_mode" => {
+            if let Some(ctx) = ctx { rpc_admin_set_maintenance_mode(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+
+        // ============================================
+        // Contacts
+        // ============================================
+        "contact_list" => {
+            if let Some(ctx) = ctx { rpc_contact_list(mm, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
+        "contact_import" => {
+            if let Some(ctx) = ctx { rpc_contact_import(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+
+        // ============================================
+        // Notifications
+        // ============================================
+        "notifications_list" => {
+            if let Some(ctx) = ctx { rpc_notifications_list(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "notifications_mark_read" => {
+            if let Some(ctx) = ctx { rpc_notifications_mark_read(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
 
         // ============================================
         // User Administration (Admin only)
         // ============================================
         "admin_list_users" => {
-            if let Some(ctx) = ctx { rpc_admin_list_users(mm, ctx).await }
+            if let Some(ctx) = ctx { rpc_admin_list_users(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
         "admin_set_user_admin" => {
@@ -180,6 +413,46 @@ pub async fn rpc_handler(
             if let Some(ctx) = ctx { rpc_admin_cancel_trade(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
         }
+        "admin_feature_trade" => {
+            if let Some(ctx) = ctx { rpc_admin_feature_trade(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_waive_commitment_fee" => {
+            if let Some(ctx) = ctx { rpc_admin_waive_commitment_fee(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_release_escrow" => {
+            if let Some(ctx) = ctx { rpc_admin_release_escrow(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_resolve_dispute" => {
+            if let Some(ctx) = ctx { rpc_admin_resolve_dispute(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_set_trading_paused" => {
+            if let Some(ctx) = ctx { rpc_admin_set_trading_paused(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_adjust_reputation" => {
+            if let Some(ctx) = ctx { rpc_admin_adjust_reputation(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_activity_feed" => {
+            if let Some(ctx) = ctx { rpc_admin_activity_feed(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_verifier_status" => {
+            if let Some(ctx) = ctx { rpc_admin_verifier_status(app_state, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_audit_log" => {
+            if let Some(ctx) = ctx { rpc_admin_audit_log(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
+        "admin_confirm_transaction" => {
+            if let Some(ctx) = ctx { rpc_admin_confirm_transaction(mm, ctx, rpc_req.params).await }
+            else { Err(unauthorized_error()) }
+        }
         "admin_delete_trade" => {
             if let Some(ctx) = ctx { rpc_admin_delete_trade(mm, ctx, rpc_req.params).await }
             else { Err(unauthorized_error()) }
@@ -212,6 +485,10 @@ pub async fn rpc_handler(
         // ============================================
         // Wallet RPC
         // ============================================
+        "chia_check_all" => {
+            if let Some(ctx) = ctx { rpc_chia_check_all(app_state, ctx).await }
+            else { Err(unauthorized_error()) }
+        }
         "get_sync_status" | "get_wallets" | "get_wallet_balance" | "wallet_get_address" => {
             crate::api::wallet_rpc::wallet_rpc_handler(
                 axum::extract::State(app_state), 
@@ -228,14 +505,27 @@ pub async fn rpc_handler(
         }),
     };
 
-    let rpc_response = match result {
+    match result {
         Ok(res) => RpcResponse { id: rpc_id, result: Some(res), error: None },
         Err(e) => RpcResponse { id: rpc_id, result: None, error: Some(e) },
-    };
-
-    Json(rpc_response).into_response()
+    }
 }
 
+/// Methods that start new trades or commitments; refused with 4503 while maintenance mode is on
+const MAINTENANCE_BLOCKED_METHODS: &[&str] = &[
+    "trade_create",
+    "trade_update_proposal",
+    "trade_accept",
+    "trade_counter_offer",
+    "trade_accept_offer",
+    "trade_commit",
+    "commitment_create_pending",
+    "commitment_submit_tx",
+    "commitment_retry",
+    "commitment_create_escrow_deposit",
+    "contract_create",
+];
+
 fn unauthorized_error() -> RpcError {
     RpcError { code: 4001, message: "Unauthorized".to_string(), data: None }
 }
@@ -244,16 +534,6 @@ fn unauthorized_error() -> RpcError {
 // Trade RPC Handlers
 // ============================================
 
-/// User info for display on trades
-#[derive(Serialize)]
-struct UserPublicInfo {
-    id: i64,
-    username: String,
-    verification_status: String,
-    reputation_score: f64,
-    total_trades: i32,
-}
-
 /// Get current user info including admin status
 async fn rpc_user_me(ctx: Ctx) -> Result<Value, RpcError> {
     Ok(json!({
@@ -265,22 +545,68 @@ async fn rpc_user_me(ctx: Ctx) -> Result<Value, RpcError> {
     }))
 }
 
-/// Trade with proposer info
+/// Set the caller's escrow signer public key (96 hex chars BLS key)
+async fn rpc_user_set_public_key(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { public_key: Option<String> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let public_key = params.public_key.map(|pk| pk.trim_start_matches("0x").to_lowercase());
+    if let Some(pk) = &public_key {
+        if pk.len() != 96 || hex::decode(pk).is_err() {
+            return Err(RpcError {
+                code: -32602,
+                message: "public_key must be a 96-character hex BLS public key".to_string(),
+                data: None,
+            });
+        }
+    }
+
+    UserBmc::set_public_key(mm.db(), ctx.user_id(), public_key.as_deref())
+        .await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Database error: {}", e),
+            data: None,
+        })?;
+
+    Ok(json!({ "success": true }))
+}
+
+/// Trade with proposer info and the proposer's wishlist
 #[derive(Serialize)]
 struct TradeWithUser {
     #[serde(flatten)]
     trade: crate::model::Trade,
     proposer: Option<UserPublicInfo>,
     acceptor: Option<UserPublicInfo>,
+    wishlist: Vec<WishlistItem>,
 }
 
 /// List open trade proposals (public) - enriched with user info
 async fn rpc_trade_list_proposals(mm: ModelManager, params: Option<Value>) -> Result<Value, RpcError> {
-    #[derive(Deserialize, Default)]
-    struct Params { limit: Option<i64>, offset: Option<i64> }
-    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).unwrap_or_default();
+    #[derive(Deserialize)]
+    struct Params { limit: Option<i64>, offset: Option<i64>, tag: Option<String>, category: Option<String> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
     
-    let trades = TradeBmc::list_proposals(&mm, params.limit.unwrap_or(50), params.offset.unwrap_or(0))
+    // Out-of-range values are clamped rather than handed to Postgres, which rejects negative offsets
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let trades = TradeBmc::list_proposals(
+        &mm,
+        limit,
+        offset,
+        params.tag.as_deref(),
+        params.category.as_deref(),
+    )
         .await
         .map_err(|e| RpcError {
             code: 5000,
@@ -288,50 +614,81 @@ async fn rpc_trade_list_proposals(mm: ModelManager, params: Option<Value>) -> Re
             data: None,
         })?;
     
-    // Enrich trades with user info
-    let mut trades_with_users = Vec::new();
-    for trade in trades {
-        let proposer = get_user_public_info(mm.db(), trade.proposer_id).await;
-        let acceptor = if let Some(acceptor_id) = trade.acceptor_id {
-            get_user_public_info(mm.db(), acceptor_id).await
-        } else {
-            None
-        };
-        trades_with_users.push(TradeWithUser { trade, proposer, acceptor });
-    }
+    let trades_with_users = with_users(mm.db(), trades).await;
     
-    Ok(json!({ "trades": trades_with_users }))
+    Ok(json!({ "trades": trades_with_users, "limit": limit, "offset": offset }))
+}
+
+/// Categories of open proposals with counts (public), for browsing by category
+async fn rpc_trade_list_categories(mm: ModelManager) -> Result<Value, RpcError> {
+    let categories = TradeBmc::list_categories(&mm).await?;
+    Ok(json!({ "categories": categories }))
+}
+
+/// Search open proposals (public) by text, category, value range and tag.
+/// `sort` is "newest" (default), "value" or "reputation".
+async fn rpc_trade_search(mm: ModelManager, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params {
+        #[serde(flatten)]
+        criteria: TradeSearchCriteria,
+        #[serde(default)]
+        sort: TradeSort,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let trades = TradeBmc::search(&mm, &params.criteria, params.sort, limit, offset).await?;
+    let trades_with_users = with_users(mm.db(), trades).await;
+
+    Ok(json!({ "trades": trades_with_users, "limit": limit, "offset": offset }))
+}
+
+/// Enrich trades with proposer/acceptor info and wishlists, one query each for all trades
+async fn with_users<'a>(
+    db: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+    trades: Vec<crate::model::Trade>,
+) -> Vec<TradeWithUser> {
+    let mut user_ids: Vec<i64> = trades
+        .iter()
+        .flat_map(|t| std::iter::once(t.proposer_id).chain(t.acceptor_id))
+        .collect();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    
+    let trade_ids: Vec<i64> = trades.iter().map(|t| t.id).collect();
+    
+    let (users, mut wishlists) = match db.acquire().await {
+        Ok(mut conn) => (
+            UserBmc::get_public_info_batch(&mut *conn, &user_ids).await.unwrap_or_default(),
+            TradeBmc::wishlists_for(&mut *conn, &trade_ids).await.unwrap_or_default(),
+        ),
+        Err(_) => Default::default(),
+    };
+    trades
+        .into_iter()
+        .map(|trade| {
+            let proposer = users.get(&trade.proposer_id).cloned();
+            let acceptor = trade.acceptor_id.and_then(|id| users.get(&id).cloned());
+            let wishlist = wishlists.remove(&trade.id).unwrap_or_default();
+            TradeWithUser { trade, proposer, acceptor, wishlist }
+        })
+        .collect()
 }
 
 /// Get user public info (username, verification status, reputation, trade count)
 async fn get_user_public_info(db: &crate::store::Db, user_id: i64) -> Option<UserPublicInfo> {
-    // Use a struct for cleaner type handling
-    #[derive(sqlx::FromRow)]
-    struct UserRow {
-        id: i64,
-        username: String,
-        verification_status: Option<String>,
-        reputation_score: Option<f64>,
-        total_trades: Option<i32>,
-    }
-    
-    sqlx::query_as::<_, UserRow>(
-        "SELECT id, username, verification_status, reputation_score::float8, total_trades FROM users WHERE id = $1"
-    )
-    .bind(user_id)
-    .fetch_optional(db)
-    .await
-    .ok()
-    .flatten()
-    .map(|row| {
-        UserPublicInfo {
-            id: row.id,
-            username: row.username,
-            verification_status: row.verification_status.unwrap_or_else(|| "unverified".to_string()),
-            reputation_score: row.reputation_score.unwrap_or(0.0),
-            total_trades: row.total_trades.unwrap_or(0),
-        }
-    })
+    UserBmc::get_public_info_batch(db, &[user_id])
+        .await
+        .ok()
+        .and_then(|mut users| users.remove(&user_id))
 }
 
 /// Get a public trade proposal
@@ -356,11 +713,42 @@ async fn rpc_trade_get_public(mm: ModelManager, params: Option<Value>) -> Result
     } else {
         None
     };
-    let trade_with_user = TradeWithUser { trade, proposer, acceptor };
+    let wishlist = TradeBmc::get_wishlist(&mm, trade.id).await?;
+    let trade_with_user = TradeWithUser { trade, proposer, acceptor, wishlist };
     
     Ok(json!({ "trade": trade_with_user }))
 }
 
+/// Maximum number of ids accepted by `trade_statuses`
+const MAX_STATUS_IDS: usize = 500;
+
+/// Get statuses for many trades at once (public, for watchlists)
+async fn rpc_trade_statuses(mm: ModelManager, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { ids: Vec<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    if params.ids.len() > MAX_STATUS_IDS {
+        return Err(RpcError {
+            code: -32602,
+            message: format!("Too many ids (max {})", MAX_STATUS_IDS),
+            data: None,
+        });
+    }
+
+    let statuses = TradeBmc::statuses_for(&mm, &params.ids).await.map_err(|e| RpcError {
+        code: 5000,
+        message: format!("Database error: {}", e),
+        data: None,
+    })?;
+
+    Ok(json!({ "statuses": statuses }))
+}
+
 /// Create a new trade proposal
 async fn rpc_trade_create(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     let trade_c: TradeForCreate = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
@@ -369,11 +757,7 @@ async fn rpc_trade_create(mm: ModelManager, ctx: Ctx, params: Option<Value>) ->
         data: None,
     })?;
     
-    let trade_id = TradeBmc::create(&ctx, &mm, trade_c).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Create failed: {}", e),
-        data: None,
-    })?;
+    let trade_id = TradeBmc::create(&ctx, &mm, trade_c).await?;
     Ok(json!({ "trade_id": trade_id }))
 }
 
@@ -387,6 +771,13 @@ async fn rpc_trade_my_trades(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcErr
     Ok(json!({ "trades": trades }))
 }
 
+/// Count the caller's trades per status (for tab badges)
+async fn rpc_user_trade_counts(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcError> {
+    let counts = TradeBmc::status_counts(&ctx, &mm).await?;
+    let total: i64 = counts.values().sum();
+    Ok(json!({ "counts": counts, "total": total }))
+}
+
 /// Get a trade (participant only)
 async fn rpc_trade_get(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
@@ -410,73 +801,165 @@ async fn rpc_trade_get(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Res
     } else {
         None
     };
-    let trade_with_user = TradeWithUser { trade, proposer, acceptor };
+    let wishlist = TradeBmc::get_wishlist(&mm, trade.id).await?;
+    let trade_with_user = TradeWithUser { trade, proposer, acceptor, wishlist };
     
     Ok(json!({ "trade": trade_with_user }))
 }
 
-/// Accept a trade proposal (make an offer)
-async fn rpc_trade_accept(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
-    let accept_params: TradeAcceptParams = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+/// Event history of a trade (proposal edits, ...), oldest first
+async fn rpc_trade_timeline(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64 }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
         code: -32602,
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
     
-    TradeBmc::accept(&ctx, &mm, accept_params).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Accept failed: {}", e),
+    let events = TradeEventBmc::list_for_trade(&ctx, &mm, params.trade_id).await?;
+    
+    Ok(json!({ "events": events }))
+}
+
+/// Edit a trade proposal; only the provided fields are changed
+async fn rpc_trade_update_proposal(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params {
+        trade_id: i64,
+        #[serde(flatten)]
+        update: TradeForUpdate,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
         data: None,
     })?;
+
+    TradeBmc::update_partial(&ctx, &mm, params.trade_id, params.update).await?;
     Ok(json!({ "success": true }))
 }
 
-/// Commit to a trade (pay fee)
-async fn rpc_trade_commit(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Accept a trade proposal (make an offer)
+async fn rpc_trade_accept(mm: ModelManager, app_state: Arc<AppState>, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
-    struct Params { trade_id: i64 }
-    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+    struct Target { trade_id: i64, offer_id: Option<i64>, expected_version: Option<i64> }
+    let params = params.unwrap_or(json!({}));
+
+    // The proposer accepting their own trade takes the offer waiting on them
+    if let Ok(target) = serde_json::from_value::<Target>(params.clone()) {
+        if let Ok(trade) = TradeBmc::get_public(&mm, target.trade_id).await {
+            if trade.proposer_id == ctx.user_id() {
+                let offer_id = proposer_offer_to_accept(&ctx, &mm, target.trade_id, target.offer_id).await?;
+                return accept_offer_and_notify(&mm, &ctx, offer_id, target.expected_version).await;
+            }
+        }
+    }
+
+    let mut accept_params: TradeAcceptParams = serde_json::from_value(params).map_err(|e| RpcError {
         code: -32602,
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
+    accept_params.xch_price_usd = offer_xch_price(&app_state, &accept_params.offer_type).await;
     
-    // TODO: Implement actual commitment transaction creation
-    // For now, just update status
-    TradeBmc::update_status(&ctx, &mm, params.trade_id, "committed").await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Commit failed: {}", e),
+    let trade_id = accept_params.trade_id;
+    let offer_id = TradeBmc::make_offer(&ctx, &mm, accept_params).await?;
+
+    // Best effort: the offer stands even if the proposer can't be notified
+    if let Ok(trade) = TradeBmc::get_public(&mm, trade_id).await {
+        let message = format!("New offer on \"{}\"", trade.proposer_item_title);
+        if let Err(e) = NotificationBmc::create(&mm, trade.proposer_id, "offer_received", Some(trade_id), &message).await {
+            tracing::warn!("Failed to notify proposer of offer on trade {}: {:?}", trade_id, e);
+        }
+    }
+    Ok(json!({ "success": true, "offer_id": offer_id, "status": "pending" }))
+}
+
+/// The pending offer a proposer's `trade_accept` takes: `offer_id`, or else the only one waiting on them
+async fn proposer_offer_to_accept(ctx: &Ctx, mm: &ModelManager, trade_id: i64, offer_id: Option<i64>) -> Result<i64, RpcError> {
+    let waiting: Vec<i64> = TradeBmc::list_offers(ctx, mm, trade_id)
+        .await?
+        .into_iter()
+        .filter(|o| o.status == "pending" && o.created_by != ctx.user_id())
+        .map(|o| o.id)
+        .collect();
+    match (offer_id, waiting.as_slice()) {
+        (Some(id), _) if waiting.contains(&id) => Ok(id),
+        (Some(_), _) => Err(RpcError { code: 4004, message: "Offer not found".to_string(), data: None }),
+        (None, [id]) => Ok(*id),
+        (None, []) => Err(Error::InvalidState("No pending offer to accept".to_string()).into()),
+        (None, _) => Err(Error::InvalidState("Several offers are pending; pass offer_id".to_string()).into()),
+    }
+}
+
+/// Server XCH/USD price for valuing an offer's XCH in the lowball guard.
+/// `None` for item-only offers, or (logged) when the price source is down.
+async fn offer_xch_price(app_state: &AppState, offer_type: &str) -> Option<f64> {
+    if offer_type != "xch" && offer_type != "mixed" {
+        return None;
+    }
+    match app_state.price_provider().xch_usd().await {
+        Ok(quote) => Some(quote.usd),
+        Err(e) => {
+            tracing::warn!("XCH price unavailable for offer valuation: {}", e);
+            None
+        }
+    }
+}
+
+/// Answer a pending offer with new terms (proposer, or the bidder answering a counter)
+async fn rpc_trade_counter_offer(
+    mm: ModelManager,
+    app_state: Arc<AppState>,
+    ctx: Ctx,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
+    let mut counter: TradeCounterParams = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
         data: None,
     })?;
+    counter.xch_price_usd = offer_xch_price(&app_state, &counter.offer_type).await;
     
-    Ok(json!({ 
-        "success": true,
-        "message": "Trade committed. Implement wallet signing in Phase 3."
-    }))
+    let offer_id = TradeBmc::counter_offer(&ctx, &mm, counter).await.map_err(|e| match e {
+        Error::NotFound => RpcError { code: 4004, message: "Offer not found".to_string(), data: None },
+        e => e.into(),
+    })?;
+    notify_offer_counterparty(&mm, &ctx, offer_id, "offer_countered", "Counter-offer on").await;
+    Ok(json!({ "success": true, "offer_id": offer_id, "status": "pending" }))
 }
 
-/// Add tracking information
-async fn rpc_trade_add_tracking(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Accept a pending offer, matching the trade (proposer for bids, bidder for the proposer's counters)
+async fn rpc_trade_accept_offer(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
-    struct Params { trade_id: i64, tracking_number: String, carrier: String }
+    struct Params { offer_id: i64, expected_version: Option<i64> }
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
         code: -32602,
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
-    
-    TradeBmc::add_tracking(&ctx, &mm, params.trade_id, &params.tracking_number, &params.carrier)
+    accept_offer_and_notify(&mm, &ctx, params.offer_id, params.expected_version).await
+}
+
+async fn accept_offer_and_notify(
+    mm: &ModelManager,
+    ctx: &Ctx,
+    offer_id: i64,
+    expected_version: Option<i64>,
+) -> Result<Value, RpcError> {
+    let trade_id = TradeBmc::accept_offer(ctx, mm, offer_id, expected_version)
         .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Add tracking failed: {}", e),
-            data: None,
+        .map_err(|e| match e {
+            Error::NotFound => RpcError { code: 4004, message: "Offer not found".to_string(), data: None },
+            e => e.into(),
         })?;
-    Ok(json!({ "success": true }))
+    notify_offer_counterparty(mm, ctx, offer_id, "offer_accepted", "Offer accepted on").await;
+    Ok(json!({ "success": true, "trade_id": trade_id, "offer_id": offer_id, "status": "matched" }))
 }
 
-/// Complete a trade
-async fn rpc_trade_complete(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Offers on a trade: all of them for the proposer, the caller's own for a bidder
+async fn rpc_trade_list_offers(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
     struct Params { trade_id: i64 }
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
@@ -485,16 +968,96 @@ async fn rpc_trade_complete(mm: ModelManager, ctx: Ctx, params: Option<Value>) -
         data: None,
     })?;
     
-    TradeBmc::update_status(&ctx, &mm, params.trade_id, "completed").await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Complete failed: {}", e),
-        data: None,
+    let offers = TradeBmc::list_offers(&ctx, &mm, params.trade_id).await.map_err(|e| match e {
+        Error::NotFound => RpcError { code: 4004, message: "Trade not found".to_string(), data: None },
+        e => RpcError { code: 5000, message: format!("Failed to list offers: {}", e), data: None },
     })?;
+    Ok(json!({ "offers": offers }))
+}
+
+/// A trade's images, cover first and the rest by position (public)
+async fn rpc_trade_list_images(mm: ModelManager, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64 }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let images = TradeBmc::list_images(&mm, params.trade_id).await?;
+    Ok(json!({ "images": images }))
+}
+
+/// Make one of the caller's trade images the cover
+async fn rpc_trade_set_primary_image(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, image_id: i64 }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TradeBmc::set_primary_image(&ctx, &mm, params.trade_id, params.image_id)
+        .await
+        .map_err(image_error)?;
     Ok(json!({ "success": true }))
 }
 
-/// Cancel a trade
-async fn rpc_trade_cancel(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Reorder the caller's trade images; `image_ids` lists every image once
+async fn rpc_trade_reorder_images(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, image_ids: Vec<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TradeBmc::reorder_images(&ctx, &mm, params.trade_id, &params.image_ids)
+        .await
+        .map_err(image_error)?;
+    Ok(json!({ "success": true }))
+}
+
+/// Image edits: unknown trade/image or not the proposer -> 4004, otherwise as `From<Error>`
+fn image_error(e: Error) -> RpcError {
+    match e {
+        Error::NotFound => RpcError { code: 4004, message: "Trade or image not found".to_string(), data: None },
+        e => e.into(),
+    }
+}
+
+/// Best effort: tell the other side of an offer thread (proposer or bidder) about `offer_id`
+async fn notify_offer_counterparty(mm: &ModelManager, ctx: &Ctx, offer_id: i64, kind: &str, prefix: &str) {
+    let Ok(offer) = TradeBmc::get_offer(mm, offer_id).await else { return };
+    let Ok(trade) = TradeBmc::get_public(mm, offer.trade_id).await else { return };
+    let recipient = if ctx.user_id() == trade.proposer_id { offer.bidder_id } else { trade.proposer_id };
+    let message = format!("{} \"{}\"", prefix, trade.proposer_item_title);
+    if let Err(e) = NotificationBmc::create(mm, recipient, kind, Some(trade.id), &message).await {
+        tracing::warn!("Failed to send {} notification for offer {}: {:?}", kind, offer_id, e);
+    }
+}
+
+/// Commit to a trade (pay fee)
+async fn rpc_trade_commit(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, expected_version: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    // Only goes through once the commitment fees are confirmed or waived
+    TradeBmc::update_status(&ctx, &mm, params.trade_id, "committed", params.expected_version).await?;
+    
+    Ok(json!({ "success": true }))
+}
+
+/// Whether the caller can commit to the trade now, with the reason when not
+async fn rpc_trade_can_commit(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
     struct Params { trade_id: i64 }
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
@@ -503,14 +1066,76 @@ async fn rpc_trade_cancel(mm: ModelManager, ctx: Ctx, params: Option<Value>) ->
         data: None,
     })?;
     
-    TradeBmc::cancel(&ctx, &mm, params.trade_id).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Cancel failed: {}", e),
+    let eligibility = TransactionBmc::can_commit(&ctx, &mm, params.trade_id).await?;
+    Ok(json!(eligibility))
+}
+
+/// Add tracking information
+async fn rpc_trade_add_tracking(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, tracking_number: String, carrier: String, expected_version: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TradeBmc::add_tracking(&ctx, &mm, params.trade_id, &params.tracking_number, &params.carrier, params.expected_version)
+        .await?;
+    Ok(json!({ "success": true }))
+}
+
+/// Complete a trade
+async fn rpc_trade_complete(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, expected_version: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let completion = TradeBmc::complete(&ctx, &mm, params.trade_id, params.expected_version).await?;
+    Ok(json!({
+        "success": true,
+        "already_completed": completion.already_completed,
+        "completed_at": completion.completed_at,
+    }))
+}
+
+/// Cancel a trade
+async fn rpc_trade_cancel(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, expected_version: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
         data: None,
     })?;
+    
+    TradeBmc::cancel(&ctx, &mm, params.trade_id, params.expected_version).await?;
     Ok(json!({ "success": true }))
 }
 
+/// Flag a problem with a committed or escrow trade (participant only); an admin resolves it
+async fn rpc_trade_raise_dispute(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, reason: String }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let disputed_at = TradeBmc::raise_dispute(&ctx, &mm, params.trade_id, &params.reason)
+        .await
+        .map_err(|e| match e {
+            Error::NotFound => RpcError { code: 4004, message: "Trade not found".to_string(), data: None },
+            e => e.into(),
+        })?;
+    Ok(json!({ "success": true, "trade_id": params.trade_id, "status": "disputed", "disputed_at": disputed_at }))
+}
+
 /// Delete a trade proposal
 async fn rpc_trade_delete(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
@@ -537,11 +1162,7 @@ async fn rpc_trade_review(mm: ModelManager, ctx: Ctx, params: Option<Value>) ->
         data: None,
     })?;
     
-    let review_id = ReviewBmc::create(&ctx, &mm, review).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Review failed: {}", e),
-        data: None,
-    })?;
+    let review_id = ReviewBmc::create(&ctx, &mm, review).await?;
     Ok(json!({ "review_id": review_id }))
 }
 
@@ -563,6 +1184,67 @@ async fn rpc_user_reviews(mm: ModelManager, params: Option<Value>) -> Result<Val
     Ok(json!({ "reviews": reviews }))
 }
 
+/// Reviews a user wrote, paged newest first (the user themselves or an admin)
+async fn rpc_user_authored_reviews(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { user_id: i64, limit: Option<i64>, offset: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    if params.user_id != ctx.user_id() && !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let reviews = ReviewBmc::list_authored(&mm, params.user_id, limit, offset).await?;
+
+    Ok(json!({ "reviews": reviews, "limit": limit, "offset": offset }))
+}
+
+/// Get a trader's public profile (no auth): public info, review summary and recent reviews.
+/// Deleted users no longer exist and return 4004.
+async fn rpc_user_profile(mm: ModelManager, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { user_id: i64, limit: Option<i64>, offset: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let profile = get_user_public_info(mm.db(), params.user_id).await.ok_or_else(|| RpcError {
+        code: 4004,
+        message: "User not found".to_string(),
+        data: None,
+    })?;
+
+    let summary = ReviewBmc::summary_for_user(&mm, params.user_id).await.map_err(|e| RpcError {
+        code: 5000,
+        message: format!("Database error: {}", e),
+        data: None,
+    })?;
+
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let reviews = ReviewBmc::list_recent_for_user(&mm, params.user_id, limit, offset)
+        .await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Database error: {}", e),
+            data: None,
+        })?;
+
+    Ok(json!({ "profile": profile, "review_summary": summary, "reviews": reviews }))
+}
+
 // ============================================
 // Legacy Contract RPC Handlers (backward compatibility)
 // ============================================
@@ -597,10 +1279,9 @@ async fn rpc_contract_create(mm: ModelManager, ctx: Ctx, params: Option<Value>)
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
-    let contract_id = ContractBmc::create(&ctx, &mm, contract_c).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Create failed: {}", e),
-        data: None,
+    let contract_id = ContractBmc::create(&ctx, &mm, contract_c).await.map_err(|e| match e {
+        e @ Error::Validation(_) => RpcError::from(e),
+        e => RpcError { code: 5000, message: format!("Create failed: {}", e), data: None },
     })?;
     Ok(json!({ "contract_id": contract_id }))
 }
@@ -627,10 +1308,9 @@ async fn rpc_contract_update(mm: ModelManager, ctx: Ctx, params: Option<Value>)
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
-    ContractBmc::update(&ctx, &mm, params.id, params.data).await.map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Update failed: {}", e),
-        data: None,
+    ContractBmc::update(&ctx, &mm, params.id, params.data).await.map_err(|e| match e {
+        e @ Error::Validation(_) => RpcError::from(e),
+        e => RpcError { code: 5000, message: format!("Update failed: {}", e), data: None },
     })?;
     Ok(json!({ "success": true }))
 }
@@ -650,13 +1330,7 @@ async fn rpc_commitment_get_details(mm: ModelManager, ctx: Ctx, params: Option<V
         data: None,
     })?;
     
-    let details = TransactionBmc::get_commitment_details(&ctx, &mm, params.trade_id)
-        .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to get commitment details: {}", e),
-            data: None,
-        })?;
+    let details = TransactionBmc::get_commitment_details(&ctx, &mm, params.trade_id).await?;
     
     Ok(json!({
         "trade_id": details.trade_id,
@@ -669,13 +1343,24 @@ async fn rpc_commitment_get_details(mm: ModelManager, ctx: Ctx, params: Option<V
     }))
 }
 
-/// Create a pending transaction record before wallet signing
-async fn rpc_commitment_create_pending(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Create a pending transaction record before wallet signing.
+/// With `xch_price_usd` the server converts the USD fee to mojos using the configured
+/// rounding mode; with neither that nor `amount_mojos` it uses its own XCH price.
+/// Either way the amount must be within the price tolerance of the fee at the server's price.
+async fn rpc_commitment_create_pending(
+    mm: ModelManager,
+    app_state: Arc<AppState>,
+    ctx: Ctx,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
     struct Params {
         trade_id: i64,
         from_address: Option<String>,
-        amount_mojos: i64,  // Frontend calculates XCH amount from USD fee using live price
+        amount_mojos: Option<i64>,
+        xch_price_usd: Option<f64>,
+        price_source: Option<String>,
+        price_timestamp: Option<chrono::DateTime<chrono::Utc>>,
     }
     
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
@@ -684,15 +1369,91 @@ async fn rpc_commitment_create_pending(mm: ModelManager, ctx: Ctx, params: Optio
         data: None,
     })?;
     
+    // Get commitment details (for destination address and validation)
+    let details = TransactionBmc::get_commitment_details(&ctx, &mm, params.trade_id).await?;
+    
+    if details.user_commit_status == "not_required" {
+        return Err(RpcError {
+            code: 4000,
+            message: format!("No commitment fee required from the {} (fee model '{}')", details.user_role, details.fee_model),
+            data: None,
+        });
+    }
+    if details.fee_waived {
+        return Err(RpcError {
+            code: 4000,
+            message: "Commitment fee waived for this trade".to_string(),
+            data: None,
+        });
+    }
+    
+    // Never hand out a payment address we can't decode
+    validate_xch_address(&details.exchange_wallet_address).map_err(|e| RpcError {
+        code: 5000,
+        message: format!("Exchange wallet address is invalid: {}", e),
+        data: None,
+    })?;
+    
+    let prices = app_state.price_provider();
+    let quote = prices.xch_usd().await.map_err(|e| RpcError {
+        code: 5002,
+        message: format!("XCH price unavailable: {}", e),
+        data: None,
+    })?;
+    let (expected_mojos, rounding) = commitment_fee_mojos(&mm, &ctx, details.commitment_fee_usd, quote.usd).await?;
+    
+    let (amount_mojos, price) = match (params.xch_price_usd, params.amount_mojos) {
+        (Some(xch_price_usd), _) => {
+            let (mojos, rounding) = commitment_fee_mojos(&mm, &ctx, details.commitment_fee_usd, xch_price_usd).await?;
+            let price = json!({
+                "source": params.price_source.unwrap_or_else(|| "client".to_string()),
+                "timestamp": params.price_timestamp.unwrap_or_else(chrono::Utc::now),
+                "xch_price_usd": xch_price_usd,
+                "fee_usd": details.commitment_fee_usd,
+                "rounding": rounding.as_str(),
+            });
+            (mojos, price)
+        }
+        (None, Some(mojos)) => (mojos, Value::Null),
+        (None, None) => {
+            let price = json!({
+                "source": "server",
+                "timestamp": quote.fetched_at,
+                "xch_price_usd": quote.usd,
+                "fee_usd": details.commitment_fee_usd,
+                "rounding": rounding.as_str(),
+            });
+            (expected_mojos, price)
+        }
+    };
+    
+    if !prices.within_tolerance(amount_mojos, expected_mojos) {
+        return Err(RpcError {
+            code: 4000,
+            message: format!(
+                "Amount {} mojos is more than {}% off the {} mojos fee at ${}/XCH",
+                amount_mojos,
+                prices.tolerance_pct(),
+                expected_mojos,
+                quote.usd
+            ),
+            data: Some(json!({
+                "expected_mojos": expected_mojos,
+                "xch_price_usd": quote.usd,
+                "tolerance_pct": prices.tolerance_pct(),
+            })),
+        });
+    }
+    
     // Validate amount is reasonable (at least 1000 mojos, less than 10 XCH)
-    if params.amount_mojos < 1000 {
+    if amount_mojos < 1000 {
         return Err(RpcError {
             code: -32602,
             message: "Amount too small".to_string(),
             data: None,
         });
     }
-    if params.amount_mojos > 10_000_000_000_000 {
+    if amount_mojos > 10_000_000_000_000 {
         return Err(RpcError {
             code: -32602,
             message: "Amount too large".to_string(),
@@ -700,23 +1461,13 @@ async fn rpc_commitment_create_pending(mm: ModelManager, ctx: Ctx, params: Optio
         });
     }
     
-    // Get commitment details (for destination address and validation)
-    let details = TransactionBmc::get_commitment_details(&ctx, &mm, params.trade_id)
-        .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to get commitment details: {}", e),
-            data: None,
-        })?;
-    
-    // Create pending transaction with frontend-calculated amount
     let tx = TradeTransactionForCreate {
         trade_id: params.trade_id,
         tx_type: "commitment_fee".to_string(),
         tx_id: None,
         from_address: params.from_address,
         to_address: Some(details.exchange_wallet_address.clone()),
-        amount_mojos: params.amount_mojos,
+        amount_mojos,
     };
     
     let transaction_id = TransactionBmc::create(&ctx, &mm, tx)
@@ -727,50 +1478,94 @@ async fn rpc_commitment_create_pending(mm: ModelManager, ctx: Ctx, params: Optio
             data: None,
         })?;
     
-    let amount_xch = params.amount_mojos as f64 / 1_000_000_000_000.0;
+    let amount_xch = amount_mojos as f64 / MOJOS_PER_XCH;
     
     Ok(json!({
         "transaction_id": transaction_id,
         "to_address": details.exchange_wallet_address,
-        "amount_mojos": params.amount_mojos,
+        "amount_mojos": amount_mojos,
         "amount_xch": amount_xch,
-        "memo": details.memo
+        "memo": details.memo,
+        "price": price
     }))
 }
 
-/// Submit the transaction ID after wallet has signed and broadcast
-async fn rpc_commitment_submit_tx(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
-    #[derive(Deserialize)]
-    struct Params {
-        transaction_id: i64,
-        tx_id: String,
+/// Commitment fee in mojos at `xch_price_usd`, rounded per the exchange's fee rounding setting
+async fn commitment_fee_mojos(
+    mm: &ModelManager,
+    ctx: &Ctx,
+    fee_usd: f64,
+    xch_price_usd: f64,
+) -> Result<(i64, FeeRounding), RpcError> {
+    if !xch_price_usd.is_finite() || xch_price_usd <= 0.0 {
+        return Err(RpcError {
+            code: -32602,
+            message: "xch_price_usd must be a positive number".to_string(),
+            data: None,
+        });
     }
-    
+    let rounding = TransactionBmc::get_commitment_fee_rounding(ctx, mm).await?;
+    Ok((rounding.usd_to_mojos(fee_usd, xch_price_usd), rounding))
+}
+
+/// Can the caller's wallet cover their commitment fee for a trade?
+/// The fee is computed at the server's XCH price, exactly as `commitment_create_pending` would.
+async fn rpc_affordability_check(
+    mm: ModelManager,
+    app_state: Arc<AppState>,
+    ctx: Ctx,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
+    crate::api::wallet_rpc::check_passthrough_limit(&app_state, &ctx)?;
+    let client = ChiaRpcClient::from_state(app_state.clone(), "wallet").await?;
+    affordability_check(&mm, &ctx, &client, app_state.price_provider(), params).await
+}
+
+async fn affordability_check(
+    mm: &ModelManager,
+    ctx: &Ctx,
+    client: &ChiaRpcClient,
+    prices: &XchPriceProvider,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { trade_id: i64, wallet_id: Option<u32> }
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
         code: -32602,
         message: format!("Invalid params: {}", e),
         data: None,
     })?;
-    
-    TransactionBmc::submit_tx_id(&ctx, &mm, params.transaction_id, &params.tx_id)
-        .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to submit transaction: {}", e),
+
+    let details = TransactionBmc::get_commitment_details(ctx, mm, params.trade_id).await?;
+    let fee_mojos = if details.user_commit_status == "not_required" || details.fee_waived {
+        0
+    } else {
+        let quote = prices.xch_usd().await.map_err(|e| RpcError {
+            code: 5002,
+            message: format!("XCH price unavailable: {}", e),
             data: None,
         })?;
-    
+        commitment_fee_mojos(mm, ctx, details.commitment_fee_usd, quote.usd).await?.0
+    };
+
+    let balance = client.get_wallet_balance(params.wallet_id.unwrap_or(1)).await?;
+    let spendable_mojos = i64::try_from(balance.spendable_mojos).unwrap_or(i64::MAX);
+    let shortfall_mojos = (fee_mojos - spendable_mojos).max(0);
+
     Ok(json!({
-        "success": true,
-        "status": "mempool",
-        "message": "Transaction submitted. Awaiting blockchain confirmation."
+        "affordable": shortfall_mojos == 0,
+        "fee_mojos": fee_mojos,
+        "spendable_mojos": spendable_mojos,
+        "shortfall_mojos": shortfall_mojos,
     }))
 }
 
-/// List all transactions for a trade
-async fn rpc_commitment_list_transactions(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+/// Replace a failed commitment transaction with a fresh pending one (same amount, current destination)
+async fn rpc_commitment_retry(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
-    struct Params { trade_id: i64 }
+    struct Params {
+        transaction_id: i64,
+    }
     
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
         code: -32602,
@@ -778,32 +1573,26 @@ async fn rpc_commitment_list_transactions(mm: ModelManager, ctx: Ctx, params: Op
         data: None,
     })?;
     
-    let transactions = TransactionBmc::list_for_trade(&ctx, &mm, params.trade_id)
-        .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to list transactions: {}", e),
-            data: None,
-        })?;
+    let tx = TransactionBmc::retry(&ctx, &mm, params.transaction_id).await?;
     
-    Ok(json!({ "transactions": transactions }))
+    Ok(json!({
+        "transaction_id": tx.id,
+        "retried_transaction_id": params.transaction_id,
+        "to_address": tx.to_address,
+        "amount_mojos": tx.amount_mojos,
+        "amount_xch": tx.amount_mojos as f64 / MOJOS_PER_XCH,
+        "memo": commitment_memo(tx.trade_id, tx.user_id),
+    }))
 }
 
-/// Set the exchange wallet address (admin only)
-async fn rpc_config_set_exchange_wallet(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
-    // Admin check
-    if !ctx.is_admin() {
-        return Err(RpcError {
-            code: 4003,
-            message: "Admin access required".to_string(),
-            data: None,
-        });
-    }
-    
+/// Create the caller's pending escrow deposit once the trade is committed; pay it to
+/// `escrow_puzzle_hash`, then submit the tx_id with `commitment_submit_tx`
+async fn rpc_commitment_create_escrow_deposit(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     #[derive(Deserialize)]
-    struct Params { 
-        wallet_address: String,
-        commitment_fee_usd: Option<f64>,  // Fee in USD (e.g., 1.0 for $1)
+    struct Params {
+        trade_id: i64,
+        amount_mojos: i64,
+        from_address: Option<String>,
     }
     
     let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
@@ -812,39 +1601,142 @@ async fn rpc_config_set_exchange_wallet(mm: ModelManager, ctx: Ctx, params: Opti
         data: None,
     })?;
     
-    // Validate address format
-    if !params.wallet_address.starts_with("xch1") || params.wallet_address.len() != 62 {
+    if params.amount_mojos <= 0 {
         return Err(RpcError {
             code: -32602,
-            message: "Invalid XCH address format".to_string(),
+            message: "Invalid params: amount_mojos must be positive".to_string(),
             data: None,
         });
     }
     
-    TransactionBmc::set_exchange_wallet(&ctx, &mm, &params.wallet_address)
-        .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to set exchange wallet: {}", e),
-            data: None,
-        })?;
+    let deposit = TransactionBmc::create_escrow_deposit(&ctx, &mm, params.trade_id, params.amount_mojos, params.from_address).await?;
     
-    // Store commitment fee in USD (default $1.00 if not provided)
-    let fee_usd = params.commitment_fee_usd.unwrap_or(1.0);
-    sqlx::query(
-        "INSERT INTO exchange_config (key, value, description, updated_at) 
-         VALUES ('commitment_fee_usd', $1, 'Commitment fee in USD - XCH calculated dynamically', NOW())
-         ON CONFLICT (key) DO UPDATE SET value = $1, updated_at = NOW()"
-    )
-    .bind(fee_usd.to_string())
-    .execute(mm.db())
-    .await
-    .map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Failed to set commitment fee: {}", e),
+    Ok(json!({
+        "transaction_id": deposit.id,
+        "trade_id": deposit.trade_id,
+        "escrow_puzzle_hash": deposit.puzzle_hash,
+        "amount_mojos": deposit.amount_mojos,
+        "amount_xch": deposit.amount_mojos as f64 / MOJOS_PER_XCH,
+        "status": deposit.status,
+    }))
+}
+
+/// Delete a pending commitment transaction the caller never submitted, so they can start over
+async fn rpc_commitment_cancel_pending(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params {
+        transaction_id: i64,
+    }
+    
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TransactionBmc::cancel_pending(&ctx, &mm, params.transaction_id).await?;
+    
+    Ok(json!({
+        "success": true,
+        "transaction_id": params.transaction_id,
+    }))
+}
+
+/// Submit the transaction ID after wallet has signed and broadcast
+async fn rpc_commitment_submit_tx(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params {
+        transaction_id: i64,
+        tx_id: String,
+    }
+    
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    // Resubmitting the same tx_id succeeds, so clients can retry after a dropped response
+    TransactionBmc::submit_tx_id(&ctx, &mm, params.transaction_id, &params.tx_id)
+        .await
+        .map_err(|e| match e {
+            Error::NotFoundMsg(msg) => RpcError { code: 4004, message: msg, data: None },
+            Error::Conflict(msg) => RpcError { code: 4009, message: msg, data: None },
+            Error::InvalidState(msg) => RpcError { code: 4000, message: msg, data: None },
+            e => RpcError {
+                code: 5000,
+                message: format!("Failed to submit transaction: {}", e),
+                data: None,
+            },
+        })?;
+    
+    Ok(json!({
+        "success": true,
+        "status": "mempool",
+        "message": "Transaction submitted. Awaiting blockchain confirmation."
+    }))
+}
+
+/// List all transactions for a trade
+async fn rpc_commitment_list_transactions(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params {
+        trade_id: i64,
+        #[serde(flatten)]
+        filter: TransactionListFilter,
+    }
+    
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let transactions = TransactionBmc::list_for_trade(&ctx, &mm, params.trade_id, &params.filter)
+        .await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Failed to list transactions: {}", e),
+            data: None,
+        })?;
+    
+    Ok(json!({ "transactions": transactions }))
+}
+
+/// Set the exchange wallet address (admin only)
+async fn rpc_config_set_exchange_wallet(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params { 
+        wallet_address: String,
+        commitment_fee_usd: Option<f64>,  // Fee in USD (e.g., 1.0 for $1)
+    }
+    
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
         data: None,
     })?;
     
+    let settings = SettingsBmc::get(&mm).await?;
+    check_exchange_address(&params.wallet_address, &settings.address_hrp)?;
+    
+    // Commitment fee in USD (default $1.00 if not provided)
+    let settings_u = ExchangeSettingsForUpdate {
+        wallet_address: Some(params.wallet_address),
+        commitment_fee_usd: Some(params.commitment_fee_usd.unwrap_or(1.0)),
+        ..Default::default()
+    };
+    SettingsBmc::update(&mm, settings_u).await?;
+    
     Ok(json!({
         "success": true,
         "message": "Exchange wallet configuration updated"
@@ -857,20 +1749,7 @@ async fn rpc_config_get_exchange_wallet(mm: ModelManager, ctx: Ctx) -> Result<Va
     let address = TransactionBmc::get_exchange_wallet(&ctx, &mm)
         .await
         .ok();
-    
-    // Get fee in USD (default $1.00)
-    let fee_usd: f64 = sqlx::query_as::<_, (String,)>(
-        "SELECT value FROM exchange_config WHERE key = 'commitment_fee_usd'"
-    )
-    .fetch_optional(mm.db())
-    .await
-    .map_err(|e| RpcError {
-        code: 5000,
-        message: format!("Database error: {}", e),
-        data: None,
-    })?
-    .and_then(|(v,)| v.parse::<f64>().ok())
-    .unwrap_or(1.0); // Default $1.00
+    let fee_usd = TransactionBmc::get_commitment_fee_usd(&ctx, &mm).await?;
     
     Ok(json!({
         "wallet_address": address,
@@ -878,12 +1757,179 @@ async fn rpc_config_get_exchange_wallet(mm: ModelManager, ctx: Ctx) -> Result<Va
     }))
 }
 
+/// Exchange addresses must be valid bech32m `xch`/`txch` addresses on the configured network
+fn check_exchange_address(address: &str, hrp: &str) -> Result<(), RpcError> {
+    let invalid = |reason: String| RpcError {
+        code: -32602,
+        message: format!("Invalid {} address: {}", hrp.to_uppercase(), reason),
+        data: None,
+    };
+    let (address_hrp, _) = decode_puzzle_hash(address).map_err(|e| invalid(e.to_string()))?;
+    if address_hrp != hrp {
+        return Err(invalid(format!("expected prefix '{}', got '{}'", hrp, address_hrp)));
+    }
+    Ok(())
+}
+
+/// Get the typed exchange settings (admin only)
+async fn rpc_config_get_settings(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    let settings = SettingsBmc::get(&mm).await?;
+    Ok(json!({ "settings": settings }))
+}
+
+/// Update exchange settings; omitted fields are unchanged (admin only, audited)
+async fn rpc_config_update_settings(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    let settings_u: ExchangeSettingsForUpdate = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let update_error = |e: sqlx::Error| RpcError {
+        code: 5000,
+        message: format!("Failed to update settings: {}", e),
+        data: None,
+    };
+    
+    // The row stays locked from reading `before` until the change and its audit row commit
+    let mut tx = mm.db().begin().await.map_err(update_error)?;
+    let before = SettingsBmc::lock_in(&mut tx).await?;
+    if let Some(address) = settings_u.wallet_address.as_deref().filter(|a| !a.is_empty()) {
+        check_exchange_address(address, settings_u.address_hrp.as_deref().unwrap_or(&before.address_hrp))?;
+    }
+    
+    let settings = SettingsBmc::update_in(&mut tx, settings_u).await?;
+    let details = json!({ "before": before, "after": settings });
+    AuditBmc::record_in(&mut tx, &ctx, "update_settings", "exchange_settings", "1", details).await?;
+    tx.commit().await.map_err(update_error)?;
+    
+    Ok(json!({ "settings": settings }))
+}
+
+/// Turn maintenance mode on or off (admin only, audited)
+async fn rpc_admin_set_maintenance_mode(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        enabled: bool,
+    }
+    
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let update_error = |e: sqlx::Error| RpcError {
+        code: 5000,
+        message: format!("Failed to set maintenance mode: {}", e),
+        data: None,
+    };
+    
+    // The flag and its audit row commit together
+    let mut tx = mm.db().begin().await.map_err(update_error)?;
+    let settings_u = ExchangeSettingsForUpdate { maintenance_mode: Some(params.enabled), ..Default::default() };
+    let settings = SettingsBmc::update_in(&mut tx, settings_u).await?;
+    let details = json!({ "enabled": params.enabled });
+    AuditBmc::record_in(&mut tx, &ctx, "set_maintenance_mode", "exchange_settings", "1", details).await?;
+    tx.commit().await.map_err(update_error)?;
+    
+    Ok(json!({ "maintenance_mode": settings.maintenance_mode }))
+}
+
+// ============================================
+// Contact RPCs
+// ============================================
+
+/// List the caller's contacts
+async fn rpc_contact_list(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcError> {
+    let contacts = ContactBmc::list(&ctx, &mm).await.map_err(|e| RpcError {
+        code: 5000,
+        message: format!("Failed to list contacts: {}", e),
+        data: None,
+    })?;
+    Ok(json!({ "contacts": contacts }))
+}
+
+/// Bulk import contacts; reports success or the error for each row
+async fn rpc_contact_import(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { contacts: Vec<ContactForCreate> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let rows = ContactBmc::import(&ctx, &mm, params.contacts).await?;
+    let imported = rows.iter().filter(|r| r.success).count();
+
+    Ok(json!({ "imported": imported, "failed": rows.len() - imported, "results": rows }))
+}
+
+// ============================================
+// Notification RPCs
+// ============================================
+
+/// Page through the caller's notifications, newest first
+async fn rpc_notifications_list(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { limit: Option<i64>, cursor: Option<String>, #[serde(default)] unread_only: bool }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let page = NotificationBmc::list(&ctx, &mm, params.limit, params.cursor.as_deref(), params.unread_only).await?;
+    Ok(json!(page))
+}
+
+/// Mark some of the caller's notifications read
+async fn rpc_notifications_mark_read(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Params { ids: Vec<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+
+    let updated = NotificationBmc::mark_read(&ctx, &mm, &params.ids).await?;
+    Ok(json!({ "updated": updated }))
+}
+
 // ============================================
 // User Administration RPCs (Admin only)
 // ============================================
 
-/// List all users (admin only)
-async fn rpc_admin_list_users(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcError> {
+const MAX_ADMIN_USERS_LIMIT: i64 = 200;
+
+/// List users newest first, a page at a time, with the total count (admin only)
+async fn rpc_admin_list_users(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     // Admin check
     if !ctx.is_admin() {
         return Err(RpcError {
@@ -893,7 +1939,17 @@ async fn rpc_admin_list_users(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcEr
         });
     }
     
-    let users = UserBmc::list_all(mm.db())
+    #[derive(Deserialize, Default)]
+    struct Params { limit: Option<i64>, offset: Option<i64> }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let limit = params.limit.unwrap_or(50).clamp(1, MAX_ADMIN_USERS_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let (users, total) = UserBmc::list_paginated(mm.db(), limit, offset)
         .await
         .map_err(|e| RpcError {
             code: 5000,
@@ -901,7 +1957,7 @@ async fn rpc_admin_list_users(mm: ModelManager, ctx: Ctx) -> Result<Value, RpcEr
             data: None,
         })?;
     
-    Ok(json!({ "users": users }))
+    Ok(json!({ "users": users, "total": total, "limit": limit, "offset": offset }))
 }
 
 /// Set user admin status (admin only)
@@ -974,10 +2030,13 @@ async fn rpc_admin_get_user_stats(mm: ModelManager, ctx: Ctx, params: Option<Val
     
     let stats = UserBmc::get_user_stats(mm.db(), params.user_id)
         .await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to get user stats: {}", e),
-            data: None,
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RpcError { code: 4004, message: "User not found".to_string(), data: None },
+            e => RpcError {
+                code: 5000,
+                message: format!("Failed to get user stats: {}", e),
+                data: None,
+            },
         })?;
     
     Ok(json!(stats))
@@ -1134,8 +2193,8 @@ async fn rpc_admin_cancel_trade(mm: ModelManager, ctx: Ctx, params: Option<Value
     Ok(json!({ "success": true, "message": "Trade cancelled by admin" }))
 }
 
-// Admin delete any trade
-async fn rpc_admin_delete_trade(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+// Admin feature/unfeature a trade on the proposal board (audited)
+async fn rpc_admin_feature_trade(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
     // Admin check
     if !ctx.is_admin() {
         return Err(RpcError {
@@ -1145,21 +2204,1400 @@ async fn rpc_admin_delete_trade(mm: ModelManager, ctx: Ctx, params: Option<Value
         });
     }
     
-    let id = params.as_ref()
-        .and_then(|p| p.get("id"))
-        .and_then(|v| v.as_i64())
-        .ok_or_else(|| RpcError {
-            code: 4000,
-            message: "Missing required parameter: id".to_string(),
-            data: None,
-        })?;
+    #[derive(Deserialize)]
+    struct Params {
+        id: i64,
+        #[serde(default = "default_featured")]
+        featured: bool,
+    }
+    fn default_featured() -> bool { true }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
     
-    TradeBmc::admin_delete(&mm, id).await
-        .map_err(|e| RpcError {
-            code: 5000,
-            message: format!("Failed to delete trade: {:?}", e),
+    TradeBmc::admin_set_featured(&ctx, &mm, params.id, params.featured).await.map_err(|e| match e {
+        Error::NotFound => RpcError { code: 4004, message: "Trade not found".to_string(), data: None },
+        e => RpcError { code: 5000, message: format!("Failed to feature trade: {:?}", e), data: None },
+    })?;
+    
+    Ok(json!({ "success": true, "id": params.id, "is_featured": params.featured }))
+}
+
+// Admin waive or reinstate a matched trade's commitment fee (audited); waiving commits the trade
+async fn rpc_admin_waive_commitment_fee(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
             data: None,
-        })?;
+        });
+    }
     
-    Ok(json!({ "success": true, "message": "Trade deleted by admin" }))
-}
\ No newline at end of file
+    #[derive(Deserialize)]
+    struct Params {
+        trade_id: i64,
+        #[serde(default = "default_waived")]
+        waived: bool,
+        reason: Option<String>,
+    }
+    fn default_waived() -> bool { true }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let status = TransactionBmc::admin_set_fee_waived(&ctx, &mm, params.trade_id, params.waived, params.reason.as_deref())
+        .await?;
+    
+    Ok(json!({ "success": true, "trade_id": params.trade_id, "commitment_fee_waived": params.waived, "status": status }))
+}
+
+// Admin record the broadcast spend releasing a trade's escrow to one side (audited)
+async fn rpc_admin_release_escrow(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        trade_id: i64,
+        recipient: String,
+        recipient_address: String,
+        tx_id: String,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let (_, puzzle_hash) = decode_puzzle_hash(params.recipient_address.trim()).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid recipient_address: {}", e),
+        data: None,
+    })?;
+    let release = TransactionBmc::admin_create_escrow_release(
+        &ctx,
+        &mm,
+        params.trade_id,
+        &params.recipient,
+        &hex::encode(puzzle_hash),
+        &params.tx_id,
+    )
+    .await?;
+    
+    Ok(json!({
+        "success": true,
+        "transaction_id": release.id,
+        "trade_id": release.trade_id,
+        "amount_mojos": release.amount_mojos,
+        "status": release.status,
+    }))
+}
+
+// Admin close a disputed trade as completed, cancelled or refunded (audited)
+async fn rpc_admin_resolve_dispute(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        trade_id: i64,
+        outcome: String,
+        resolution: String,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TradeBmc::admin_resolve_dispute(&ctx, &mm, params.trade_id, &params.outcome, &params.resolution)
+        .await
+        .map_err(|e| match e {
+            Error::NotFound => RpcError { code: 4004, message: "Trade not found".to_string(), data: None },
+            e => e.into(),
+        })?;
+    
+    Ok(json!({ "success": true, "trade_id": params.trade_id, "status": params.outcome }))
+}
+
+// Admin force-confirm a transaction verified out-of-band (audited)
+async fn rpc_admin_confirm_transaction(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        tx_id: String,
+        coin_id: String,
+        confirmations: i32,
+        reason: Option<String>,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    TransactionBmc::admin_confirm(
+        &ctx,
+        &mm,
+        &params.tx_id,
+        &params.coin_id,
+        params.confirmations,
+        params.reason.as_deref(),
+    )
+    .await?;
+    
+    Ok(json!({ "success": true, "message": "Transaction confirmed by admin" }))
+}
+
+// Admin pause or resume a user's trading: no new trades or offers, existing trades continue (audited)
+async fn rpc_admin_set_trading_paused(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        user_id: i64,
+        paused: bool,
+        reason: Option<String>,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let update_error = |e: sqlx::Error| RpcError {
+        code: 5000,
+        message: format!("Failed to update user: {}", e),
+        data: None,
+    };
+    
+    // The pause and its audit row commit together
+    let mut tx = mm.db().begin().await.map_err(update_error)?;
+    let found = UserBmc::set_trading_paused(&mut *tx, params.user_id, params.paused)
+        .await
+        .map_err(update_error)?;
+    if !found {
+        return Err(RpcError { code: 4004, message: "User not found".to_string(), data: None });
+    }
+    
+    let details = json!({ "paused": params.paused, "reason": params.reason });
+    AuditBmc::record_in(&mut tx, &ctx, "set_trading_paused", "user", &params.user_id.to_string(), details).await?;
+    tx.commit().await.map_err(update_error)?;
+    
+    Ok(json!({ "success": true, "user_id": params.user_id, "trading_paused": params.paused }))
+}
+
+// Admin correct a user's reputation: pin a score, shift it by a delta, or clear the pin (audited)
+async fn rpc_admin_adjust_reputation(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        user_id: i64,
+        score: Option<f64>,
+        delta: Option<f64>,
+        #[serde(default)]
+        clear: bool,
+        reason: String,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let adjustment = match (params.score, params.delta, params.clear) {
+        (Some(score), None, false) => ReputationAdjustment::Set(score),
+        (None, Some(delta), false) => ReputationAdjustment::Delta(delta),
+        (None, None, true) => ReputationAdjustment::Clear,
+        _ => {
+            return Err(RpcError {
+                code: -32602,
+                message: "Invalid params: pass exactly one of score, delta or clear".to_string(),
+                data: None,
+            })
+        }
+    };
+    let reason = params.reason.trim();
+    if reason.is_empty() {
+        return Err(RpcError {
+            code: -32602,
+            message: "Invalid params: reason is required".to_string(),
+            data: None,
+        });
+    }
+    
+    let (previous, score) = ReviewBmc::admin_adjust_reputation(&ctx, &mm, params.user_id, adjustment, reason)
+        .await
+        .map_err(|e| match e {
+            Error::NotFound => RpcError { code: 4004, message: "User not found".to_string(), data: None },
+            e => e.into(),
+        })?;
+    
+    Ok(json!({
+        "success": true,
+        "user_id": params.user_id,
+        "previous_score": previous,
+        "reputation_score": score,
+        "reputation_override": !params.clear,
+    }))
+}
+
+const MAX_FEED_LIMIT: i64 = 200;
+
+// Admin time-ordered feed of platform activity
+async fn rpc_admin_activity_feed(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    let limit = params.as_ref()
+        .and_then(|p| p.get("limit"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(50)
+        .clamp(1, MAX_FEED_LIMIT);
+    let offset = params.as_ref()
+        .and_then(|p| p.get("offset"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0)
+        .max(0);
+    
+    let items = ActivityBmc::feed(&mm, limit, offset).await.map_err(|e| RpcError {
+        code: 5000,
+        message: format!("Database error: {}", e),
+        data: None,
+    })?;
+    
+    Ok(json!({ "items": items, "limit": limit, "offset": offset }))
+}
+
+/// Probe the full node and wallet connections together, each with its own URL and SSL config
+async fn rpc_chia_check_all(app_state: Arc<AppState>, ctx: Ctx) -> Result<Value, RpcError> {
+    crate::api::wallet_rpc::check_passthrough_limit(&app_state, &ctx)?;
+    let (full_node, wallet) = crate::api::chia::check_all_modes(app_state).await;
+    Ok(json!({ "full_node": full_node, "wallet": wallet }))
+}
+
+// Admin view of the background transaction verifier
+async fn rpc_admin_verifier_status(app_state: Arc<AppState>, ctx: Ctx) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    let (status, healthy) = crate::api::verify::verifier_health(&app_state).await;
+    Ok(json!({
+        "healthy": healthy,
+        "interval_secs": crate::api::verify::VerifierConfig::from_env().interval.as_secs(),
+        "status": status,
+    }))
+}
+
+// Admin view of the audit trail for a target
+async fn rpc_admin_audit_log(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    #[derive(Deserialize)]
+    struct Params {
+        target_type: String,
+        target_id: String,
+    }
+    let params: Params = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })?;
+    
+    let entries = AuditBmc::list_for_target(&mm, &params.target_type, &params.target_id)
+        .await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Failed to list audit log: {}", e),
+            data: None,
+        })?;
+    
+    Ok(json!({ "entries": entries }))
+}
+
+// Admin delete any trade
+async fn rpc_admin_delete_trade(mm: ModelManager, ctx: Ctx, params: Option<Value>) -> Result<Value, RpcError> {
+    // Admin check
+    if !ctx.is_admin() {
+        return Err(RpcError {
+            code: 4003,
+            message: "Admin access required".to_string(),
+            data: None,
+        });
+    }
+    
+    let id = params.as_ref()
+        .and_then(|p| p.get("id"))
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| RpcError {
+            code: 4000,
+            message: "Missing required parameter: id".to_string(),
+            data: None,
+        })?;
+    
+    TradeBmc::admin_delete(&mm, id).await
+        .map_err(|e| RpcError {
+            code: 5000,
+            message: format!("Failed to delete trade: {:?}", e),
+            data: None,
+        })?;
+    
+    Ok(json!({ "success": true, "message": "Trade deleted by admin" }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, commitment_fee, proposal_with_value, xch_offer};
+
+    const EXCHANGE_ADDRESS: &str = "xch1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqm6ks6e8mvy";
+
+    /// Register and log in through the RPC layer, resolving the Ctx the way `mw_ctx_resolve` does
+    async fn register_and_login(mm: &ModelManager, username: &str) -> Ctx {
+        let creds = json!({ "username": username, "pwd": "welcome123" });
+        crate::api::auth::rpc_register(mm.clone(), Some(creds.clone())).await.unwrap();
+        let login = crate::api::auth::rpc_login(mm.clone(), Some(creds)).await.unwrap();
+
+        let token = login["token"].as_str().unwrap();
+        let user_id = crate::api::auth::validate_token(token).unwrap().user_id;
+        let user = UserBmc::first_by_id_for_auth(mm.db(), user_id).await.unwrap();
+        Ctx::new_with_admin(user.id, user.username, user.is_admin)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_e2e_register_login_create_accept() {
+        _dev_utils::ensure_token_secret();
+        let state = price_state(30.0).await;
+        let mm = _dev_utils::init_test().await;
+        let proposer = register_and_login(&mm, "proposer").await;
+        let acceptor = register_and_login(&mm, "acceptor").await;
+
+        let created = rpc_trade_create(
+            mm.clone(),
+            proposer.clone(),
+            Some(json!({
+                "item_title": "Guitar",
+                "item_description": "Acoustic, lightly used",
+                "item_value_usd": 300.0
+            })),
+        )
+        .await
+        .unwrap();
+        let trade_id = created["trade_id"].as_i64().unwrap();
+
+        let res = rpc_trade_accept(
+            mm.clone(),
+            state.clone(),
+            acceptor.clone(),
+            Some(json!({ "trade_id": trade_id, "offer_type": "xch", "xch_amount": 2_000_000_000_000i64 })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(res["status"], "pending");
+        assert_eq!(TradeBmc::get_public(&mm, trade_id).await.unwrap().status, "proposal");
+
+        // Only the proposer can take the bidder's offer
+        let accept = json!({ "offer_id": res["offer_id"] });
+        let err = rpc_trade_accept_offer(mm.clone(), acceptor.clone(), Some(accept.clone())).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+        let res = rpc_trade_accept_offer(mm.clone(), proposer.clone(), Some(accept)).await.unwrap();
+        assert_eq!(res["status"], "matched");
+
+        let trade = TradeBmc::get(&proposer, &mm, trade_id).await.unwrap();
+        assert_eq!(trade.status, "matched");
+        assert_eq!(trade.acceptor_id, Some(acceptor.user_id()));
+        assert_eq!(trade.trade_type.as_deref(), Some("item_for_xch"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_proposer_trade_accept_takes_waiting_offer() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let carol = _dev_utils::seed_ctx(&mm, "carol_accept").await;
+        let state = price_state(30.0).await;
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(150.0)).await.unwrap();
+        let own = json!({ "trade_id": trade_id });
+
+        let err = rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(own.clone())).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+
+        let bid = json!({ "trade_id": trade_id, "offer_type": "xch", "xch_amount": 5_000_000_000_000i64 });
+        let bob_offer = rpc_trade_accept(mm.clone(), state.clone(), bob.clone(), Some(bid.clone())).await.unwrap();
+        let carol_offer = rpc_trade_accept(mm.clone(), state.clone(), carol.clone(), Some(bid)).await.unwrap();
+
+        // Two bids waiting: the proposer has to pick one
+        let err = rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(own)).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+        let stray = json!({ "trade_id": trade_id, "offer_id": 999_999 });
+        let err = rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(stray)).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+
+        let pick = json!({ "trade_id": trade_id, "offer_id": carol_offer["offer_id"] });
+        let res = rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(pick)).await.unwrap();
+        assert_eq!(res["status"], "matched");
+        assert_eq!(res["offer_id"], carol_offer["offer_id"]);
+        let trade = TradeBmc::get(&alice, &mm, trade_id).await.unwrap();
+        assert_eq!(trade.acceptor_id, Some(carol.user_id()));
+        assert_ne!(bob_offer["offer_id"], carol_offer["offer_id"]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_user_profile_unauthenticated() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let state = price_state(30.0).await;
+
+        // Complete a trade so bob can review alice
+        let created = rpc_trade_create(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "item_title": "Bike", "item_description": "Road bike", "item_value_usd": 250.0 })),
+        )
+        .await
+        .unwrap();
+        let trade_id = created["trade_id"].as_i64().unwrap();
+        let bid = json!({ "trade_id": trade_id, "offer_type": "xch", "xch_amount": 1_000_000_000_000i64 });
+        let offer = rpc_trade_accept(mm.clone(), state.clone(), bob.clone(), Some(bid)).await.unwrap();
+        rpc_trade_accept_offer(mm.clone(), alice.clone(), Some(json!({ "offer_id": offer["offer_id"] }))).await.unwrap();
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(trade_id).execute(mm.db()).await.unwrap();
+        TradeBmc::update_status(&alice, &mm, trade_id, "completed", None).await.unwrap();
+        let review = json!({
+            "trade_id": trade_id, "timeliness": 5, "packaging": 4, "value_honesty": 5, "state_accuracy": 4
+        });
+        rpc_trade_review(mm.clone(), bob.clone(), Some(review)).await.unwrap();
+
+        let res = rpc_user_profile(mm.clone(), Some(json!({ "user_id": alice.user_id() }))).await.unwrap();
+        assert_eq!(res["profile"]["username"], "alice");
+        assert_eq!(res["review_summary"]["review_count"], 1);
+        assert_eq!(res["review_summary"]["avg_timeliness"], 5.0);
+        assert_eq!(res["reviews"].as_array().unwrap().len(), 1);
+
+        let missing = rpc_user_profile(mm.clone(), Some(json!({ "user_id": 999_999 }))).await;
+        assert_eq!(missing.unwrap_err().code, 4004);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_admin_force_confirm_is_audited() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+
+        let created = rpc_trade_create(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "item_title": "Desk", "item_description": "Oak desk", "item_value_usd": 90.0 })),
+        )
+        .await
+        .unwrap();
+        let trade_id = created["trade_id"].as_i64().unwrap();
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xstuck").await.unwrap();
+
+        let params = json!({ "tx_id": "0xstuck", "coin_id": "0xcoin", "confirmations": 32, "reason": "node desync" });
+
+        // Non-admins are rejected
+        let denied = rpc_admin_confirm_transaction(mm.clone(), bob.clone(), Some(params.clone())).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+
+        rpc_admin_confirm_transaction(mm.clone(), admin.clone(), Some(params.clone())).await.unwrap();
+
+        let tx = TransactionBmc::get_by_tx_id(&admin, &mm, "0xstuck").await.unwrap().unwrap();
+        assert_eq!(tx.status, "confirmed");
+        assert_eq!(tx.coin_id.as_deref(), Some("0xcoin"));
+        assert_eq!(tx.confirmations, Some(32));
+
+        let audit = rpc_admin_audit_log(
+            mm.clone(),
+            admin.clone(),
+            Some(json!({ "target_type": "trade_transaction", "target_id": "0xstuck" })),
+        )
+        .await
+        .unwrap();
+        let entries = audit["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "confirm_transaction");
+        assert_eq!(entries[0]["admin_id"], admin.user_id());
+        assert_eq!(entries[0]["details"]["reason"], "node desync");
+
+        // Already confirmed: guarded, and nothing more is audited
+        let again = rpc_admin_confirm_transaction(mm.clone(), admin.clone(), Some(params)).await;
+        assert_eq!(again.unwrap_err().code, 4000);
+        let missing = json!({ "tx_id": "0xmissing", "coin_id": "0xcoin", "confirmations": 1 });
+        let err = rpc_admin_confirm_transaction(mm.clone(), admin.clone(), Some(missing)).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+        assert_eq!(AuditBmc::list_for_target(&mm, "trade_transaction", "0xstuck").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_paused_user_keeps_existing_trades() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let state = price_state(30.0).await;
+        let listing = json!({ "item_title": "Amp", "item_description": "Tube amp", "item_value_usd": 200.0 });
+
+        let trade_id = create_listing(&mm, &alice).await;
+        match_listing(&mm, &state, &alice, &bob, trade_id).await;
+        let other = rpc_trade_create(mm.clone(), bob.clone(), Some(listing.clone())).await.unwrap();
+
+        let pause = json!({ "user_id": alice.user_id(), "paused": true, "reason": "chargeback review" });
+        let denied = rpc_admin_set_trading_paused(mm.clone(), bob.clone(), Some(pause.clone())).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+        rpc_admin_set_trading_paused(mm.clone(), admin.clone(), Some(pause)).await.unwrap();
+
+        // No new trades or offers
+        let err = rpc_trade_create(mm.clone(), alice.clone(), Some(listing)).await.unwrap_err();
+        assert_eq!((err.code, err.message.as_str()), (4006, "Trading paused"));
+        let offer = json!({ "trade_id": other["trade_id"], "offer_type": "xch", "xch_amount": 1_000_000_000_000i64 });
+        let err = rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(offer.clone())).await.unwrap_err();
+        assert_eq!(err.code, 4006);
+
+        // The existing trade can still be read and finished
+        let res = rpc_trade_get(mm.clone(), alice.clone(), Some(json!({ "id": trade_id }))).await.unwrap();
+        assert_eq!(res["trade"]["status"], "matched");
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(trade_id).execute(mm.db()).await.unwrap();
+        rpc_trade_complete(mm.clone(), alice.clone(), Some(json!({ "trade_id": trade_id }))).await.unwrap();
+
+        let users = rpc_admin_list_users(mm.clone(), admin.clone(), None).await.unwrap();
+        let listed = users["users"].as_array().unwrap().iter().find(|u| u["id"] == alice.user_id()).unwrap();
+        assert_eq!(listed["trading_paused"], true);
+
+        let resume = json!({ "user_id": alice.user_id(), "paused": false });
+        rpc_admin_set_trading_paused(mm.clone(), admin.clone(), Some(resume)).await.unwrap();
+        rpc_trade_accept(mm.clone(), state.clone(), alice.clone(), Some(offer)).await.unwrap();
+
+        let missing = json!({ "user_id": i64::MAX, "paused": true });
+        let err = rpc_admin_set_trading_paused(mm.clone(), admin, Some(missing)).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+        assert!(AuditBmc::list_for_target(&mm, "user", &i64::MAX.to_string()).await.unwrap().is_empty());
+
+        // Pause and resume were each audited
+        let audit = AuditBmc::list_for_target(&mm, "user", &alice.user_id().to_string()).await.unwrap();
+        let paused: Vec<&serde_json::Value> = audit.iter().map(|e| &e.details["paused"]).collect();
+        assert_eq!(paused, [&json!(false), &json!(true)]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_admin_user_stats_unknown_user() {
+        let (mm, alice, _bob, admin) = _dev_utils::init_test_with_admin().await;
+
+        let res = rpc_admin_get_user_stats(mm.clone(), admin.clone(), Some(json!({ "user_id": alice.user_id() })));
+        assert_eq!(res.await.unwrap()["total_trades"], 0);
+
+        let err = rpc_admin_get_user_stats(mm.clone(), admin, Some(json!({ "user_id": i64::MAX }))).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+        assert_eq!(err.message, "User not found");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_admin_adjust_reputation_is_audited() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let target = alice.user_id();
+
+        let params = json!({ "user_id": target, "score": 1.5, "reason": "fraudulent reviews removed" });
+        let denied = rpc_admin_adjust_reputation(mm.clone(), bob, Some(params.clone())).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+
+        let res = rpc_admin_adjust_reputation(mm.clone(), admin.clone(), Some(params)).await.unwrap();
+        assert_eq!(res["reputation_score"], 1.5);
+        assert_eq!(res["reputation_override"], true);
+
+        // Exactly one adjustment, and a reason, are required
+        for bad in [
+            json!({ "user_id": target, "score": 2.0, "delta": 1.0, "reason": "both" }),
+            json!({ "user_id": target, "reason": "neither" }),
+            json!({ "user_id": target, "delta": 1.0, "reason": "  " }),
+        ] {
+            let err = rpc_admin_adjust_reputation(mm.clone(), admin.clone(), Some(bad)).await.unwrap_err();
+            assert_eq!(err.code, -32602);
+        }
+        let missing = json!({ "user_id": i64::MAX, "clear": true, "reason": "typo" });
+        let err = rpc_admin_adjust_reputation(mm.clone(), admin.clone(), Some(missing)).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+
+        let audit = rpc_admin_audit_log(
+            mm.clone(),
+            admin.clone(),
+            Some(json!({ "target_type": "user", "target_id": target.to_string() })),
+        )
+        .await
+        .unwrap();
+        let entries = audit["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "adjust_reputation");
+        assert_eq!(entries[0]["details"]["new_score"], 1.5);
+        assert_eq!(entries[0]["details"]["reason"], "fraudulent reviews removed");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_dispute_rpcs() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let state = price_state(30.0).await;
+        let trade_id = create_listing(&mm, &alice).await;
+
+        // A proposal can't be disputed
+        let dispute = json!({ "trade_id": trade_id, "reason": "Lens is cracked" });
+        let err = rpc_trade_raise_dispute(mm.clone(), alice.clone(), Some(dispute.clone())).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+
+        match_listing(&mm, &state, &alice, &bob, trade_id).await;
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(trade_id).execute(mm.db()).await.unwrap();
+        let res = rpc_trade_raise_dispute(mm.clone(), bob.clone(), Some(dispute)).await.unwrap();
+        assert_eq!(res["status"], "disputed");
+        let blank = json!({ "trade_id": trade_id, "reason": " " });
+        let err = rpc_trade_raise_dispute(mm.clone(), alice.clone(), Some(blank)).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+
+        let resolve = json!({ "trade_id": trade_id, "outcome": "cancelled", "resolution": "Both sides agreed to unwind" });
+        let denied = rpc_admin_resolve_dispute(mm.clone(), alice.clone(), Some(resolve.clone())).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+        let res = rpc_admin_resolve_dispute(mm.clone(), admin.clone(), Some(resolve.clone())).await.unwrap();
+        assert_eq!(res["status"], "cancelled");
+        let again = rpc_admin_resolve_dispute(mm.clone(), admin.clone(), Some(resolve)).await;
+        assert_eq!(again.unwrap_err().code, 4000);
+        let missing = json!({ "trade_id": i64::MAX, "outcome": "refunded", "resolution": "n/a" });
+        assert_eq!(rpc_admin_resolve_dispute(mm.clone(), admin.clone(), Some(missing)).await.unwrap_err().code, 4004);
+
+        let audit = rpc_admin_audit_log(
+            mm.clone(),
+            admin.clone(),
+            Some(json!({ "target_type": "trade", "target_id": trade_id.to_string() })),
+        )
+        .await
+        .unwrap();
+        let entries = audit["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "resolve_dispute");
+        assert_eq!(entries[0]["details"]["outcome"], "cancelled");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_trade_can_commit() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let state = price_state(30.0).await;
+        let trade_id = create_listing(&mm, &alice).await;
+        let check = json!({ "trade_id": trade_id });
+
+        let res = rpc_trade_can_commit(mm.clone(), alice.clone(), Some(check.clone())).await.unwrap();
+        assert_eq!(res["can_commit"], false);
+        assert_eq!(res["reason"], "Trade status 'proposal' does not allow commitment. Must be 'matched'.");
+        // Not a participant yet
+        assert_eq!(rpc_trade_can_commit(mm.clone(), bob.clone(), Some(check.clone())).await.unwrap_err().code, 4001);
+
+        match_listing(&mm, &state, &alice, &bob, trade_id).await;
+        for ctx in [&alice, &bob] {
+            let res = rpc_trade_can_commit(mm.clone(), ctx.clone(), Some(check.clone())).await.unwrap();
+            assert_eq!(res, json!({ "can_commit": true, "reason": null }));
+        }
+
+        // Eligible to pay, but trade_commit can't skip the fee
+        let err = rpc_trade_commit(mm.clone(), alice.clone(), Some(check)).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_admin_waive_commitment_fee() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let state = price_state(30.0).await;
+        TransactionBmc::set_exchange_wallet(&admin, &mm, "xch1exchange").await.unwrap();
+        let trade_id = create_listing(&mm, &alice).await;
+        match_listing(&mm, &state, &alice, &bob, trade_id).await;
+
+        let waive = json!({ "trade_id": trade_id, "reason": "launch promotion" });
+        let denied = rpc_admin_waive_commitment_fee(mm.clone(), alice.clone(), Some(waive.clone())).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+        let res = rpc_admin_waive_commitment_fee(mm.clone(), admin.clone(), Some(waive)).await.unwrap();
+        assert_eq!((res["commitment_fee_waived"].clone(), res["status"].clone()), (json!(true), json!("escrow")));
+
+        // No fee to pay
+        let details = rpc_commitment_get_details(mm.clone(), bob.clone(), Some(json!({ "trade_id": trade_id }))).await.unwrap();
+        assert_eq!(details["commitment_fee_usd"], 0.0);
+        let pending = json!({ "trade_id": trade_id, "amount_mojos": 5_000 });
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let err = rpc_commitment_create_pending(mm.clone(), state, bob.clone(), Some(pending)).await.unwrap_err();
+        assert_eq!((err.code, err.message.as_str()), (4000, "Commitment fee waived for this trade"));
+
+        let again = rpc_admin_waive_commitment_fee(mm.clone(), admin.clone(), Some(json!({ "trade_id": trade_id }))).await;
+        assert_eq!(again.unwrap_err().code, 4000);
+
+        let audit = rpc_admin_audit_log(
+            mm.clone(),
+            admin.clone(),
+            Some(json!({ "target_type": "trade", "target_id": trade_id.to_string() })),
+        )
+        .await
+        .unwrap();
+        let entries = audit["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "waive_commitment_fee");
+        assert_eq!(entries[0]["details"]["reason"], "launch promotion");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_commitment_details_error_codes() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = _dev_utils::seed_proposal(&mm, &alice).await;
+
+        let carol = _dev_utils::seed_ctx(&mm, "carol_details").await;
+        let err = rpc_commitment_get_details(mm.clone(), carol, Some(json!({ "trade_id": trade_id }))).await.unwrap_err();
+        assert_eq!(err.code, 4001);
+
+        let err = rpc_commitment_get_details(mm.clone(), alice, Some(json!({ "trade_id": i64::MAX }))).await.unwrap_err();
+        assert_eq!(err.code, 4004);
+        assert_eq!(err.message, "Trade not found");
+    }
+
+    #[test]
+    fn test_check_exchange_address_network() {
+        assert!(check_exchange_address(EXCHANGE_ADDRESS, "xch").is_ok());
+
+        let testnet = "txch1umpes4mkuj48yjdhxw6c34anla7vakaq20l2srhzwu3xa9a833as4hf6zj";
+        assert!(check_exchange_address(testnet, "txch").is_ok());
+        let err = check_exchange_address(testnet, "xch").unwrap_err();
+        assert_eq!(err.message, "Invalid XCH address: expected prefix 'xch', got 'txch'");
+
+        let garbage = format!("xch1{}", "b".repeat(58));
+        assert_eq!(check_exchange_address(&garbage, "xch").unwrap_err().code, -32602);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_commitment_pending_server_side_mojos() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        TransactionBmc::set_exchange_wallet(&alice, &mm, EXCHANGE_ADDRESS).await.unwrap();
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(40.0)).await.unwrap();
+        let offer = xch_offer(trade_id);
+        _dev_utils::match_trade(&mm, &bob, offer).await;
+
+        // The server prices XCH at $3: the default $1 fee is 333_333_333_334 mojos, rounded up
+        let state = price_state(3.0).await;
+
+        // 40% short of the server figure
+        let err = rpc_commitment_create_pending(
+            mm.clone(),
+            state.clone(),
+            alice.clone(),
+            Some(json!({ "trade_id": trade_id, "amount_mojos": 200_000_000_000i64 })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, 4000);
+        assert_eq!(err.data.unwrap()["expected_mojos"], 333_333_333_334i64);
+
+        // A client price 3% off converts to an amount within tolerance
+        let res = rpc_commitment_create_pending(
+            mm.clone(),
+            state.clone(),
+            alice.clone(),
+            Some(json!({ "trade_id": trade_id, "xch_price_usd": 3.1, "price_source": "coingecko",
+                         "price_timestamp": "2024-01-01T00:00:00Z" })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(res["amount_mojos"], 322_580_645_162i64);
+        assert_eq!(res["price"]["source"], "coingecko");
+        assert_eq!(res["price"]["timestamp"], "2024-01-01T00:00:00Z");
+        assert_eq!(res["price"]["rounding"], "ceil");
+
+        // Without an amount or price the server's own quote is used
+        let res = rpc_commitment_create_pending(mm.clone(), state, bob, Some(json!({ "trade_id": trade_id })))
+            .await
+            .unwrap();
+        assert_eq!(res["amount_mojos"], 333_333_333_334i64);
+        assert_eq!(res["price"]["source"], "server");
+        assert_eq!(res["price"]["xch_price_usd"], 3.0);
+    }
+
+    /// App state whose price provider quotes XCH at `usd` from a local mock
+    async fn price_state(usd: f64) -> Arc<AppState> {
+        use axum::{routing::get, Router};
+
+        let router = Router::new().route("/price", get(move || async move { Json(json!({ "chia": { "usd": usd } })) }));
+        let url = format!("{}/price", _dev_utils::spawn_mock_node(router).await);
+        let provider = XchPriceProvider::new(&url, std::time::Duration::from_secs(60), 5.0);
+        Arc::new(AppState::new("http://localhost:8555".to_string()).with_price_provider(provider))
+    }
+
+    /// Create a $200 "Amp" listing as `proposer` and return its trade id
+    async fn create_listing(mm: &ModelManager, proposer: &Ctx) -> i64 {
+        let listing = json!({ "item_title": "Amp", "item_description": "Tube amp", "item_value_usd": 200.0 });
+        let created = rpc_trade_create(mm.clone(), proposer.clone(), Some(listing)).await.unwrap();
+        created["trade_id"].as_i64().unwrap()
+    }
+
+    /// Bid 1 XCH on `trade_id` as `bidder` and have `proposer` accept it
+    async fn match_listing(mm: &ModelManager, state: &Arc<AppState>, proposer: &Ctx, bidder: &Ctx, trade_id: i64) {
+        let bid = json!({ "trade_id": trade_id, "offer_type": "xch", "xch_amount": 1_000_000_000_000i64 });
+        let offer = rpc_trade_accept(mm.clone(), state.clone(), bidder.clone(), Some(bid)).await.unwrap();
+        let accept = json!({ "offer_id": offer["offer_id"] });
+        rpc_trade_accept_offer(mm.clone(), proposer.clone(), Some(accept)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_affordability_check_reports_shortfall() {
+        use axum::{routing::post, Router};
+
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        TransactionBmc::set_exchange_wallet(&alice, &mm, EXCHANGE_ADDRESS).await.unwrap();
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(25.0)).await.unwrap();
+        let offer = xch_offer(trade_id);
+        _dev_utils::match_trade(&mm, &bob, offer).await;
+
+        // Wallet 1 can cover the fee, wallet 2 has a larger confirmed balance but little spendable
+        let router = Router::new().route(
+            "/get_wallet_balance",
+            post(|Json(body): Json<Value>| async move {
+                let (confirmed, spendable) = match body["wallet_id"].as_u64() {
+                    Some(1) => (500_000_000_000u64, 400_000_000_000u64),
+                    _ => (500_000_000_000, 100_000_000_000),
+                };
+                Json(json!({
+                    "wallet_balance": { "confirmed_wallet_balance": confirmed, "spendable_balance": spendable },
+                    "success": true
+                }))
+            }),
+        );
+        let client = ChiaRpcClient::new(_dev_utils::spawn_mock_node(router).await);
+        let state = price_state(3.0).await;
+        let prices = state.price_provider();
+
+        // Default $1 fee at the server's $3/XCH; a client price is ignored
+        let params = json!({ "trade_id": trade_id, "xch_price_usd": 1000.0 });
+        let res = affordability_check(&mm, &alice, &client, prices, Some(params)).await.unwrap();
+        assert_eq!(
+            res,
+            json!({ "affordable": true, "fee_mojos": 333_333_333_334i64,
+                    "spendable_mojos": 400_000_000_000i64, "shortfall_mojos": 0 })
+        );
+
+        let params = json!({ "trade_id": trade_id, "wallet_id": 2 });
+        let res = affordability_check(&mm, &alice, &client, prices, Some(params)).await.unwrap();
+        assert_eq!(res["affordable"], false);
+        assert_eq!(res["spendable_mojos"], 100_000_000_000i64);
+        assert_eq!(res["shortfall_mojos"], 233_333_333_334i64);
+    }
+
+    /// POST `body` to `rpc_handler` and return the JSON-RPC response body
+    async fn post_rpc(mm: &ModelManager, state: &Arc<AppState>, ctx: Option<&Ctx>, body: Value) -> Value {
+        let res = rpc_handler(
+            State(mm.clone()),
+            State(state.clone()),
+            OptionCtx(ctx.cloned()),
+            None,
+            Json(body),
+        )
+        .await
+        .into_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    /// Dispatch a parameterless method through `rpc_handler`
+    async fn call(mm: &ModelManager, state: &Arc<AppState>, ctx: &Ctx, method: &str) -> Value {
+        post_rpc(mm, state, Some(ctx), json!({ "id": 1, "method": method })).await
+    }
+
+    /// A ModelManager whose pool never connects, for paths that must not reach the database
+    fn offline_mm() -> ModelManager {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .unwrap();
+        ModelManager::new(db)
+    }
+
+    #[tokio::test]
+    async fn test_logout_requires_session() {
+        // An unauthenticated logout is refused before any query
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let res = post_rpc(&offline_mm(), &state, None, json!({ "id": 1, "method": "logout" })).await;
+        assert_eq!(res["error"]["code"], 4001);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_echoed_on_error() {
+        use crate::api::mw_panic::{mw_request_id, REQUEST_ID_HEADER};
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let router = axum::Router::new()
+            .route("/api/rpc", axum::routing::post(rpc_handler))
+            .with_state(RpcState(offline_mm(), state))
+            .layer(axum::middleware::from_fn(mw_request_id));
+        let url = format!("{}/api/rpc", _dev_utils::spawn_mock_node(router).await);
+        let client = reqwest::Client::new();
+
+        let res = client
+            .post(&url)
+            .header(REQUEST_ID_HEADER, "req-280")
+            .json(&json!({ "id": 1, "method": "logout" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.headers()[REQUEST_ID_HEADER], "req-280");
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["error"]["code"], 4001);
+        assert_eq!(body["error"]["data"], json!({ "request_id": "req-280" }));
+
+        // Every failed entry of a batch carries it
+        let batch = json!([{ "id": 1, "method": "no_such_method" }, { "id": 2, "method": "user_set_public_key" }]);
+        let body: Value = client.post(&url).header(REQUEST_ID_HEADER, "req-281").json(&batch).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body[0]["error"]["data"]["request_id"], "req-281");
+        assert_eq!(body[1]["error"]["data"]["request_id"], "req-281");
+
+        // Existing error data keeps its fields; successes are left alone
+        let error = RpcError { code: 4000, message: "bad".to_string(), data: Some(json!({ "fields": ["x"] })) };
+        let res = with_request_id(RpcResponse { id: None, result: None, error: Some(error) }, Some("req-282"));
+        assert_eq!(res.error.unwrap().data, Some(json!({ "fields": ["x"], "request_id": "req-282" })));
+        let res = with_request_id(RpcResponse { id: None, result: Some(json!(1)), error: None }, Some("req-282"));
+        assert_eq!(res.result, Some(json!(1)));
+    }
+
+    #[tokio::test]
+    async fn test_batch_shape_and_invalid_entries() {
+        let mm = offline_mm();
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let ctx = Ctx::new(7, "alice".to_string());
+
+        // A single object still gets a single object back
+        let res = post_rpc(&mm, &state, Some(&ctx), json!({ "id": "a", "method": "user_me" })).await;
+        assert_eq!(res["id"], "a");
+        assert_eq!(res["result"]["user"]["id"], 7);
+
+        let res = post_rpc(&mm, &state, Some(&ctx), json!([])).await;
+        assert_eq!(res["error"]["code"], -32600);
+        let too_many: Vec<Value> = (0..=MAX_BATCH_LEN).map(|i| json!({ "id": i, "method": "user_me" })).collect();
+        let res = post_rpc(&mm, &state, Some(&ctx), Value::Array(too_many)).await;
+        assert_eq!(res["error"]["code"], -32600);
+        let res = post_rpc(&mm, &state, Some(&ctx), json!("user_me")).await;
+        assert_eq!(res["error"]["code"], -32600);
+
+        // Bad entries fail on their own, in place
+        let batch = json!([
+            { "id": 1, "method": "user_me" },
+            42,
+            { "id": 3, "method": "no_such_method" },
+            { "id": 4, "method": "user_me" },
+        ]);
+        let res = post_rpc(&mm, &state, Some(&ctx), batch).await;
+        let res = res.as_array().unwrap();
+        assert_eq!(res.len(), 4);
+        assert_eq!(res[0]["result"]["user"]["username"], "alice");
+        assert_eq!(res[1]["error"]["code"], -32600);
+        assert_eq!((res[2]["id"].clone(), res[2]["error"]["code"].clone()), (json!(3), json!(-32601)));
+        assert_eq!(res[3]["id"], 4);
+        assert!(res[3]["error"].is_null());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_batch_page_load() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let created = rpc_trade_create(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "item_title": "Lamp", "item_description": "Brass desk lamp", "item_value_usd": 40.0 })),
+        )
+        .await
+        .unwrap();
+
+        let batch = json!([
+            { "id": "me", "method": "user_me" },
+            { "id": "trades", "method": "trade_my_trades" },
+            { "id": "missing", "method": "trade_get", "params": { "id": -1 } },
+            { "id": "wallet", "method": "config_get_exchange_wallet" },
+        ]);
+        let res = post_rpc(&mm, &state, Some(&alice), batch).await;
+        let ids: Vec<&str> = res.as_array().unwrap().iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["me", "trades", "missing", "wallet"]);
+        assert_eq!(res[0]["result"]["user"]["id"], alice.user_id());
+        assert_eq!(res[1]["result"]["trades"][0]["id"], created["trade_id"]);
+        // A failing entry doesn't take the ones after it down
+        assert!(res[2]["error"]["code"].is_number());
+        assert_eq!(res[3]["result"]["commitment_fee_usd"], 1.0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_passthrough_rate_limited_per_user() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        // Unreachable node: calls fail fast, but still spend tokens
+        let state = Arc::new(AppState::new("http://0.0.0.0:1".to_string()).with_passthrough_limit(2, 1));
+
+        for _ in 0..2 {
+            let res = call(&mm, &state, &alice, "get_sync_status").await;
+            assert_ne!(res["error"]["code"], 4029);
+        }
+        let res = call(&mm, &state, &alice, "get_sync_status").await;
+        assert_eq!(res["error"]["code"], 4029);
+        // Probing both connections draws from the same bucket
+        let res = call(&mm, &state, &alice, "chia_check_all").await;
+        assert_eq!(res["error"]["code"], 4029);
+
+        // Other users and regular RPCs are unaffected
+        let res = call(&mm, &state, &bob, "get_sync_status").await;
+        assert_ne!(res["error"]["code"], 4029);
+        let res = call(&mm, &state, &alice, "user_me").await;
+        assert!(res["error"].is_null());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_disabled_methods_rejected() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(
+            AppState::new("http://localhost:8555".to_string()).with_disabled_rpc_methods(" contract_create, ,admin_list_users"),
+        );
+
+        let res = call(&mm, &state, &alice, "contract_create").await;
+        assert_eq!(res["error"]["code"], -32601);
+        let admin = Ctx::new_with_admin(alice.user_id(), "alice".to_string(), true);
+        let res = call(&mm, &state, &admin, "admin_list_users").await;
+        assert_eq!(res["error"]["code"], -32601);
+
+        let res = call(&mm, &state, &alice, "user_me").await;
+        assert!(res["error"].is_null());
+        let res = call(&mm, &state, &alice, "contract_list").await;
+        assert!(res["error"].is_null());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_config_update_settings_is_audited() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let admin = Ctx::new_with_admin(alice.user_id(), "alice".to_string(), true);
+
+        let res = rpc_config_update_settings(mm.clone(), admin.clone(), Some(json!({ "min_confirmations": 12 })))
+            .await
+            .unwrap();
+        assert_eq!(res["settings"]["min_confirmations"], 12);
+
+        // Rejected updates change nothing and leave no audit row
+        let err = rpc_config_update_settings(mm.clone(), admin.clone(), Some(json!({ "min_confirmations": 0 })))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 4000);
+
+        let audit = AuditBmc::list_for_target(&mm, "exchange_settings", "1").await.unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "update_settings");
+        assert_eq!(audit[0].details["before"]["min_confirmations"], 6);
+        assert_eq!(audit[0].details["after"]["min_confirmations"], 12);
+        assert_eq!(SettingsBmc::get(&mm).await.unwrap().min_confirmations, 12);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_maintenance_mode_blocks_mutations_only() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        let admin = Ctx::new_with_admin(alice.user_id(), "alice".to_string(), true);
+
+        let err = rpc_admin_set_maintenance_mode(mm.clone(), alice.clone(), Some(json!({ "enabled": true })))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 4003);
+        let res = rpc_admin_set_maintenance_mode(mm.clone(), admin.clone(), Some(json!({ "enabled": true })))
+            .await
+            .unwrap();
+        assert_eq!(res["maintenance_mode"], true);
+
+        for method in MAINTENANCE_BLOCKED_METHODS {
+            let res = call(&mm, &state, &alice, method).await;
+            assert_eq!(res["error"]["code"], 4503, "{} should be blocked", method);
+        }
+        for method in ["trade_my_trades", "notifications_list", "user_me"] {
+            let res = call(&mm, &state, &alice, method).await;
+            assert!(res["error"].is_null(), "{} should still work: {}", method, res);
+        }
+        let res = call(&mm, &state, &admin, "admin_list_users").await;
+        assert!(res["error"].is_null());
+
+        // Switched off, mutations reach their handlers again (and fail on the missing params)
+        rpc_admin_set_maintenance_mode(mm.clone(), admin, Some(json!({ "enabled": false }))).await.unwrap();
+        let res = call(&mm, &state, &alice, "trade_create").await;
+        assert_eq!(res["error"]["code"], -32602);
+
+        // Only the admin's two switches were audited
+        let audit = AuditBmc::list_for_target(&mm, "exchange_settings", "1").await.unwrap();
+        let enabled: Vec<&Value> = audit.iter().map(|e| &e.details["enabled"]).collect();
+        assert_eq!(enabled, [&json!(false), &json!(true)]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_trade_reads_include_wishlist() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let wishlist = json!([
+            { "wishlist_type": "item", "item_description": "Tripod", "item_min_value_usd": 60.0 },
+            { "wishlist_type": "xch", "xch_amount": 1_000_000_000_000i64 }
+        ]);
+        let created = rpc_trade_create(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "item_title": "Flash", "item_description": "Speedlight", "item_value_usd": 80.0,
+                         "wishlist": wishlist })),
+        )
+        .await
+        .unwrap();
+        let id = created["trade_id"].as_i64().unwrap();
+        let expected = json!([
+            { "wishlist_type": "item", "item_description": "Tripod", "item_min_value_usd": 60.0, "xch_amount": null },
+            { "wishlist_type": "xch", "item_description": null, "item_min_value_usd": null,
+              "xch_amount": 1_000_000_000_000i64 }
+        ]);
+
+        let own = rpc_trade_get(mm.clone(), alice.clone(), Some(json!({ "id": id }))).await.unwrap();
+        assert_eq!(own["trade"]["wishlist"], expected);
+        let public = rpc_trade_get_public(mm.clone(), Some(json!({ "id": id }))).await.unwrap();
+        assert_eq!(public["trade"]["wishlist"], expected);
+
+        let listed = rpc_trade_list_proposals(mm.clone(), None).await.unwrap();
+        let trades = listed["trades"].as_array().unwrap();
+        let entry = trades.iter().find(|t| t["id"] == id).unwrap();
+        assert_eq!(entry["wishlist"], expected);
+        // Trades without a wishlist get an empty list rather than null
+        assert!(trades.iter().filter(|t| t["id"] != id).all(|t| t["wishlist"] == json!([])));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_proposals_bounds_paging() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let listing = json!({ "item_title": "Lamp", "item_description": "Desk lamp", "item_value_usd": 30.0 });
+        rpc_trade_create(mm.clone(), alice.clone(), Some(listing)).await.unwrap();
+
+        let res = rpc_trade_list_proposals(mm.clone(), Some(json!({ "offset": -5 }))).await.unwrap();
+        assert_eq!((res["limit"].clone(), res["offset"].clone()), (json!(50), json!(0)));
+        assert!(!res["trades"].as_array().unwrap().is_empty());
+        let res = rpc_trade_list_proposals(mm.clone(), Some(json!({ "limit": 1_000_000, "offset": 0 }))).await.unwrap();
+        assert_eq!(res["limit"], 100);
+        let res = rpc_trade_list_proposals(mm.clone(), Some(json!({ "limit": 0 }))).await.unwrap();
+        assert_eq!(res["limit"], 1);
+
+        let err = rpc_trade_list_proposals(mm.clone(), Some(json!({ "limit": "ten" }))).await.unwrap_err();
+        assert_eq!(err.code, -32602);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_proposals_enriches_users_in_one_query() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let carol = _dev_utils::seed_ctx(&mm, "carol").await;
+        for (i, ctx) in [&alice, &bob, &carol, &alice, &bob].into_iter().enumerate() {
+            let trade_c = TradeForCreate {
+                item_title: format!("Item {}", i),
+                ..proposal_with_value(40.0)
+            };
+            TradeBmc::create(ctx, &mm, trade_c).await.unwrap();
+        }
+        // Defaults apply when the public columns are unset
+        sqlx::query("UPDATE users SET verification_status = NULL, reputation_score = NULL, total_trades = NULL WHERE id = $1")
+            .bind(carol.user_id())
+            .execute(mm.db())
+            .await
+            .unwrap();
+
+        // Scans of `users` within one transaction, visible before the stats are flushed
+        async fn user_scans(conn: &mut sqlx::PgConnection) -> i64 {
+            sqlx::query_scalar(
+                "SELECT COALESCE(seq_scan, 0) + COALESCE(idx_scan, 0) FROM pg_stat_xact_user_tables WHERE relname = 'users'",
+            )
+            .fetch_optional(conn)
+            .await
+            .unwrap()
+            .unwrap_or(0)
+        }
+
+        for len in [1usize, 5] {
+            let trades = TradeBmc::list_proposals(&mm, 50, 0, None, None).await.unwrap();
+            let trades: Vec<_> = trades.into_iter().take(len).collect();
+
+            let mut tx = mm.pool().begin().await.unwrap();
+            let before = user_scans(&mut tx).await;
+            let enriched = with_users(&mut *tx, trades).await;
+            assert_eq!(user_scans(&mut tx).await - before, 1, "{} proposals", len);
+            tx.rollback().await.unwrap();
+
+            assert_eq!(enriched.len(), len);
+            for t in &enriched {
+                assert_eq!(t.proposer.as_ref().map(|u| u.id), Some(t.trade.proposer_id));
+            }
+            if let Some(t) = enriched.iter().find(|t| t.trade.proposer_id == carol.user_id()) {
+                let carol_info = t.proposer.as_ref().unwrap();
+                assert_eq!(carol_info.verification_status, "unverified");
+                assert_eq!(carol_info.reputation_score, 0.0);
+                assert_eq!(carol_info.total_trades, 0);
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_contact_import_partial_success() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let key = "ab".repeat(48);
+
+        let res = rpc_contact_import(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "contacts": [
+                { "name": "Carol", "public_key": key, "email": "Carol@Example.com" },
+                { "name": "", "public_key": "abcd" },
+                { "name": "Dave", "public_key": "cd".repeat(48), "email": "not-an-email" },
+                { "name": "Carol again", "public_key": key },
+                { "name": "Erin", "public_key": "ef".repeat(48) },
+            ]})),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(res["imported"], 2);
+        assert_eq!(res["failed"], 3);
+        let results = res["results"].as_array().unwrap();
+        let ok: Vec<bool> = results.iter().map(|r| r["success"].as_bool().unwrap()).collect();
+        assert_eq!(ok, vec![true, false, false, false, true]);
+        assert!(results[1]["error"].as_str().unwrap().contains("name"));
+        assert!(results[2]["error"].as_str().unwrap().contains("email"));
+        assert!(results[3]["error"].as_str().unwrap().contains("already"));
+
+        let list = rpc_contact_list(mm.clone(), alice.clone()).await.unwrap();
+        let contacts = list["contacts"].as_array().unwrap();
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0]["email"], "carol@example.com");
+
+        // Oversized batches are rejected outright
+        let too_many: Vec<Value> = (0..=crate::model::MAX_CONTACT_IMPORT)
+            .map(|i| json!({ "name": format!("c{}", i), "public_key": key }))
+            .collect();
+        let err = rpc_contact_import(mm.clone(), alice, Some(json!({ "contacts": too_many }))).await.unwrap_err();
+        assert_eq!(err.code, 4000);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_admin_activity_feed_order_and_pages() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+
+        let created = rpc_trade_create(
+            mm.clone(),
+            alice.clone(),
+            Some(json!({ "item_title": "Lamp", "item_description": "Brass lamp", "item_value_usd": 40.0 })),
+        )
+        .await
+        .unwrap();
+        let trade_id = created["trade_id"].as_i64().unwrap();
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xfeed").await.unwrap();
+        TransactionBmc::confirm(&admin, &mm, "0xfeed", "0xcoin", 1, None).await.unwrap();
+        let mut conn = mm.db().acquire().await.unwrap();
+        AuditBmc::record_in(&mut conn, &admin, "confirm_transaction", "trade_transaction", "0xfeed", json!({})).await.unwrap();
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(trade_id).execute(mm.db()).await.unwrap();
+        TradeBmc::raise_dispute(&alice, &mm, trade_id, "Never shipped").await.unwrap();
+        // Backdate the proposal so it is the oldest event
+        sqlx::query("UPDATE trade_events SET created_at = NOW() - INTERVAL '1 day' WHERE trade_id = $1 AND event_type = 'trade_created'")
+            .bind(trade_id)
+            .execute(mm.db())
+            .await
+            .unwrap();
+
+        let denied = rpc_admin_activity_feed(mm.clone(), bob, None).await;
+        assert_eq!(denied.unwrap_err().code, 4003);
+
+        let all = rpc_admin_activity_feed(mm.clone(), admin.clone(), Some(json!({ "limit": 50 }))).await.unwrap();
+        let items = all["items"].as_array().unwrap().clone();
+        let types: Vec<&str> = items.iter().map(|i| i["type"].as_str().unwrap()).collect();
+        for expected in ["trade_created", "dispute_raised", "user_registered", "transaction_confirmed", "admin_action"] {
+            assert!(types.contains(&expected), "missing {}", expected);
+        }
+        assert_eq!(*types.last().unwrap(), "trade_created");
+
+        let times: Vec<chrono::DateTime<chrono::Utc>> = items
+            .iter()
+            .map(|i| serde_json::from_value(i["occurred_at"].clone()).unwrap())
+            .collect();
+        assert!(times.windows(2).all(|w| w[0] >= w[1]), "feed must be newest first");
+
+        // Pages stitch back together into the full feed
+        let mut paged = Vec::new();
+        for offset in (0..items.len()).step_by(2) {
+            let page = rpc_admin_activity_feed(mm.clone(), admin.clone(), Some(json!({ "limit": 2, "offset": offset })))
+                .await
+                .unwrap();
+            paged.extend(page["items"].as_array().unwrap().iter().cloned());
+        }
+        assert_eq!(paged, items);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_escrow_deposit_rejects_non_positive_amount() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        // Rejected as bad params before the (missing) trade is even looked up
+        for amount in [0, -5_000] {
+            let params = json!({ "trade_id": i64::MAX, "amount_mojos": amount });
+            let err = rpc_commitment_create_escrow_deposit(mm.clone(), alice.clone(), Some(params)).await.unwrap_err();
+            assert_eq!(err.code, -32602);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use axum::Router;
+use std::future::Future;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+use crate::api::metrics::InFlight;
+
+/// How long shutdown waits for in-flight requests before exiting anyway
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Resolves on Ctrl+C or (on unix) SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Serve `app` until `signal` resolves, then stop accepting connections and wait
+/// (at most `drain_timeout`) for in-flight requests to finish.
+/// Returns true if all requests drained in time.
+pub async fn serve_with_drain(
+    listener: TcpListener,
+    app: Router,
+    in_flight: InFlight,
+    signal: impl Future<Output = ()> + Send + 'static,
+    drain_timeout: Duration,
+) -> bool {
+    let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                stop_rx.await.ok();
+            })
+            .await
+    });
+
+    signal.await;
+    tracing::info!(in_flight = in_flight.count(), "Shutdown signal received, draining requests");
+    let _ = stop_tx.send(());
+
+    let drained = in_flight.wait_for_drain(drain_timeout).await;
+    if drained {
+        tracing::info!("All in-flight requests drained");
+    } else {
+        tracing::warn!(
+            in_flight = in_flight.count(),
+            timeout_secs = drain_timeout.as_secs(),
+            "Drain timed out, exiting with requests still in flight"
+        );
+        server.abort();
+    }
+    drained
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::metrics::mw_track_in_flight;
+    use axum::{middleware, routing::get};
+
+    #[tokio::test]
+    async fn test_slow_request_finishes_during_shutdown() {
+        let in_flight = InFlight::new();
+        let app = Router::new()
+            .route("/slow", get(|| async {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                "done"
+            }))
+            .layer(middleware::from_fn_with_state(in_flight.clone(), mw_track_in_flight));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/slow", listener.local_addr().unwrap());
+        let (signal_tx, signal_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_with_drain(
+            listener,
+            app,
+            in_flight.clone(),
+            async { signal_rx.await.ok(); },
+            Duration::from_secs(5),
+        ));
+
+        let request = tokio::spawn(async move { reqwest::get(url).await.unwrap().text().await.unwrap() });
+        while in_flight.count() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Shut down while the slow request is still running
+        signal_tx.send(()).unwrap();
+
+        assert_eq!(request.await.unwrap(), "done");
+        assert!(server.await.unwrap());
+        assert_eq!(in_flight.count(), 0);
+    }
+}
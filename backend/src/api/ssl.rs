@@ -38,33 +38,67 @@ pub struct SetSslPathsRequest {
     pub ca_path: Option<String>,
 }
 
-/// Upload SSL certificate files for Chia RPC connection
-pub async fn upload_ssl_certificates(
-    State(state): State<Arc<AppState>>,
-    mut multipart: Multipart,
-) -> Result<Json<SslUploadResponse>, StatusCode> {
-    // Determine type: wallet or full_node (from query param or multipart field)
-    // For now, default to full_node unless a field 'type'=='wallet' is present
+/// Largest accepted cert/key/ca field (PEM material is a few KB)
+const MAX_SSL_FIELD_BYTES: usize = 64 * 1024;
+
+/// Read the upload's multipart fields, returning the ssl type and (name, bytes) pairs.
+/// Read errors, oversized fields and empty cert/key/ca fields are rejected with a 400.
+async fn read_upload_fields(multipart: &mut Multipart) -> Result<(String, Vec<(String, Vec<u8>)>), (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+
+    // Default to full_node unless a field 'type'=='wallet' is present
     let mut ssl_type = "full_node".to_string();
     let mut fields: Vec<(String, Vec<u8>)> = Vec::new();
-    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| bad_request(format!("Malformed multipart body: {}", e)))?
+    {
         let name = field.name().unwrap_or("").to_string();
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| bad_request(format!("Failed to read field '{}': {}", name, e)))?
+        {
+            if data.len() + chunk.len() > MAX_SSL_FIELD_BYTES {
+                return Err(bad_request(format!(
+                    "Field '{}' exceeds the {} KB limit",
+                    name,
+                    MAX_SSL_FIELD_BYTES / 1024
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
         if name == "type" {
-            let data = field.bytes().await.unwrap_or_default();
             if let Ok(s) = std::str::from_utf8(&data) {
                 if s.trim() == "wallet" { ssl_type = "wallet".to_string(); }
             }
             continue;
         }
-        let data = field.bytes().await.unwrap_or_default().to_vec();
+        if matches!(name.as_str(), "cert" | "key" | "ca") && data.iter().all(|b| b.is_ascii_whitespace()) {
+            return Err(bad_request(format!("Field '{}' is empty", name)));
+        }
         fields.push((name, data));
     }
+
+    Ok((ssl_type, fields))
+}
+
+/// Upload SSL certificate files for Chia RPC connection
+pub async fn upload_ssl_certificates(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<Json<SslUploadResponse>, (StatusCode, String)> {
+    let (ssl_type, fields) = read_upload_fields(&mut multipart).await?;
     let ssl_dir = PathBuf::from("ssl").join(&ssl_type);
 
     // Create ssl directory if it doesn't exist
     if let Err(e) = fs::create_dir_all(&ssl_dir).await {
         eprintln!("Failed to create ssl directory: {}", e);
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to create ssl directory".to_string()));
     }
 
     let mut cert_saved = false;
@@ -104,13 +138,13 @@ pub async fn upload_ssl_certificates(
             Ok(f) => f,
             Err(e) => {
                 eprintln!("Failed to create file {:?}: {}", file_path, e);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to save SSL file".to_string()));
             }
         };
 
         if let Err(e) = file.write_all(&data).await {
             eprintln!("Failed to write file {:?}: {}", file_path, e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to save SSL file".to_string()));
         }
 
         println!("✅ Saved SSL file: {:?}", file_path);
@@ -260,3 +294,67 @@ pub async fn set_ssl_paths(
         message: "SSL certificate paths/identity/CA set successfully".to_string(),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::Request;
+
+    const BOUNDARY: &str = "XBOUNDARY";
+
+    async fn multipart(body: Vec<u8>) -> Multipart {
+        let req = Request::builder()
+            .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+            .body(Body::from(body))
+            .unwrap();
+        Multipart::from_request(req, &()).await.unwrap()
+    }
+
+    fn part(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut out = format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n", BOUNDARY, name).into_bytes();
+        out.extend_from_slice(data);
+        out.extend_from_slice(b"\r\n");
+        out
+    }
+
+    fn finish(mut body: Vec<u8>) -> Vec<u8> {
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    #[tokio::test]
+    async fn test_reads_valid_fields() {
+        let mut body = part("type", b"wallet");
+        body.extend(part("cert", b"-----BEGIN CERTIFICATE-----"));
+        let (ssl_type, fields) = read_upload_fields(&mut multipart(finish(body)).await).await.unwrap();
+        assert_eq!(ssl_type, "wallet");
+        assert_eq!(fields, vec![("cert".to_string(), b"-----BEGIN CERTIFICATE-----".to_vec())]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_field() {
+        let body = finish(part("key", &vec![b'A'; MAX_SSL_FIELD_BYTES + 1]));
+        let (status, msg) = read_upload_fields(&mut multipart(body).await).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(msg.contains("exceeds"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_empty_required_field() {
+        let body = finish(part("cert", b"  "));
+        let (status, msg) = read_upload_fields(&mut multipart(body).await).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(msg.contains("empty"));
+    }
+
+    #[tokio::test]
+    async fn test_read_error_is_bad_request() {
+        // Body ends mid-field without a closing boundary
+        let mut body = part("cert", b"-----BEGIN");
+        body.truncate(body.len() - 2);
+        let (status, _) = read_upload_fields(&mut multipart(body).await).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
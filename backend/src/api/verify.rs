@@ -8,49 +8,228 @@
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time;
-use crate::app_state::AppState;
+use crate::api::retention::{run_retention_sweep, RetentionConfig};
+use crate::app_state::{AppState, VerifierStatus};
 use crate::ctx::Ctx;
-use crate::model::{ModelManager, TransactionBmc};
+use crate::model::{parse_commitment_memo, ModelManager, ReviewBmc, SettingsBmc, TradeTransaction, TransactionBmc, TxConfirmation};
+use crate::blockchain::hash::{CoinId, PuzzleHash};
 use crate::rpc::ChiaRpcClient;
-use tracing::{info, warn, error};
+use crate::util::webhook::WebhookNotifier;
+use tracing::{debug, info, warn, error};
 
-const VERIFICATION_INTERVAL_SECS: u64 = 30; // Check every 30 seconds
-const MIN_CONFIRMATIONS: u64 = 6; // Require 6 confirmations for finality
+/// Time between verifier ticks unless `VERIFY_INTERVAL_SECS` says otherwise
+pub const DEFAULT_VERIFICATION_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Recent exchange wallet transactions scanned for memo-tagged commitment payments each tick
+const MEMO_SCAN_LIMIT: u32 = 100;
+
+/// Hours a transaction may sit unsubmitted or unconfirmed before it is failed, unless `STALE_TX_HOURS` says otherwise
+pub const DEFAULT_STALE_TX_HOURS: i64 = 24;
+
+/// Ticks between stale-transaction sweeps unless `STALE_TX_CLEANUP_TICKS` says otherwise (hourly at 30s ticks)
+pub const DEFAULT_STALE_TX_CLEANUP_TICKS: u64 = 120;
+
+/// Per-deployment verifier settings (`VERIFY_INTERVAL_SECS`, `MIN_CONFIRMATIONS`,
+/// `STALE_TX_HOURS`, `STALE_TX_CLEANUP_TICKS`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerifierConfig {
+    /// Time between ticks, at least 1s
+    pub interval: Duration,
+    /// Confirmations required for finality; `None` uses the exchange setting (default 6)
+    pub min_confirmations: Option<u64>,
+    /// Transactions unsubmitted, or submitted but unconfirmed, for this many hours are failed (at least 1)
+    pub stale_tx_hours: i64,
+    /// Run the stale-transaction sweep every this many ticks (at least 1)
+    pub cleanup_every_ticks: u64,
+}
+
+impl VerifierConfig {
+    pub fn from_env() -> Self {
+        let env = |key: &str| std::env::var(key).ok();
+        Self {
+            stale_tx_hours: positive_or(env("STALE_TX_HOURS").as_deref(), "STALE_TX_HOURS", DEFAULT_STALE_TX_HOURS as u64) as i64,
+            cleanup_every_ticks: positive_or(
+                env("STALE_TX_CLEANUP_TICKS").as_deref(),
+                "STALE_TX_CLEANUP_TICKS",
+                DEFAULT_STALE_TX_CLEANUP_TICKS,
+            ),
+            ..Self::parse(env("VERIFY_INTERVAL_SECS").as_deref(), env("MIN_CONFIRMATIONS").as_deref())
+        }
+    }
+
+    /// Missing or invalid values (interval < 1, negative confirmations) fall back to the defaults
+    fn parse(interval_secs: Option<&str>, min_confirmations: Option<&str>) -> Self {
+        let interval = Duration::from_secs(positive_or(
+            interval_secs,
+            "VERIFY_INTERVAL_SECS",
+            DEFAULT_VERIFICATION_INTERVAL.as_secs(),
+        ));
+        let min_confirmations = match min_confirmations.map(|v| v.trim().parse::<u64>()) {
+            None => None,
+            Some(Ok(n)) => Some(n),
+            Some(Err(_)) => {
+                warn!("Ignoring invalid MIN_CONFIRMATIONS {:?}", min_confirmations.unwrap_or_default());
+                None
+            }
+        };
+        Self {
+            interval,
+            min_confirmations,
+            stale_tx_hours: DEFAULT_STALE_TX_HOURS,
+            cleanup_every_ticks: DEFAULT_STALE_TX_CLEANUP_TICKS,
+        }
+    }
+}
+
+/// `value` as a whole number of at least 1; missing or invalid gives `default` (invalid with a warning)
+fn positive_or(value: Option<&str>, key: &str, default: u64) -> u64 {
+    match value.map(|v| v.trim().parse::<u64>()) {
+        None => default,
+        Some(Ok(n)) if n >= 1 => n,
+        Some(_) => {
+            warn!("Ignoring invalid {} {:?}", key, value.unwrap_or_default());
+            default
+        }
+    }
+}
 
 /// Start the transaction verification background task
 pub async fn start_verification_service(mm: ModelManager, state: Arc<AppState>) {
+    let config = VerifierConfig::from_env();
+    let retention = RetentionConfig::from_env();
     tokio::spawn(async move {
-        info!("Transaction verification service started");
+        match config.min_confirmations {
+            Some(n) => info!("Transaction verification service started: every {}s, {} confirmations", config.interval.as_secs(), n),
+            None => info!("Transaction verification service started: every {}s, confirmations from exchange settings", config.interval.as_secs()),
+        }
+        info!(
+            "Transactions unsubmitted or unconfirmed after {}h are failed every {} ticks",
+            config.stale_tx_hours, config.cleanup_every_ticks
+        );
+        match &retention {
+            Some(r) => info!(
+                "Retention on: failed transactions after {}d, read notifications after {}d, {} rows per batch",
+                r.failed_tx_days, r.read_notification_days, r.batch_size
+            ),
+            None => info!("Retention off (set RETENTION_ENABLED to prune old rows)"),
+        }
+        state.mark_verifier_started().await;
         
-        let mut interval = time::interval(Duration::from_secs(VERIFICATION_INTERVAL_SECS));
+        let mut interval = time::interval(config.interval);
         
-        loop {
+        // Sweep stale transactions (and prune, if enabled) on the first tick, then every `cleanup_every_ticks`
+        for tick in 0u64.. {
             interval.tick().await;
-            
-            if let Err(e) = verify_pending_transactions(&mm, &state).await {
-                error!("Transaction verification error: {}", e);
+            run_tick(&mm, &state, &config).await;
+            if tick % config.cleanup_every_ticks == 0 {
+                match cleanup_stale_transactions(&mm, config.stale_tx_hours).await {
+                    Ok(swept) => info!("Stale transaction sweep failed {} transactions", swept),
+                    Err(e) => error!("Stale transaction sweep error: {}", e),
+                }
+                if let Some(retention) = &retention {
+                    match run_retention_sweep(&mm, retention).await {
+                        Ok(report) => info!(
+                            "Retention sweep deleted {} failed transactions and {} read notifications",
+                            report.failed_transactions, report.read_notifications
+                        ),
+                        Err(e) => error!("Retention sweep error: {:?}", e),
+                    }
+                }
             }
         }
     });
 }
 
-/// Check all pending transactions and update their status
-async fn verify_pending_transactions(mm: &ModelManager, state: &Arc<AppState>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Verifier status plus whether it counts as healthy right now
+pub async fn verifier_health(state: &AppState) -> (VerifierStatus, bool) {
+    let status = state.verifier_status().await;
+    let healthy = status.is_healthy(chrono::Utc::now(), VerifierConfig::from_env().interval);
+    (status, healthy)
+}
+
+/// One verifier tick. Runs the sweep in its own task so a panic is recorded
+/// in the verifier status instead of killing the service.
+async fn run_tick(mm: &ModelManager, state: &Arc<AppState>, config: &VerifierConfig) {
+    let (mm_t, state_t, min_confirmations) = (mm.clone(), state.clone(), config.min_confirmations);
+    let outcome = tokio::spawn(async move {
+        verify_pending_transactions(&mm_t, &state_t, min_confirmations).await.map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("verifier task panicked: {}", e)));
+
+    if let Err(e) = &outcome {
+        error!("Transaction verification error: {}", e);
+    }
+    state.record_verifier_run(outcome).await;
+}
+
+/// Check all pending transactions and update their status.
+/// Returns how many pending transactions were processed.
+async fn verify_pending_transactions(
+    mm: &ModelManager,
+    state: &Arc<AppState>,
+    min_confirmations: Option<u64>,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     // Create a system context (no user auth needed for background tasks)
     let ctx = Ctx::root_ctx();
     
+    // Review reminders don't need the node; a failure shouldn't hold up verification
+    match ReviewBmc::send_review_reminders(mm).await {
+        Ok(0) => {}
+        Ok(sent) => info!("Sent {} review reminders", sent),
+        Err(e) => warn!("Failed to send review reminders: {}", e),
+    }
+    
+    // Payments are looked up through the configured wallet connection; without one,
+    // only escrow releases (checked on chain) can be verified
+    let wallet = ChiaRpcClient::from_state(state.clone(), "wallet").await;
+    
+    // Payments made outside the app are only known by their memo; a wallet problem
+    // shouldn't hold up verifying the rest
+    if TransactionBmc::has_unsubmitted_commitments(mm).await? {
+        let attached = match &wallet {
+            Ok(wallet) => associate_memo_payments(mm, wallet).await,
+            Err(e) => Err(e.to_string().into()),
+        };
+        match attached {
+            Ok(0) => {}
+            Ok(n) => info!("Matched {} commitment payments by memo", n),
+            Err(e) => warn!("Failed to scan wallet for memo payments: {}", e),
+        }
+    }
+    
     // Get pending transactions
     let pending = TransactionBmc::list_pending_verification(&ctx, mm).await?;
+    let tracked = TransactionBmc::count_tracked_confirmations(&ctx, mm).await?;
     
-    if pending.is_empty() {
-        return Ok(());
+    // Nothing to verify and no confirmation counts to keep current
+    if pending.is_empty() && tracked == 0 {
+        return Ok(0);
     }
     
-    info!("Verifying {} pending transactions", pending.len());
-    
     // Get RPC client
     let rpc_client = ChiaRpcClient::from_state(state.clone(), "full_node").await?;
     
+    let processed = pending.len();
+    if let Err(e) = &wallet {
+        warn!("No wallet connection, commitment payments can't be looked up: {}", e);
+    }
+    run_sweep(&ctx, mm, &rpc_client, wallet.as_ref().ok(), pending, min_confirmations, Some(state.as_ref())).await?;
+    Ok(processed)
+}
+
+/// One verification pass: confirm pending transactions and refresh confirmation counts.
+/// Payments are looked up in `wallet`, escrow releases on the node (`rpc_client`).
+/// With `state`, confirmations are announced to the webhook and trade event subscribers.
+async fn run_sweep(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    rpc_client: &ChiaRpcClient,
+    wallet: Option<&ChiaRpcClient>,
+    pending: Vec<TradeTransaction>,
+    min_confirmations: Option<u64>,
+    state: Option<&AppState>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Get current blockchain height
     let blockchain_state = rpc_client.get_blockchain_state().await?;
     let current_height = blockchain_state
@@ -64,14 +243,37 @@ async fn verify_pending_transactions(mm: &ModelManager, state: &Arc<AppState>) -
         return Ok(());
     }
     
+    if !pending.is_empty() {
+        info!("Verifying {} pending transactions", pending.len());
+    }
+    
+    // Confirmations required for finality: MIN_CONFIRMATIONS, else exchange settings (default 6)
+    let min_confirmations = match min_confirmations {
+        Some(n) => n,
+        None => SettingsBmc::get(mm).await?.min_confirmations.max(1) as u64,
+    };
+    
+    // Collect confirmations, then write them in one round trip
+    let mut ready = Vec::new();
     for tx in pending {
         if let Some(tx_id) = &tx.tx_id {
-            match verify_single_transaction(&ctx, mm, &rpc_client, tx_id, current_height).await {
-                Ok(confirmed) => {
-                    if confirmed {
-                        info!("Transaction {} confirmed", tx_id);
+            let outcome = match (tx.tx_type.as_str(), &tx.coin_id, &tx.puzzle_hash) {
+                ("escrow_release", Some(escrow_coin_id), Some(recipient)) => {
+                    match (escrow_coin_id.parse::<CoinId>(), recipient.parse::<PuzzleHash>()) {
+                        (Ok(escrow_coin_id), Ok(recipient)) => {
+                            verify_escrow_release(rpc_client, &tx, tx_id, &escrow_coin_id, &recipient, current_height, min_confirmations).await
+                        }
+                        (Err(e), _) | (_, Err(e)) => Err(format!("Escrow release has an invalid coin id or puzzle hash: {}", e).into()),
                     }
                 }
+                _ => match wallet {
+                    Some(wallet) => verify_single_transaction(rpc_client, wallet, &tx, tx_id, current_height, min_confirmations).await,
+                    None => Ok(None),
+                },
+            };
+            match outcome {
+                Ok(Some(confirmation)) => ready.push(confirmation),
+                Ok(None) => {}
                 Err(e) => {
                     warn!("Failed to verify transaction {}: {}", tx_id, e);
                     // Don't fail the whole batch for one error
@@ -80,27 +282,78 @@ async fn verify_pending_transactions(mm: &ModelManager, state: &Arc<AppState>) -
         }
     }
     
+    if !ready.is_empty() {
+        let webhook = state.and_then(|s| s.webhook());
+        let mut confirmed_trades = Vec::new();
+        for (tx_id, trade_id) in TransactionBmc::confirm_batch(mm, &ready).await? {
+            info!("Transaction {} confirmed", tx_id);
+            if let Some(webhook) = &webhook {
+                let confirmations = ready.iter().find(|c| c.tx_id == tx_id).map_or(0, |c| c.confirmations);
+                notify_confirmed(webhook.clone(), trade_id, tx_id, confirmations);
+            }
+            if !confirmed_trades.contains(&trade_id) {
+                confirmed_trades.push(trade_id);
+            }
+        }
+        if let Some(state) = state {
+            for trade_id in confirmed_trades {
+                crate::api::events::publish_trade_status(mm, state, trade_id, "confirmation").await;
+            }
+        }
+    }
+    
+    // Keep confirmation counts live for already-confirmed transactions
+    let refreshed = TransactionBmc::refresh_confirmations(ctx, mm, current_height as i64).await?;
+    if refreshed > 0 {
+        debug!("Refreshed confirmation counts for {} transactions", refreshed);
+    }
+    
     Ok(())
 }
 
-/// Verify a single transaction
+/// Announce a confirmed transaction in the background so retries don't hold up the sweep
+fn notify_confirmed(webhook: Arc<WebhookNotifier>, trade_id: i64, tx_id: String, confirmations: i32) {
+    tokio::spawn(async move {
+        let payload = serde_json::json!({
+            "event": "transaction_confirmed",
+            "trade_id": trade_id,
+            "tx_id": tx_id,
+            "status": "confirmed",
+            "confirmations": confirmations,
+        });
+        if let Err(e) = webhook.send(&payload).await {
+            warn!("Confirmation webhook for transaction {} failed: {}", tx_id, e);
+        }
+    });
+}
+
+/// Verify a single transaction: the node's mempool first, then the wallet's record.
+/// Returns the confirmation to apply once it has enough confirmations and its memo
+/// (if any) names the same trade/user as the pending row; the caller writes it.
 async fn verify_single_transaction(
-    ctx: &Ctx,
-    mm: &ModelManager,
     rpc_client: &ChiaRpcClient,
+    wallet: &ChiaRpcClient,
+    pending: &TradeTransaction,
     tx_id: &str,
     current_height: u64,
-) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    // First check if it's in mempool
-    let in_mempool = rpc_client.is_tx_in_mempool(tx_id).await?;
+    min_confirmations: u64,
+) -> Result<Option<TxConfirmation>, Box<dyn std::error::Error + Send + Sync>> {
+    // First check if it's in mempool (direct lookup, bulk scan if the node lacks the endpoint)
+    let in_mempool = match rpc_client.get_mempool_item_by_tx_id(tx_id).await {
+        Ok(item) => item.is_some(),
+        Err(e) => {
+            debug!("Direct mempool lookup failed ({}), falling back to full scan", e);
+            rpc_client.is_tx_in_mempool(tx_id).await?
+        }
+    };
     
     if in_mempool {
         info!("Transaction {} is in mempool, waiting for confirmation", tx_id);
-        return Ok(false);
+        return Ok(None);
     }
     
     // Try to get transaction details from wallet RPC
-    match rpc_client.get_transaction(tx_id).await {
+    match wallet.get_transaction(tx_id).await {
         Ok(tx_record) => {
             if tx_record.confirmed {
                 // Calculate confirmations
@@ -108,18 +361,29 @@ async fn verify_single_transaction(
                     .map(|h| current_height.saturating_sub(h))
                     .unwrap_or(0);
                 
-                if confirmations >= MIN_CONFIRMATIONS {
-                    // Mark as confirmed (we don't have coin_id from this API, pass empty string)
-                    TransactionBmc::confirm(ctx, mm, tx_id, "", confirmations as i32).await?;
+                if confirmations >= min_confirmations {
+                    if !memo_matches(pending, &tx_record.memos) {
+                        warn!(
+                            "Transaction {} memo names a different trade/user than pending row {} (trade {}, user {}); not confirming",
+                            tx_id, pending.id, pending.trade_id, pending.user_id
+                        );
+                        return Ok(None);
+                    }
+                    // We don't have coin_id from this API, pass empty string
                     info!(
                         "Transaction {} confirmed at height {:?} ({} confirmations)",
                         tx_id, tx_record.confirmed_at_height, confirmations
                     );
-                    return Ok(true);
+                    return Ok(Some(TxConfirmation {
+                        tx_id: tx_id.to_string(),
+                        coin_id: String::new(),
+                        confirmations: confirmations as i32,
+                        confirmed_height: tx_record.confirmed_at_height.map(|h| h as i64),
+                    }));
                 } else {
                     info!(
                         "Transaction {} has {} confirmations, waiting for {}",
-                        tx_id, confirmations, MIN_CONFIRMATIONS
+                        tx_id, confirmations, min_confirmations
                     );
                 }
             }
@@ -133,33 +397,418 @@ async fn verify_single_transaction(
         }
     }
     
-    Ok(false)
+    Ok(None)
 }
 
-/// Mark stale pending transactions as failed
-/// Transactions pending for more than 24 hours without mempool entry are considered failed
-pub async fn cleanup_stale_transactions(mm: &ModelManager) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ctx = Ctx::root_ctx();
-    
-    // Find transactions that have been pending for > 24 hours
-    let stale: Vec<(String,)> = sqlx::query_as(
-        "SELECT tx_id FROM trade_transactions 
-         WHERE status = 'pending' 
-         AND tx_id IS NOT NULL
-         AND created_at < NOW() - INTERVAL '24 hours'"
+/// Verify an escrow release against the chain: the escrow coin must have been
+/// spent into a coin paying at least the pending amount to `recipient_puzzle_hash`.
+/// Confirmations count from the block that created that coin.
+async fn verify_escrow_release(
+    rpc_client: &ChiaRpcClient,
+    pending: &TradeTransaction,
+    tx_id: &str,
+    escrow_coin_id: &CoinId,
+    recipient_puzzle_hash: &PuzzleHash,
+    current_height: u64,
+    min_confirmations: u64,
+) -> Result<Option<TxConfirmation>, Box<dyn std::error::Error + Send + Sync>> {
+    let children = rpc_client
+        .get_coin_records_by_parent_ids(&[*escrow_coin_id])
+        .await?;
+
+    if children.is_empty() {
+        info!("Escrow coin {} not spent yet (release {})", escrow_coin_id, tx_id);
+        return Ok(None);
+    }
+
+    let min_amount = pending.amount_mojos.max(0) as u64;
+    let payout = children
+        .iter()
+        .find(|c| c.puzzle_hash == *recipient_puzzle_hash && c.amount >= min_amount);
+
+    let Some(payout) = payout else {
+        warn!(
+            "Escrow coin {} was spent but no output pays {} mojos to {} (release {}); not confirming",
+            escrow_coin_id, min_amount, recipient_puzzle_hash, tx_id
+        );
+        return Ok(None);
+    };
+
+    let confirmations = current_height.saturating_sub(payout.confirmed_height);
+    if confirmations < min_confirmations {
+        info!(
+            "Escrow release {} has {} confirmations, waiting for {}",
+            tx_id, confirmations, min_confirmations
+        );
+        return Ok(None);
+    }
+
+    info!(
+        "Escrow release {} confirmed at height {} ({} confirmations)",
+        tx_id, payout.confirmed_height, confirmations
+    );
+    Ok(Some(TxConfirmation {
+        tx_id: tx_id.to_string(),
+        coin_id: escrow_coin_id.to_string(),
+        confirmations: confirmations as i32,
+        confirmed_height: Some(payout.confirmed_height as i64),
+    }))
+}
+
+/// Attach wallet payments carrying a commitment memo to the trade/user's unsubmitted
+/// commitment fee, so the sweep verifies them like any submitted transaction.
+/// Returns how many were attached.
+async fn associate_memo_payments(
+    mm: &ModelManager,
+    wallet: &ChiaRpcClient,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let mut attached = 0;
+    for record in wallet.get_transactions(1, MEMO_SCAN_LIMIT).await? {
+        let Some((trade_id, user_id)) = record.memos.iter().find_map(|m| parse_commitment_memo(m)) else {
+            continue;
+        };
+        if record.transaction_id.is_empty() {
+            continue;
+        }
+        let amount = i64::try_from(record.amount).unwrap_or(i64::MAX);
+        if let Some(id) = TransactionBmc::attach_memo_payment(mm, trade_id, user_id, &record.transaction_id, amount).await? {
+            info!(
+                "Payment {} matched commitment {} (trade {}, user {}) by memo",
+                record.transaction_id, id, trade_id, user_id
+            );
+            attached += 1;
+        }
+    }
+    Ok(attached)
+}
+
+/// Whether an on-chain payment belongs to `pending`. The first commitment memo on
+/// the payment decides; payments without one fall back to trusting the pending row.
+fn memo_matches(pending: &TradeTransaction, memos: &[String]) -> bool {
+    match memos.iter().find_map(|m| parse_commitment_memo(m)) {
+        Some((trade_id, user_id)) => trade_id == pending.trade_id && user_id == pending.user_id,
+        None => true,
+    }
+}
+
+/// Mark stale transactions as failed: pending ones never submitted within `stale_after_hours`
+/// of being created, and submitted ones still unconfirmed `stale_after_hours` after submission.
+/// Returns how many were failed.
+async fn cleanup_stale_transactions(mm: &ModelManager, stale_after_hours: i64) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let stale: Vec<(i64, Option<String>)> = sqlx::query_as(
+        "UPDATE trade_transactions
+         SET status = 'failed',
+             error_message = CASE WHEN status = 'mempool' THEN $2 ELSE $3 END
+         WHERE (status = 'mempool' AND mempool_at < NOW() - make_interval(hours => $1::INT))
+            OR (status = 'pending' AND tx_id IS NULL AND created_at < NOW() - make_interval(hours => $1::INT))
+         RETURNING id, tx_id"
     )
+    .bind(stale_after_hours)
+    .bind(format!("Transaction was not confirmed within {} hours of submission", stale_after_hours))
+    .bind(format!("Transaction was not submitted within {} hours", stale_after_hours))
     .fetch_all(mm.pool())
     .await?;
     
-    for (tx_id,) in stale {
-        TransactionBmc::fail(
-            &ctx, 
-            mm, 
-            &tx_id, 
-            "Transaction did not appear in mempool within 24 hours"
-        ).await?;
-        warn!("Marked stale transaction {} as failed", tx_id);
+    for (id, tx_id) in &stale {
+        warn!("Marked stale transaction {} ({}) as failed", id, tx_id.as_deref().unwrap_or("never submitted"));
     }
     
-    Ok(())
+    Ok(stale.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, commitment_fee, proposal_with_value};
+    use crate::model::{commitment_memo, TradeBmc};
+    use axum::{routing::post, Json, Router};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_sweep_refreshes_confirmations() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(40.0)).await.unwrap();
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xfeed").await.unwrap();
+        TransactionBmc::confirm(&alice, &mm, "0xfeed", "", 6, Some(100)).await.unwrap();
+
+        // Mock node whose peak height the test controls
+        let height = Arc::new(AtomicU64::new(106));
+        let peak = height.clone();
+        let router = Router::new().route(
+            "/get_blockchain_state",
+            post(move || {
+                let peak = peak.clone();
+                async move { Json(json!({ "blockchain_state": { "peak": { "height": peak.load(Ordering::SeqCst) } } })) }
+            }),
+        );
+        let rpc_client = ChiaRpcClient::new(_dev_utils::spawn_mock_node(router).await);
+        let ctx = Ctx::root_ctx();
+
+        run_sweep(&ctx, &mm, &rpc_client, None, Vec::new(), None, None).await.unwrap();
+        let txs = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap();
+        assert_eq!(txs[0].confirmations, Some(6));
+
+        height.store(110, Ordering::SeqCst);
+        run_sweep(&ctx, &mm, &rpc_client, None, Vec::new(), None, None).await.unwrap();
+        let txs = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap();
+        assert_eq!(txs[0].confirmations, Some(10));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_sweep_looks_payments_up_in_the_wallet() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(40.0)).await.unwrap();
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xpaid").await.unwrap();
+        let ctx = Ctx::root_ctx();
+
+        // The node no longer has it in the mempool; only the wallet knows the payment
+        let node = Router::new()
+            .route("/get_blockchain_state", post(|| async { Json(json!({ "blockchain_state": { "peak": { "height": 110 } } })) }))
+            .route(
+                "/get_mempool_item_by_tx_id",
+                post(|| async { Json(json!({ "success": false, "error": "Transaction 0xpaid not in the mempool" })) }),
+            );
+        let rpc_client = ChiaRpcClient::new(_dev_utils::spawn_mock_node(node).await);
+        let wallet = Router::new().route(
+            "/get_transaction",
+            post(|| async {
+                let tx = json!({ "name": "0xpaid", "confirmed": true, "confirmed_at_height": 100, "amount": 1_000 });
+                Json(json!({ "transaction": tx, "success": true }))
+            }),
+        );
+        let wallet = ChiaRpcClient::new(_dev_utils::spawn_mock_node(wallet).await);
+        let status = || async {
+            let status: String = sqlx::query_scalar("SELECT status FROM trade_transactions WHERE id = $1")
+                .bind(id)
+                .fetch_one(mm.pool())
+                .await
+                .unwrap();
+            status
+        };
+
+        // Without a wallet connection the payment waits
+        let pending = TransactionBmc::list_pending_verification(&ctx, &mm).await.unwrap();
+        run_sweep(&ctx, &mm, &rpc_client, None, pending, Some(6), None).await.unwrap();
+        assert_eq!(status().await, "mempool");
+
+        let pending = TransactionBmc::list_pending_verification(&ctx, &mm).await.unwrap();
+        run_sweep(&ctx, &mm, &rpc_client, Some(&wallet), pending, Some(6), None).await.unwrap();
+        assert_eq!(status().await, "confirmed");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_memo_payments_attach_to_their_commitment() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(40.0)).await.unwrap();
+        let mine = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        let other_trade = TradeBmc::create(&bob, &mm, proposal_with_value(40.0)).await.unwrap();
+        let theirs = TransactionBmc::create(&bob, &mm, commitment_fee(other_trade, 1_000)).await.unwrap();
+        assert!(TransactionBmc::has_unsubmitted_commitments(&mm).await.unwrap());
+
+        // Exchange wallet history: alice's payment, an underpayment and a stray memo for bob,
+        // and a payment with no memo at all
+        let memo = |trade_id: i64, user_id: i64| hex::encode(commitment_memo(trade_id, user_id));
+        let history = json!({ "transactions": [
+            { "name": "0xoob", "confirmed": true, "amount": 1_000, "memos": { "0xc1": [memo(trade_id, alice.user_id())] } },
+            { "name": "0xshort", "confirmed": true, "amount": 999, "memos": { "0xc2": [memo(other_trade, bob.user_id())] } },
+            { "name": "0xwrong", "confirmed": true, "amount": 1_000, "memos": { "0xc3": [memo(trade_id, bob.user_id())] } },
+            { "name": "0xplain", "confirmed": true, "amount": 1_000, "memos": {} },
+        ], "success": true });
+        let router = Router::new().route("/get_transactions", post(move || async move { Json(history) }));
+        let wallet = ChiaRpcClient::new(_dev_utils::spawn_mock_node(router).await);
+
+        assert_eq!(associate_memo_payments(&mm, &wallet).await.unwrap(), 1);
+        let ctx = Ctx::root_ctx();
+        let pending = TransactionBmc::list_pending_verification(&ctx, &mm).await.unwrap();
+        assert_eq!(pending.iter().map(|tx| (tx.id, tx.tx_id.as_deref())).collect::<Vec<_>>(), [(mine, Some("0xoob"))]);
+        let status: String = sqlx::query_scalar("SELECT status FROM trade_transactions WHERE id = $1")
+            .bind(theirs)
+            .fetch_one(mm.pool())
+            .await
+            .unwrap();
+        assert_eq!(status, "pending");
+
+        // The same payment isn't attached twice
+        assert_eq!(associate_memo_payments(&mm, &wallet).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_stale_transactions_failed() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        // Two never submitted and two submitted, one of each past the window; one commitment per trade
+        let mut ids = Vec::new();
+        for tx_id in [None, None, Some("0xstale"), Some("0xfresh")] {
+            let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(25.0)).await.unwrap();
+            let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+            if let Some(tx_id) = tx_id {
+                TransactionBmc::submit_tx_id(&alice, &mm, id, tx_id).await.unwrap();
+            }
+            ids.push(id);
+        }
+        for (column, id) in [("created_at", ids[0]), ("mempool_at", ids[2])] {
+            sqlx::query(&format!("UPDATE trade_transactions SET {} = NOW() - INTERVAL '13 hours' WHERE id = $1", column))
+                .bind(id)
+                .execute(mm.pool())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(cleanup_stale_transactions(&mm, 12).await.unwrap(), 2);
+        for (id, expected) in ids.iter().zip(["failed", "pending", "failed", "mempool"]) {
+            let status: String = sqlx::query_scalar("SELECT status FROM trade_transactions WHERE id = $1")
+                .bind(id)
+                .fetch_one(mm.pool())
+                .await
+                .unwrap();
+            assert_eq!(status, expected, "{}", id);
+        }
+
+        // Already failed rows aren't swept again
+        assert_eq!(cleanup_stale_transactions(&mm, 12).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_tick_updates_verifier_status() {
+        let (mm, alice, _bob) = _dev_utils::init_test_with_users().await;
+        let state = Arc::new(AppState::new("http://localhost:8555".to_string()));
+        state.mark_verifier_started().await;
+        assert!(verifier_health(&state).await.1);
+
+        // Nothing pending: a clean run
+        run_tick(&mm, &state, &VerifierConfig::from_env()).await;
+        let status = state.verifier_status().await;
+        assert_eq!(status.runs, 1);
+        assert!(status.last_run_at.is_some());
+        assert_eq!(status.last_error, None);
+        assert_eq!(status.last_processed, 0);
+
+        // A pending transaction and an unusable node URL: the error is recorded
+        let trade_id = TradeBmc::create(&alice, &mm, proposal_with_value(40.0)).await.unwrap();
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xstatus").await.unwrap();
+        state.set_rpc_url("http://127.0.0.1:1".to_string()).await;
+
+        run_tick(&mm, &state, &VerifierConfig::from_env()).await;
+        let status = state.verifier_status().await;
+        assert_eq!(status.runs, 2);
+        assert!(status.last_error.is_some());
+    }
+
+    fn pending_tx(tx_type: &str) -> TradeTransaction {
+        TradeTransaction {
+            id: 1,
+            trade_id: 42,
+            user_id: 7,
+            tx_type: tx_type.to_string(),
+            tx_id: Some("0xfeed".to_string()),
+            coin_id: None,
+            puzzle_hash: None,
+            from_address: None,
+            to_address: None,
+            amount_mojos: 1_000,
+            status: "pending".to_string(),
+            confirmations: None,
+            confirmed_height: None,
+            error_message: None,
+            retry_count: None,
+            created_at: chrono::Utc::now(),
+            mempool_at: None,
+            confirmed_at: None,
+            archived_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_escrow_release_checks_children_of_escrow_coin() {
+        // The escrow coin 0xe5…e5 was spent into a payout to 0xbb…bb and change back to 0xcc…cc.
+        // The node mixes prefixed and bare, upper and lower case hex.
+        let escrow = CoinId::from([0xe5; 32]);
+        let hash = |byte: u8| PuzzleHash::from([byte; 32]);
+        let router = Router::new().route(
+            "/get_coin_records_by_parent_ids",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                let records = if body["parent_ids"] == json!([format!("0x{}", "e5".repeat(32))]) {
+                    json!([
+                        { "coin": { "parent_coin_info": "e5".repeat(32), "puzzle_hash": format!("0x{}", "BB".repeat(32)), "amount": 1_000 }, "confirmed_block_index": 100, "spent": false },
+                        { "coin": { "parent_coin_info": "e5".repeat(32), "puzzle_hash": "cc".repeat(32), "amount": 50 }, "confirmed_block_index": 100, "spent": false }
+                    ])
+                } else {
+                    json!([])
+                };
+                Json(json!({ "coin_records": records, "success": true }))
+            }),
+        );
+        let rpc_client = ChiaRpcClient::new(_dev_utils::spawn_mock_node(router).await);
+        let pending = pending_tx("escrow_release");
+
+        let confirmation = verify_escrow_release(&rpc_client, &pending, "0xfeed", &escrow, &hash(0xbb), 106, 6)
+            .await
+            .unwrap()
+            .expect("payout to the recipient confirms the release");
+        assert_eq!(confirmation.coin_id, escrow.to_string());
+        assert_eq!(confirmation.confirmations, 6);
+        assert_eq!(confirmation.confirmed_height, Some(100));
+
+        // Too few confirmations yet
+        assert!(verify_escrow_release(&rpc_client, &pending, "0xfeed", &escrow, &hash(0xbb), 103, 6).await.unwrap().is_none());
+        // Funds went elsewhere
+        assert!(verify_escrow_release(&rpc_client, &pending, "0xfeed", &escrow, &hash(0xdd), 106, 6).await.unwrap().is_none());
+        // Change output is too small to count as the payout
+        assert!(verify_escrow_release(&rpc_client, &pending, "0xfeed", &escrow, &hash(0xcc), 106, 6).await.unwrap().is_none());
+        // Escrow coin not spent yet
+        let unspent = CoinId::from([0x12; 32]);
+        assert!(verify_escrow_release(&rpc_client, &pending, "0xfeed", &unspent, &hash(0xbb), 106, 6).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memo_associates_payment_with_trade_and_user() {
+        use crate::model::commitment_memo;
+
+        let pending = pending_tx("commitment_fee");
+
+        assert!(memo_matches(&pending, &["hello".to_string(), commitment_memo(42, 7)]));
+        assert!(!memo_matches(&pending, &[commitment_memo(42, 8)]));
+        assert!(!memo_matches(&pending, &[commitment_memo(43, 7)]));
+        // No memo of ours: the pending row is all we have
+        assert!(memo_matches(&pending, &[]));
+        assert!(memo_matches(&pending, &["DTREX-COMMIT-x-7".to_string()]));
+    }
+
+    #[test]
+    fn test_verifier_stalls_after_three_intervals() {
+        let now = chrono::Utc::now();
+        let status = |last_run_secs_ago: i64| VerifierStatus {
+            last_run_at: Some(now - chrono::Duration::seconds(last_run_secs_ago)),
+            ..Default::default()
+        };
+        assert!(status(60).is_healthy(now, DEFAULT_VERIFICATION_INTERVAL));
+        assert!(!status(91).is_healthy(now, DEFAULT_VERIFICATION_INTERVAL));
+        assert!(!VerifierStatus::default().is_healthy(now, DEFAULT_VERIFICATION_INTERVAL));
+    }
+
+    #[test]
+    fn test_verifier_config_falls_back_to_defaults() {
+        let defaults = VerifierConfig::parse(None, None);
+        assert_eq!((defaults.interval, defaults.min_confirmations), (DEFAULT_VERIFICATION_INTERVAL, None));
+        for (interval, confirmations) in [("0", "-1"), ("soon", "six"), ("", " "), ("-5", "1.5")] {
+            assert_eq!(VerifierConfig::parse(Some(interval), Some(confirmations)), defaults);
+        }
+
+        let testnet = VerifierConfig::parse(Some(" 5 "), Some("0"));
+        assert_eq!((testnet.interval, testnet.min_confirmations), (Duration::from_secs(5), Some(0)));
+        let mainnet = VerifierConfig::parse(Some("60"), Some("32"));
+        assert_eq!((mainnet.interval, mainnet.min_confirmations), (Duration::from_secs(60), Some(32)));
+
+        assert_eq!(positive_or(None, "STALE_TX_HOURS", 24), 24);
+        assert_eq!(positive_or(Some("0"), "STALE_TX_HOURS", 24), 24);
+        assert_eq!(positive_or(Some("48"), "STALE_TX_HOURS", 24), 48);
+    }
 }
@@ -1,9 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex};
+use crate::util::price::XchPriceProvider;
+use crate::util::rate_limit::RateLimiter;
+use crate::util::webhook::WebhookNotifier;
+
+/// Health of the background transaction verifier, updated every tick
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct VerifierStatus {
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    /// Pending transactions processed by the last run
+    pub last_processed: usize,
+    pub runs: u64,
+}
+
+impl VerifierStatus {
+    /// Healthy if the verifier ran (or, before its first run, started) within 3x its interval
+    pub fn is_healthy(&self, now: chrono::DateTime<chrono::Utc>, interval: Duration) -> bool {
+        let limit = chrono::Duration::from_std(interval * 3).unwrap_or(chrono::Duration::MAX);
+        match self.last_run_at.or(self.started_at) {
+            Some(at) => now - at <= limit,
+            None => false,
+        }
+    }
+}
+
+/// A trade's status after a change, sent to its `/trades/:id/events` subscribers
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TradeStatusEvent {
+    pub trade_id: i64,
+    pub status: String,
+    /// What happened: `status` change, new `tracking` info, or a payment `confirmation`
+    pub kind: String,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Events a slow subscriber may fall behind by before it skips ahead
+const TRADE_EVENT_BUFFER: usize = 16;
+
+/// Where the SSL paths are saved unless `SSL_PATHS_FILE` says otherwise
+pub const DEFAULT_SSL_PATHS_FILE: &str = "ssl/paths.json";
+
+/// Certificate, key and CA paths of one connection mode
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModeSslPaths {
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+    pub ca_path: Option<String>,
+}
+
+/// Contents of the SSL paths file, so uploaded certificates are trusted again after a restart
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersistedSslPaths {
+    #[serde(default)]
+    pub full_node: ModeSslPaths,
+    #[serde(default)]
+    pub wallet: ModeSslPaths,
+}
+
+type SslPathSlot = Mutex<Option<String>>;
 
 #[derive(Clone)]
 pub struct AppState {
-    rpc_url: Arc<Mutex<String>>,
+    rpc_url_full_node: Arc<Mutex<String>>,
+    rpc_url_wallet: Arc<Mutex<String>>,
     connection_mode: Arc<Mutex<String>>, // "full_node" or "wallet"
     ssl_cert_path_full_node: Arc<Mutex<Option<String>>>,
     ssl_key_path_full_node: Arc<Mutex<Option<String>>>,
@@ -11,30 +76,283 @@ pub struct AppState {
     ssl_key_path_wallet: Arc<Mutex<Option<String>>>,
     ssl_ca_path_full_node: Arc<Mutex<Option<String>>>,
     ssl_ca_path_wallet: Arc<Mutex<Option<String>>>,
+    verifier_status: Arc<Mutex<VerifierStatus>>,
+    passthrough_limiter: Arc<RateLimiter>,
+    disabled_rpc_methods: Arc<HashSet<String>>,
+    price_provider: Arc<XchPriceProvider>,
+    /// Where SSL path changes are saved; `None` keeps them in memory only
+    ssl_paths_file: Option<Arc<PathBuf>>,
+    /// Receives confirmed-transaction webhooks; `None` unless `WEBHOOK_URL` is set
+    webhook: Option<Arc<WebhookNotifier>>,
+    /// One broadcast channel per trade with live event subscribers
+    trade_channels: Arc<std::sync::Mutex<HashMap<i64, broadcast::Sender<TradeStatusEvent>>>>,
+}
+
+/// Parse a comma-separated method list (as in `RPC_DISABLED_METHODS`), ignoring blanks
+pub fn parse_method_list(value: &str) -> HashSet<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Where each Chia RPC listens on a default local install
+pub fn default_rpc_url(mode: &str) -> &'static str {
+    if mode == "wallet" {
+        "https://localhost:9256"
+    } else {
+        "https://localhost:8555"
+    }
+}
+
+/// Whether `url` points at an RPC on this machine (a local node install)
+pub fn is_local_rpc_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .is_some_and(|u| matches!(u.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")))
+}
+
+/// Complete a partial RPC URL for `mode`: blank means the local default, and local
+/// URLs always use https (the Chia RPCs only serve TLS) with the mode's port if none is given
+pub fn normalize_rpc_url(url: &str, mode: &str) -> String {
+    let url = url.trim();
+    if url.is_empty() {
+        return default_rpc_url(mode).to_string();
+    }
+    if !(url.contains("localhost") || url.contains("127.0.0.1")) {
+        return url.to_string();
+    }
+    let url = url.replacen("http://", "https://", 1);
+    if reqwest::Url::parse(&url).ok().and_then(|u| u.port()).is_some() {
+        url
+    } else {
+        let port = if mode == "wallet" { 9256 } else { 8555 };
+        format!("{}:{}", url.trim_end_matches('/'), port)
+    }
 }
 
 impl AppState {
+    /// `initial_url` is the single pre-split setting (`CHIA_RPC_URL`): it fills the slot of
+    /// the mode its port points at (full node when unclear), which also becomes the
+    /// connection mode; the other mode starts at its local default
     pub fn new(initial_url: String) -> Self {
+        let mode = crate::api::chia::infer_connection_mode(&initial_url).unwrap_or("full_node");
+        let (full_node_url, wallet_url) = if mode == "wallet" {
+            (default_rpc_url("full_node").to_string(), normalize_rpc_url(&initial_url, mode))
+        } else {
+            (normalize_rpc_url(&initial_url, mode), default_rpc_url("wallet").to_string())
+        };
         Self {
-            rpc_url: Arc::new(Mutex::new(initial_url)),
-            connection_mode: Arc::new(Mutex::new("full_node".to_string())),
+            rpc_url_full_node: Arc::new(Mutex::new(full_node_url)),
+            rpc_url_wallet: Arc::new(Mutex::new(wallet_url)),
+            connection_mode: Arc::new(Mutex::new(mode.to_string())),
             ssl_cert_path_full_node: Arc::new(Mutex::new(None)),
             ssl_key_path_full_node: Arc::new(Mutex::new(None)),
             ssl_cert_path_wallet: Arc::new(Mutex::new(None)),
             ssl_key_path_wallet: Arc::new(Mutex::new(None)),
             ssl_ca_path_full_node: Arc::new(Mutex::new(None)),
             ssl_ca_path_wallet: Arc::new(Mutex::new(None)),
+            verifier_status: Arc::new(Mutex::new(VerifierStatus::default())),
+            passthrough_limiter: Arc::new(RateLimiter::from_env(
+                "CHIA_PASSTHROUGH_BURST",
+                "CHIA_PASSTHROUGH_PER_MINUTE",
+                10,
+                30,
+            )),
+            disabled_rpc_methods: Arc::new(parse_method_list(
+                &std::env::var("RPC_DISABLED_METHODS").unwrap_or_default(),
+            )),
+            price_provider: Arc::new(XchPriceProvider::from_env()),
+            ssl_paths_file: None,
+            webhook: WebhookNotifier::from_env().map(Arc::new),
+            trade_channels: Arc::default(),
+        }
+    }
+
+    /// Save SSL path changes to `path` (see `restore_ssl_paths`)
+    pub fn with_ssl_paths_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ssl_paths_file = Some(Arc::new(path.into()));
+        self
+    }
+
+    /// Load the SSL paths saved by an earlier run, if any. A missing file is not an error.
+    pub async fn restore_ssl_paths(&self) -> Result<(), String> {
+        let Some(file) = &self.ssl_paths_file else { return Ok(()) };
+        let json = match tokio::fs::read(file.as_path()).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(format!("Cannot read {}: {}", file.display(), e)),
+        };
+        let saved: PersistedSslPaths =
+            serde_json::from_slice(&json).map_err(|e| format!("Invalid {}: {}", file.display(), e))?;
+        for (mode, paths) in [("full_node", saved.full_node), ("wallet", saved.wallet)] {
+            let (cert, key, ca) = self.ssl_slots(mode);
+            *cert.lock().await = paths.cert_path;
+            *key.lock().await = paths.key_path;
+            *ca.lock().await = paths.ca_path;
         }
+        Ok(())
+    }
+
+    /// Cert, key and CA slots of `mode`
+    fn ssl_slots(&self, mode: &str) -> (&SslPathSlot, &SslPathSlot, &SslPathSlot) {
+        if mode == "wallet" {
+            (&self.ssl_cert_path_wallet, &self.ssl_key_path_wallet, &self.ssl_ca_path_wallet)
+        } else {
+            (&self.ssl_cert_path_full_node, &self.ssl_key_path_full_node, &self.ssl_ca_path_full_node)
+        }
+    }
+
+    async fn mode_ssl_paths(&self, mode: &str) -> ModeSslPaths {
+        let (cert, key, ca) = self.ssl_slots(mode);
+        ModeSslPaths {
+            cert_path: cert.lock().await.clone(),
+            key_path: key.lock().await.clone(),
+            ca_path: ca.lock().await.clone(),
+        }
+    }
+
+    /// Write the current SSL paths to the paths file. Failures are logged: the
+    /// in-memory paths still apply until the next restart.
+    async fn persist_ssl_paths(&self) {
+        let Some(file) = &self.ssl_paths_file else { return };
+        let saved = PersistedSslPaths {
+            full_node: self.mode_ssl_paths("full_node").await,
+            wallet: self.mode_ssl_paths("wallet").await,
+        };
+        let result = async {
+            if let Some(dir) = file.parent().filter(|d| !d.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let json = serde_json::to_vec_pretty(&saved).map_err(std::io::Error::other)?;
+            tokio::fs::write(file.as_path(), json).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to save SSL paths to {}: {}", file.display(), e);
+        }
+    }
+
+    /// Replace the set of JSON-RPC methods this deployment refuses
+    pub fn with_disabled_rpc_methods(mut self, methods: &str) -> Self {
+        self.disabled_rpc_methods = Arc::new(parse_method_list(methods));
+        self
+    }
+
+    /// JSON-RPC methods disabled for this deployment (`RPC_DISABLED_METHODS`)
+    pub fn disabled_rpc_methods(&self) -> &HashSet<String> {
+        &self.disabled_rpc_methods
+    }
+
+    /// Replace the per-user limit on wallet/node passthrough RPCs
+    pub fn with_passthrough_limit(mut self, burst: u32, per_minute: u32) -> Self {
+        self.passthrough_limiter = Arc::new(RateLimiter::new(burst, per_minute));
+        self
     }
 
+    /// Per-user limiter for wallet/node passthrough RPCs
+    pub fn passthrough_limiter(&self) -> &RateLimiter {
+        &self.passthrough_limiter
+    }
+
+    /// Replace the XCH/USD price source
+    pub fn with_price_provider(mut self, provider: XchPriceProvider) -> Self {
+        self.price_provider = Arc::new(provider);
+        self
+    }
+
+    /// Server-side XCH/USD price used to check commitment fee amounts
+    pub fn price_provider(&self) -> &XchPriceProvider {
+        &self.price_provider
+    }
+
+    /// Where confirmed transactions are announced, if anywhere
+    pub fn webhook(&self) -> Option<Arc<WebhookNotifier>> {
+        self.webhook.clone()
+    }
+
+    pub async fn verifier_status(&self) -> VerifierStatus {
+        self.verifier_status.lock().await.clone()
+    }
+
+    /// Listen for a trade's status events. Channels nobody listens to any more are dropped here.
+    pub fn subscribe_trade(&self, trade_id: i64) -> broadcast::Receiver<TradeStatusEvent> {
+        let mut channels = self.trade_channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels.retain(|_, tx| tx.receiver_count() > 0);
+        channels
+            .entry(trade_id)
+            .or_insert_with(|| broadcast::channel(TRADE_EVENT_BUFFER).0)
+            .subscribe()
+    }
+
+    /// Whether anyone is listening for a trade's events (to skip building unwanted ones)
+    pub fn has_trade_subscribers(&self, trade_id: i64) -> bool {
+        let channels = self.trade_channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels.get(&trade_id).is_some_and(|tx| tx.receiver_count() > 0)
+    }
+
+    /// Send an event to the trade's subscribers, if any
+    pub fn publish_trade_event(&self, event: TradeStatusEvent) {
+        let mut channels = self.trade_channels.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tx) = channels.get(&event.trade_id) {
+            let trade_id = event.trade_id;
+            if tx.send(event).is_err() {
+                channels.remove(&trade_id);
+            }
+        }
+    }
+
+    pub async fn mark_verifier_started(&self) {
+        self.verifier_status.lock().await.started_at = Some(chrono::Utc::now());
+    }
+
+    /// Record the outcome of one verifier tick (processed count or error message)
+    pub async fn record_verifier_run(&self, result: Result<usize, String>) {
+        let mut status = self.verifier_status.lock().await;
+        status.last_run_at = Some(chrono::Utc::now());
+        status.runs += 1;
+        match result {
+            Ok(processed) => {
+                status.last_processed = processed;
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e),
+        }
+    }
+
+    fn rpc_url_slot(&self, mode: &str) -> &Mutex<String> {
+        if mode == "wallet" {
+            &self.rpc_url_wallet
+        } else {
+            &self.rpc_url_full_node
+        }
+    }
+
+    /// Store the RPC URL for one mode ("wallet" or "full_node"); the other mode's URL is untouched
+    pub async fn set_rpc_url_for_mode(&self, mode: &str, url: &str) {
+        *self.rpc_url_slot(mode).lock().await = normalize_rpc_url(url, mode);
+    }
+
+    pub async fn rpc_url_for_mode(&self, mode: &str) -> String {
+        self.rpc_url_slot(mode).lock().await.clone()
+    }
+
+    /// Store a URL without an explicit mode: it goes to the mode its port points at,
+    /// else to the current connection mode
     pub async fn set_rpc_url(&self, url: String) {
-        let mut guard = self.rpc_url.lock().await;
-        *guard = url;
+        let mode = match crate::api::chia::infer_connection_mode(&url) {
+            Some(mode) => mode.to_string(),
+            None => self.connection_mode().await,
+        };
+        self.set_rpc_url_for_mode(&mode, &url).await;
     }
 
+    /// RPC URL of the current connection mode
     pub async fn rpc_url(&self) -> String {
-        let guard = self.rpc_url.lock().await;
-        guard.clone()
+        let mode = self.connection_mode().await;
+        self.rpc_url_for_mode(&mode).await
     }
 
     pub async fn set_connection_mode(&self, mode: String) {
@@ -59,6 +377,7 @@ impl AppState {
             let mut key_guard = self.ssl_key_path_full_node.lock().await;
             *key_guard = Some(key_path.clone());
         }
+        self.persist_ssl_paths().await;
     }
 
     pub async fn get_ssl_paths(&self) -> (Option<String>, Option<String>) {
@@ -74,6 +393,18 @@ impl AppState {
         }
     }
 
+    pub async fn get_ssl_paths_for_mode(&self, mode: &str) -> (Option<String>, Option<String>) {
+        if mode == "wallet" {
+            let cert = self.ssl_cert_path_wallet.lock().await;
+            let key = self.ssl_key_path_wallet.lock().await;
+            (cert.clone(), key.clone())
+        } else {
+            let cert = self.ssl_cert_path_full_node.lock().await;
+            let key = self.ssl_key_path_full_node.lock().await;
+            (cert.clone(), key.clone())
+        }
+    }
+
     // Set PKCS#12 identity path and optional password
 
 
@@ -85,6 +416,7 @@ impl AppState {
             let mut guard = self.ssl_ca_path_full_node.lock().await;
             *guard = Some(ca_path.clone());
         }
+        self.persist_ssl_paths().await;
     }
 
     pub async fn get_ssl_ca_path_for_mode(&self, mode: &str) -> Option<String> {
@@ -97,3 +429,42 @@ impl AppState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ssl_paths_survive_restart() {
+        let file = std::env::temp_dir().join(format!("dtrex-ssl-{}", uuid::Uuid::new_v4())).join("paths.json");
+        let state = AppState::new("http://localhost:8555".to_string()).with_ssl_paths_file(&file);
+        // Nothing saved yet
+        state.restore_ssl_paths().await.unwrap();
+        assert_eq!(state.get_ssl_paths_for_mode("wallet").await, (None, None));
+
+        state.set_ssl_paths_for_mode("wallet", "w.crt".to_string(), "w.key".to_string()).await;
+        state.set_ssl_ca_path_for_mode("wallet", "w_ca.crt".to_string()).await;
+        state.set_ssl_paths_for_mode("full_node", "n.crt".to_string(), "n.key".to_string()).await;
+
+        // A fresh state (as after a restart) picks them up from the file
+        let restarted = AppState::new("http://localhost:8555".to_string()).with_ssl_paths_file(&file);
+        restarted.restore_ssl_paths().await.unwrap();
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+        assert_eq!(
+            restarted.get_ssl_paths_for_mode("wallet").await,
+            (Some("w.crt".to_string()), Some("w.key".to_string()))
+        );
+        assert_eq!(restarted.get_ssl_ca_path_for_mode("wallet").await, Some("w_ca.crt".to_string()));
+        assert_eq!(
+            restarted.get_ssl_paths_for_mode("full_node").await,
+            (Some("n.crt".to_string()), Some("n.key".to_string()))
+        );
+        assert_eq!(restarted.get_ssl_ca_path_for_mode("full_node").await, None);
+
+        // Without a file nothing is written or read
+        let memory_only = AppState::new("http://localhost:8555".to_string());
+        memory_only.set_ssl_paths_for_mode("wallet", "x.crt".to_string(), "x.key".to_string()).await;
+        memory_only.restore_ssl_paths().await.unwrap();
+        assert_eq!(memory_only.get_ssl_paths_for_mode("wallet").await.0, Some("x.crt".to_string()));
+    }
+}
@@ -0,0 +1,411 @@
+use std::io::Cursor;
+
+use clvmr::serde::{
+    node_from_bytes_backrefs, node_to_bytes, node_to_bytes_limit, serialized_length_from_bytes, tree_hash_from_stream,
+};
+use clvmr::{Allocator, NodePtr};
+
+use super::escrow::decode_pubkey;
+
+/// A CLVM program, held in its standard serialization (no back-references)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program(Vec<u8>);
+
+/// Largest serialization `Program::deserialize` expands back-references into
+const MAX_PROGRAM_BYTES: usize = 1024 * 1024;
+
+/// Operator names accepted by `assemble`
+const OPERATORS: &[(&str, u8)] = &[
+    ("q", 1),
+    ("a", 2),
+    ("i", 3),
+    ("c", 4),
+    ("f", 5),
+    ("r", 6),
+    ("l", 7),
+    ("x", 8),
+    ("=", 9),
+    ("sha256", 11),
+    ("+", 16),
+    ("-", 17),
+    ("*", 18),
+    (">", 21),
+];
+
+/// Recursive helper computing the sha256 tree hash of its argument.
+/// Env: (SELF TREE)
+const SHA256TREE_MOD: &str = "
+(a (i (l 5)
+      (q . (sha256 (q . 2) (a 2 (c 2 (c 9 ()))) (a 2 (c 2 (c 13 ())))))
+      (q . (sha256 (q . 1) 5)))
+   1)";
+
+/// Recursive helper emitting an AGG_SIG_ME (50) for each selected key, then the conditions.
+/// Fails with `(x)` if fewer than NEEDED keys were selected.
+/// Env: (SELF NEEDED PUBKEYS SELECTORS MSG CONDITIONS)
+const SIGS_MOD: &str = "
+(a (i 23
+      (q . (a (i 39
+                 (q . (c (c (q . 50) (c 19 (c 47 ())))
+                         (a 2 (c 2 (c (- 5 (q . 1)) (c 27 (c 55 (c 47 (c 95 ())))))))))
+                 (q . (a 2 (c 2 (c 5 (c 27 (c 55 (c 47 (c 95 ())))))))))
+              1))
+      (q . (a (i (> 5 ()) (q . (x)) (q . 95)) 1)))
+   1)";
+
+/// Build the m-of-n puzzle source.
+///
+/// Curried env: (M PUBKEYS TERMS_HASH), solution: (SELECTORS CONDITIONS), where
+/// SELECTORS has one entry per pubkey (non-nil = signs). Every selected key must
+/// sign `sha256tree(CONDITIONS)` with AGG_SIG_ME. TERMS_HASH is only committed to.
+fn m_of_n_source() -> String {
+    format!(
+        "(a (q . {sigs}) (c (q . {sigs}) (c 2 (c 5 (c 23 (c (a (q . {tree}) (c (q . {tree}) (c 47 ()))) (c 47 ())))))))",
+        sigs = SIGS_MOD,
+        tree = SHA256TREE_MOD,
+    )
+}
+
+impl Program {
+    /// Serialize `node` from `a`
+    pub fn from_node(a: &Allocator, node: NodePtr) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Program(node_to_bytes(a, node)?))
+    }
+
+    /// Parse a serialized program, expanding any back-references.
+    /// The whole input must be one program.
+    pub fn deserialize(bytes: &[u8]) -> Result<Program, Box<dyn std::error::Error + Send + Sync>> {
+        let len = serialized_length_from_bytes(bytes)?;
+        if len != bytes.len() as u64 {
+            return Err(format!("{} trailing bytes after CLVM program", bytes.len() as u64 - len).into());
+        }
+        let mut a = Allocator::new();
+        let node = node_from_bytes_backrefs(&mut a, bytes)?;
+        Ok(Program(node_to_bytes_limit(&a, node, MAX_PROGRAM_BYTES)?))
+    }
+
+    /// Standard CLVM serialization
+    pub fn serialize(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// sha256 tree hash (the puzzle hash of a puzzle)
+    pub fn tree_hash(&self) -> [u8; 32] {
+        tree_hash_from_stream(&mut Cursor::new(&self.0)).expect("Program holds a valid serialization")
+    }
+}
+
+/// Bind leading arguments: `(a (q . MOD) (c (q . arg1) (c (q . arg2) ... 1)))`
+pub fn curry(
+    a: &mut Allocator,
+    module: NodePtr,
+    args: &[NodePtr],
+) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    let mut env = a.one();
+    for &arg in args.iter().rev() {
+        let quoted = quote(a, arg)?;
+        let cons = a.new_small_number(4)?;
+        env = list(a, &[cons, quoted, env])?;
+    }
+    let quoted = quote(a, module)?;
+    let apply = a.new_small_number(2)?;
+    list(a, &[apply, quoted, env])
+}
+
+/// `(q . node)`
+fn quote(a: &mut Allocator, node: NodePtr) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    let q = a.one();
+    Ok(a.new_pair(q, node)?)
+}
+
+/// Proper (nil-terminated) list of the given items
+fn list(a: &mut Allocator, items: &[NodePtr]) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    let mut rest = a.nil();
+    for &item in items.iter().rev() {
+        rest = a.new_pair(item, rest)?;
+    }
+    Ok(rest)
+}
+
+/// Assemble CLVM assembly text, e.g. `(a (q . 2) 1)`, into `a`.
+///
+/// Supports lists, dotted pairs, `()`, decimal integers, `0x` hex atoms and the
+/// operator names in `OPERATORS`.
+pub fn assemble(a: &mut Allocator, src: &str) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    let spaced = src.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut pos = 0;
+    let program = parse_expr(a, &tokens, &mut pos)?;
+    if pos != tokens.len() {
+        return Err(format!("Unexpected token '{}' after program", tokens[pos]).into());
+    }
+    Ok(program)
+}
+
+fn next_token<'a>(tokens: &[&'a str], pos: &mut usize) -> Result<&'a str, Box<dyn std::error::Error + Send + Sync>> {
+    let token = tokens.get(*pos).ok_or("Unexpected end of program")?;
+    *pos += 1;
+    Ok(token)
+}
+
+fn parse_expr(
+    a: &mut Allocator,
+    tokens: &[&str],
+    pos: &mut usize,
+) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    match next_token(tokens, pos)? {
+        "(" => parse_list(a, tokens, pos),
+        ")" | "." => Err(format!("Unexpected '{}'", tokens[*pos - 1]).into()),
+        token => parse_atom(a, token),
+    }
+}
+
+fn parse_list(
+    a: &mut Allocator,
+    tokens: &[&str],
+    pos: &mut usize,
+) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    match tokens.get(*pos).copied() {
+        Some(")") => {
+            *pos += 1;
+            Ok(a.nil())
+        }
+        Some(".") => {
+            *pos += 1;
+            let rest = parse_expr(a, tokens, pos)?;
+            match next_token(tokens, pos)? {
+                ")" => Ok(rest),
+                other => Err(format!("Expected ')' after dotted pair, got '{}'", other).into()),
+            }
+        }
+        _ => {
+            let first = parse_expr(a, tokens, pos)?;
+            let rest = parse_list(a, tokens, pos)?;
+            Ok(a.new_pair(first, rest)?)
+        }
+    }
+}
+
+fn parse_atom(a: &mut Allocator, token: &str) -> Result<NodePtr, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some((_, op)) = OPERATORS.iter().find(|(name, _)| *name == token) {
+        return Ok(a.new_small_number(*op as u32)?);
+    }
+    if let Some(hex_str) = token.strip_prefix("0x") {
+        let bytes = hex::decode(hex_str).map_err(|e| format!("Invalid hex atom '{}': {}", token, e))?;
+        return Ok(a.new_atom(&bytes)?);
+    }
+    let n = token.parse::<i64>().map_err(|_| format!("Unknown token '{}'", token))?;
+    Ok(a.new_number(n.into())?)
+}
+
+/// A puzzle ready to put on chain
+#[derive(Debug, Clone)]
+pub struct CompiledPuzzle {
+    /// Serialized puzzle program (the puzzle reveal)
+    pub program: Vec<u8>,
+    /// Tree hash of the program (the puzzle hash)
+    pub tree_hash: [u8; 32],
+}
+
+/// Assemble an m-of-n puzzle over the participants' 48-byte BLS public keys (hex).
+///
+/// The solution is `(SELECTORS CONDITIONS)`: one selector per key, in the given
+/// order, non-nil for each key that signs. At least `threshold` keys must be
+/// selected and each must sign `sha256tree(CONDITIONS)` with AGG_SIG_ME.
+/// `terms_hash` (hex) is curried in so each set of contract terms gets its own puzzle hash.
+pub fn m_of_n_puzzle(
+    pubkeys: &[String],
+    threshold: usize,
+    terms_hash: &str,
+) -> Result<CompiledPuzzle, Box<dyn std::error::Error + Send + Sync>> {
+    if threshold == 0 || threshold > pubkeys.len() {
+        return Err(format!("Threshold must be between 1 and {}, got {}", pubkeys.len(), threshold).into());
+    }
+    let mut a = Allocator::new();
+    let keys = pubkeys
+        .iter()
+        .map(|pk| Ok(a.new_atom(&decode_pubkey("participant", pk)?)?))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+    let keys = list(&mut a, &keys)?;
+    let terms = hex::decode(terms_hash.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid terms hash: {}", e))?;
+    let terms = a.new_atom(&terms)?;
+    let threshold = a.new_small_number(threshold as u32)?;
+
+    let module = assemble(&mut a, &m_of_n_source())?;
+    let puzzle = curry(&mut a, module, &[threshold, keys, terms])?;
+    let puzzle = Program::from_node(&a, puzzle)?;
+
+    Ok(CompiledPuzzle {
+        tree_hash: puzzle.tree_hash(),
+        program: puzzle.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clvmr::serde::{node_from_bytes, node_to_bytes_backrefs};
+    use clvmr::{run_program, ChiaDialect};
+
+    fn pk(byte: u8) -> String {
+        hex::encode([byte; 48])
+    }
+
+    /// Assemble `src` on its own allocator
+    fn program(src: &str) -> Program {
+        let mut a = Allocator::new();
+        let node = assemble(&mut a, src).unwrap();
+        Program::from_node(&a, node).unwrap()
+    }
+
+    /// Run a serialized puzzle against `solution` with clvmr, returning the serialized output
+    fn run(puzzle: &[u8], solution: &str) -> Result<Vec<u8>, clvmr::reduction::EvalErr> {
+        let mut a = Allocator::new();
+        let puzzle = node_from_bytes(&mut a, puzzle).unwrap();
+        let solution = assemble(&mut a, solution).unwrap();
+        let output = run_program(&mut a, &ChiaDialect::new(0), puzzle, solution, 11_000_000_000)?.1;
+        Ok(node_to_bytes(&a, output).unwrap())
+    }
+
+    #[test]
+    fn test_deserialize_round_trip() {
+        let long_atom = format!("0x{}", "07".repeat(0x2000));
+        for src in ["()", "1", "-129", &long_atom, "(a (q . (c 2 (q 1 2 3))) (c (q . 0xdeadbeef) 1))"] {
+            let p = program(src);
+            assert_eq!(Program::deserialize(p.serialize()).unwrap(), p, "{}", src);
+        }
+
+        let compiled = m_of_n_puzzle(&[pk(1), pk(2)], 2, &hex::encode([0u8; 32])).unwrap();
+        let parsed = Program::deserialize(&compiled.program).unwrap();
+        assert_eq!(parsed.tree_hash(), compiled.tree_hash);
+        assert_eq!(parsed.serialize(), compiled.program);
+
+        // Back-references expand to the standard serialization
+        let mut a = Allocator::new();
+        let key = a.new_atom(&[0xab; 48]).unwrap();
+        let pair = a.new_pair(key, key).unwrap();
+        let repeated = list(&mut a, &[pair, pair, pair]).unwrap();
+        let compressed = node_to_bytes_backrefs(&a, repeated).unwrap();
+        let expanded = node_to_bytes(&a, repeated).unwrap();
+        assert!(compressed.len() < expanded.len());
+        assert_eq!(Program::deserialize(&compressed).unwrap().serialize(), expanded);
+
+        for bad in [&[][..], &[0xff, 0x80], &[0x80, 0x80], &[0x82, 0x01], &[0xfc]] {
+            assert!(Program::deserialize(bad).is_err(), "{:02x?}", bad);
+        }
+
+        // A small compressed program must not expand past the size limit
+        let mut bomb = a.new_atom(&[0xcd; 1024]).unwrap();
+        for _ in 0..12 {
+            bomb = a.new_pair(bomb, bomb).unwrap();
+        }
+        let compressed = node_to_bytes_backrefs(&a, bomb).unwrap();
+        assert!(compressed.len() < 2048);
+        assert!(Program::deserialize(&compressed).is_err());
+    }
+
+    #[test]
+    fn test_tree_hash_known_programs() {
+        // Reference values from chia-blockchain
+        let nil = program("()");
+        assert_eq!(hex::encode(nil.serialize()), "80");
+        assert_eq!(
+            hex::encode(nil.tree_hash()),
+            "4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a"
+        );
+        assert_eq!(
+            hex::encode(program("1").tree_hash()),
+            "9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2"
+        );
+
+        // DEFAULT_HIDDEN_PUZZLE `(=)`
+        let hidden = program("(=)");
+        assert_eq!(hex::encode(hidden.serialize()), "ff0980");
+        assert_eq!(
+            hex::encode(hidden.tree_hash()),
+            "711d6c4e32c92e53179b199484cf8c897542bc57f2b22582799f9d657eec4699"
+        );
+    }
+
+    #[test]
+    fn test_tree_hash_matches_clvm_sha256tree() {
+        let sha256tree = program(SHA256TREE_MOD);
+        for src in ["()", "0xdeadbeef", "(1 2 3)", "((a . q) (0x00ff . -5) ())"] {
+            let output = run(sha256tree.serialize(), &format!("({} {})", SHA256TREE_MOD, src)).unwrap();
+            assert_eq!(output[0], 0xa0);
+            assert_eq!(output[1..], program(src).tree_hash(), "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_assemble_and_serialize() {
+        assert_eq!(hex::encode(program("(a (q . 2) 1)").serialize()), "ff02ffff0102ff0180");
+        assert_eq!(hex::encode(program("(q . 0x00ff)").serialize()), "ff018200ff");
+        let mut a = Allocator::new();
+        assert!(assemble(&mut a, "(a (q . 2)").is_err());
+        assert!(assemble(&mut a, "(nope)").is_err());
+
+        assert_eq!(hex::encode(program("128").serialize()), "820080");
+        assert_eq!(hex::encode(program("-1").serialize()), "81ff");
+        assert_eq!(hex::encode(program("256").serialize()), "820100");
+        assert_eq!(hex::encode(program(&format!("0x{}", pk(0xaa))).serialize())[..2], *"b0");
+    }
+
+    #[test]
+    fn test_curry() {
+        let mut a = Allocator::new();
+        let module = assemble(&mut a, "1").unwrap();
+        let five = a.new_small_number(5).unwrap();
+        let curried = curry(&mut a, module, &[five]).unwrap();
+        assert_eq!(Program::from_node(&a, curried).unwrap(), program("(a (q . 1) (c (q . 5) 1))"));
+
+        // Curried arguments come first in the environment, then the solution
+        let module = assemble(&mut a, "(+ 2 5)").unwrap();
+        let three = a.new_small_number(3).unwrap();
+        let curried = curry(&mut a, module, &[three]).unwrap();
+        let curried = Program::from_node(&a, curried).unwrap();
+        assert_eq!(run(curried.serialize(), "(4)").unwrap(), [0x07]);
+    }
+
+    #[test]
+    fn test_m_of_n_puzzle() {
+        let keys = vec![pk(1), pk(2), pk(3)];
+        let terms = hex::encode([0u8; 32]);
+
+        let puzzle = m_of_n_puzzle(&keys, 2, &terms).unwrap();
+        assert_eq!(
+            hex::encode(puzzle.tree_hash),
+            "b5d5e37ae5e9673fdb548c4333968503bde2c5de328ba65560f5591bbef18393"
+        );
+        assert_eq!(m_of_n_puzzle(&keys, 2, &terms).unwrap().program, puzzle.program);
+        assert_ne!(m_of_n_puzzle(&keys, 3, &terms).unwrap().tree_hash, puzzle.tree_hash);
+        assert_ne!(m_of_n_puzzle(&keys, 2, &hex::encode([1u8; 32])).unwrap().tree_hash, puzzle.tree_hash);
+
+        assert!(m_of_n_puzzle(&keys, 0, &terms).is_err());
+        assert!(m_of_n_puzzle(&keys, 4, &terms).is_err());
+        assert!(m_of_n_puzzle(&["0xpubkey1".to_string()], 1, &terms).is_err());
+        assert!(m_of_n_puzzle(&keys, 2, "not-hex").is_err());
+    }
+
+    #[test]
+    fn test_m_of_n_puzzle_runs() {
+        let keys = vec![pk(1), pk(2), pk(3)];
+        let puzzle = m_of_n_puzzle(&keys, 2, &hex::encode([0u8; 32])).unwrap();
+        let conditions = "((51 0xcafe 100))";
+        let msg = hex::encode(program(conditions).tree_hash());
+
+        // Keys 1 and 3 sign: one AGG_SIG_ME each over sha256tree(CONDITIONS), then the conditions
+        let output = run(&puzzle.program, &format!("((1 () 1) {})", conditions)).unwrap();
+        let expected = program(&format!(
+            "((50 0x{k1} 0x{msg}) (50 0x{k3} 0x{msg}) (51 0xcafe 100))",
+            k1 = keys[0],
+            k3 = keys[2],
+            msg = msg
+        ));
+        assert_eq!(output, expected.serialize());
+
+        // Below the threshold the puzzle raises
+        assert!(run(&puzzle.program, &format!("((1 () ()) {})", conditions)).is_err());
+    }
+}
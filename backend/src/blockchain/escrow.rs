@@ -0,0 +1,82 @@
+use sha2::{Digest, Sha256};
+
+/// Domain tag so escrow hashes can never collide with other puzzle hashes
+const ESCROW_DOMAIN: &[u8] = b"dtrex-escrow-v1";
+
+/// Signatures required to release an escrow (any 2 of proposer, acceptor, exchange)
+pub const ESCROW_REQUIRED_SIGS: u8 = 2;
+
+/// Length of a BLS G1 public key in bytes
+const PUBKEY_LEN: usize = 48;
+
+/// Derive the escrow puzzle hash for a trade
+///
+/// The hash commits to a 2-of-3 escrow between the proposer, the acceptor and
+/// the exchange. It is computed as:
+///
+/// `sha256("dtrex-escrow-v1" || trade_id (u64 BE) || proposer_pk || acceptor_pk || exchange_pk || 2)`
+///
+/// Public keys are 48-byte BLS keys given as hex (an optional `0x` prefix is
+/// accepted). Key order is fixed by role, so the same trade and keys always
+/// produce the same hash, and each trade gets its own escrow address.
+pub fn derive_escrow_puzzle_hash(
+    trade_id: i64,
+    proposer_pk: &str,
+    acceptor_pk: &str,
+    exchange_pk: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let trade_id = u64::try_from(trade_id).map_err(|_| "Trade id must be positive")?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(ESCROW_DOMAIN);
+    hasher.update(trade_id.to_be_bytes());
+    for (role, pk) in [("proposer", proposer_pk), ("acceptor", acceptor_pk), ("exchange", exchange_pk)] {
+        hasher.update(decode_pubkey(role, pk)?);
+    }
+    hasher.update([ESCROW_REQUIRED_SIGS]);
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Decode a hex BLS public key, checking its length
+pub(crate) fn decode_pubkey(role: &str, pk: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = hex::decode(pk.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid {} public key: {}", role, e))?;
+    if bytes.len() != PUBKEY_LEN {
+        return Err(format!("Invalid {} public key: expected {} bytes, got {}", role, PUBKEY_LEN, bytes.len()).into());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pk(byte: u8) -> String {
+        hex::encode([byte; PUBKEY_LEN])
+    }
+
+    #[test]
+    fn test_escrow_hash_deterministic() {
+        let hash1 = derive_escrow_puzzle_hash(42, &pk(1), &pk(2), &pk(3)).unwrap();
+        let hash2 = derive_escrow_puzzle_hash(42, &format!("0x{}", pk(1)), &pk(2), &pk(3)).unwrap();
+
+        assert_eq!(hash1, hash2);
+        assert_eq!(hash1.len(), 64);
+    }
+
+    #[test]
+    fn test_escrow_hash_differs_per_trade_and_role() {
+        let base = derive_escrow_puzzle_hash(1, &pk(1), &pk(2), &pk(3)).unwrap();
+
+        assert_ne!(base, derive_escrow_puzzle_hash(2, &pk(1), &pk(2), &pk(3)).unwrap());
+        assert_ne!(base, derive_escrow_puzzle_hash(1, &pk(2), &pk(1), &pk(3)).unwrap());
+    }
+
+    #[test]
+    fn test_escrow_hash_rejects_bad_keys() {
+        assert!(derive_escrow_puzzle_hash(1, "abcd", &pk(2), &pk(3)).is_err());
+        assert!(derive_escrow_puzzle_hash(1, &pk(1), "zz", &pk(3)).is_err());
+        assert!(derive_escrow_puzzle_hash(-1, &pk(1), &pk(2), &pk(3)).is_err());
+    }
+}
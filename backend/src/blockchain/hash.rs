@@ -0,0 +1,166 @@
+//! 32-byte identifiers used on chain: puzzle hashes and coin ids.
+//!
+//! Nodes, wallets and our own database disagree on whether hex hashes carry a
+//! `0x` prefix (and on case), so comparing them as strings is unreliable. These
+//! types parse either form once and compare by bytes; they always print and
+//! serialize as lowercase `0x…`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// Length of a sha256 digest in bytes
+const HASH_LEN: usize = 32;
+
+/// Why a string is not a 32-byte hex hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashError {
+    /// Not hexadecimal (after an optional `0x` prefix)
+    NotHex,
+    /// Hex decodes to the wrong number of bytes
+    BadLength(usize),
+}
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashError::NotHex => write!(f, "hash is not hex"),
+            HashError::BadLength(len) => write!(f, "hash is {} bytes, expected {}", len, HASH_LEN),
+        }
+    }
+}
+
+impl std::error::Error for HashError {}
+
+/// Decode `0x`-prefixed or bare hex of either case into 32 bytes
+fn parse_bytes32(s: &str) -> Result<[u8; HASH_LEN], HashError> {
+    let s = s.trim();
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    let bytes = hex::decode(digits).map_err(|_| HashError::NotHex)?;
+    <[u8; HASH_LEN]>::try_from(bytes.as_slice()).map_err(|_| HashError::BadLength(bytes.len()))
+}
+
+macro_rules! bytes32_type {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name([u8; HASH_LEN]);
+
+        impl $name {
+            pub fn as_bytes(&self) -> &[u8; HASH_LEN] {
+                &self.0
+            }
+        }
+
+        impl From<[u8; HASH_LEN]> for $name {
+            fn from(bytes: [u8; HASH_LEN]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = HashError;
+
+            fn from_str(s: &str) -> Result<Self, HashError> {
+                parse_bytes32(s).map(Self)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "0x{}", hex::encode(self.0))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+bytes32_type!(
+    /// Hash of a coin's puzzle (what an address encodes)
+    PuzzleHash
+);
+
+bytes32_type!(
+    /// A coin's name: `sha256(parent_coin_id || puzzle_hash || amount)`
+    CoinId
+);
+
+impl CoinId {
+    /// Compute a coin's id the way the node does. The amount is hashed as a
+    /// minimal big-endian CLVM integer (no bytes for 0, a leading zero byte
+    /// when the top bit is set).
+    pub fn compute(parent: &CoinId, puzzle_hash: &PuzzleHash, amount: u64) -> Self {
+        let be = amount.to_be_bytes();
+        let start = be.iter().position(|b| *b != 0).unwrap_or(be.len());
+        let mut hasher = Sha256::new();
+        hasher.update(parent.as_bytes());
+        hasher.update(puzzle_hash.as_bytes());
+        if be.get(start).is_some_and(|b| b & 0x80 != 0) {
+            hasher.update([0u8]);
+        }
+        hasher.update(&be[start..]);
+        Self(hasher.finalize().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a";
+
+    #[test]
+    fn test_prefix_and_case_insensitive_equality() {
+        let bare: PuzzleHash = HEX.parse().unwrap();
+        let prefixed: PuzzleHash = format!("0x{}", HEX).parse().unwrap();
+        let upper: PuzzleHash = format!("0X{}", HEX.to_uppercase()).parse().unwrap();
+        assert_eq!(bare, prefixed);
+        assert_eq!(bare, upper);
+        assert_eq!(bare.to_string(), format!("0x{}", HEX));
+
+        let json = serde_json::to_value(bare).unwrap();
+        assert_eq!(json, serde_json::json!(format!("0x{}", HEX)));
+        assert_eq!(serde_json::from_value::<PuzzleHash>(serde_json::json!(HEX)).unwrap(), bare);
+    }
+
+    #[test]
+    fn test_rejects_invalid_hex() {
+        assert_eq!("0xzz".parse::<CoinId>(), Err(HashError::NotHex));
+        assert_eq!(format!("0x{}", &HEX[1..]).parse::<CoinId>(), Err(HashError::NotHex));
+        assert_eq!("0xabcd".parse::<CoinId>(), Err(HashError::BadLength(2)));
+        assert_eq!(format!("{}00", HEX).parse::<PuzzleHash>(), Err(HashError::BadLength(33)));
+        assert_eq!("".parse::<PuzzleHash>(), Err(HashError::BadLength(0)));
+        assert!(serde_json::from_value::<PuzzleHash>(serde_json::json!("0xbb")).is_err());
+    }
+
+    #[test]
+    fn test_coin_id_amount_encoding() {
+        let parent = CoinId::from([1; HASH_LEN]);
+        let ph = PuzzleHash::from([2; HASH_LEN]);
+        let expected = |amount: &[u8]| {
+            let mut h = Sha256::new();
+            h.update([1; HASH_LEN]);
+            h.update([2; HASH_LEN]);
+            h.update(amount);
+            let digest: [u8; HASH_LEN] = h.finalize().into();
+            CoinId::from(digest)
+        };
+        assert_eq!(CoinId::compute(&parent, &ph, 0), expected(&[]));
+        assert_eq!(CoinId::compute(&parent, &ph, 1), expected(&[1]));
+        assert_eq!(CoinId::compute(&parent, &ph, 128), expected(&[0, 128]));
+        assert_eq!(CoinId::compute(&parent, &ph, 1_000), expected(&[0x03, 0xe8]));
+    }
+}
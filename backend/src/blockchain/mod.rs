@@ -1,2 +1,5 @@
+pub mod clvm;
+pub mod escrow;
+pub mod hash;
 pub mod puzzles;
 pub mod spend;
@@ -1,32 +1,18 @@
-use sha2::{Digest, Sha256};
+use super::clvm;
 
 /// Generate a puzzle hash for a contract
 ///
-/// This creates a deterministic puzzle hash based on:
-/// - List of participant public keys
-/// - SHA256 hash of contract terms
-/// - Number of required signatures
+/// This is the tree hash of the `required_sigs`-of-n puzzle over the
+/// participants' public keys, committing to the contract terms hash
+/// (see `clvm::m_of_n_puzzle`).
 pub fn generate_contract_puzzle_hash(
     participants: &[String],
     terms_hash: &str,
     required_sigs: usize,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    // Create a deterministic hash from the contract parameters
-    let mut hasher = Sha256::new();
-
-    // Add each participant
-    for participant in participants {
-        hasher.update(participant.as_bytes());
-    }
-
-    // Add terms hash
-    hasher.update(terms_hash.as_bytes());
-
-    // Add required signatures count
-    hasher.update(&required_sigs.to_le_bytes());
-
-    let result = hasher.finalize();
-    Ok(hex::encode(result))
+    let puzzle = clvm::m_of_n_puzzle(participants, required_sigs, terms_hash)
+        .map_err(|e| e as Box<dyn std::error::Error>)?;
+    Ok(hex::encode(puzzle.tree_hash))
 }
 
 /// Compile CLVM puzzle with parameters
@@ -68,9 +54,13 @@ pub fn validate_puzzle_conditions(
 mod tests {
     use super::*;
 
+    fn pk(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 48]))
+    }
+
     #[test]
     fn test_generate_puzzle_hash() {
-        let participants = vec!["0xpubkey1".to_string(), "0xpubkey2".to_string()];
+        let participants = vec![pk(1), pk(2)];
         let terms_hash = "abc123";
         let required_sigs = 2;
 
@@ -79,12 +69,21 @@ mod tests {
 
         let hash = result.unwrap();
         assert_eq!(hash.len(), 64); // SHA256 hex length
+
+        let puzzle = clvm::m_of_n_puzzle(&participants, required_sigs, terms_hash).unwrap();
+        assert_eq!(hash, hex::encode(puzzle.tree_hash));
     }
 
     #[test]
-    fn test_deterministic_puzzle_hash() {
+    fn test_rejects_invalid_participant_key() {
         let participants = vec!["0xpubkey1".to_string()];
-        let terms_hash = "test";
+        assert!(generate_contract_puzzle_hash(&participants, "abc123", 1).is_err());
+    }
+
+    #[test]
+    fn test_deterministic_puzzle_hash() {
+        let participants = vec![pk(1)];
+        let terms_hash = "abcd";
 
         let hash1 = generate_contract_puzzle_hash(&participants, terms_hash, 1).unwrap();
         let hash2 = generate_contract_puzzle_hash(&participants, terms_hash, 1).unwrap();
@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use super::clvm::Program;
+use super::hash::{CoinId, PuzzleHash};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coin {
-    pub parent_coin_id: String,
-    pub puzzle_hash: String,
+    pub parent_coin_id: CoinId,
+    pub puzzle_hash: PuzzleHash,
     pub amount: u64,
 }
 
@@ -68,8 +71,26 @@ pub fn validate_spend_bundle(
         return Err("Spend bundle must have an aggregated signature".into());
     }
 
+    for (i, coin_spend) in spend_bundle.coin_spends.iter().enumerate() {
+        let reveal = hex::decode(coin_spend.puzzle_reveal.trim_start_matches("0x"))
+            .map_err(|e| format!("Coin spend {}: puzzle_reveal is not hex: {}", i, e))?;
+        let reveal_hash = Program::deserialize(&reveal)
+            .map_err(|e| format!("Coin spend {}: invalid puzzle_reveal: {}", i, e))?
+            .tree_hash();
+        let puzzle_hash = coin_spend.coin.puzzle_hash;
+
+        if *puzzle_hash.as_bytes() != reveal_hash {
+            return Err(format!(
+                "Coin spend {}: puzzle reveal hashes to 0x{}, but the coin's puzzle hash is {}",
+                i,
+                hex::encode(reveal_hash),
+                puzzle_hash
+            )
+            .into());
+        }
+    }
+
     // TODO: Additional validation
-    // - Verify puzzle reveals match puzzle hashes
     // - Validate solutions
     // - Check signature validity
 
@@ -109,8 +130,8 @@ mod tests {
     #[test]
     fn test_build_spend_bundle() {
         let coin = Coin {
-            parent_coin_id: "0x123".to_string(),
-            puzzle_hash: "0xabc".to_string(),
+            parent_coin_id: CoinId::from([1; 32]),
+            puzzle_hash: PuzzleHash::from([2; 32]),
             amount: 1000,
         };
 
@@ -134,4 +155,57 @@ mod tests {
         let result = validate_spend_bundle(&bundle);
         assert!(result.is_err());
     }
+
+    fn test_puzzle() -> Program {
+        let mut a = clvmr::Allocator::new();
+        let node = crate::blockchain::clvm::assemble(&mut a, "(a (q . (c 2 ())) 1)").unwrap();
+        Program::from_node(&a, node).unwrap()
+    }
+
+    fn bundle_for(puzzle_reveal: &[u8], puzzle_hash: [u8; 32]) -> SpendBundle {
+        SpendBundle {
+            coin_spends: vec![CoinSpend {
+                coin: Coin {
+                    parent_coin_id: CoinId::from([1; 32]),
+                    puzzle_hash: PuzzleHash::from(puzzle_hash),
+                    amount: 1000,
+                },
+                puzzle_reveal: hex::encode(puzzle_reveal),
+                solution: "80".to_string(),
+            }],
+            aggregated_signature: "sig".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_puzzle_reveal_matches_hash() {
+        let puzzle = test_puzzle();
+        let bundle = bundle_for(puzzle.serialize(), puzzle.tree_hash());
+        assert!(validate_spend_bundle(&bundle).unwrap());
+    }
+
+    #[test]
+    fn test_validate_puzzle_reveal_mismatch() {
+        let puzzle = test_puzzle();
+        let err = validate_spend_bundle(&bundle_for(puzzle.serialize(), [0u8; 32])).unwrap_err();
+        assert!(err.to_string().contains("puzzle reveal hashes to"), "{}", err);
+
+        let mut garbled = bundle_for(puzzle.serialize(), puzzle.tree_hash());
+        garbled.coin_spends[0].puzzle_reveal = "ff80".to_string();
+        assert!(validate_spend_bundle(&garbled).is_err());
+    }
+
+    #[test]
+    fn test_validate_compressed_puzzle_reveal() {
+        // Wallets may send reveals with back-references; they hash like the expanded puzzle
+        let keys: Vec<String> = (1..=3).map(|b| hex::encode([b; 48])).collect();
+        let puzzle = crate::blockchain::clvm::m_of_n_puzzle(&keys, 2, &hex::encode([0u8; 32])).unwrap();
+        let mut a = clvmr::Allocator::new();
+        let node = clvmr::serde::node_from_bytes(&mut a, &puzzle.program).unwrap();
+        let compressed = clvmr::serde::node_to_bytes_backrefs(&a, node).unwrap();
+        assert!(compressed.len() < puzzle.program.len());
+
+        assert!(validate_spend_bundle(&bundle_for(&compressed, puzzle.tree_hash)).unwrap());
+        assert!(validate_spend_bundle(&bundle_for(&compressed, [0u8; 32])).is_err());
+    }
 }
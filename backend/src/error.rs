@@ -2,6 +2,8 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
+use crate::util::validation::ValidationErrors;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Serialize, strum_macros::AsRefStr)]
@@ -23,6 +25,12 @@ pub enum Error {
     InvalidState(String),
     // Not found with message
     NotFoundMsg(String),
+    // Concurrent modification (stale version)
+    Conflict(String),
+    // Rejected input, keyed by field
+    Validation(ValidationErrors),
+    // An admin paused this user's trading (existing trades continue)
+    TradingPaused,
 }
 
 // This allows your Error to be returned directly by Axum handlers
@@ -59,6 +67,15 @@ impl IntoResponse for Error {
             Error::NotFoundMsg(msg) => {
                 return (StatusCode::NOT_FOUND, msg.clone()).into_response();
             }
+            Error::Conflict(msg) => {
+                return (StatusCode::CONFLICT, msg.clone()).into_response();
+            }
+            Error::Validation(errs) => {
+                return (StatusCode::BAD_REQUEST, axum::Json(errs.clone())).into_response();
+            }
+            Error::TradingPaused => {
+                return (StatusCode::FORBIDDEN, "Trading paused").into_response();
+            }
         };
         (StatusCode::INTERNAL_SERVER_ERROR, error_msg).into_response()
     }
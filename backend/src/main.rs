@@ -1,10 +1,13 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{get, post},
     Router,
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 use tower_cookies::CookieManagerLayer;
 use tracing_subscriber;
 
@@ -19,11 +22,27 @@ mod ctx;
 mod model;
 mod error;
 
+#[cfg(test)]
+mod _dev_utils;
+
 pub use self::error::{Error, Result};
 use app_state::AppState;
 use model::ModelManager;
+use api::files::FileRoutesConfig;
+use api::metrics::InFlight;
 use api::mw_auth::mw_ctx_resolve;
 
+/// Request body limit for every route but the file routes, which have their own
+const DEFAULT_REQUEST_BODY_MAX_BYTES: usize = 15 * 1024 * 1024;
+
+/// `REQUEST_BODY_MAX_BYTES`, or 15 MiB
+fn request_body_max_bytes() -> usize {
+    std::env::var("REQUEST_BODY_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_REQUEST_BODY_MAX_BYTES)
+}
+
 #[tokio::main]
 async fn main() {
     // Initialize tracing
@@ -40,17 +59,69 @@ async fn main() {
     let mm = ModelManager::new(db);
 
     let default_rpc = std::env::var("CHIA_RPC_URL").unwrap_or_else(|_| "http://localhost:8555".to_string());
-    let state = AppState::new(default_rpc);
-    let app_state = std::sync::Arc::new(state);
+    let ssl_paths_file = std::env::var("SSL_PATHS_FILE").unwrap_or_else(|_| app_state::DEFAULT_SSL_PATHS_FILE.to_string());
+    let state = AppState::new(default_rpc).with_ssl_paths_file(ssl_paths_file);
+    if let Err(e) = state.restore_ssl_paths().await {
+        tracing::warn!("SSL paths not restored: {}", e);
+    }
+    if !state.disabled_rpc_methods().is_empty() {
+        let mut disabled: Vec<_> = state.disabled_rpc_methods().iter().cloned().collect();
+        disabled.sort();
+        tracing::info!("RPC methods disabled: {}", disabled.join(", "));
+    }
+    let app_state = Arc::new(state);
+
+    if let Err(e) = api::events::start_trade_status_listener(&mm, app_state.clone()).await {
+        tracing::warn!("Trade status events disabled: {}", e);
+    }
+
+    // File routes carry their own upload limit and CORS policy
+    let file_config = FileRoutesConfig::from_env();
+    let body_max_bytes = request_body_max_bytes();
+    tracing::info!(
+        "Request bodies limited to {} bytes, file uploads to {} bytes; file CORS origins: {}",
+        body_max_bytes,
+        file_config.upload_max_bytes,
+        if file_config.cors_origins.is_empty() { "any".to_string() } else { file_config.cors_origins.join(", ") }
+    );
+
+    // In-flight request counter (exposed on /metrics, used to drain on shutdown)
+    let in_flight = InFlight::new();
+    let app = app(mm.clone(), app_state.clone(), in_flight.clone(), &file_config, body_max_bytes);
+
+    // Start the transaction verification background service
+    api::verify::start_verification_service(mm.clone(), app_state.clone()).await;
+
+    // Start server
+    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    tracing::info!("Server listening on {}", addr);
 
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    api::shutdown::serve_with_drain(
+        listener,
+        app,
+        in_flight,
+        api::shutdown::shutdown_signal(),
+        api::shutdown::SHUTDOWN_DRAIN_TIMEOUT,
+    )
+    .await;
+}
+
+/// Every route with its middleware; `main` starts the background services and serves it
+fn app(
+    mm: ModelManager,
+    app_state: Arc<AppState>,
+    in_flight: InFlight,
+    file_config: &FileRoutesConfig,
+    body_max_bytes: usize,
+) -> Router {
     // Build application routes
     let app = Router::new()
         .route("/", get(root))
-        .route("/health", get(health_check))
-        
-        // Authenticated file upload/download (REST - binary data doesn't work well with JSON-RPC)
-        .route("/files", get(api::files::list_files).post(api::files::upload_file))
-        .route("/files/:id", get(api::files::get_file).delete(api::files::delete_file))
+        // Probes: liveness (process up) vs readiness (DB + config); /health kept as alias of /readyz
+        .route("/health", get(api::health::readyz))
+        .route("/livez", get(api::health::livez))
+        .route("/readyz", get(api::health::readyz))
         .layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
         .layer(CookieManagerLayer::new())
         .with_state(mm.clone());
@@ -76,9 +147,7 @@ async fn main() {
         // .route("/ssl/status", get(api::ssl::get_ssl_status))
         // .route("/ssl/delete", post(api::ssl::delete_ssl_certificates))
         
-    let app = app
-        .layer(CorsLayer::permissive())
-        .layer(tower_http::limit::RequestBodyLimitLayer::new(15 * 1024 * 1024)); // 15MB limit
+    let app = app.layer(CorsLayer::permissive());
     
     // Create RPC routes with authentication (ModelManager + AppState)
     let rpc_state = api::rpc::RpcState(mm.clone(), app_state.clone());
@@ -89,6 +158,9 @@ async fn main() {
         .layer(CookieManagerLayer::new())
         .with_state(rpc_state);
 
+    // Trade status streams (Server-Sent Events) for participants
+    let event_routes = api::events::routes(mm.clone(), app_state.clone());
+
     // Create configuration routes (AppState state)
     let config_routes = Router::new()
         // Chia node connection endpoints
@@ -102,30 +174,26 @@ async fn main() {
         .route("/ssl/set", post(api::ssl::set_ssl_paths))
         .with_state(app_state.clone());
     
-    // Merge all routes
-    let app = app.merge(rpc_routes).merge(config_routes)
-        .layer(CorsLayer::permissive());
+    let metrics_routes = Router::new()
+        .route("/metrics", get(api::metrics::metrics))
+        .with_state(in_flight.clone());
 
-    // Start the transaction verification background service
-    api::verify::start_verification_service(mm.clone(), app_state.clone()).await;
+    // File routes carry their own upload limit and CORS policy
+    let file_routes = api::files::routes(mm.clone(), file_config);
 
-    // Start server
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
-    tracing::info!("Server listening on {}", addr);
-
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // Merge all routes (the permissive CORS layer and body limit stop before the file routes)
+    app.merge(rpc_routes).merge(event_routes).merge(config_routes).merge(metrics_routes)
+        .layer(CorsLayer::permissive())
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(body_max_bytes))
+        .merge(file_routes)
+        // Panics in any handler become a JSON 500 logged with the request id
+        .layer(api::mw_panic::catch_panic_layer())
+        .layer(middleware::from_fn(api::mw_panic::mw_request_id))
+        .layer(middleware::from_fn_with_state(in_flight, api::metrics::mw_track_in_flight))
+        .layer(axum::Extension(app_state))
 }
 
 async fn root() -> &'static str {
     "Chia Contract Backend API"
 }
-
-use axum::http::HeaderMap;
-async fn health_check() -> (HeaderMap, &'static str) {
-    let mut headers = HeaderMap::new();
-    if std::env::var("CHIA_ALLOW_INSECURE").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false) {
-        headers.insert("X-Chia-Insecure", "true".parse().unwrap());
-    }
-    (headers, "OK")
-}
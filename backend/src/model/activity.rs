@@ -0,0 +1,55 @@
+// ============================================
+// Platform Activity Feed (admin)
+// ============================================
+//
+// Trade activity comes from the trade event log (`TradeEventBmc`), which
+// records every lifecycle step and confirmed payment; registrations and
+// admin actions come from the users table and the admin audit log.
+
+use super::ModelManager;
+use serde::Serialize;
+use sqlx::FromRow;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ActivityItem {
+    /// A trade event type (trade_created, offer_made, trade_committed,
+    /// transaction_confirmed, dispute_raised, trade_completed, ...),
+    /// user_registered or admin_action
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+    pub trade_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub summary: String,
+}
+
+const FEED_SQL: &str = r#"
+    SELECT kind, occurred_at, trade_id, user_id, summary FROM (
+        SELECT e.event_type AS kind, e.created_at AS occurred_at, e.trade_id AS trade_id,
+               e.actor_id AS user_id, t.proposer_item_title AS summary
+        FROM trade_events e JOIN trades t ON t.id = e.trade_id
+        UNION ALL
+        SELECT 'user_registered', u.created_at, NULL, u.id, u.username
+        FROM users u
+        UNION ALL
+        SELECT 'admin_action', a.created_at, NULL, a.admin_id, a.action || ' ' || a.target_type || ' ' || a.target_id
+        FROM admin_audit_log a
+    ) feed
+    ORDER BY occurred_at DESC, kind, trade_id, user_id
+    LIMIT $1 OFFSET $2
+"#;
+
+pub struct ActivityBmc;
+
+impl ActivityBmc {
+    /// Newest-first page of platform activity
+    pub async fn feed(mm: &ModelManager, limit: i64, offset: i64) -> Result<Vec<ActivityItem>> {
+        sqlx::query_as::<_, ActivityItem>(FEED_SQL)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(mm.pool())
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+}
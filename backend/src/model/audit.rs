@@ -0,0 +1,94 @@
+// ============================================
+// Admin Audit Log
+// ============================================
+
+use crate::ctx::Ctx;
+use super::ModelManager;
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AdminAuditEntry {
+    pub id: i64,
+    pub admin_id: Option<i64>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub details: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Row shape as read from Postgres (details come back as JSON text)
+#[derive(FromRow)]
+struct AdminAuditRow {
+    id: i64,
+    admin_id: Option<i64>,
+    action: String,
+    target_type: String,
+    target_id: String,
+    details: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<AdminAuditRow> for AdminAuditEntry {
+    fn from(row: AdminAuditRow) -> Self {
+        Self {
+            id: row.id,
+            admin_id: row.admin_id,
+            action: row.action,
+            target_type: row.target_type,
+            target_id: row.target_id,
+            details: serde_json::from_str(&row.details).unwrap_or(serde_json::Value::Null),
+            created_at: row.created_at,
+        }
+    }
+}
+
+pub struct AuditBmc;
+
+impl AuditBmc {
+    /// Record an admin action against a target inside the caller's transaction, so the
+    /// action and its audit row commit (or roll back) together
+    pub async fn record_in(
+        conn: &mut PgConnection,
+        ctx: &Ctx,
+        action: &str,
+        target_type: &str,
+        target_id: &str,
+        details: serde_json::Value,
+    ) -> Result<i64> {
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO admin_audit_log (admin_id, action, target_type, target_id, details)
+             VALUES ($1, $2, $3, $4, $5::jsonb)
+             RETURNING id"
+        )
+        .bind(ctx.user_id())
+        .bind(action)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(details.to_string())
+        .fetch_one(conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(id)
+    }
+    
+    /// List audit entries for a target, newest first
+    pub async fn list_for_target(mm: &ModelManager, target_type: &str, target_id: &str) -> Result<Vec<AdminAuditEntry>> {
+        let rows: Vec<AdminAuditRow> = sqlx::query_as(
+            "SELECT id, admin_id, action, target_type, target_id, details::text AS details, created_at
+             FROM admin_audit_log
+             WHERE target_type = $1 AND target_id = $2
+             ORDER BY created_at DESC, id DESC"
+        )
+        .bind(target_type)
+        .bind(target_id)
+        .fetch_all(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(rows.into_iter().map(AdminAuditEntry::from).collect())
+    }
+}
@@ -0,0 +1,60 @@
+// ============================================
+// Exchange Config (key/value settings)
+// ============================================
+
+use super::ModelManager;
+use crate::error::{Error, Result};
+use serde::Serialize;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ExchangeConfig {
+    pub id: i32,
+    pub key: String,
+    pub value: String,
+    pub description: Option<String>,
+}
+
+pub struct ConfigBmc;
+
+impl ConfigBmc {
+    /// Get a config value by key.
+    /// `key` is unique, but if duplicate rows ever slip in (e.g. a database created
+    /// before the constraint existed) the most recently updated row wins and a warning is logged.
+    pub async fn get(mm: &ModelManager, key: &str) -> Result<Option<String>> {
+        let rows: Vec<ExchangeConfig> = sqlx::query_as(
+            "SELECT * FROM exchange_config WHERE key = $1 ORDER BY updated_at DESC, id DESC"
+        )
+        .bind(key)
+        .fetch_all(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if rows.len() > 1 {
+            tracing::warn!("exchange_config has {} rows for key '{}'; using the latest", rows.len(), key);
+        }
+        
+        Ok(rows.into_iter().next().map(|c| c.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_duplicate_key_rejected() {
+        let mm = _dev_utils::init_test().await;
+
+        let res = sqlx::query("INSERT INTO exchange_config (key, value) VALUES ('escrow_duration_days', '60')")
+            .execute(mm.db())
+            .await;
+        let err = res.expect_err("duplicate key must be rejected");
+        assert!(err.to_string().contains("unique"), "unexpected error: {}", err);
+
+        assert_eq!(ConfigBmc::get(&mm, "escrow_duration_days").await.unwrap().as_deref(), Some("30"));
+        assert_eq!(ConfigBmc::get(&mm, "no_such_key").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,181 @@
+// ============================================
+// Contacts (per-user address book)
+// ============================================
+
+use crate::ctx::Ctx;
+use super::ModelManager;
+use crate::util::email;
+use crate::util::text::{self, MAX_LONG_TEXT_LEN};
+use crate::util::validation::ValidationErrors;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::error::{Error, Result};
+
+/// Largest batch accepted by `ContactBmc::import`
+pub const MAX_CONTACT_IMPORT: usize = 200;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Contact {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub public_key: String,
+    pub xch_address: Option<String>,
+    pub email: Option<String>,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContactForCreate {
+    pub name: String,
+    pub public_key: String,
+    pub xch_address: Option<String>,
+    pub email: Option<String>,
+    pub note: Option<String>,
+}
+
+impl ContactForCreate {
+    /// Trim fields, lowercase the email, drop blank optionals and strip control
+    /// characters from the name and note (see `util::text`)
+    pub fn normalized(self) -> Self {
+        let clean = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        Self {
+            name: text::strip_controls(self.name.trim()),
+            public_key: self.public_key.trim().to_lowercase(),
+            xch_address: clean(self.xch_address),
+            email: self.email.map(|e| email::normalize(&e)).filter(|e| !e.is_empty()),
+            note: clean(text::strip_controls_opt(self.note)),
+        }
+    }
+
+    /// Escape the validated name and note for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        Self { name: text::escape(&self.name), note: text::escape_opt(self.note), ..self }
+    }
+
+    /// Validate a normalized contact
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        errs.check(!self.name.is_empty(), "name", "is required");
+        errs.check(self.name.chars().count() <= 255, "name", "must be at most 255 characters");
+        errs.check(
+            self.public_key.len() == 96 && self.public_key.chars().all(|c| c.is_ascii_hexdigit()),
+            "public_key",
+            "must be a 96-character hex string (compressed BLS pubkey)",
+        );
+        if let Some(address) = &self.xch_address {
+            errs.check(address.len() <= 100, "xch_address", "must be at most 100 characters");
+        }
+        if let Some(address) = &self.email {
+            errs.check(email::validate(address), "email", "is not a valid address");
+        }
+        if let Some(note) = &self.note {
+            errs.check(note.chars().count() <= MAX_LONG_TEXT_LEN, "note", "must be at most 5000 characters");
+        }
+        errs
+    }
+}
+
+/// Outcome of one row of a bulk import
+#[derive(Debug, Clone, Serialize)]
+pub struct ContactImportRow {
+    pub index: usize,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub struct ContactBmc;
+
+impl ContactBmc {
+    /// List the caller's contacts
+    pub async fn list(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<Contact>> {
+        sqlx::query_as::<_, Contact>("SELECT * FROM contacts WHERE user_id = $1 ORDER BY name, id")
+            .bind(ctx.user_id())
+            .fetch_all(mm.pool())
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+
+    /// Import contacts for the caller. Invalid or duplicate rows are reported and skipped;
+    /// the valid ones are inserted in a single transaction.
+    pub async fn import(ctx: &Ctx, mm: &ModelManager, contacts: Vec<ContactForCreate>) -> Result<Vec<ContactImportRow>> {
+        if contacts.len() > MAX_CONTACT_IMPORT {
+            return Err(Error::InvalidState(format!("Too many contacts (max {})", MAX_CONTACT_IMPORT)));
+        }
+
+        let mut tx = mm.pool().begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let mut rows = Vec::with_capacity(contacts.len());
+
+        for (index, contact) in contacts.into_iter().enumerate() {
+            let contact = contact.normalized();
+            let errs = contact.validate();
+            if !errs.is_empty() {
+                rows.push(ContactImportRow { index, success: false, id: None, error: Some(errs.to_string()) });
+                continue;
+            }
+            let contact = contact.escaped();
+
+            let id: Option<i64> = sqlx::query_scalar(
+                "INSERT INTO contacts (user_id, name, public_key, xch_address, email, note)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (user_id, public_key) DO NOTHING
+                 RETURNING id"
+            )
+            .bind(ctx.user_id())
+            .bind(&contact.name)
+            .bind(&contact.public_key)
+            .bind(&contact.xch_address)
+            .bind(&contact.email)
+            .bind(&contact.note)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            rows.push(match id {
+                Some(id) => ContactImportRow { index, success: true, id: Some(id), error: None },
+                None => ContactImportRow {
+                    index,
+                    success: false,
+                    id: None,
+                    error: Some("public_key: already in your contacts".to_string()),
+                },
+            });
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(name: &str, public_key: &str, email: Option<&str>) -> ContactForCreate {
+        ContactForCreate {
+            name: name.to_string(),
+            public_key: public_key.to_string(),
+            xch_address: None,
+            email: email.map(str::to_string),
+            note: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_normalize_and_validate() {
+        let c = contact(" Alice ", &"AB".repeat(48), Some(" Alice@Example.com ")).normalized();
+        assert!(c.validate().is_empty());
+        assert_eq!(c.name, "Alice");
+        assert_eq!(c.public_key, "ab".repeat(48));
+        assert_eq!(c.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(c.note, None);
+
+        let errs = contact("", "beef", Some("nope")).normalized().validate();
+        assert_eq!(errs.fields.len(), 3);
+    }
+}
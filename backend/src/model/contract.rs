@@ -2,6 +2,8 @@ use crate::ctx::Ctx;
 use crate::model::ModelManager;
 // FIX: Point specifically to your error module
 use crate::error::Error;
+use crate::util::text;
+use crate::util::validation::ValidationErrors;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -34,6 +36,42 @@ pub struct ContractForCreate {
     pub amount: i64,
 }
 
+/// Contract text is hashed and signed as written, so control characters are rejected
+/// rather than stripped (as `terms_text` is on the REST API)
+fn check_plain_text(errs: &mut ValidationErrors, field: &str, value: Option<&str>) {
+    let ok = !value.is_some_and(text::has_control_chars);
+    errs.check(ok, field, "must be plain text (no control characters)");
+}
+
+impl ContractForCreate {
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        check_plain_text(&mut errs, "name", Some(&self.name));
+        check_plain_text(&mut errs, "description", self.description.as_deref());
+        check_plain_text(&mut errs, "terms", Some(&self.terms));
+        errs
+    }
+
+    /// Escape the validated user-written fields for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        Self {
+            name: text::escape(&self.name),
+            description: text::escape_opt(self.description),
+            terms: text::escape(&self.terms),
+            ..self
+        }
+    }
+}
+
+impl ContractForUpdate {
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        check_plain_text(&mut errs, "name", self.name.as_deref());
+        check_plain_text(&mut errs, "description", self.description.as_deref());
+        errs
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ContractForUpdate {
     pub name: Option<String>,
@@ -48,6 +86,8 @@ pub struct ContractBmc;
 impl ContractBmc {
     pub async fn create(ctx: &Ctx, mm: &ModelManager, c_c: ContractForCreate) -> Result<i64, Error> {
         let db = mm.db();
+        c_c.validate().into_result()?;
+        let c_c = c_c.escaped();
         let (id,) = sqlx::query_as::<_, (i64,)>(
             r#"INSERT INTO contracts 
                (user_id, name, description, party1_public_key, party2_public_key, 
@@ -79,14 +119,53 @@ impl ContractBmc {
     }
 
     pub async fn update(ctx: &Ctx, mm: &ModelManager, id: i64, c_u: ContractForUpdate) -> Result<(), Error> {
+        c_u.validate().into_result()?;
+        let (name, description) = (text::escape_opt(c_u.name), text::escape_opt(c_u.description));
         sqlx::query(
             r#"UPDATE contracts SET name = COALESCE($3, name), description = COALESCE($4, description),
                party1_xch_address = COALESCE($5, party1_xch_address), status = COALESCE($6, status)
                WHERE id = $1 AND user_id = $2"#
         )
-        .bind(id).bind(ctx.user_id()).bind(c_u.name).bind(c_u.description)
+        .bind(id).bind(ctx.user_id()).bind(name).bind(description)
         .bind(c_u.party1_xch_address).bind(c_u.status)
         .execute(mm.db()).await.map_err(|_| Error::InternalServer)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, contract};
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_is_owner_scoped() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = ContractBmc::create(&alice, &mm, contract("alice's")).await.unwrap();
+
+        let mine = ContractBmc::list(&alice, &mm).await.unwrap();
+        assert_eq!(mine.iter().map(|c| c.id).collect::<Vec<_>>(), vec![id]);
+
+        assert!(ContractBmc::list(&bob, &mm).await.unwrap().is_empty());
+        assert!(ContractBmc::get(&bob, &mm, id).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_control_characters_rejected() {
+        let (mm, alice, _) = _dev_utils::init_test_with_users().await;
+        let c_c = ContractForCreate { terms: "Pay on\u{0} delivery".to_string(), ..contract("nul") };
+        match ContractBmc::create(&alice, &mm, c_c).await {
+            Err(Error::Validation(errs)) => {
+                assert_eq!(errs.fields["terms"], "must be plain text (no control characters)")
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        // Line breaks and tabs are plain text
+        let c_c = ContractForCreate { terms: "1.\tPay\r\n2.\tShip".to_string(), ..contract("lines") };
+        let id = ContractBmc::create(&alice, &mm, c_c).await.unwrap();
+        assert_eq!(ContractBmc::get(&alice, &mm, id).await.unwrap().terms, "1.\tPay\r\n2.\tShip");
+    }
+}
@@ -1,7 +1,7 @@
 use crate::ctx::Ctx;
 use crate::store::Db;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Row};
+use sqlx::{FromRow, PgConnection, Row};
 use std::path::Path;
 
 // ============================================================================
@@ -79,13 +79,13 @@ impl FileBmc {
     }
 
     /// Create a new file record
-    pub async fn create(ctx: &Ctx, db: &Db, file_c: FileForCreate) -> Result<i64, sqlx::Error> {
+    pub async fn create(ctx: &Ctx, conn: &mut PgConnection, file_c: FileForCreate) -> Result<i64, sqlx::Error> {
         // First verify the user owns this contract
         let contract_check =
             sqlx::query_scalar::<_, i64>("SELECT id FROM contracts WHERE id = $1 AND user_id = $2")
                 .bind(file_c.contract_id)
                 .bind(ctx.user_id())
-                .fetch_optional(db)
+                .fetch_optional(&mut *conn)
                 .await?;
 
         if contract_check.is_none() {
@@ -103,7 +103,7 @@ impl FileBmc {
         .bind(file_c.file_path)
         .bind(file_c.file_size)
         .bind(file_c.mime_type)
-        .fetch_one(db)
+        .fetch_one(conn)
         .await?;
 
         let file_id: i64 = result.get("id");
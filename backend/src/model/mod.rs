@@ -1,12 +1,26 @@
+mod activity;
+mod audit;
+mod config;
+mod contact;
 mod contract;
 mod file;
+mod notification;
+mod settings;
 mod trade;
+mod trade_event;
 mod transaction;
 mod user;
 
+pub use activity::*;
+pub use audit::*;
+pub use config::*;
+pub use contact::*;
 pub use contract::*;
 pub use file::*;
+pub use notification::*;
+pub use settings::*;
 pub use trade::*;
+pub use trade_event::*;
 pub use transaction::*;
 pub use user::*;
 
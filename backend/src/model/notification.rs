@@ -0,0 +1,206 @@
+// ============================================
+// User Notifications
+// ============================================
+
+use crate::ctx::Ctx;
+use crate::util::validation::ValidationErrors;
+use super::ModelManager;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use crate::error::{Error, Result};
+
+pub const DEFAULT_NOTIFICATION_LIMIT: i64 = 20;
+pub const MAX_NOTIFICATION_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Notification {
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: String,
+    pub trade_id: Option<i64>,
+    pub message: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One page of a user's notifications, newest first
+#[derive(Debug, Serialize)]
+pub struct NotificationPage {
+    pub notifications: Vec<Notification>,
+    pub unread_count: i64,
+    /// Pass back as `cursor` for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Opaque cursor for the position after `n`: "<created_at micros>_<id>"
+fn encode_cursor(n: &Notification) -> String {
+    format!("{}_{}", n.created_at.timestamp_micros(), n.id)
+}
+
+fn decode_cursor(cursor: &str) -> Option<(DateTime<Utc>, i64)> {
+    let (micros, id) = cursor.split_once('_')?;
+    let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    Some((created_at, id.parse().ok()?))
+}
+
+pub struct NotificationBmc;
+
+impl NotificationBmc {
+    /// Queue a notification for a user
+    pub async fn create(
+        mm: &ModelManager,
+        user_id: i64,
+        kind: &str,
+        trade_id: Option<i64>,
+        message: &str,
+    ) -> Result<i64> {
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO notifications (user_id, kind, trade_id, message)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id"
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(trade_id)
+        .bind(message)
+        .fetch_one(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(id)
+    }
+    
+    /// Page through the caller's notifications, newest first, keyed on (created_at, id)
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        unread_only: bool,
+    ) -> Result<NotificationPage> {
+        let limit = limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT).clamp(1, MAX_NOTIFICATION_LIMIT);
+        let after = match cursor {
+            Some(cursor) => Some(decode_cursor(cursor).ok_or_else(|| {
+                let mut errs = ValidationErrors::new();
+                errs.add("cursor", "is not a valid cursor");
+                Error::Validation(errs)
+            })?),
+            None => None,
+        };
+        
+        // One extra row tells us whether another page exists
+        let mut notifications: Vec<Notification> = sqlx::query_as(
+            "SELECT * FROM notifications
+             WHERE user_id = $1
+               AND (NOT $2 OR read_at IS NULL)
+               AND ($3::TIMESTAMPTZ IS NULL OR (created_at, id) < ($3, $4))
+             ORDER BY created_at DESC, id DESC
+             LIMIT $5"
+        )
+        .bind(ctx.user_id())
+        .bind(unread_only)
+        .bind(after.map(|(created_at, _)| created_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(limit + 1)
+        .fetch_all(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let next_cursor = if notifications.len() as i64 > limit {
+            notifications.truncate(limit as usize);
+            notifications.last().map(encode_cursor)
+        } else {
+            None
+        };
+        
+        let (unread_count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL"
+        )
+        .bind(ctx.user_id())
+        .fetch_one(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(NotificationPage { notifications, unread_count, next_cursor })
+    }
+    
+    /// Mark some of the caller's notifications read; returns how many changed
+    pub async fn mark_read(ctx: &Ctx, mm: &ModelManager, ids: &[i64]) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW()
+             WHERE user_id = $1 AND id = ANY($2) AND read_at IS NULL"
+        )
+        .bind(ctx.user_id())
+        .bind(ids)
+        .execute(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    /// Hard-delete up to `limit` notifications read more than `older_than_days` ago; returns how many
+    pub async fn prune_read(mm: &ModelManager, older_than_days: i64, limit: i64) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM notifications WHERE id IN (
+                 SELECT id FROM notifications
+                 WHERE read_at < NOW() - make_interval(days => $1::INT)
+                 LIMIT $2
+             )"
+        )
+        .bind(older_than_days)
+        .bind(limit)
+        .execute(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_pages_and_filters_unread() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            ids.push(NotificationBmc::create(&mm, alice.user_id(), "test", None, &format!("note {}", i)).await.unwrap());
+        }
+        NotificationBmc::create(&mm, bob.user_id(), "test", None, "not alice's").await.unwrap();
+        assert_eq!(NotificationBmc::mark_read(&alice, &mm, &[ids[0], ids[3]]).await.unwrap(), 2);
+        // Someone else's notification is untouched
+        assert_eq!(NotificationBmc::mark_read(&bob, &mm, &[ids[1]]).await.unwrap(), 0);
+
+        // Newest first, two per page
+        let mut seen = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = NotificationBmc::list(&alice, &mm, Some(2), cursor.as_deref(), false).await.unwrap();
+            assert_eq!(page.unread_count, 3);
+            assert!(page.notifications.len() <= 2);
+            seen.extend(page.notifications.iter().map(|n| n.id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        ids.reverse();
+        assert_eq!(seen, ids);
+
+        let unread = NotificationBmc::list(&alice, &mm, None, None, true).await.unwrap();
+        let unread_ids: Vec<i64> = unread.notifications.iter().map(|n| n.id).collect();
+        assert_eq!(unread_ids, vec![ids[0], ids[2], ids[3]]);
+        assert!(unread.next_cursor.is_none());
+
+        assert!(matches!(
+            NotificationBmc::list(&alice, &mm, None, Some("garbage"), false).await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,204 @@
+// ============================================
+// Exchange Settings (typed, single row)
+// ============================================
+
+use super::ModelManager;
+use crate::error::{Error, Result};
+use crate::util::validation::ValidationErrors;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct ExchangeSettings {
+    /// Where commitment fees are sent; empty until an admin sets it
+    pub wallet_address: String,
+    pub commitment_fee_usd: f64,
+    pub webhook_url: Option<String>,
+    /// Confirmations before the verifier treats a payment as final
+    pub min_confirmations: i32,
+    /// Bech32m prefix of exchange addresses (`xch`, `txch` on testnet)
+    pub address_hrp: String,
+    /// Refuse new trades and commitments (upgrades); reads and admin RPCs still work
+    pub maintenance_mode: bool,
+}
+
+impl Default for ExchangeSettings {
+    fn default() -> Self {
+        Self {
+            wallet_address: String::new(),
+            commitment_fee_usd: 1.0,
+            webhook_url: None,
+            min_confirmations: 6,
+            address_hrp: "xch".to_string(),
+            maintenance_mode: false,
+        }
+    }
+}
+
+/// Partial update; omitted fields keep their value. An empty `webhook_url` clears it.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExchangeSettingsForUpdate {
+    pub wallet_address: Option<String>,
+    pub commitment_fee_usd: Option<f64>,
+    pub webhook_url: Option<String>,
+    pub min_confirmations: Option<i32>,
+    pub address_hrp: Option<String>,
+    pub maintenance_mode: Option<bool>,
+}
+
+impl ExchangeSettingsForUpdate {
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        if let Some(fee) = self.commitment_fee_usd {
+            errs.check(fee.is_finite() && fee >= 0.0, "commitment_fee_usd", "must not be negative");
+        }
+        if let Some(url) = self.webhook_url.as_deref().filter(|u| !u.is_empty()) {
+            errs.check(url.starts_with("https://") || url.starts_with("http://"), "webhook_url", "must be an http(s) URL");
+        }
+        if let Some(n) = self.min_confirmations {
+            errs.check(n > 0, "min_confirmations", "must be positive");
+        }
+        if let Some(hrp) = &self.address_hrp {
+            errs.check(
+                !hrp.is_empty() && hrp.len() <= 16 && hrp.bytes().all(|b| b.is_ascii_lowercase()),
+                "address_hrp",
+                "must be 1-16 lowercase letters",
+            );
+        }
+        errs
+    }
+}
+
+const SETTINGS_COLUMNS: &str = "wallet_address, commitment_fee_usd, webhook_url, min_confirmations, address_hrp, maintenance_mode";
+
+pub struct SettingsBmc;
+
+impl SettingsBmc {
+    /// Current settings; defaults when the row has not been created yet
+    pub async fn get(mm: &ModelManager) -> Result<ExchangeSettings> {
+        let settings: Option<ExchangeSettings> =
+            sqlx::query_as(&format!("SELECT {} FROM exchange_settings", SETTINGS_COLUMNS))
+                .fetch_optional(mm.pool())
+                .await
+                .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(settings.unwrap_or_default())
+    }
+    
+    /// Apply a partial update (creating the row if needed) and return the result
+    pub async fn update(mm: &ModelManager, settings_u: ExchangeSettingsForUpdate) -> Result<ExchangeSettings> {
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        let settings = Self::update_in(&mut tx, settings_u).await?;
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(settings)
+    }
+    
+    /// Current settings, creating the row if needed and locking it until the caller's
+    /// transaction ends, so a read-then-update (and its audit row) sees no concurrent change
+    pub async fn lock_in(conn: &mut PgConnection) -> Result<ExchangeSettings> {
+        sqlx::query("INSERT INTO exchange_settings DEFAULT VALUES ON CONFLICT (id) DO NOTHING")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        sqlx::query_as(&format!("SELECT {} FROM exchange_settings FOR UPDATE", SETTINGS_COLUMNS))
+            .fetch_one(conn)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+    
+    /// `update` inside the caller's transaction
+    pub async fn update_in(conn: &mut PgConnection, settings_u: ExchangeSettingsForUpdate) -> Result<ExchangeSettings> {
+        settings_u.validate().into_result()?;
+        Self::lock_in(&mut *conn).await?;
+        
+        sqlx::query_as(&format!(
+            "UPDATE exchange_settings SET
+                wallet_address = COALESCE($1, wallet_address),
+                commitment_fee_usd = COALESCE($2, commitment_fee_usd),
+                webhook_url = CASE WHEN $3::TEXT IS NULL THEN webhook_url ELSE NULLIF($3, '') END,
+                min_confirmations = COALESCE($4, min_confirmations),
+                address_hrp = COALESCE($5, address_hrp),
+                maintenance_mode = COALESCE($6, maintenance_mode),
+                updated_at = NOW()
+             RETURNING {}",
+            SETTINGS_COLUMNS
+        ))
+        .bind(settings_u.wallet_address)
+        .bind(settings_u.commitment_fee_usd)
+        .bind(settings_u.webhook_url)
+        .bind(settings_u.min_confirmations)
+        .bind(settings_u.address_hrp)
+        .bind(settings_u.maintenance_mode)
+        .fetch_one(conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_defaults_when_row_absent() {
+        let mm = _dev_utils::init_test().await;
+        sqlx::query("DELETE FROM exchange_settings").execute(mm.db()).await.unwrap();
+
+        assert_eq!(SettingsBmc::get(&mm).await.unwrap(), ExchangeSettings::default());
+
+        // Updating recreates the row from defaults
+        let settings_u = ExchangeSettingsForUpdate { min_confirmations: Some(3), ..Default::default() };
+        let settings = SettingsBmc::update(&mm, settings_u).await.unwrap();
+        assert_eq!(settings, ExchangeSettings { min_confirmations: 3, ..Default::default() });
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_update_round_trip() {
+        let mm = _dev_utils::init_test().await;
+
+        let settings_u = ExchangeSettingsForUpdate {
+            wallet_address: Some("txch1exchange".to_string()),
+            commitment_fee_usd: Some(2.5),
+            webhook_url: Some("https://hooks.example.com/dtrex".to_string()),
+            address_hrp: Some("txch".to_string()),
+            ..Default::default()
+        };
+        SettingsBmc::update(&mm, settings_u).await.unwrap();
+
+        // Partial update keeps the rest; an empty webhook clears it
+        let settings_u = ExchangeSettingsForUpdate {
+            min_confirmations: Some(12),
+            webhook_url: Some(String::new()),
+            ..Default::default()
+        };
+        SettingsBmc::update(&mm, settings_u).await.unwrap();
+
+        let settings = SettingsBmc::get(&mm).await.unwrap();
+        assert_eq!(settings, ExchangeSettings {
+            wallet_address: "txch1exchange".to_string(),
+            commitment_fee_usd: 2.5,
+            webhook_url: None,
+            min_confirmations: 12,
+            address_hrp: "txch".to_string(),
+            maintenance_mode: false,
+        });
+
+        let bad = ExchangeSettingsForUpdate {
+            commitment_fee_usd: Some(-1.0),
+            min_confirmations: Some(0),
+            address_hrp: Some("XCH".to_string()),
+            webhook_url: Some("ftp://nope".to_string()),
+            ..Default::default()
+        };
+        let Err(Error::Validation(errs)) = SettingsBmc::update(&mm, bad).await else {
+            panic!("invalid settings must be rejected");
+        };
+        assert_eq!(errs.fields.len(), 4);
+        assert_eq!(SettingsBmc::get(&mm).await.unwrap(), settings);
+    }
+}
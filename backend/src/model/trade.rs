@@ -1,8 +1,11 @@
 use crate::ctx::Ctx;
 use crate::error::Error;
-use crate::model::ModelManager;
+use crate::model::{AuditBmc, ConfigBmc, ModelManager, TradeEventBmc, TransactionBmc, MOJOS_PER_XCH};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use crate::util::text::{self, MAX_LONG_TEXT_LEN};
+use crate::util::validation::ValidationErrors;
+use sqlx::{FromRow, PgExecutor, Postgres, QueryBuilder};
+use std::collections::HashMap;
 
 // ============================================
 // Trade Entity
@@ -59,6 +62,30 @@ pub struct Trade {
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub final_blockchain_hash: Option<String>,
 
+    // Optimistic concurrency
+    pub version: i64,
+
+    // Who pays the commitment fee (see FeeModel)
+    pub fee_model: String,
+
+    // Admin-featured proposals sort first on the board
+    pub is_featured: bool,
+
+    // Reject offers far below the proposer's item value (see min_offer_value_ratio)
+    pub lowball_guard: bool,
+
+    // Admin waived the commitment fee; the trade commits without payments
+    pub commitment_fee_waived: bool,
+
+    // Dispute raised by a participant and the admin's resolution
+    pub dispute_reason: Option<String>,
+    pub disputed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub dispute_resolution: Option<String>,
+
+    // Only filled in by queries that select them (e.g. list_proposals)
+    #[sqlx(default)]
+    pub tags: Vec<String>,
+
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -75,9 +102,124 @@ pub struct TradeForCreate {
     pub item_value_usd: f64,
     pub item_category: Option<String>,
     pub wishlist: Option<Vec<WishlistItem>>,
+    /// "both" (default), "proposer_only" or "acceptor_only"
+    pub fee_model: Option<String>,
+    /// Free-form keywords; normalized with `normalize_tags` before storing
+    pub tags: Option<Vec<String>>,
+    /// Reject lowball offers (default true)
+    pub lowball_guard: Option<bool>,
+}
+
+/// Lifecycle of a trade. Moves outside `allowed_transitions` are rejected by `TradeBmc::update_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeStatus {
+    Proposal,
+    Matched,
+    Committed,
+    Escrow,
+    Disputed,
+    Completed,
+    Cancelled,
+    Refunded,
+}
+
+impl TradeStatus {
+    pub const ALL: [TradeStatus; 8] = [
+        TradeStatus::Proposal,
+        TradeStatus::Matched,
+        TradeStatus::Committed,
+        TradeStatus::Escrow,
+        TradeStatus::Disputed,
+        TradeStatus::Completed,
+        TradeStatus::Cancelled,
+        TradeStatus::Refunded,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == s)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeStatus::Proposal => "proposal",
+            TradeStatus::Matched => "matched",
+            TradeStatus::Committed => "committed",
+            TradeStatus::Escrow => "escrow",
+            TradeStatus::Disputed => "disputed",
+            TradeStatus::Completed => "completed",
+            TradeStatus::Cancelled => "cancelled",
+            TradeStatus::Refunded => "refunded",
+        }
+    }
+
+    /// Statuses this one may move to; completed, cancelled and refunded are final
+    pub fn allowed_transitions(&self) -> &'static [TradeStatus] {
+        use TradeStatus::*;
+        match self {
+            Proposal => &[Matched, Cancelled],
+            Matched => &[Committed, Cancelled],
+            Committed => &[Escrow, Completed, Cancelled, Disputed],
+            Escrow => &[Completed, Disputed],
+            Disputed => &[Completed, Cancelled, Refunded],
+            Completed | Cancelled | Refunded => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: TradeStatus) -> bool {
+        self.allowed_transitions().contains(&next)
+    }
+}
+
+/// Outcome of `TradeBmc::complete`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Completion {
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+    /// The trade was completed by an earlier call; nothing changed
+    pub already_completed: bool,
+}
+
+/// Which participants must pay a commitment fee before a trade commits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeModel {
+    Both,
+    ProposerOnly,
+    AcceptorOnly,
+}
+
+impl FeeModel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "both" => Some(FeeModel::Both),
+            "proposer_only" => Some(FeeModel::ProposerOnly),
+            "acceptor_only" => Some(FeeModel::AcceptorOnly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeModel::Both => "both",
+            FeeModel::ProposerOnly => "proposer_only",
+            FeeModel::AcceptorOnly => "acceptor_only",
+        }
+    }
+
+    /// Whether the given side ("proposer" or "acceptor") must pay
+    pub fn requires(&self, is_proposer: bool) -> bool {
+        match self {
+            FeeModel::Both => true,
+            FeeModel::ProposerOnly => is_proposer,
+            FeeModel::AcceptorOnly => !is_proposer,
+        }
+    }
+
+    /// Whether every required fee has been paid
+    pub fn is_satisfied(&self, proposer_paid: bool, acceptor_paid: bool) -> bool {
+        (!self.requires(true) || proposer_paid) && (!self.requires(false) || acceptor_paid)
+    }
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, FromRow)]
 pub struct WishlistItem {
     pub wishlist_type: String, // "item", "xch", "mixed"
     pub item_description: Option<String>,
@@ -85,6 +227,51 @@ pub struct WishlistItem {
     pub xch_amount: Option<i64>, // in mojos
 }
 
+/// An item category and its number of open proposals (`TradeBmc::list_categories`)
+#[derive(Debug, Clone, Serialize, FromRow, PartialEq)]
+pub struct CategoryCount {
+    pub category: String,
+    pub count: i64,
+}
+
+/// Explicit column list for `WishlistItem` (item_min_value_usd is DECIMAL in the schema)
+const WISHLIST_COLUMNS: &str =
+    "wishlist_type, item_description, item_min_value_usd::float8 AS item_min_value_usd, xch_amount";
+
+/// Filters for `TradeBmc::search`; unset fields don't filter and set ones combine with AND
+#[derive(Debug, Default, Deserialize)]
+pub struct TradeSearchCriteria {
+    /// Case-insensitive substring of the item title, description or category; blank matches everything
+    pub query: Option<String>,
+    pub category: Option<String>,
+    pub min_value_usd: Option<f64>,
+    pub max_value_usd: Option<f64>,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSort {
+    #[default]
+    Newest,
+    /// Highest proposer item value first
+    Value,
+    /// Best-rated proposers first
+    Reputation,
+}
+
+/// Escape `%`, `_` and `\` so user text matches literally inside a LIKE pattern
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[derive(Deserialize)]
 pub struct TradeAcceptParams {
     pub trade_id: i64,
@@ -94,16 +281,104 @@ pub struct TradeAcceptParams {
     pub item_condition: Option<String>,
     pub item_value_usd: Option<f64>,
     pub xch_amount: Option<i64>,
+    /// Server XCH/USD price used to value `xch_amount` for the lowball guard.
+    /// Filled in by the RPC layer, never read from the request.
+    #[serde(skip)]
+    pub xch_price_usd: Option<f64>,
+    pub expected_version: Option<i64>,
 }
 
+/// A counter-offer: new terms for the bidder's side of a pending offer
 #[derive(Deserialize)]
+pub struct TradeCounterParams {
+    pub offer_id: i64,
+    pub offer_type: String,
+    pub item_title: Option<String>,
+    pub item_description: Option<String>,
+    pub item_condition: Option<String>,
+    pub item_value_usd: Option<f64>,
+    pub xch_amount: Option<i64>,
+    /// Server XCH/USD price, as on `TradeAcceptParams`
+    #[serde(skip)]
+    pub xch_price_usd: Option<f64>,
+}
+
+impl TradeCounterParams {
+    /// The counter's terms as an offer on `trade_id`
+    fn into_terms(self, trade_id: i64) -> TradeAcceptParams {
+        TradeAcceptParams {
+            trade_id,
+            offer_type: self.offer_type,
+            item_title: self.item_title,
+            item_description: self.item_description,
+            item_condition: self.item_condition,
+            item_value_usd: self.item_value_usd,
+            xch_amount: self.xch_amount,
+            xch_price_usd: self.xch_price_usd,
+            expected_version: None,
+        }
+    }
+}
+
+/// One offer in a bidder's negotiation with the proposer.
+/// Status: pending, countered, accepted, rejected or withdrawn.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TradeOffer {
+    pub id: i64,
+    pub trade_id: i64,
+    /// Who would become the acceptor if this offer is taken
+    pub bidder_id: i64,
+    /// The bidder, or the proposer for their counter-offers
+    pub created_by: i64,
+    pub parent_offer_id: Option<i64>,
+    pub offer_type: String,
+    pub item_title: Option<String>,
+    pub item_description: Option<String>,
+    pub item_condition: Option<String>,
+    pub item_value_usd: Option<f64>,
+    pub xch_amount: Option<i64>,
+    pub status: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An image attached to a trade (`trade_photos`)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TradeImage {
+    pub id: i64,
+    pub trade_id: i64,
+    pub user_id: i64,
+    pub photo_type: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub mime_type: Option<String>,
+    /// 0-based place in the trade's gallery
+    pub position: i32,
+    /// The trade's cover image; at most one per trade
+    pub is_primary: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Explicit column list for `TradeImage` (the position is stored as display_order)
+const IMAGE_COLUMNS: &str =
+    "id, trade_id, user_id, photo_type, file_path, file_size, mime_type, display_order AS position, is_primary, created_at";
+
+/// Partial trade update: only fields that are `Some` are written
+#[derive(Deserialize, Default)]
 pub struct TradeForUpdate {
-    pub status: Option<String>,
-    pub acceptor_id: Option<i64>,
-    pub proposer_tracking_number: Option<String>,
-    pub proposer_tracking_carrier: Option<String>,
-    pub acceptor_tracking_number: Option<String>,
-    pub acceptor_tracking_carrier: Option<String>,
+    // Proposal details (proposer only, while still a proposal)
+    pub item_title: Option<String>,
+    pub item_description: Option<String>,
+    pub item_condition: Option<String>,
+    pub item_value_usd: Option<f64>,
+    pub item_category: Option<String>,
+    pub lowball_guard: Option<bool>,
+
+    // Shipping (applies to the caller's own side of the trade)
+    pub tracking_number: Option<String>,
+    pub tracking_carrier: Option<String>,
+
+    pub expected_version: Option<i64>,
 }
 
 // ============================================
@@ -125,6 +400,34 @@ pub struct TradeReview {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Explicit column list for `TradeReview` (overall_score is DECIMAL in the schema)
+const REVIEW_COLUMNS: &str = "id, trade_id, reviewer_id, reviewee_id, timeliness_score, packaging_score, \
+    value_honesty_score, state_accuracy_score, overall_score::float8 AS overall_score, comment, created_at";
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReviewSummary {
+    pub review_count: i64,
+    pub avg_overall: f64,
+    pub avg_timeliness: f64,
+    pub avg_packaging: f64,
+    pub avg_value_honesty: f64,
+    pub avg_state_accuracy: f64,
+}
+
+/// Highest reputation score (reviews score 1-5)
+pub const MAX_REPUTATION: f64 = 5.0;
+
+/// An admin correction to a user's reputation (`ReviewBmc::admin_adjust_reputation`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReputationAdjustment {
+    /// Pin the score to this value
+    Set(f64),
+    /// Pin the score to the current value plus this (negative to lower it)
+    Delta(f64),
+    /// Drop the override and go back to the review average
+    Clear,
+}
+
 #[derive(Deserialize)]
 pub struct ReviewForCreate {
     pub trade_id: i64,
@@ -135,6 +438,257 @@ pub struct ReviewForCreate {
     pub comment: Option<String>,
 }
 
+// ============================================
+// DTO Validation
+// ============================================
+
+const MAX_TITLE_LEN: usize = 256;
+const MAX_TAGS: usize = 10;
+const MAX_TAG_LEN: usize = 32;
+/// Largest XCH amount an offer may name (1M XCH), far above any real trade
+const MAX_OFFER_XCH_MOJOS: i64 = 1_000_000 * 1_000_000_000_000;
+
+/// Lowercase and trim tags, dropping empty ones and duplicates (first occurrence wins)
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Check a dispute reason or resolution note (non-empty, at most 5000 characters) and
+/// escape it for storage
+fn dispute_text(field: &str, value: &str) -> Result<String, Error> {
+    let value = text::strip_controls(value.trim());
+    let mut errs = ValidationErrors::new();
+    errs.check(!value.trim().is_empty(), field, "must not be empty");
+    errs.check(value.chars().count() <= MAX_LONG_TEXT_LEN, field, "must be at most 5000 characters");
+    errs.into_result()?;
+    Ok(text::escape(&value))
+}
+
+fn check_item_fields(errs: &mut ValidationErrors, title: &str, description: &str, value_usd: f64) {
+    errs.check(!title.trim().is_empty(), "item_title", "must not be empty");
+    errs.check(title.chars().count() <= MAX_TITLE_LEN, "item_title", "must be at most 256 characters");
+    errs.check(!description.trim().is_empty(), "item_description", "must not be empty");
+    errs.check(description.chars().count() <= MAX_LONG_TEXT_LEN, "item_description", "must be at most 5000 characters");
+    errs.check(value_usd.is_finite() && value_usd > 0.0, "item_value_usd", "must be positive");
+}
+
+impl TradeForCreate {
+    /// Strip control characters from the user-written fields (see `util::text`)
+    pub fn sanitized(self) -> Self {
+        self.map_text(text::strip_controls)
+    }
+
+    /// Escape the validated user-written fields for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        self.map_text(text::escape)
+    }
+
+    fn map_text(self, f: fn(&str) -> String) -> Self {
+        let wishlist = self.wishlist.map(|items| {
+            items
+                .into_iter()
+                .map(|item| WishlistItem { item_description: item.item_description.map(|d| f(&d)), ..item })
+                .collect()
+        });
+        Self {
+            item_title: f(&self.item_title),
+            item_description: f(&self.item_description),
+            item_condition: self.item_condition.map(|v| f(&v)),
+            item_category: self.item_category.map(|v| f(&v)),
+            wishlist,
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        check_item_fields(&mut errs, &self.item_title, &self.item_description, self.item_value_usd);
+
+        for (i, item) in self.wishlist.iter().flatten().enumerate() {
+            let field = format!("wishlist[{}].wishlist_type", i);
+            errs.check(matches!(item.wishlist_type.as_str(), "item" | "xch" | "mixed"), &field, "must be item, xch or mixed");
+        }
+        if let Some(fee_model) = &self.fee_model {
+            errs.check(FeeModel::parse(fee_model).is_some(), "fee_model", "must be both, proposer_only or acceptor_only");
+        }
+        if let Some(tags) = &self.tags {
+            let tags = normalize_tags(tags);
+            errs.check(tags.len() <= MAX_TAGS, "tags", "must have at most 10 tags");
+            errs.check(tags.iter().all(|t| t.chars().count() <= MAX_TAG_LEN), "tags", "each tag must be at most 32 characters");
+        }
+
+        errs
+    }
+}
+
+impl TradeForUpdate {
+    /// Strip control characters from the user-written fields (see `util::text`)
+    pub fn sanitized(self) -> Self {
+        self.map_text(text::strip_controls)
+    }
+
+    /// Escape the validated user-written fields for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        self.map_text(text::escape)
+    }
+
+    fn map_text(self, f: fn(&str) -> String) -> Self {
+        let map = |v: Option<String>| v.map(|v| f(&v));
+        Self {
+            item_title: map(self.item_title),
+            item_description: map(self.item_description),
+            item_condition: map(self.item_condition),
+            item_category: map(self.item_category),
+            tracking_number: map(self.tracking_number),
+            tracking_carrier: map(self.tracking_carrier),
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        if let Some(title) = &self.item_title {
+            errs.check(!title.trim().is_empty(), "item_title", "must not be empty");
+            errs.check(title.chars().count() <= MAX_TITLE_LEN, "item_title", "must be at most 256 characters");
+        }
+        if let Some(description) = &self.item_description {
+            errs.check(!description.trim().is_empty(), "item_description", "must not be empty");
+            errs.check(description.chars().count() <= MAX_LONG_TEXT_LEN, "item_description", "must be at most 5000 characters");
+        }
+        if let Some(value) = self.item_value_usd {
+            errs.check(value.is_finite() && value > 0.0, "item_value_usd", "must be positive");
+        }
+        if let Some(tracking) = &self.tracking_number {
+            errs.check(!tracking.trim().is_empty(), "tracking_number", "must not be empty");
+        }
+        errs
+    }
+
+    fn has_proposal_fields(&self) -> bool {
+        self.item_title.is_some()
+            || self.item_description.is_some()
+            || self.item_condition.is_some()
+            || self.item_value_usd.is_some()
+            || self.item_category.is_some()
+            || self.lowball_guard.is_some()
+    }
+
+    /// Old/new values of the proposal fields this update actually changes
+    fn proposal_changes(&self, current: &Trade) -> serde_json::Map<String, serde_json::Value> {
+        use serde_json::json;
+
+        let mut changes = serde_json::Map::new();
+        let mut diff = |field: &str, old: serde_json::Value, new: Option<serde_json::Value>| {
+            if let Some(new) = new.filter(|new| *new != old) {
+                changes.insert(field.to_string(), json!({ "old": old, "new": new }));
+            }
+        };
+        diff("item_title", json!(current.proposer_item_title), self.item_title.as_ref().map(|v| json!(v)));
+        diff("item_description", json!(current.proposer_item_description), self.item_description.as_ref().map(|v| json!(v)));
+        diff("item_condition", json!(current.proposer_item_condition), self.item_condition.as_ref().map(|v| json!(v)));
+        diff("item_value_usd", json!(current.proposer_item_value_usd), self.item_value_usd.map(|v| json!(v)));
+        diff("item_category", json!(current.proposer_item_category), self.item_category.as_ref().map(|v| json!(v)));
+        diff("lowball_guard", json!(current.lowball_guard), self.lowball_guard.map(|v| json!(v)));
+        changes
+    }
+}
+
+impl TradeAcceptParams {
+    /// Strip control characters from the offered item's fields (see `util::text`)
+    pub fn sanitized(self) -> Self {
+        Self {
+            item_title: text::strip_controls_opt(self.item_title),
+            item_description: text::strip_controls_opt(self.item_description),
+            item_condition: text::strip_controls_opt(self.item_condition),
+            ..self
+        }
+    }
+
+    /// Escape the validated item fields for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        Self {
+            item_title: text::escape_opt(self.item_title),
+            item_description: text::escape_opt(self.item_description),
+            item_condition: text::escape_opt(self.item_condition),
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        let offers_item = matches!(self.offer_type.as_str(), "item" | "mixed");
+        let offers_xch = matches!(self.offer_type.as_str(), "xch" | "mixed");
+
+        errs.check(offers_item || offers_xch, "offer_type", "must be item, xch or mixed");
+        if offers_item {
+            check_item_fields(
+                &mut errs,
+                self.item_title.as_deref().unwrap_or(""),
+                self.item_description.as_deref().unwrap_or(""),
+                self.item_value_usd.unwrap_or(0.0),
+            );
+        }
+        if offers_xch {
+            match self.xch_amount {
+                None => errs.check(false, "xch_amount", "is required for xch and mixed offers"),
+                Some(mojos) if mojos <= 0 => errs.check(false, "xch_amount", "must be positive"),
+                Some(mojos) => errs.check(mojos <= MAX_OFFER_XCH_MOJOS, "xch_amount", "must be at most 1000000 XCH"),
+            }
+        }
+
+        errs
+    }
+
+    /// Offer value in USD, item and XCH parts together; `None` when XCH is offered
+    /// without a price to value it
+    fn offer_value_usd(&self) -> Option<f64> {
+        let item_usd = match self.offer_type.as_str() {
+            "item" | "mixed" => self.item_value_usd.unwrap_or(0.0),
+            _ => 0.0,
+        };
+        let xch_usd = match (self.offer_type.as_str(), self.xch_amount) {
+            ("xch" | "mixed", Some(mojos)) => mojos as f64 / MOJOS_PER_XCH * self.xch_price_usd?,
+            _ => 0.0,
+        };
+        Some(item_usd + xch_usd)
+    }
+}
+
+impl ReviewForCreate {
+    /// Strip control characters from the comment (see `util::text`)
+    pub fn sanitized(self) -> Self {
+        Self { comment: text::strip_controls_opt(self.comment), ..self }
+    }
+
+    /// Escape the validated comment for storage (see `util::text`)
+    pub fn escaped(self) -> Self {
+        Self { comment: text::escape_opt(self.comment), ..self }
+    }
+
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errs = ValidationErrors::new();
+        for (field, score) in [
+            ("timeliness", self.timeliness),
+            ("packaging", self.packaging),
+            ("value_honesty", self.value_honesty),
+            ("state_accuracy", self.state_accuracy),
+        ] {
+            errs.check((1..=5).contains(&score), field, "must be between 1 and 5");
+        }
+        if let Some(comment) = &self.comment {
+            errs.check(comment.chars().count() <= MAX_LONG_TEXT_LEN, "comment", "must be at most 5000 characters");
+        }
+        errs
+    }
+}
+
 // ============================================
 // Trade BMC (Business Model Controller)
 // ============================================
@@ -144,13 +698,26 @@ pub struct TradeBmc;
 impl TradeBmc {
     /// Create a new trade proposal
     pub async fn create(ctx: &Ctx, mm: &ModelManager, trade: TradeForCreate) -> Result<i64, Error> {
-        let db = mm.db();
+        Self::ensure_trading_allowed(mm, ctx.user_id()).await?;
+
+        let trade = trade.sanitized();
+        let mut errs = trade.validate();
+        let min_value = Self::min_proposal_value_usd(mm).await?;
+        if trade.item_value_usd < min_value {
+            errs.add("item_value_usd", format!("must be at least ${:.2}", min_value));
+        }
+        errs.into_result()?;
+        let trade = trade.escaped();
+
+        // The trade, its wishlist and its tags are written together
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
 
         let (id,) = sqlx::query_as::<_, (i64,)>(
             r#"INSERT INTO trades 
                (proposer_id, status, proposer_item_title, proposer_item_description, 
-                proposer_item_condition, proposer_item_value_usd, proposer_item_category, trade_type)
-               VALUES ($1, 'proposal', $2, $3, $4, $5, $6, 'item_for_item') 
+                proposer_item_condition, proposer_item_value_usd, proposer_item_category, trade_type, fee_model,
+                lowball_guard)
+               VALUES ($1, 'proposal', $2, $3, $4, $5, $6, 'item_for_item', $7, $8) 
                RETURNING id"#,
         )
         .bind(ctx.user_id())
@@ -159,7 +726,9 @@ impl TradeBmc {
         .bind(&trade.item_condition)
         .bind(trade.item_value_usd)
         .bind(&trade.item_category)
-        .fetch_one(db)
+        .bind(trade.fee_model.as_deref().unwrap_or(FeeModel::Both.as_str()))
+        .bind(trade.lowball_guard.unwrap_or(true))
+        .fetch_one(&mut *tx)
         .await
         .map_err(|_| Error::InternalServer)?;
 
@@ -176,15 +745,53 @@ impl TradeBmc {
                 .bind(&item.item_description)
                 .bind(item.item_min_value_usd)
                 .bind(item.xch_amount)
-                .execute(db)
+                .execute(&mut *tx)
                 .await
                 .map_err(|_| Error::InternalServer)?;
             }
         }
 
+        let tags = normalize_tags(trade.tags.as_deref().unwrap_or_default());
+        if !tags.is_empty() {
+            sqlx::query("INSERT INTO trade_tags (trade_id, tag) SELECT $1, UNNEST($2::TEXT[])")
+                .bind(id)
+                .bind(&tags)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| Error::InternalServer)?;
+        }
+
+        TradeEventBmc::record(&mut tx, id, Some(ctx.user_id()), "trade_created", serde_json::json!({}))
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
         Ok(id)
     }
 
+    /// Minimum offer value as a fraction of the proposer's item value (0 when unset, which disables the guard)
+    pub async fn min_offer_value_ratio(mm: &ModelManager) -> Result<f64, Error> {
+        match ConfigBmc::get(mm, "min_offer_value_ratio").await? {
+            Some(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|r| r.is_finite() && *r >= 0.0)
+                .ok_or_else(|| Error::Config("Invalid min_offer_value_ratio value".to_string())),
+            None => Ok(0.0),
+        }
+    }
+
+    /// Minimum proposer item value from config (defaults to 0 when unset)
+    pub async fn min_proposal_value_usd(mm: &ModelManager) -> Result<f64, Error> {
+        match ConfigBmc::get(mm, "min_proposal_value_usd").await? {
+            Some(v) => v
+                .parse::<f64>()
+                .map_err(|_| Error::Config("Invalid min_proposal_value_usd value".to_string())),
+            None => Ok(0.0),
+        }
+    }
+
     /// Get a trade by ID (participant access only)
     pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Trade, Error> {
         sqlx::query_as::<_, Trade>(
@@ -206,13 +813,87 @@ impl TradeBmc {
             .map_err(|_| Error::NotFound)
     }
 
-    /// List open trade proposals (public)
-    pub async fn list_proposals(mm: &ModelManager, limit: i64, offset: i64) -> Result<Vec<Trade>, Error> {
+    /// Refuse new trades and offers from users an admin has paused
+    async fn ensure_trading_allowed(mm: &ModelManager, user_id: i64) -> Result<(), Error> {
+        let paused: Option<bool> = sqlx::query_scalar("SELECT trading_paused FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(mm.db())
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        if paused == Some(true) {
+            return Err(Error::TradingPaused);
+        }
+        Ok(())
+    }
+
+    /// The proposer's wishlist for a trade, in the order it was given (public)
+    pub async fn get_wishlist(mm: &ModelManager, trade_id: i64) -> Result<Vec<WishlistItem>, Error> {
+        sqlx::query_as::<_, WishlistItem>(&format!(
+            "SELECT {} FROM trade_wishlists WHERE trade_id = $1 ORDER BY id",
+            WISHLIST_COLUMNS
+        ))
+        .bind(trade_id)
+        .fetch_all(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// Wishlists for many trades in one query; trades without one are absent from the map
+    pub async fn wishlists_for<'e>(
+        db: impl sqlx::PgExecutor<'e>,
+        trade_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<WishlistItem>>, Error> {
+        #[derive(FromRow)]
+        struct Row {
+            trade_id: i64,
+            #[sqlx(flatten)]
+            item: WishlistItem,
+        }
+        let rows = sqlx::query_as::<_, Row>(&format!(
+            "SELECT trade_id, {} FROM trade_wishlists WHERE trade_id = ANY($1) ORDER BY id",
+            WISHLIST_COLUMNS
+        ))
+        .bind(trade_ids)
+        .fetch_all(db)
+        .await
+        .map_err(|e| {
+            tracing::error!("wishlists_for error: {:?}", e);
+            Error::InternalServer
+        })?;
+
+        let mut wishlists: HashMap<i64, Vec<WishlistItem>> = HashMap::new();
+        for row in rows {
+            wishlists.entry(row.trade_id).or_default().push(row.item);
+        }
+        Ok(wishlists)
+    }
+
+    /// List open trade proposals (public), optionally only those carrying `tag` and/or in `category`
+    /// (compared case-insensitively)
+    pub async fn list_proposals(
+        mm: &ModelManager,
+        limit: i64,
+        offset: i64,
+        tag: Option<&str>,
+        category: Option<&str>,
+    ) -> Result<Vec<Trade>, Error> {
+        let tag = tag.map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+        // Stored text is escaped per the free-text policy, so the filter must be too
+        let category = category.map(str::trim).filter(|c| !c.is_empty()).map(text::escape);
         sqlx::query_as::<_, Trade>(
-            "SELECT * FROM trades WHERE status = 'proposal' ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            r#"SELECT t.*, ARRAY(SELECT g.tag FROM trade_tags g WHERE g.trade_id = t.id ORDER BY g.tag) AS tags
+               FROM trades t
+               WHERE t.status = 'proposal'
+                 AND ($3::TEXT IS NULL OR EXISTS (SELECT 1 FROM trade_tags g WHERE g.trade_id = t.id AND g.tag = $3))
+                 AND ($4::TEXT IS NULL OR LOWER(t.proposer_item_category) = LOWER($4))
+               ORDER BY t.is_featured DESC, t.created_at DESC
+               LIMIT $1 OFFSET $2"#,
         )
         .bind(limit)
         .bind(offset)
+        .bind(tag)
+        .bind(category)
         .fetch_all(mm.db())
         .await
         .map_err(|e| {
@@ -221,6 +902,94 @@ impl TradeBmc {
         })
     }
 
+    /// Categories of open proposals with how many each has (public), largest first.
+    /// Categories differing only in case are counted together.
+    pub async fn list_categories(mm: &ModelManager) -> Result<Vec<CategoryCount>, Error> {
+        sqlx::query_as::<_, CategoryCount>(
+            r#"SELECT MIN(proposer_item_category) AS category, COUNT(*) AS count
+               FROM trades
+               WHERE status = 'proposal' AND proposer_item_category IS NOT NULL AND proposer_item_category <> ''
+               GROUP BY LOWER(proposer_item_category)
+               ORDER BY count DESC, category"#,
+        )
+        .fetch_all(mm.db())
+        .await
+        .map_err(|e| {
+            tracing::error!("list_categories error: {:?}", e);
+            Error::InternalServer
+        })
+    }
+
+    /// Search open trade proposals (public) by text, category, value range and tag.
+    /// User input is only ever bound, never interpolated into the SQL.
+    pub async fn search(
+        mm: &ModelManager,
+        criteria: &TradeSearchCriteria,
+        sort: TradeSort,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Trade>, Error> {
+        // Free-text filters are escaped like the stored text they're compared against
+        let input = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(text::escape);
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT t.*, ARRAY(SELECT g.tag FROM trade_tags g WHERE g.trade_id = t.id ORDER BY g.tag) AS tags \
+             FROM trades t JOIN users u ON u.id = t.proposer_id \
+             WHERE t.status = 'proposal'",
+        );
+        if let Some(query) = input(&criteria.query) {
+            let pattern = format!("%{}%", escape_like(&query));
+            qb.push(" AND (t.proposer_item_title ILIKE ").push_bind(pattern.clone());
+            qb.push(" OR t.proposer_item_description ILIKE ").push_bind(pattern.clone());
+            qb.push(" OR t.proposer_item_category ILIKE ").push_bind(pattern).push(")");
+        }
+        if let Some(category) = input(&criteria.category) {
+            qb.push(" AND LOWER(t.proposer_item_category) = LOWER(").push_bind(category).push(")");
+        }
+        if let Some(min) = criteria.min_value_usd {
+            qb.push(" AND t.proposer_item_value_usd >= ").push_bind(min);
+        }
+        if let Some(max) = criteria.max_value_usd {
+            qb.push(" AND t.proposer_item_value_usd <= ").push_bind(max);
+        }
+        if let Some(tag) = criteria.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            qb.push(" AND EXISTS (SELECT 1 FROM trade_tags g WHERE g.trade_id = t.id AND g.tag = ")
+                .push_bind(tag.to_lowercase())
+                .push(")");
+        }
+
+        qb.push(match sort {
+            TradeSort::Newest => " ORDER BY t.created_at DESC, t.id DESC",
+            TradeSort::Value => " ORDER BY t.proposer_item_value_usd DESC, t.created_at DESC, t.id DESC",
+            TradeSort::Reputation => {
+                " ORDER BY COALESCE(u.reputation_score, 0) DESC, t.created_at DESC, t.id DESC"
+            }
+        });
+        qb.push(" LIMIT ").push_bind(limit);
+        qb.push(" OFFSET ").push_bind(offset);
+
+        qb.build_query_as::<Trade>().fetch_all(mm.db()).await.map_err(|e| {
+            tracing::error!("search error: {:?}", e);
+            Error::InternalServer
+        })
+    }
+
+    /// Look up statuses for many trades in one query (public).
+    /// Missing ids are simply absent from the map; deleted trades are removed
+    /// from the table, so they never show up here.
+    pub async fn statuses_for(mm: &ModelManager, ids: &[i64]) -> Result<HashMap<i64, String>, Error> {
+        let rows = sqlx::query_as::<_, (i64, String)>("SELECT id, status FROM trades WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(mm.db())
+            .await
+            .map_err(|e| {
+                tracing::error!("statuses_for error: {:?}", e);
+                Error::InternalServer
+            })?;
+
+        Ok(rows.into_iter().collect())
+    }
+
     /// List user's own trades (as proposer or acceptor)
     pub async fn list_my_trades(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<Trade>, Error> {
         sqlx::query_as::<_, Trade>(
@@ -232,14 +1001,33 @@ impl TradeBmc {
         .map_err(|_| Error::InternalServer)
     }
 
-    /// Accept a trade proposal (make an offer)
-    pub async fn accept(ctx: &Ctx, mm: &ModelManager, params: TradeAcceptParams) -> Result<(), Error> {
-        let db = mm.db();
+    /// Count the user's trades (as proposer or acceptor) per status
+    pub async fn status_counts(ctx: &Ctx, mm: &ModelManager) -> Result<HashMap<String, i64>, Error> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT status, COUNT(*) FROM trades WHERE proposer_id = $1 OR acceptor_id = $1 GROUP BY status",
+        )
+        .bind(ctx.user_id())
+        .fetch_all(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Make an offer on a proposal (anyone but the proposer). The trade stays a proposal
+    /// until the proposer accepts an offer; the caller's earlier pending offer is withdrawn.
+    pub async fn make_offer(ctx: &Ctx, mm: &ModelManager, params: TradeAcceptParams) -> Result<i64, Error> {
+        Self::ensure_trading_allowed(mm, ctx.user_id()).await?;
+        let params = params.sanitized();
+        params.validate().into_result()?;
+        let params = params.escaped();
+
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
 
         // Verify trade exists and is a proposal
-        let trade: Trade = sqlx::query_as("SELECT * FROM trades WHERE id = $1 AND status = 'proposal'")
+        let trade: Trade = sqlx::query_as("SELECT * FROM trades WHERE id = $1 AND status = 'proposal' FOR UPDATE")
             .bind(params.trade_id)
-            .fetch_one(db)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|_| Error::NotFound)?;
 
@@ -247,61 +1035,495 @@ impl TradeBmc {
         if trade.proposer_id == ctx.user_id() {
             return Err(Error::BadRequest);
         }
-
-        // Determine trade type based on offer
-        let trade_type = match params.offer_type.as_str() {
-            "xch" => "item_for_xch",
-            "mixed" => "mixed",
-            _ => "item_for_item",
-        };
+        if let Some(expected) = params.expected_version.filter(|expected| *expected != trade.version) {
+            return Err(Error::Conflict(format!(
+                "Trade {} was modified concurrently (current version {}, expected {})",
+                trade.id, trade.version, expected
+            )));
+        }
+        Self::check_lowball(mm, &trade, &params).await?;
 
         sqlx::query(
-            r#"UPDATE trades SET 
-               acceptor_id = $2,
-               status = 'matched',
-               acceptor_item_title = $3,
+            "UPDATE trade_offers SET status = 'withdrawn', updated_at = NOW()
+             WHERE trade_id = $1 AND bidder_id = $2 AND status = 'pending'",
+        )
+        .bind(trade.id)
+        .bind(ctx.user_id())
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        let offer_id = Self::insert_offer(&mut tx, &params, ctx.user_id(), ctx.user_id(), None).await?;
+        TradeEventBmc::record(&mut tx, trade.id, Some(ctx.user_id()), "offer_made", serde_json::json!({ "offer_id": offer_id }))
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(offer_id)
+    }
+
+    /// Answer a pending offer with new terms (the proposer, or the bidder answering the
+    /// proposer's counter). The answered offer becomes `countered`. Returns the new offer's id.
+    pub async fn counter_offer(ctx: &Ctx, mm: &ModelManager, params: TradeCounterParams) -> Result<i64, Error> {
+        Self::ensure_trading_allowed(mm, ctx.user_id()).await?;
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let (offer, trade) = Self::lock_offer(&mut tx, ctx, params.offer_id).await?;
+        if offer.created_by == ctx.user_id() {
+            return Err(Error::InvalidState(format!(
+                "Offer {} is your own; make a new offer instead of countering it",
+                offer.id
+            )));
+        }
+
+        let terms = params.into_terms(trade.id).sanitized();
+        terms.validate().into_result()?;
+        if ctx.user_id() == offer.bidder_id {
+            Self::check_lowball(mm, &trade, &terms).await?;
+        }
+        let terms = terms.escaped();
+
+        sqlx::query("UPDATE trade_offers SET status = 'countered', updated_at = NOW() WHERE id = $1")
+            .bind(offer.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+        let counter_id = Self::insert_offer(&mut tx, &terms, offer.bidder_id, ctx.user_id(), Some(offer.id)).await?;
+        TradeEventBmc::record(
+            &mut tx,
+            trade.id,
+            Some(ctx.user_id()),
+            "offer_countered",
+            serde_json::json!({ "offer_id": offer.id, "counter_offer_id": counter_id }),
+        )
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(counter_id)
+    }
+
+    /// Accept a pending offer, matching the trade with its bidder. Bidders' offers are
+    /// accepted by the proposer; the proposer's counter-offers already carry the proposer's
+    /// agreement, so the bidder accepts those. Other pending offers on the trade are rejected.
+    /// Returns the trade id.
+    pub async fn accept_offer(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        offer_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<i64, Error> {
+        Self::ensure_trading_allowed(mm, ctx.user_id()).await?;
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let (offer, trade) = Self::lock_offer(&mut tx, ctx, offer_id).await?;
+        if offer.created_by == ctx.user_id() {
+            return Err(Error::InvalidState(format!(
+                "Offer {} must be accepted by the {}",
+                offer.id,
+                if ctx.user_id() == trade.proposer_id { "bidder" } else { "proposer" }
+            )));
+        }
+        if let Some(expected) = expected_version.filter(|expected| *expected != trade.version) {
+            return Err(Error::Conflict(format!(
+                "Trade {} was modified concurrently (current version {}, expected {})",
+                trade.id, trade.version, expected
+            )));
+        }
+
+        // Determine trade type based on offer
+        let trade_type = match offer.offer_type.as_str() {
+            "xch" => "item_for_xch",
+            "mixed" => "mixed",
+            _ => "item_for_item",
+        };
+
+        sqlx::query(
+            r#"UPDATE trades SET 
+               acceptor_id = $2,
+               status = 'matched',
+               acceptor_item_title = $3,
                acceptor_item_description = $4,
                acceptor_item_condition = $5,
                acceptor_item_value_usd = $6,
                acceptor_xch_offer = $7,
                trade_type = $8,
+               proposer_commit_status = 'pending',
+               acceptor_commit_status = 'pending',
+               version = version + 1,
                updated_at = NOW()
                WHERE id = $1"#,
         )
-        .bind(params.trade_id)
-        .bind(ctx.user_id())
-        .bind(&params.item_title)
-        .bind(&params.item_description)
-        .bind(&params.item_condition)
-        .bind(params.item_value_usd)
-        .bind(params.xch_amount)
+        .bind(trade.id)
+        .bind(offer.bidder_id)
+        .bind(&offer.item_title)
+        .bind(&offer.item_description)
+        .bind(&offer.item_condition)
+        .bind(offer.item_value_usd)
+        .bind(offer.xch_amount)
         .bind(trade_type)
-        .execute(db)
+        .execute(&mut *tx)
         .await
         .map_err(|_| Error::InternalServer)?;
 
-        Ok(())
+        sqlx::query(
+            "UPDATE trade_offers SET status = CASE WHEN id = $2 THEN 'accepted' ELSE 'rejected' END, updated_at = NOW()
+             WHERE trade_id = $1 AND status = 'pending'",
+        )
+        .bind(trade.id)
+        .bind(offer.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+        TradeEventBmc::record(&mut tx, trade.id, Some(ctx.user_id()), "offer_accepted", serde_json::json!({ "offer_id": offer.id }))
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(trade.id)
     }
 
-    /// Update trade status (participant only)
-    pub async fn update_status(ctx: &Ctx, mm: &ModelManager, id: i64, status: &str) -> Result<(), Error> {
-        let result = sqlx::query(
-            "UPDATE trades SET status = $3, updated_at = NOW() WHERE id = $1 AND (proposer_id = $2 OR acceptor_id = $2)",
+    /// Offers on a trade, newest first: all of them for the proposer, a bidder's own otherwise
+    pub async fn list_offers(ctx: &Ctx, mm: &ModelManager, trade_id: i64) -> Result<Vec<TradeOffer>, Error> {
+        let proposer_id: i64 = sqlx::query_scalar("SELECT proposer_id FROM trades WHERE id = $1")
+            .bind(trade_id)
+            .fetch_optional(mm.db())
+            .await
+            .map_err(|_| Error::InternalServer)?
+            .ok_or(Error::NotFound)?;
+
+        sqlx::query_as::<_, TradeOffer>(
+            "SELECT * FROM trade_offers WHERE trade_id = $1 AND ($2 OR bidder_id = $3)
+             ORDER BY created_at DESC, id DESC",
         )
-        .bind(id)
+        .bind(trade_id)
+        .bind(proposer_id == ctx.user_id())
         .bind(ctx.user_id())
-        .bind(status)
-        .execute(mm.db())
+        .fetch_all(mm.db())
         .await
-        .map_err(|_| Error::InternalServer)?;
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// One offer by id, without access checks
+    pub async fn get_offer(mm: &ModelManager, offer_id: i64) -> Result<TradeOffer, Error> {
+        sqlx::query_as("SELECT * FROM trade_offers WHERE id = $1")
+            .bind(offer_id)
+            .fetch_optional(mm.db())
+            .await
+            .map_err(|_| Error::InternalServer)?
+            .ok_or(Error::NotFound)
+    }
+
+    /// A trade's images, cover first and the rest by position (public)
+    pub async fn list_images(mm: &ModelManager, trade_id: i64) -> Result<Vec<TradeImage>, Error> {
+        sqlx::query_as::<_, TradeImage>(&format!(
+            "SELECT {} FROM trade_photos WHERE trade_id = $1 ORDER BY is_primary DESC, display_order, id",
+            IMAGE_COLUMNS
+        ))
+        .bind(trade_id)
+        .fetch_all(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
 
+    /// Make `image_id` the trade's cover, replacing the previous one (proposer only)
+    pub async fn set_primary_image(ctx: &Ctx, mm: &ModelManager, trade_id: i64, image_id: i64) -> Result<(), Error> {
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+        Self::lock_own_trade(&mut tx, ctx, trade_id).await?;
+
+        // Clear first: the one-primary index is checked row by row
+        sqlx::query("UPDATE trade_photos SET is_primary = FALSE WHERE trade_id = $1 AND is_primary AND id <> $2")
+            .bind(trade_id)
+            .bind(image_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+        let result = sqlx::query("UPDATE trade_photos SET is_primary = TRUE WHERE trade_id = $1 AND id = $2")
+            .bind(trade_id)
+            .bind(image_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
         if result.rows_affected() == 0 {
             return Err(Error::NotFound);
         }
 
+        tx.commit().await.map_err(|_| Error::InternalServer)
+    }
+
+    /// Put a trade's images in the order of `image_ids`, which must list each of them once (proposer only)
+    pub async fn reorder_images(ctx: &Ctx, mm: &ModelManager, trade_id: i64, image_ids: &[i64]) -> Result<(), Error> {
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+        Self::lock_own_trade(&mut tx, ctx, trade_id).await?;
+
+        let mut current: Vec<i64> = sqlx::query_scalar("SELECT id FROM trade_photos WHERE trade_id = $1")
+            .bind(trade_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+        let mut requested = image_ids.to_vec();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Err(Error::InvalidState("image_ids must list each of the trade's images exactly once".to_string()));
+        }
+
+        sqlx::query(
+            "UPDATE trade_photos p SET display_order = (v.ord - 1)::INT
+             FROM UNNEST($2::BIGINT[]) WITH ORDINALITY AS v(id, ord)
+             WHERE p.id = v.id AND p.trade_id = $1",
+        )
+        .bind(trade_id)
+        .bind(image_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)
+    }
+
+    /// Lock a trade the caller proposed; anyone else gets `NotFound`
+    async fn lock_own_trade(tx: &mut sqlx::PgConnection, ctx: &Ctx, trade_id: i64) -> Result<(), Error> {
+        sqlx::query_scalar::<_, i64>("SELECT id FROM trades WHERE id = $1 AND proposer_id = $2 FOR UPDATE")
+            .bind(trade_id)
+            .bind(ctx.user_id())
+            .fetch_optional(tx)
+            .await
+            .map_err(|_| Error::InternalServer)?
+            .ok_or(Error::NotFound)
+            .map(|_| ())
+    }
+
+    /// Lock a pending offer and its trade (still a proposal) for the proposer or the offer's
+    /// bidder; anyone else gets `NotFound`. The trade row is locked first, in the same order
+    /// as `make_offer`, so concurrent offer changes can't deadlock.
+    async fn lock_offer(
+        tx: &mut sqlx::PgConnection,
+        ctx: &Ctx,
+        offer_id: i64,
+    ) -> Result<(TradeOffer, Trade), Error> {
+        let trade_id: i64 = sqlx::query_scalar("SELECT trade_id FROM trade_offers WHERE id = $1")
+            .bind(offer_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?
+            .ok_or(Error::NotFound)?;
+        let trade: Trade = sqlx::query_as("SELECT * FROM trades WHERE id = $1 FOR UPDATE")
+            .bind(trade_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+        let offer: TradeOffer = sqlx::query_as("SELECT * FROM trade_offers WHERE id = $1 FOR UPDATE")
+            .bind(offer_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        if ctx.user_id() != trade.proposer_id && ctx.user_id() != offer.bidder_id {
+            return Err(Error::NotFound);
+        }
+        if offer.status != "pending" {
+            return Err(Error::InvalidState(format!("Offer {} is {}", offer.id, offer.status)));
+        }
+        if trade.status != TradeStatus::Proposal.as_str() {
+            return Err(Error::InvalidState(format!("Trade {} is no longer open for offers ({})", trade.id, trade.status)));
+        }
+        Ok((offer, trade))
+    }
+
+    async fn insert_offer(
+        tx: &mut sqlx::PgConnection,
+        terms: &TradeAcceptParams,
+        bidder_id: i64,
+        created_by: i64,
+        parent_offer_id: Option<i64>,
+    ) -> Result<i64, Error> {
+        sqlx::query_scalar(
+            r#"INSERT INTO trade_offers
+               (trade_id, bidder_id, created_by, parent_offer_id, offer_type,
+                item_title, item_description, item_condition, item_value_usd, xch_amount)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id"#,
+        )
+        .bind(terms.trade_id)
+        .bind(bidder_id)
+        .bind(created_by)
+        .bind(parent_offer_id)
+        .bind(&terms.offer_type)
+        .bind(&terms.item_title)
+        .bind(&terms.item_description)
+        .bind(&terms.item_condition)
+        .bind(terms.item_value_usd)
+        .bind(terms.xch_amount)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// Reject offers worth less than `min_offer_value_ratio` of the proposal when its lowball guard is on
+    async fn check_lowball(mm: &ModelManager, trade: &Trade, params: &TradeAcceptParams) -> Result<(), Error> {
+        if trade.lowball_guard {
+            // XCH that can't be valued (no server price) fails closed
+            let Some(value) = params.offer_value_usd() else {
+                let mut errs = ValidationErrors::new();
+                errs.add("xch_amount", "can't be valued right now (XCH price unavailable); try again later");
+                return Err(Error::Validation(errs));
+            };
+            let ratio = Self::min_offer_value_ratio(mm).await?;
+            let floor = trade.proposer_item_value_usd * ratio;
+            if value < floor {
+                let mut errs = ValidationErrors::new();
+                errs.add(
+                    "offer_value",
+                    format!("must be at least ${:.2} ({:.0}% of the proposal value)", floor, ratio * 100.0),
+                );
+                return Err(Error::Validation(errs));
+            }
+        }
+        Ok(())
+    }
+
+    /// Update trade status (participant only). Only moves allowed by
+    /// `TradeStatus::allowed_transitions` are written; others are `InvalidState`.
+    /// Committing also needs the commitment fees confirmed or waived, and then starts escrow.
+    pub async fn update_status(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        status: &str,
+        expected_version: Option<i64>,
+    ) -> Result<(), Error> {
+        let next = TradeStatus::parse(status)
+            .ok_or_else(|| Error::InvalidState(format!("Unknown trade status '{}'", status)))?;
+
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        // Lock the row so the transition is checked against the status we overwrite
+        let (current, version) = sqlx::query_as::<_, (String, i64)>(
+            "SELECT status, version FROM trades WHERE id = $1 AND (proposer_id = $2 OR acceptor_id = $2) FOR UPDATE",
+        )
+        .bind(id)
+        .bind(ctx.user_id())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?
+        .ok_or(Error::NotFound)?;
+
+        if let Some(expected) = expected_version.filter(|expected| *expected != version) {
+            return Err(Error::Conflict(format!(
+                "Trade {} was modified concurrently (current version {}, expected {})",
+                id, version, expected
+            )));
+        }
+
+        let current = TradeStatus::parse(&current)
+            .ok_or_else(|| Error::InvalidState(format!("Trade {} has unknown status '{}'", id, current)))?;
+        if !current.can_transition_to(next) {
+            let allowed: Vec<&str> = current.allowed_transitions().iter().map(TradeStatus::as_str).collect();
+            return Err(Error::InvalidState(format!(
+                "Cannot move trade {} from {} to {} (allowed: {})",
+                id,
+                current.as_str(),
+                next.as_str(),
+                if allowed.is_empty() { "none, the trade is final".to_string() } else { allowed.join(", ") }
+            )));
+        }
+
+        // The same path a confirmed or waived fee takes, so committing can't skip the fee
+        if next == TradeStatus::Committed {
+            let settled = TransactionBmc::check_and_update_trade_commitment(&mut tx, id)
+                .await
+                .map_err(|_| Error::InternalServer)?;
+            if !settled {
+                return Err(Error::InvalidState(format!(
+                    "Trade {} can't commit until its commitment fees are confirmed or waived",
+                    id
+                )));
+            }
+            tx.commit().await.map_err(|_| Error::InternalServer)?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"UPDATE trades SET status = $2, version = version + 1, updated_at = NOW(),
+                   completed_at = CASE WHEN $2 = 'completed' THEN COALESCE(completed_at, NOW()) ELSE completed_at END
+               WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(next.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
         Ok(())
     }
 
+    /// Complete a trade in escrow or committed (participant only). Safe to repeat:
+    /// completing an already-completed trade changes nothing and reports when it happened.
+    pub async fn complete(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Completion, Error> {
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let (status, version, completed_at) = sqlx::query_as::<_, (String, i64, Option<chrono::DateTime<chrono::Utc>>)>(
+            "SELECT status, version, completed_at FROM trades WHERE id = $1 AND (proposer_id = $2 OR acceptor_id = $2) FOR UPDATE",
+        )
+        .bind(id)
+        .bind(ctx.user_id())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?
+        .ok_or(Error::NotFound)?;
+
+        // Checked before the version: the first completion bumped it
+        if status == TradeStatus::Completed.as_str() {
+            let completed_at = completed_at.ok_or(Error::InternalServer)?;
+            return Ok(Completion { completed_at, already_completed: true });
+        }
+
+        if let Some(expected) = expected_version.filter(|expected| *expected != version) {
+            return Err(Error::Conflict(format!(
+                "Trade {} was modified concurrently (current version {}, expected {})",
+                id, version, expected
+            )));
+        }
+
+        // A disputed trade may also end completed, but only through an admin resolution
+        let from = TradeStatus::parse(&status).filter(|s| *s != TradeStatus::Disputed);
+        if !from.is_some_and(|s| s.can_transition_to(TradeStatus::Completed)) {
+            return Err(Error::InvalidState(format!(
+                "Cannot complete trade {} from {} (must be escrow or committed)",
+                id, status
+            )));
+        }
+
+        let (completed_at,) = sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>,)>(
+            r#"UPDATE trades SET status = 'completed', completed_at = NOW(), version = version + 1, updated_at = NOW()
+               WHERE id = $1
+               RETURNING completed_at"#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        TradeEventBmc::record(&mut tx, id, Some(ctx.user_id()), "trade_completed", serde_json::json!({}))
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(Completion { completed_at, already_completed: false })
+    }
+
     /// Add tracking information
     pub async fn add_tracking(
         ctx: &Ctx,
@@ -309,45 +1531,152 @@ impl TradeBmc {
         trade_id: i64,
         tracking_number: &str,
         carrier: &str,
+        expected_version: Option<i64>,
     ) -> Result<(), Error> {
-        let trade = Self::get(ctx, mm, trade_id).await?;
+        let trade_u = TradeForUpdate {
+            tracking_number: Some(tracking_number.to_string()),
+            tracking_carrier: Some(carrier.to_string()),
+            expected_version,
+            ..Default::default()
+        };
+
+        Self::update_partial(ctx, mm, trade_id, trade_u).await
+    }
+
+    /// Update only the provided fields of a trade (participant only).
+    /// Proposal details may only be edited by the proposer while the trade is still
+    /// a proposal; tracking fields go to the caller's own side of the trade.
+    pub async fn update_partial(ctx: &Ctx, mm: &ModelManager, id: i64, trade_u: TradeForUpdate) -> Result<(), Error> {
+        let trade_u = trade_u.sanitized();
+        let mut errs = trade_u.validate();
+        if let Some(value) = trade_u.item_value_usd {
+            let min_value = Self::min_proposal_value_usd(mm).await?;
+            if value < min_value {
+                errs.add("item_value_usd", format!("must be at least ${:.2}", min_value));
+            }
+        }
+        errs.into_result()?;
+        let trade_u = trade_u.escaped();
 
-        // Determine which party is adding tracking
-        let (column_tracking, column_carrier, column_shipped) = if trade.proposer_id == ctx.user_id() {
-            ("proposer_tracking_number", "proposer_tracking_carrier", "proposer_shipped_at")
+        let trade = Self::get(ctx, mm, id).await?;
+        let is_proposer = trade.proposer_id == ctx.user_id();
+        let edits_proposal = trade_u.has_proposal_fields();
+
+        if edits_proposal {
+            if !is_proposer {
+                return Err(Error::InvalidState("Only the proposer can edit proposal details".to_string()));
+            }
+            if trade.status != "proposal" {
+                return Err(Error::InvalidState("Proposal details can only be edited before an offer is accepted".to_string()));
+            }
+        }
+
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        // Read the current proposal under lock so the edit history matches what we overwrite
+        let changes = if edits_proposal {
+            let current = sqlx::query_as::<_, Trade>("SELECT * FROM trades WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|_| Error::InternalServer)?;
+            trade_u.proposal_changes(&current)
         } else {
-            ("acceptor_tracking_number", "acceptor_tracking_carrier", "acceptor_shipped_at")
+            serde_json::Map::new()
         };
 
-        let query = format!(
-            "UPDATE trades SET {} = $2, {} = $3, {} = NOW(), updated_at = NOW() WHERE id = $1",
-            column_tracking, column_carrier, column_shipped
-        );
+        // Column names below are fixed strings; only values are bound
+        let mut qb = QueryBuilder::<Postgres>::new("UPDATE trades SET version = version + 1, updated_at = NOW()");
+        let mut changed = false;
+
+        for (column, value) in [
+            ("proposer_item_title", trade_u.item_title),
+            ("proposer_item_description", trade_u.item_description),
+            ("proposer_item_condition", trade_u.item_condition),
+            ("proposer_item_category", trade_u.item_category),
+        ] {
+            if let Some(value) = value {
+                qb.push(format!(", {} = ", column)).push_bind(value);
+                changed = true;
+            }
+        }
+        if let Some(value) = trade_u.item_value_usd {
+            qb.push(", proposer_item_value_usd = ").push_bind(value);
+            changed = true;
+        }
+        if let Some(guard) = trade_u.lowball_guard {
+            qb.push(", lowball_guard = ").push_bind(guard);
+            changed = true;
+        }
 
-        sqlx::query(&query)
-            .bind(trade_id)
-            .bind(tracking_number)
-            .bind(carrier)
-            .execute(mm.db())
-            .await
-            .map_err(|_| Error::InternalServer)?;
+        let side = if is_proposer { "proposer" } else { "acceptor" };
+        if let Some(tracking) = trade_u.tracking_number {
+            qb.push(format!(", {side}_tracking_number = ")).push_bind(tracking);
+            // Keep the first ship time when a tracking number is corrected
+            qb.push(format!(", {side}_shipped_at = COALESCE({side}_shipped_at, NOW())"));
+            changed = true;
+        }
+        if let Some(carrier) = trade_u.tracking_carrier {
+            qb.push(format!(", {side}_tracking_carrier = ")).push_bind(carrier);
+            changed = true;
+        }
+
+        if !changed {
+            // Nothing to write, but a stale version is still a conflict
+            if trade_u.expected_version.is_some_and(|expected| expected != trade.version) {
+                drop(tx);
+                return Err(Self::not_found_or_conflict(mm, id, trade_u.expected_version).await);
+            }
+            return Ok(());
+        }
+
+        qb.push(" WHERE id = ").push_bind(id);
+        qb.push(" AND (proposer_id = ").push_bind(ctx.user_id());
+        qb.push(" OR acceptor_id = ").push_bind(ctx.user_id()).push(")");
+        if edits_proposal {
+            qb.push(" AND status = 'proposal'");
+        }
+        if let Some(version) = trade_u.expected_version {
+            qb.push(" AND version = ").push_bind(version);
+        }
+
+        let result = qb.build().execute(&mut *tx).await.map_err(|e| {
+            tracing::error!("update_partial error: {:?}", e);
+            Error::InternalServer
+        })?;
+
+        if result.rows_affected() == 0 {
+            drop(tx);
+            return Err(Self::not_found_or_conflict(mm, id, trade_u.expected_version).await);
+        }
+
+        if !changes.is_empty() {
+            TradeEventBmc::record(&mut tx, id, Some(ctx.user_id()), "proposal_edited", serde_json::json!({ "changes": changes }))
+                .await
+                .map_err(|_| Error::InternalServer)?;
+        }
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
 
         Ok(())
     }
 
     /// Cancel a trade (proposer only, must be in proposal/matched status)
-    pub async fn cancel(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<(), Error> {
+    pub async fn cancel(ctx: &Ctx, mm: &ModelManager, id: i64, expected_version: Option<i64>) -> Result<(), Error> {
         let result = sqlx::query(
-            "UPDATE trades SET status = 'cancelled', updated_at = NOW() WHERE id = $1 AND proposer_id = $2 AND status IN ('proposal', 'matched')",
+            r#"UPDATE trades SET status = 'cancelled', version = version + 1, updated_at = NOW()
+               WHERE id = $1 AND proposer_id = $2 AND status IN ('proposal', 'matched')
+               AND ($3::BIGINT IS NULL OR version = $3)"#,
         )
         .bind(id)
         .bind(ctx.user_id())
+        .bind(expected_version)
         .execute(mm.db())
         .await
         .map_err(|_| Error::InternalServer)?;
 
         if result.rows_affected() == 0 {
-            return Err(Error::NotFound);
+            return Err(Self::not_found_or_conflict(mm, id, expected_version).await);
         }
 
         Ok(())
@@ -356,7 +1685,7 @@ impl TradeBmc {
     /// Cancel a trade as admin (any open trade)
     pub async fn admin_cancel(mm: &ModelManager, id: i64) -> Result<(), Error> {
         let result = sqlx::query(
-            "UPDATE trades SET status = 'cancelled', updated_at = NOW() WHERE id = $1 AND status IN ('proposal', 'matched', 'committed')",
+            "UPDATE trades SET status = 'cancelled', version = version + 1, updated_at = NOW() WHERE id = $1 AND status IN ('proposal', 'matched', 'committed')",
         )
         .bind(id)
         .execute(mm.db())
@@ -370,52 +1699,278 @@ impl TradeBmc {
         Ok(())
     }
 
-    /// Delete a trade proposal (proposer only, proposal status only)
-    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<(), Error> {
-        let result = sqlx::query(
-            "DELETE FROM trades WHERE id = $1 AND proposer_id = $2 AND status = 'proposal'",
-        )
-        .bind(id)
-        .bind(ctx.user_id())
-        .execute(mm.db())
-        .await
-        .map_err(|_| Error::InternalServer)?;
+    /// Admin feature/unfeature a trade; the change and its audit row commit together
+    pub async fn admin_set_featured(ctx: &Ctx, mm: &ModelManager, id: i64, featured: bool) -> Result<(), Error> {
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let result = sqlx::query("UPDATE trades SET is_featured = $2, version = version + 1, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(featured)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
 
         if result.rows_affected() == 0 {
             return Err(Error::NotFound);
         }
 
+        AuditBmc::record_in(&mut tx, ctx, "feature_trade", "trade", &id.to_string(), serde_json::json!({ "featured": featured }))
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
         Ok(())
     }
 
-    /// Delete a trade as admin (any proposal or cancelled trade)
-    pub async fn admin_delete(mm: &ModelManager, id: i64) -> Result<(), Error> {
-        let result = sqlx::query(
-            "DELETE FROM trades WHERE id = $1 AND status IN ('proposal', 'cancelled')",
+    /// Flag a problem with a committed or escrow trade (participant only). The trade
+    /// stays disputed until an admin resolves it. Returns when the dispute was raised.
+    pub async fn raise_dispute(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        reason: &str,
+    ) -> Result<chrono::DateTime<chrono::Utc>, Error> {
+        let reason = dispute_text("reason", reason)?;
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let status = sqlx::query_scalar::<_, String>(
+            "SELECT status FROM trades WHERE id = $1 AND (proposer_id = $2 OR acceptor_id = $2) FOR UPDATE",
         )
         .bind(id)
-        .execute(mm.db())
+        .bind(ctx.user_id())
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|_| Error::InternalServer)?;
-
-        if result.rows_affected() == 0 {
-            return Err(Error::NotFound);
+        .map_err(|_| Error::InternalServer)?
+        .ok_or(Error::NotFound)?;
+
+        if !TradeStatus::parse(&status).is_some_and(|s| s.can_transition_to(TradeStatus::Disputed)) {
+            return Err(Error::InvalidState(format!(
+                "Cannot dispute trade {} from {} (must be committed or escrow)",
+                id, status
+            )));
         }
 
-        Ok(())
-    }
-}
+        let (disputed_at,) = sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>,)>(
+            r#"UPDATE trades SET status = 'disputed', dispute_reason = $2, disputed_at = NOW(), dispute_resolution = NULL,
+                   version = version + 1, updated_at = NOW()
+               WHERE id = $1
+               RETURNING disputed_at"#,
+        )
+        .bind(id)
+        .bind(&reason)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
 
-// ============================================
-// Review BMC
-// ============================================
+        TradeEventBmc::record(&mut tx, id, Some(ctx.user_id()), "dispute_raised", serde_json::json!({ "reason": reason }))
+            .await
+            .map_err(|_| Error::InternalServer)?;
 
-pub struct ReviewBmc;
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(disputed_at)
+    }
+
+    /// Admin: close a disputed trade as completed, cancelled or refunded, with a note.
+    /// The resolution is audited in the same transaction.
+    pub async fn admin_resolve_dispute(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        outcome: &str,
+        resolution: &str,
+    ) -> Result<(), Error> {
+        let outcome = TradeStatus::parse(outcome)
+            .filter(|s| TradeStatus::Disputed.can_transition_to(*s))
+            .ok_or_else(|| Error::InvalidState(format!(
+                "Unknown dispute outcome '{}' (allowed: completed, cancelled, refunded)",
+                outcome
+            )))?;
+        let resolution = dispute_text("resolution", resolution)?;
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let status = sqlx::query_scalar::<_, String>("SELECT status FROM trades WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?
+            .ok_or(Error::NotFound)?;
+        if status != TradeStatus::Disputed.as_str() {
+            return Err(Error::InvalidState(format!("Trade {} is not disputed (status '{}')", id, status)));
+        }
+
+        sqlx::query(
+            r#"UPDATE trades SET status = $2, dispute_resolution = $3, version = version + 1, updated_at = NOW(),
+                   completed_at = CASE WHEN $2 = 'completed' THEN COALESCE(completed_at, NOW()) ELSE completed_at END
+               WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(outcome.as_str())
+        .bind(&resolution)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        TradeEventBmc::record(
+            &mut tx,
+            id,
+            Some(ctx.user_id()),
+            "dispute_resolved",
+            serde_json::json!({ "outcome": outcome.as_str(), "resolution": resolution }),
+        )
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        let details = serde_json::json!({ "outcome": outcome.as_str(), "resolution": resolution });
+        AuditBmc::record_in(&mut tx, ctx, "resolve_dispute", "trade", &id.to_string(), details)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok(())
+    }
+
+    /// Delete a trade proposal (proposer only, proposal status only)
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<(), Error> {
+        let result = sqlx::query(
+            "DELETE FROM trades WHERE id = $1 AND proposer_id = $2 AND status = 'proposal'",
+        )
+        .bind(id)
+        .bind(ctx.user_id())
+        .execute(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Delete a trade as admin (any proposal or cancelled trade)
+    pub async fn admin_delete(mm: &ModelManager, id: i64) -> Result<(), Error> {
+        let result = sqlx::query(
+            "DELETE FROM trades WHERE id = $1 AND status IN ('proposal', 'cancelled')",
+        )
+        .bind(id)
+        .execute(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Explain a versioned UPDATE that matched no rows: a stale `expected_version`
+    /// is a conflict, anything else (missing trade, wrong owner/status) is not found
+    async fn not_found_or_conflict(mm: &ModelManager, id: i64, expected_version: Option<i64>) -> Error {
+        let Some(expected) = expected_version else {
+            return Error::NotFound;
+        };
+
+        let current = sqlx::query_scalar::<_, i64>("SELECT version FROM trades WHERE id = $1")
+            .bind(id)
+            .fetch_optional(mm.db())
+            .await;
+
+        match current {
+            Ok(Some(version)) if version != expected => Error::Conflict(format!(
+                "Trade {} was modified concurrently (current version {}, expected {})",
+                id, version, expected
+            )),
+            _ => Error::NotFound,
+        }
+    }
+}
+
+// ============================================
+// Review BMC
+// ============================================
+
+pub struct ReviewBmc;
+
+/// Days after completion during which a trade can be reviewed, when not configured
+const DEFAULT_REVIEW_WINDOW_DAYS: i64 = 30;
+
+/// Days after completion before unreviewed participants are reminded, when not configured
+const DEFAULT_REVIEW_REMINDER_DAYS: i64 = 3;
+
+impl ReviewBmc {
+    /// Review window from config (defaults to 30 days when unset)
+    pub async fn review_window_days(mm: &ModelManager) -> Result<i64, Error> {
+        match ConfigBmc::get(mm, "review_window_days").await? {
+            Some(v) => v
+                .parse::<i64>()
+                .map_err(|_| Error::Config("Invalid review_window_days value".to_string())),
+            None => Ok(DEFAULT_REVIEW_WINDOW_DAYS),
+        }
+    }
+
+    /// Reminder delay from config (defaults to 3 days when unset; 0 or less disables reminders)
+    pub async fn review_reminder_days(mm: &ModelManager) -> Result<i64, Error> {
+        match ConfigBmc::get(mm, "review_reminder_days").await? {
+            Some(v) => v
+                .parse::<i64>()
+                .map_err(|_| Error::Config("Invalid review_reminder_days value".to_string())),
+            None => Ok(DEFAULT_REVIEW_REMINDER_DAYS),
+        }
+    }
+
+    /// Notify each participant of a trade completed `review_reminder_days` ago (and still
+    /// within the review window) who hasn't reviewed it. Each participant is reminded once
+    /// per trade. Returns how many reminders were sent.
+    pub async fn send_review_reminders(mm: &ModelManager) -> Result<u64, Error> {
+        let reminder_days = Self::review_reminder_days(mm).await?;
+        if reminder_days <= 0 {
+            return Ok(0);
+        }
+        let window_days = Self::review_window_days(mm).await?;
+
+        let sent = sqlx::query(
+            r#"WITH due AS (
+                   SELECT t.id AS trade_id, p.user_id
+                   FROM trades t
+                   CROSS JOIN LATERAL (VALUES (t.proposer_id), (t.acceptor_id)) AS p(user_id)
+                   WHERE t.status = 'completed' AND p.user_id IS NOT NULL
+                     AND t.completed_at <= NOW() - make_interval(days => $1::int)
+                     AND t.completed_at > NOW() - make_interval(days => $2::int)
+                     AND NOT EXISTS (
+                         SELECT 1 FROM trade_reviews r WHERE r.trade_id = t.id AND r.reviewer_id = p.user_id
+                     )
+               ), recorded AS (
+                   INSERT INTO review_reminders (trade_id, user_id)
+                   SELECT trade_id, user_id FROM due
+                   ON CONFLICT DO NOTHING
+                   RETURNING trade_id, user_id
+               )
+               INSERT INTO notifications (user_id, kind, trade_id, message)
+               SELECT user_id, 'review_reminder', trade_id,
+                      'Trade #' || trade_id || ' is complete. How did it go? Leave a review for your trading partner.'
+               FROM recorded"#,
+        )
+        .bind(reminder_days)
+        .bind(window_days)
+        .execute(mm.db())
+        .await
+        .map_err(|e| {
+            tracing::error!("send_review_reminders error: {:?}", e);
+            Error::InternalServer
+        })?;
+
+        Ok(sent.rows_affected())
+    }
 
-impl ReviewBmc {
     /// Create a review for a trade
     pub async fn create(ctx: &Ctx, mm: &ModelManager, review: ReviewForCreate) -> Result<i64, Error> {
         let db = mm.db();
+        let review = review.sanitized();
+        review.validate().into_result()?;
+        let review = review.escaped();
 
         // Verify user is participant in this trade
         let trade: Trade = sqlx::query_as(
@@ -427,6 +1982,13 @@ impl ReviewBmc {
         .await
         .map_err(|_| Error::NotFound)?;
 
+        // Only within the review window (trades completed before completed_at was tracked fall back to updated_at)
+        let window = chrono::Duration::days(Self::review_window_days(mm).await?);
+        let completed_at = trade.completed_at.unwrap_or(trade.updated_at);
+        if chrono::Utc::now() - completed_at > window {
+            return Err(Error::InvalidState("review window closed".to_string()));
+        }
+
         // Determine reviewee (the other party)
         let reviewee_id = if trade.proposer_id == ctx.user_id() {
             trade.acceptor_id.ok_or(Error::BadRequest)?
@@ -458,10 +2020,15 @@ impl ReviewBmc {
         .bind(&review.comment)
         .fetch_one(db)
         .await
-        .map_err(|_| Error::InternalServer)?;
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+                Error::Conflict("You have already reviewed this trade".to_string())
+            }
+            _ => Error::InternalServer,
+        })?;
 
         // Update reviewee's reputation score
-        Self::update_reputation(mm, reviewee_id).await?;
+        Self::update_reputation(mm.db(), reviewee_id).await?;
 
         Ok(id)
     }
@@ -469,7 +2036,7 @@ impl ReviewBmc {
     /// Get reviews for a user
     pub async fn get_for_user(mm: &ModelManager, user_id: i64) -> Result<Vec<TradeReview>, Error> {
         sqlx::query_as::<_, TradeReview>(
-            "SELECT * FROM trade_reviews WHERE reviewee_id = $1 ORDER BY created_at DESC",
+            &format!("SELECT {} FROM trade_reviews WHERE reviewee_id = $1 ORDER BY created_at DESC", REVIEW_COLUMNS),
         )
         .bind(user_id)
         .fetch_all(mm.db())
@@ -477,14 +2044,63 @@ impl ReviewBmc {
         .map_err(|_| Error::InternalServer)
     }
 
-    /// Update user's reputation score (average of all reviews)
-    async fn update_reputation(mm: &ModelManager, user_id: i64) -> Result<(), Error> {
+    /// Get a page of a user's most recent reviews
+    pub async fn list_recent_for_user(mm: &ModelManager, user_id: i64, limit: i64, offset: i64) -> Result<Vec<TradeReview>, Error> {
+        sqlx::query_as::<_, TradeReview>(
+            &format!(
+                "SELECT {} FROM trade_reviews WHERE reviewee_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+                REVIEW_COLUMNS
+            ),
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// Get a page of the reviews a user wrote, newest first
+    pub async fn list_authored(mm: &ModelManager, reviewer_id: i64, limit: i64, offset: i64) -> Result<Vec<TradeReview>, Error> {
+        sqlx::query_as::<_, TradeReview>(
+            &format!(
+                "SELECT {} FROM trade_reviews WHERE reviewer_id = $1 ORDER BY created_at DESC, id DESC LIMIT $2 OFFSET $3",
+                REVIEW_COLUMNS
+            ),
+        )
+        .bind(reviewer_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// Per-pillar review averages for a user (all zero when unreviewed)
+    pub async fn summary_for_user(mm: &ModelManager, user_id: i64) -> Result<ReviewSummary, Error> {
+        sqlx::query_as::<_, ReviewSummary>(
+            r#"SELECT COUNT(*) AS review_count,
+                      COALESCE(AVG(overall_score), 0)::float8 AS avg_overall,
+                      COALESCE(AVG(timeliness_score), 0)::float8 AS avg_timeliness,
+                      COALESCE(AVG(packaging_score), 0)::float8 AS avg_packaging,
+                      COALESCE(AVG(value_honesty_score), 0)::float8 AS avg_value_honesty,
+                      COALESCE(AVG(state_accuracy_score), 0)::float8 AS avg_state_accuracy
+               FROM trade_reviews WHERE reviewee_id = $1"#,
+        )
+        .bind(user_id)
+        .fetch_one(mm.db())
+        .await
+        .map_err(|_| Error::InternalServer)
+    }
+
+    /// Update user's reputation score (average of all reviews), unless an admin has pinned it
+    async fn update_reputation<'e>(executor: impl PgExecutor<'e>, user_id: i64) -> Result<(), Error> {
         sqlx::query(
             r#"UPDATE users SET 
-               reputation_score = (
+               reputation_score = CASE WHEN reputation_override THEN reputation_score ELSE (
                    SELECT COALESCE(AVG(overall_score), 0) 
                    FROM trade_reviews WHERE reviewee_id = $1
-               ),
+               ) END,
                total_trades = (
                    SELECT COUNT(DISTINCT trade_id) 
                    FROM trade_reviews WHERE reviewee_id = $1
@@ -493,10 +2109,1143 @@ impl ReviewBmc {
                WHERE id = $1"#,
         )
         .bind(user_id)
-        .execute(mm.db())
+        .execute(executor)
         .await
         .map_err(|_| Error::InternalServer)?;
 
         Ok(())
     }
+
+    /// Apply an admin correction to a user's reputation (admin only, checked by the caller).
+    /// Set and Delta pin the result (clamped to 0..=MAX_REPUTATION) so later reviews don't
+    /// recompute it away; Clear unpins and recomputes. Audited with the reason in the same
+    /// transaction. Returns the (previous, new) score.
+    pub async fn admin_adjust_reputation(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        user_id: i64,
+        adjustment: ReputationAdjustment,
+        reason: &str,
+    ) -> Result<(f64, f64), Error> {
+        let mut tx = mm.db().begin().await.map_err(|_| Error::InternalServer)?;
+
+        let (previous,) = sqlx::query_as::<_, (f64,)>(
+            "SELECT COALESCE(reputation_score, 0)::float8 FROM users WHERE id = $1 FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| Error::InternalServer)?
+        .ok_or(Error::NotFound)?;
+
+        let pinned = match adjustment {
+            ReputationAdjustment::Set(score) => Some(score),
+            ReputationAdjustment::Delta(delta) => Some(previous + delta),
+            ReputationAdjustment::Clear => None,
+        };
+        match pinned {
+            Some(score) if !score.is_finite() => {
+                return Err(Error::InvalidState("Reputation score must be a number".to_string()));
+            }
+            Some(score) => {
+                sqlx::query(
+                    "UPDATE users SET reputation_score = $2, reputation_override = TRUE, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(user_id)
+                .bind(score.clamp(0.0, MAX_REPUTATION))
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| Error::InternalServer)?;
+            }
+            None => {
+                sqlx::query("UPDATE users SET reputation_override = FALSE WHERE id = $1")
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|_| Error::InternalServer)?;
+            }
+        }
+        if pinned.is_none() {
+            Self::update_reputation(&mut *tx, user_id).await?;
+        }
+
+        let (score,) = sqlx::query_as::<_, (f64,)>("SELECT COALESCE(reputation_score, 0)::float8 FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        let details = serde_json::json!({
+            "score": match adjustment { ReputationAdjustment::Set(score) => Some(score), _ => None },
+            "delta": match adjustment { ReputationAdjustment::Delta(delta) => Some(delta), _ => None },
+            "clear": adjustment == ReputationAdjustment::Clear,
+            "previous_score": previous,
+            "new_score": score,
+            "reason": reason,
+        });
+        AuditBmc::record_in(&mut tx, ctx, "adjust_reputation", "user", &user_id.to_string(), details)
+            .await
+            .map_err(|_| Error::InternalServer)?;
+
+        tx.commit().await.map_err(|_| Error::InternalServer)?;
+
+        Ok((previous, score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, item_offer, proposal_with_value, seed_proposal, xch_offer};
+
+    #[test]
+    fn test_validate_reports_all_fields() {
+        let trade_c = TradeForCreate {
+            item_title: "  ".to_string(),
+            item_description: String::new(),
+            ..proposal_with_value(-5.0)
+        };
+        let errs = trade_c.validate();
+        assert_eq!(errs.fields.len(), 3);
+        assert_eq!(errs.fields["item_title"], "must not be empty");
+        assert_eq!(errs.fields["item_description"], "must not be empty");
+        assert_eq!(errs.fields["item_value_usd"], "must be positive");
+
+        let review = ReviewForCreate {
+            trade_id: 1,
+            timeliness: 0,
+            packaging: 3,
+            value_honesty: 6,
+            state_accuracy: 5,
+            comment: None,
+        };
+        let errs = review.validate();
+        assert_eq!(errs.fields.keys().collect::<Vec<_>>(), vec!["timeliness", "value_honesty"]);
+    }
+
+    #[test]
+    fn test_fee_model_requirements() {
+        assert!(FeeModel::Both.requires(true) && FeeModel::Both.requires(false));
+        assert!(FeeModel::ProposerOnly.requires(true) && !FeeModel::ProposerOnly.requires(false));
+        assert!(!FeeModel::AcceptorOnly.requires(true) && FeeModel::AcceptorOnly.requires(false));
+
+        assert!(!FeeModel::Both.is_satisfied(true, false));
+        assert!(FeeModel::Both.is_satisfied(true, true));
+        assert!(FeeModel::ProposerOnly.is_satisfied(true, false));
+        assert!(!FeeModel::ProposerOnly.is_satisfied(false, true));
+        assert!(FeeModel::AcceptorOnly.is_satisfied(false, true));
+
+        assert_eq!(FeeModel::parse("proposer_only"), Some(FeeModel::ProposerOnly));
+        assert_eq!(FeeModel::parse("nobody"), None);
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use TradeStatus::*;
+        let legal = [
+            (Proposal, Matched),
+            (Proposal, Cancelled),
+            (Matched, Committed),
+            (Matched, Cancelled),
+            (Committed, Escrow),
+            (Committed, Completed),
+            (Committed, Cancelled),
+            (Committed, Disputed),
+            (Escrow, Completed),
+            (Escrow, Disputed),
+            (Disputed, Completed),
+            (Disputed, Cancelled),
+            (Disputed, Refunded),
+        ];
+        for from in TradeStatus::ALL {
+            for to in TradeStatus::ALL {
+                assert_eq!(from.can_transition_to(to), legal.contains(&(from, to)), "{:?} -> {:?}", from, to);
+            }
+        }
+        assert!([Completed, Cancelled, Refunded].iter().all(|s| s.allowed_transitions().is_empty()));
+
+        for status in TradeStatus::ALL {
+            assert_eq!(TradeStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(TradeStatus::parse("active"), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_update_status_rejects_illegal_moves() {
+        let (mm, alice, _) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+
+        let err = TradeBmc::update_status(&alice, &mm, id, "completed", None).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidState(msg) if msg == &format!("Cannot move trade {} from proposal to completed (allowed: matched, cancelled)", id)),
+            "{:?}",
+            err
+        );
+        assert!(matches!(TradeBmc::update_status(&alice, &mm, id, "active", None).await, Err(Error::InvalidState(_))));
+
+        TradeBmc::update_status(&alice, &mm, id, "cancelled", None).await.unwrap();
+        let err = TradeBmc::update_status(&alice, &mm, id, "matched", None).await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg.ends_with("(allowed: none, the trade is final)")), "{:?}", err);
+
+        // Rejected moves leave the trade untouched
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!((trade.status.as_str(), trade.version), ("cancelled", 2));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_complete_once_then_repeat_is_noop() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+
+        // Not yet committed
+        let err = TradeBmc::complete(&alice, &mm, id, None).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidState(msg) if msg == &format!("Cannot complete trade {} from matched (must be escrow or committed)", id)),
+            "{:?}",
+            err
+        );
+
+        // Disputes are only closed by an admin
+        sqlx::query("UPDATE trades SET status = 'disputed' WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+        assert!(matches!(TradeBmc::complete(&alice, &mm, id, None).await, Err(Error::InvalidState(_))));
+
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+        let version = TradeBmc::get(&alice, &mm, id).await.unwrap().version;
+        let first = TradeBmc::complete(&alice, &mm, id, Some(version)).await.unwrap();
+        assert!(!first.already_completed);
+
+        // Repeats (from either side, even with the old version) change nothing
+        let again = TradeBmc::complete(&bob, &mm, id, Some(version)).await.unwrap();
+        assert_eq!(again, Completion { completed_at: first.completed_at, already_completed: true });
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!((trade.status.as_str(), trade.version), ("completed", version + 1));
+        assert_eq!(trade.completed_at, Some(first.completed_at));
+
+        let outsider = _dev_utils::seed_ctx(&mm, "mallory").await;
+        assert!(matches!(TradeBmc::complete(&outsider, &mm, id, None).await, Err(Error::NotFound)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_dispute_raised_and_resolved() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let id = seed_proposal(&mm, &alice).await;
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+        sqlx::query("UPDATE trades SET status = 'escrow' WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+
+        let disputed_at = TradeBmc::raise_dispute(&bob, &mm, id, "  Item never arrived\u{0}  ").await.unwrap();
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!(trade.status, "disputed");
+        assert_eq!(trade.dispute_reason.as_deref(), Some("Item never arrived"));
+        assert_eq!(trade.disputed_at, Some(disputed_at));
+
+        TradeBmc::admin_resolve_dispute(&admin, &mm, id, "refunded", "Seller could not show proof of shipment").await.unwrap();
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!(trade.status, "refunded");
+        assert_eq!(trade.dispute_resolution.as_deref(), Some("Seller could not show proof of shipment"));
+        assert_eq!(trade.completed_at, None);
+
+        let events: Vec<String> = TradeEventBmc::list_for_trade(&alice, &mm, id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_type)
+            .collect();
+        assert!(events.contains(&"dispute_raised".to_string()) && events.contains(&"dispute_resolved".to_string()));
+
+        let audit = AuditBmc::list_for_target(&mm, "trade", &id.to_string()).await.unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!((audit[0].action.as_str(), &audit[0].details["outcome"]), ("resolve_dispute", &serde_json::json!("refunded")));
+
+        // A second resolution is rejected and leaves no audit row
+        assert!(TradeBmc::admin_resolve_dispute(&admin, &mm, id, "completed", "Oops").await.is_err());
+        assert_eq!(AuditBmc::list_for_target(&mm, "trade", &id.to_string()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_dispute_rejected_outside_committed_or_escrow() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let id = seed_proposal(&mm, &alice).await;
+
+        // Proposals and matched trades can't be disputed, nor can outsiders dispute
+        let err = TradeBmc::raise_dispute(&alice, &mm, id, "changed my mind").await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg == &format!("Cannot dispute trade {} from proposal (must be committed or escrow)", id)), "{:?}", err);
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+        assert!(matches!(TradeBmc::raise_dispute(&bob, &mm, id, "late").await, Err(Error::InvalidState(_))));
+        let outsider = _dev_utils::seed_ctx(&mm, "mallory").await;
+        assert!(matches!(TradeBmc::raise_dispute(&outsider, &mm, id, "spam").await, Err(Error::NotFound)));
+
+        // Only disputed trades can be resolved
+        let err = TradeBmc::admin_resolve_dispute(&admin, &mm, id, "cancelled", "n/a").await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg == &format!("Trade {} is not disputed (status 'matched')", id)), "{:?}", err);
+
+        sqlx::query("UPDATE trades SET status = 'committed' WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+        assert!(matches!(TradeBmc::raise_dispute(&alice, &mm, id, " \u{7} ").await, Err(Error::Validation(_))));
+        TradeBmc::raise_dispute(&alice, &mm, id, "Wrong item shipped").await.unwrap();
+
+        // Already disputed; and the outcome must be a final status
+        assert!(matches!(TradeBmc::raise_dispute(&bob, &mm, id, "me too").await, Err(Error::InvalidState(_))));
+        let err = TradeBmc::admin_resolve_dispute(&admin, &mm, id, "escrow", "back to escrow").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+        TradeBmc::admin_resolve_dispute(&admin, &mm, id, "completed", "Buyer kept the item").await.unwrap();
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!(trade.status, "completed");
+        assert!(trade.completed_at.is_some());
+    }
+
+    #[test]
+    fn test_free_text_cleaned_and_capped() {
+        let trade_c = TradeForCreate {
+            item_title: "Lamp\u{0}\u{1b}".to_string(),
+            item_description: "Brass\r\n<b>lamp</b>\u{7}".to_string(),
+            ..proposal_with_value(120.0)
+        }
+        .sanitized();
+        assert_eq!(trade_c.item_title, "Lamp");
+        assert!(trade_c.item_description.starts_with("Brass\r\n") && !trade_c.item_description.contains('\u{7}'));
+
+        let too_long = TradeForCreate { item_description: "x".repeat(5001), ..proposal_with_value(120.0) };
+        assert_eq!(too_long.validate().fields["item_description"], "must be at most 5000 characters");
+
+        // Limits count the characters written, not their escaped form
+        let ampersands = TradeForCreate { item_description: "&".repeat(5000), ..proposal_with_value(120.0) }.sanitized();
+        assert!(ampersands.validate().is_empty());
+
+        // Control-only input is empty once cleaned and so rejected
+        let blank = TradeForUpdate { item_title: Some("\u{0}\u{0}".to_string()), ..Default::default() }.sanitized();
+        assert_eq!(blank.validate().fields["item_title"], "must not be empty");
+    }
+
+    #[test]
+    fn test_tags_normalized_and_bounded() {
+        let tags: Vec<String> = ["  Camera ", "camera", "", "FILM", "   "].iter().map(|t| t.to_string()).collect();
+        assert_eq!(normalize_tags(&tags), vec!["camera", "film"]);
+
+        let too_many = TradeForCreate {
+            tags: Some((0..11).map(|i| format!("tag{}", i)).collect()),
+            ..proposal_with_value(120.0)
+        };
+        assert_eq!(too_many.validate().fields["tags"], "must have at most 10 tags");
+
+        // Duplicates collapse before the count is checked
+        let repeated = TradeForCreate { tags: Some(vec!["Film".to_string(); 20]), ..proposal_with_value(120.0) };
+        assert!(repeated.validate().is_empty());
+
+        let too_long = TradeForCreate { tags: Some(vec!["x".repeat(33)]), ..proposal_with_value(120.0) };
+        assert_eq!(too_long.validate().fields["tags"], "each tag must be at most 32 characters");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_commit_requires_settled_fees() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+
+        let err = TradeBmc::update_status(&bob, &mm, id, "committed", None).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(ref msg) if msg.contains("commitment fees")), "{:?}", err);
+        assert_eq!(TradeBmc::get(&bob, &mm, id).await.unwrap().status, "matched");
+
+        // Settled but not yet picked up: committing catches up and starts escrow
+        sqlx::query("UPDATE trades SET commitment_fee_waived = TRUE WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+        TradeBmc::update_status(&bob, &mm, id, "committed", None).await.unwrap();
+        let trade = TradeBmc::get(&bob, &mm, id).await.unwrap();
+        assert_eq!(trade.status, "escrow");
+        assert!(trade.committed_at.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_versioned_update_ok() {
+        let mm = _dev_utils::init_test().await;
+        let ctx = _dev_utils::seed_ctx(&mm, "alice").await;
+        let id = seed_proposal(&mm, &ctx).await;
+
+        let trade = TradeBmc::get(&ctx, &mm, id).await.unwrap();
+        assert_eq!(trade.version, 1);
+
+        TradeBmc::update_status(&ctx, &mm, id, "matched", Some(trade.version)).await.unwrap();
+        TradeBmc::cancel(&ctx, &mm, id, None).await.unwrap();
+
+        let trade = TradeBmc::get(&ctx, &mm, id).await.unwrap();
+        assert_eq!(trade.status, "cancelled");
+        assert_eq!(trade.version, 3);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_stale_version_conflict() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+
+        // Bob's offer lands first and bumps the version
+        let offer = TradeAcceptParams { expected_version: Some(1), ..xch_offer(id) };
+        _dev_utils::match_trade(&mm, &bob, offer).await;
+
+        // Alice still holds version 1
+        let res = TradeBmc::add_tracking(&alice, &mm, id, "1Z999", "UPS", Some(1)).await;
+        assert!(matches!(res, Err(Error::Conflict(_))));
+
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!(trade.version, 2);
+        assert!(trade.proposer_tracking_number.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_statuses_for_mixed_ids() {
+        let mm = _dev_utils::init_test().await;
+        let ctx = _dev_utils::seed_ctx(&mm, "carol").await;
+        let open_id = seed_proposal(&mm, &ctx).await;
+        let cancelled_id = seed_proposal(&mm, &ctx).await;
+        TradeBmc::cancel(&ctx, &mm, cancelled_id, None).await.unwrap();
+        let deleted_id = seed_proposal(&mm, &ctx).await;
+        TradeBmc::delete(&ctx, &mm, deleted_id).await.unwrap();
+
+        let statuses = TradeBmc::statuses_for(&mm, &[open_id, cancelled_id, deleted_id, 999_999])
+            .await
+            .unwrap();
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses.get(&open_id).map(String::as_str), Some("proposal"));
+        assert_eq!(statuses.get(&cancelled_id).map(String::as_str), Some("cancelled"));
+        assert!(!statuses.contains_key(&deleted_id));
+        assert!(!statuses.contains_key(&999_999));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_create_enforces_min_value() {
+        let mm = _dev_utils::init_test().await;
+        let ctx = _dev_utils::seed_ctx(&mm, "dave").await;
+
+        // Default floor is 0
+        assert_eq!(TradeBmc::min_proposal_value_usd(&mm).await.unwrap(), 0.0);
+
+        sqlx::query("UPDATE exchange_config SET value = '25' WHERE key = 'min_proposal_value_usd'")
+            .execute(mm.db())
+            .await
+            .unwrap();
+
+        let res = TradeBmc::create(&ctx, &mm, proposal_with_value(24.99)).await;
+        assert!(matches!(res, Err(Error::Validation(_))));
+
+        TradeBmc::create(&ctx, &mm, proposal_with_value(25.0)).await.unwrap();
+        TradeBmc::create(&ctx, &mm, proposal_with_value(500.0)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_update_partial_single_field() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_c = TradeForCreate {
+            item_condition: Some("used".to_string()),
+            item_category: Some("electronics".to_string()),
+            ..proposal_with_value(120.0)
+        };
+        let id = TradeBmc::create(&alice, &mm, trade_c).await.unwrap();
+
+        let trade_u = TradeForUpdate {
+            item_description: Some("Works fine, new battery".to_string()),
+            ..Default::default()
+        };
+        TradeBmc::update_partial(&alice, &mm, id, trade_u).await.unwrap();
+
+        let trade = TradeBmc::get(&alice, &mm, id).await.unwrap();
+        assert_eq!(trade.proposer_item_description, "Works fine, new battery");
+        assert_eq!(trade.proposer_item_title, "Vintage camera");
+        assert_eq!(trade.proposer_item_condition.as_deref(), Some("used"));
+        assert_eq!(trade.proposer_item_category.as_deref(), Some("electronics"));
+        assert_eq!(trade.proposer_item_value_usd, 120.0);
+        assert_eq!(trade.version, 2);
+
+        // An empty update still checks the version
+        let stale = TradeForUpdate { expected_version: Some(1), ..Default::default() };
+        assert!(matches!(TradeBmc::update_partial(&alice, &mm, id, stale).await, Err(Error::Conflict(_))));
+        let current = TradeForUpdate { expected_version: Some(2), ..Default::default() };
+        TradeBmc::update_partial(&alice, &mm, id, current).await.unwrap();
+
+        // Non-participants cannot edit
+        let trade_u = TradeForUpdate { item_title: Some("Mine now".to_string()), ..Default::default() };
+        assert!(TradeBmc::update_partial(&bob, &mm, id, trade_u).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_proposal_edits_recorded_in_timeline() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = TradeBmc::create(&alice, &mm, proposal_with_value(120.0)).await.unwrap();
+
+        for value in [100.0, 90.0] {
+            let trade_u = TradeForUpdate { item_value_usd: Some(value), ..Default::default() };
+            TradeBmc::update_partial(&alice, &mm, id, trade_u).await.unwrap();
+        }
+        // Re-sending the current title is not a change
+        let trade_u = TradeForUpdate { item_title: Some("Vintage camera".to_string()), ..Default::default() };
+        TradeBmc::update_partial(&alice, &mm, id, trade_u).await.unwrap();
+
+        let events = TradeEventBmc::list_for_trade(&alice, &mm, id).await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].event_type, "trade_created");
+        assert!(events[1..].iter().all(|e| e.event_type == "proposal_edited" && e.actor_id == Some(alice.user_id())));
+        assert_eq!(events[1].details["changes"]["item_value_usd"], serde_json::json!({ "old": 120.0, "new": 100.0 }));
+        assert_eq!(events[2].details["changes"]["item_value_usd"], serde_json::json!({ "old": 100.0, "new": 90.0 }));
+
+        // Only participants see the timeline
+        assert!(matches!(TradeEventBmc::list_for_trade(&bob, &mm, id).await, Err(Error::NotFound)));
+    }
+
+    #[test]
+    fn test_xch_amount_required_and_bounded() {
+        let error = |xch_amount: Option<i64>, offer_type: &str| {
+            let params = TradeAcceptParams { xch_amount, offer_type: offer_type.to_string(), ..xch_offer(1) };
+            params.validate().fields.get("xch_amount").cloned()
+        };
+        assert_eq!(error(None, "xch").as_deref(), Some("is required for xch and mixed offers"));
+        assert_eq!(error(Some(0), "xch").as_deref(), Some("must be positive"));
+        assert_eq!(error(Some(-5), "xch").as_deref(), Some("must be positive"));
+        assert_eq!(error(Some(MAX_OFFER_XCH_MOJOS + 1), "xch").as_deref(), Some("must be at most 1000000 XCH"));
+        assert_eq!(error(Some(i64::MAX), "xch").as_deref(), Some("must be at most 1000000 XCH"));
+        assert_eq!(error(Some(MAX_OFFER_XCH_MOJOS), "xch"), None);
+        assert_eq!(error(None, "mixed").as_deref(), Some("is required for xch and mixed offers"));
+        // Item offers don't carry XCH
+        assert_eq!(error(None, "item"), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_lowball_guard() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+
+        // Default ratio is 10% of the $120 proposal
+        let id = seed_proposal(&mm, &alice).await;
+        let Err(Error::Validation(errs)) = TradeBmc::make_offer(&bob, &mm, item_offer(id, 11.0)).await else {
+            panic!("lowball offer must be rejected");
+        };
+        assert_eq!(errs.fields["offer_value"], "must be at least $12.00 (10% of the proposal value)");
+        // 0.01 XCH at $1000 is $10: still too low
+        let xch_lowball = TradeAcceptParams { xch_amount: Some(10_000_000_000), xch_price_usd: Some(1000.0), ..xch_offer(id) };
+        assert!(matches!(TradeBmc::make_offer(&bob, &mm, xch_lowball).await, Err(Error::Validation(_))));
+        // Without a server price XCH can't be valued, so the guard fails closed
+        let unpriced = TradeAcceptParams { xch_price_usd: None, ..xch_offer(id) };
+        let Err(Error::Validation(errs)) = TradeBmc::make_offer(&bob, &mm, unpriced).await else {
+            panic!("unpriced XCH offer must be rejected");
+        };
+        assert!(errs.fields.contains_key("xch_amount"));
+        // A mixed offer counts its item too: $5 item + $10 XCH clears the $12 floor
+        let mixed = TradeAcceptParams {
+            offer_type: "mixed".to_string(),
+            xch_amount: Some(10_000_000_000),
+            xch_price_usd: Some(1000.0),
+            ..item_offer(id, 5.0)
+        };
+        TradeBmc::make_offer(&bob, &mm, mixed).await.unwrap();
+        TradeBmc::make_offer(&bob, &mm, item_offer(id, 100.0)).await.unwrap();
+
+        // The proposer can switch the guard off for their trade
+        let id = seed_proposal(&mm, &alice).await;
+        let trade_u = TradeForUpdate { lowball_guard: Some(false), ..Default::default() };
+        TradeBmc::update_partial(&alice, &mm, id, trade_u).await.unwrap();
+        TradeBmc::make_offer(&bob, &mm, item_offer(id, 1.0)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_competing_offers_and_counter() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let carol = _dev_utils::seed_ctx(&mm, "carol").await;
+        let id = seed_proposal(&mm, &alice).await;
+
+        let bob_offer = TradeBmc::make_offer(&bob, &mm, item_offer(id, 50.0)).await.unwrap();
+        let carol_offer = TradeBmc::make_offer(&carol, &mm, item_offer(id, 60.0)).await.unwrap();
+        assert_eq!(TradeBmc::get_public(&mm, id).await.unwrap().status, "proposal");
+
+        // Bidders see only their own offers; the proposer sees all
+        assert_eq!(TradeBmc::list_offers(&alice, &mm, id).await.unwrap().len(), 2);
+        let carols = TradeBmc::list_offers(&carol, &mm, id).await.unwrap();
+        assert_eq!(carols.iter().map(|o| o.id).collect::<Vec<_>>(), vec![carol_offer]);
+        assert!(matches!(TradeBmc::accept_offer(&carol, &mm, bob_offer, None).await, Err(Error::NotFound)));
+
+        // Alice counters bob; bob can't accept his own superseded offer, but takes the counter
+        let counter = TradeCounterParams {
+            offer_id: bob_offer,
+            offer_type: "item".to_string(),
+            item_title: Some("Old radio".to_string()),
+            item_description: Some("Plus the antenna".to_string()),
+            item_condition: None,
+            item_value_usd: Some(70.0),
+            xch_amount: None,
+            xch_price_usd: None,
+        };
+        let counter_id = TradeBmc::counter_offer(&alice, &mm, counter).await.unwrap();
+        assert!(matches!(TradeBmc::accept_offer(&alice, &mm, bob_offer, None).await, Err(Error::InvalidState(_))));
+        assert!(matches!(TradeBmc::accept_offer(&alice, &mm, counter_id, None).await, Err(Error::InvalidState(_))));
+        assert_eq!(TradeBmc::accept_offer(&bob, &mm, counter_id, None).await.unwrap(), id);
+
+        let trade = TradeBmc::get_public(&mm, id).await.unwrap();
+        assert_eq!((trade.status.as_str(), trade.acceptor_id), ("matched", Some(bob.user_id())));
+        assert_eq!(trade.acceptor_item_description.as_deref(), Some("Plus the antenna"));
+        let statuses: HashMap<i64, String> = TradeBmc::list_offers(&alice, &mm, id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| (o.id, o.status))
+            .collect();
+        assert_eq!(statuses[&bob_offer], "countered");
+        assert_eq!(statuses[&carol_offer], "rejected");
+        assert_eq!(statuses[&counter_id], "accepted");
+        assert!(matches!(TradeBmc::accept_offer(&alice, &mm, carol_offer, None).await, Err(Error::InvalidState(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_every_update_touches_updated_at() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        let stale = chrono::Utc::now() - chrono::Duration::days(1);
+        let backdate = || async {
+            sqlx::query("UPDATE trades SET updated_at = $2 WHERE id = $1")
+                .bind(id)
+                .bind(stale)
+                .execute(mm.db())
+                .await
+                .unwrap();
+        };
+        let updated_at = || async { TradeBmc::get_public(&mm, id).await.unwrap().updated_at };
+
+        // An explicit value is kept, so the backdating above sticks
+        backdate().await;
+        assert_eq!(updated_at().await.timestamp(), stale.timestamp());
+
+        // A write that forgets updated_at still bumps it
+        sqlx::query("UPDATE trades SET proposer_item_category = 'Audio' WHERE id = $1").bind(id).execute(mm.db()).await.unwrap();
+        assert!(updated_at().await > stale);
+
+        backdate().await;
+        let trade_u = TradeForUpdate { lowball_guard: Some(false), ..Default::default() };
+        TradeBmc::update_partial(&alice, &mm, id, trade_u).await.unwrap();
+        assert!(updated_at().await > stale);
+
+        backdate().await;
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+        assert!(updated_at().await > stale);
+
+        backdate().await;
+        TradeBmc::cancel(&alice, &mm, id, None).await.unwrap();
+        assert!(updated_at().await > stale);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_accept_initializes_commit_statuses() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        // Rows from before the column defaults existed carry NULLs
+        sqlx::query("UPDATE trades SET proposer_commit_status = NULL, acceptor_commit_status = NULL WHERE id = $1")
+            .bind(id)
+            .execute(mm.db())
+            .await
+            .unwrap();
+
+        _dev_utils::match_trade(&mm, &bob, xch_offer(id)).await;
+
+        let statuses: (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT proposer_commit_status, acceptor_commit_status FROM trades WHERE id = $1")
+                .bind(id)
+                .fetch_one(mm.db())
+                .await
+                .unwrap();
+        assert_eq!(statuses, (Some("pending".to_string()), Some("pending".to_string())));
+    }
+
+    /// A trade between alice and bob marked completed `days_ago` days ago
+    async fn completed_trade(mm: &ModelManager, alice: &Ctx, bob: &Ctx, days_ago: i32) -> i64 {
+        let id = seed_proposal(mm, alice).await;
+        _dev_utils::match_trade(mm, bob, xch_offer(id)).await;
+        sqlx::query("UPDATE trades SET status = 'completed', completed_at = NOW() - make_interval(days => $2) WHERE id = $1")
+            .bind(id)
+            .bind(days_ago)
+            .execute(mm.db())
+            .await
+            .unwrap();
+        id
+    }
+
+    fn review_for(trade_id: i64) -> ReviewForCreate {
+        ReviewForCreate {
+            trade_id,
+            timeliness: 5,
+            packaging: 4,
+            value_honesty: 5,
+            state_accuracy: 4,
+            comment: None,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_review_within_window_once() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = completed_trade(&mm, &alice, &bob, 29).await;
+
+        ReviewBmc::create(&alice, &mm, review_for(id)).await.unwrap();
+        let again = ReviewBmc::create(&alice, &mm, review_for(id)).await;
+        assert!(matches!(again, Err(Error::Conflict(_))));
+
+        // The other direction is a separate review
+        ReviewBmc::create(&bob, &mm, review_for(id)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_authored_pages() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let mut written = Vec::new();
+        for _ in 0..3 {
+            let id = completed_trade(&mm, &alice, &bob, 1).await;
+            written.push(ReviewBmc::create(&alice, &mm, review_for(id)).await.unwrap());
+        }
+        // Reviews about alice are not hers
+        let id = completed_trade(&mm, &alice, &bob, 1).await;
+        ReviewBmc::create(&bob, &mm, review_for(id)).await.unwrap();
+
+        let first = ReviewBmc::list_authored(&mm, alice.user_id(), 2, 0).await.unwrap();
+        let rest = ReviewBmc::list_authored(&mm, alice.user_id(), 2, 2).await.unwrap();
+        let ids: Vec<i64> = first.iter().chain(&rest).map(|r| r.id).collect();
+        written.reverse();
+        assert_eq!((first.len(), rest.len()), (2, 1));
+        assert_eq!(ids, written);
+        assert!(first.iter().chain(&rest).all(|r| r.reviewer_id == alice.user_id()));
+    }
+
+    fn mixed_wishlist() -> Vec<WishlistItem> {
+        vec![
+            WishlistItem {
+                wishlist_type: "item".to_string(),
+                item_description: Some("Film scanner".to_string()),
+                item_min_value_usd: Some(150.5),
+                xch_amount: None,
+            },
+            WishlistItem {
+                wishlist_type: "xch".to_string(),
+                item_description: None,
+                item_min_value_usd: None,
+                xch_amount: Some(2_500_000_000_000),
+            },
+            WishlistItem {
+                wishlist_type: "mixed".to_string(),
+                item_description: Some("Any prime lens plus XCH".to_string()),
+                item_min_value_usd: Some(40.0),
+                xch_amount: Some(500_000_000_000),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_wishlist_round_trip() {
+        let (mm, alice, _) = _dev_utils::init_test_with_users().await;
+        let with_wishlist = TradeBmc::create(&alice, &mm, TradeForCreate {
+            wishlist: Some(mixed_wishlist()),
+            ..proposal_with_value(300.0)
+        }).await.unwrap();
+        let without = seed_proposal(&mm, &alice).await;
+
+        assert_eq!(TradeBmc::get_wishlist(&mm, with_wishlist).await.unwrap(), mixed_wishlist());
+        assert!(TradeBmc::get_wishlist(&mm, without).await.unwrap().is_empty());
+
+        let batch = TradeBmc::wishlists_for(mm.db(), &[with_wishlist, without]).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[&with_wishlist], mixed_wishlist());
+    }
+
+    /// Attach three images at positions 0, 1, 2; returns their ids in that order
+    async fn seed_images(mm: &ModelManager, ctx: &Ctx, trade_id: i64) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for position in 0..3 {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO trade_photos (trade_id, user_id, photo_type, file_path, file_size, display_order)
+                 VALUES ($1, $2, 'proposer_item', $3, 100, $4) RETURNING id",
+            )
+            .bind(trade_id)
+            .bind(ctx.user_id())
+            .bind(format!("uploads/{}.png", position))
+            .bind(position)
+            .fetch_one(mm.db())
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        ids
+    }
+
+    async fn image_ids(mm: &ModelManager, trade_id: i64) -> Vec<i64> {
+        TradeBmc::list_images(mm, trade_id).await.unwrap().iter().map(|i| i.id).collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_primary_image_listed_first_and_unique() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        let [a, b, c] = seed_images(&mm, &alice, id).await[..] else { unreachable!() };
+        assert_eq!(image_ids(&mm, id).await, vec![a, b, c]);
+
+        TradeBmc::set_primary_image(&alice, &mm, id, c).await.unwrap();
+        assert_eq!(image_ids(&mm, id).await, vec![c, a, b]);
+
+        // A new cover replaces the old one
+        TradeBmc::set_primary_image(&alice, &mm, id, b).await.unwrap();
+        let images = TradeBmc::list_images(&mm, id).await.unwrap();
+        assert_eq!(images.iter().map(|i| i.id).collect::<Vec<_>>(), vec![b, a, c]);
+        assert_eq!(images.iter().filter(|i| i.is_primary).count(), 1);
+
+        // Only the proposer, and only the trade's own images
+        assert!(matches!(TradeBmc::set_primary_image(&bob, &mm, id, a).await, Err(Error::NotFound)));
+        let other = seed_proposal(&mm, &alice).await;
+        assert!(matches!(TradeBmc::set_primary_image(&alice, &mm, other, a).await, Err(Error::NotFound)));
+        assert_eq!(image_ids(&mm, id).await, vec![b, a, c]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_reorder_images() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = seed_proposal(&mm, &alice).await;
+        let [a, b, c] = seed_images(&mm, &alice, id).await[..] else { unreachable!() };
+
+        TradeBmc::reorder_images(&alice, &mm, id, &[c, a, b]).await.unwrap();
+        let images = TradeBmc::list_images(&mm, id).await.unwrap();
+        assert_eq!(images.iter().map(|i| (i.id, i.position)).collect::<Vec<_>>(), vec![(c, 0), (a, 1), (b, 2)]);
+
+        // The cover stays first whatever its position
+        TradeBmc::set_primary_image(&alice, &mm, id, b).await.unwrap();
+        assert_eq!(image_ids(&mm, id).await, vec![b, c, a]);
+
+        // Every image exactly once, by the proposer
+        for bad in [vec![a, b], vec![a, b, c, c], vec![a, b, -1]] {
+            assert!(matches!(TradeBmc::reorder_images(&alice, &mm, id, &bad).await, Err(Error::InvalidState(_))));
+        }
+        assert!(matches!(TradeBmc::reorder_images(&bob, &mm, id, &[a, b, c]).await, Err(Error::NotFound)));
+        assert_eq!(image_ids(&mm, id).await, vec![b, c, a]);
+    }
+
+    async fn reputation_of(mm: &ModelManager, user_id: i64) -> (f64, i32) {
+        sqlx::query_as("SELECT reputation_score::float8, total_trades FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(mm.db())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_reputation_override_survives_new_reviews() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let id = completed_trade(&mm, &alice, &bob, 1).await;
+        ReviewBmc::create(&bob, &mm, review_for(id)).await.unwrap();
+        assert_eq!(reputation_of(&mm, alice.user_id()).await, (4.5, 1));
+
+        let adjusted = ReviewBmc::admin_adjust_reputation(&admin, &mm, alice.user_id(), ReputationAdjustment::Set(2.0), "spam").await;
+        assert_eq!(adjusted.unwrap(), (4.5, 2.0));
+
+        // A new review updates the trade count but not the pinned score
+        let id = completed_trade(&mm, &alice, &bob, 1).await;
+        ReviewBmc::create(&bob, &mm, review_for(id)).await.unwrap();
+        assert_eq!(reputation_of(&mm, alice.user_id()).await, (2.0, 2));
+
+        // Deltas stay within range
+        let adjusted = ReviewBmc::admin_adjust_reputation(&admin, &mm, alice.user_id(), ReputationAdjustment::Delta(-3.0), "spam").await;
+        assert_eq!(adjusted.unwrap(), (2.0, 0.0));
+
+        // Clearing goes back to the review average
+        let adjusted = ReviewBmc::admin_adjust_reputation(&admin, &mm, alice.user_id(), ReputationAdjustment::Clear, "appeal").await;
+        assert_eq!(adjusted.unwrap(), (0.0, 4.5));
+
+        let missing = ReviewBmc::admin_adjust_reputation(&admin, &mm, i64::MAX, ReputationAdjustment::Clear, "none").await;
+        assert!(matches!(missing, Err(Error::NotFound)));
+
+        // Each adjustment was audited with its outcome
+        let audit = AuditBmc::list_for_target(&mm, "user", &alice.user_id().to_string()).await.unwrap();
+        assert_eq!(audit.len(), 3);
+        assert_eq!(audit[0].details["new_score"], 4.5);
+        assert_eq!(audit[0].details["reason"], "appeal");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_review_reminder_sent_once() {
+        use crate::model::NotificationBmc;
+
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let due = completed_trade(&mm, &alice, &bob, 5).await;
+        completed_trade(&mm, &alice, &bob, 1).await; // too recent
+        completed_trade(&mm, &alice, &bob, 31).await; // review window closed
+        ReviewBmc::create(&bob, &mm, review_for(due)).await.unwrap();
+
+        // Only alice still owes a review on the due trade, and repeats send nothing new
+        assert_eq!(ReviewBmc::send_review_reminders(&mm).await.unwrap(), 1);
+        assert_eq!(ReviewBmc::send_review_reminders(&mm).await.unwrap(), 0);
+
+        let inbox = NotificationBmc::list(&alice, &mm, None, None, false).await.unwrap().notifications;
+        assert_eq!(inbox.len(), 1);
+        assert_eq!((inbox[0].kind.as_str(), inbox[0].trade_id), ("review_reminder", Some(due)));
+        let inbox = NotificationBmc::list(&bob, &mm, None, None, false).await.unwrap().notifications;
+        assert!(inbox.iter().all(|n| n.kind != "review_reminder"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_review_window_closed() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let id = completed_trade(&mm, &alice, &bob, 31).await;
+
+        let res = ReviewBmc::create(&alice, &mm, review_for(id)).await;
+        assert!(matches!(res, Err(Error::InvalidState(msg)) if msg == "review window closed"));
+
+        // Widening the window reopens it
+        sqlx::query("UPDATE exchange_config SET value = '60' WHERE key = 'review_window_days'")
+            .execute(mm.db())
+            .await
+            .unwrap();
+        ReviewBmc::create(&alice, &mm, review_for(id)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_featured_proposals_listed_first() {
+        let mm = _dev_utils::init_test().await;
+        let ctx = _dev_utils::seed_ctx(&mm, "erin").await;
+        let admin = _dev_utils::seed_ctx(&mm, "admin").await;
+        let oldest = seed_proposal(&mm, &ctx).await;
+        let middle = seed_proposal(&mm, &ctx).await;
+        let newest = seed_proposal(&mm, &ctx).await;
+
+        TradeBmc::admin_set_featured(&admin, &mm, oldest, true).await.unwrap();
+
+        let ids: Vec<i64> = TradeBmc::list_proposals(&mm, 10, 0, None, None).await.unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![oldest, newest, middle]);
+        let featured = TradeBmc::get(&ctx, &mm, oldest).await.unwrap();
+        assert!(featured.is_featured);
+        assert_eq!(featured.version, 2);
+
+        TradeBmc::admin_set_featured(&admin, &mm, oldest, false).await.unwrap();
+        assert_eq!(TradeBmc::get(&ctx, &mm, oldest).await.unwrap().version, 3);
+        let ids: Vec<i64> = TradeBmc::list_proposals(&mm, 10, 0, None, None).await.unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![newest, middle, oldest]);
+
+        assert!(matches!(TradeBmc::admin_set_featured(&admin, &mm, 999_999, true).await, Err(Error::NotFound)));
+        assert!(AuditBmc::list_for_target(&mm, "trade", "999999").await.unwrap().is_empty());
+
+        let audit = AuditBmc::list_for_target(&mm, "trade", &oldest.to_string()).await.unwrap();
+        let featured: Vec<&serde_json::Value> = audit.iter().map(|e| &e.details["featured"]).collect();
+        assert_eq!(featured, [&serde_json::json!(false), &serde_json::json!(true)]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_status_counts() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let carol = _dev_utils::seed_ctx(&mm, "carol").await;
+        assert!(TradeBmc::status_counts(&alice, &mm).await.unwrap().is_empty());
+
+        // Alice: two open proposals, one matched with bob, one completed with bob, one cancelled
+        seed_proposal(&mm, &alice).await;
+        seed_proposal(&mm, &alice).await;
+        let matched = seed_proposal(&mm, &alice).await;
+        _dev_utils::match_trade(&mm, &bob, xch_offer(matched)).await;
+        completed_trade(&mm, &alice, &bob, 1).await;
+        let cancelled = seed_proposal(&mm, &alice).await;
+        TradeBmc::cancel(&alice, &mm, cancelled, None).await.unwrap();
+        // Not alice's
+        seed_proposal(&mm, &carol).await;
+
+        let counts = TradeBmc::status_counts(&alice, &mm).await.unwrap();
+        let expected: HashMap<String, i64> =
+            [("proposal", 2), ("matched", 1), ("completed", 1), ("cancelled", 1)]
+                .into_iter()
+                .map(|(s, n)| (s.to_string(), n))
+                .collect();
+        assert_eq!(counts, expected);
+
+        let counts = TradeBmc::status_counts(&bob, &mm).await.unwrap();
+        assert_eq!(counts.get("matched"), Some(&1));
+        assert_eq!(counts.get("completed"), Some(&1));
+        assert_eq!(counts.get("proposal"), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_proposals_filters_by_tag() {
+        let mm = _dev_utils::init_test().await;
+        let ctx = _dev_utils::seed_ctx(&mm, "frank").await;
+        let tagged = |tags: &[&str]| TradeForCreate {
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            ..proposal_with_value(120.0)
+        };
+        let camera = TradeBmc::create(&ctx, &mm, tagged(&[" Vintage", "CAMERA", "camera"])).await.unwrap();
+        let lens = TradeBmc::create(&ctx, &mm, tagged(&["vintage", "lens"])).await.unwrap();
+        let untagged = seed_proposal(&mm, &ctx).await;
+
+        let ids = |trades: Vec<Trade>| trades.iter().map(|t| t.id).collect::<Vec<_>>();
+        let vintage = ids(TradeBmc::list_proposals(&mm, 100, 0, Some("Vintage "), None).await.unwrap());
+        assert!(vintage.contains(&camera) && vintage.contains(&lens) && !vintage.contains(&untagged));
+        let cameras = ids(TradeBmc::list_proposals(&mm, 100, 0, Some("camera"), None).await.unwrap());
+        assert!(cameras.contains(&camera) && !cameras.contains(&lens));
+
+        let all = TradeBmc::list_proposals(&mm, 100, 0, None, None).await.unwrap();
+        let listed = all.iter().find(|t| t.id == camera).unwrap();
+        assert_eq!(listed.tags, vec!["camera", "vintage"]);
+        assert!(all.iter().any(|t| t.id == untagged));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_proposals_filters_by_category() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let in_category = |category: Option<&str>| TradeForCreate {
+            item_category: category.map(str::to_string),
+            ..proposal_with_value(60.0)
+        };
+        let phone = TradeBmc::create(&alice, &mm, in_category(Some("electronics"))).await.unwrap();
+        let radio = TradeBmc::create(&bob, &mm, in_category(Some("Electronics"))).await.unwrap();
+        let card = TradeBmc::create(&alice, &mm, in_category(Some("trading_cards"))).await.unwrap();
+        let uncategorized = TradeBmc::create(&alice, &mm, in_category(None)).await.unwrap();
+        // Taken proposals drop out of both the list and the counts
+        let taken = TradeBmc::create(&bob, &mm, in_category(Some("trading_cards"))).await.unwrap();
+        _dev_utils::match_trade(&mm, &alice, xch_offer(taken)).await;
+
+        let ids = |trades: Vec<Trade>| {
+            let mut ids: Vec<i64> = trades.iter().map(|t| t.id).collect();
+            ids.sort_unstable();
+            ids
+        };
+        let electronics = ids(TradeBmc::list_proposals(&mm, 100, 0, None, Some("electronics")).await.unwrap());
+        assert_eq!(electronics, vec![phone, radio]);
+        let cards = ids(TradeBmc::list_proposals(&mm, 100, 0, None, Some(" Trading_Cards ")).await.unwrap());
+        assert_eq!(cards, vec![card]);
+
+        // No (or a blank) category lists everything open
+        let all = ids(TradeBmc::list_proposals(&mm, 100, 0, None, None).await.unwrap());
+        assert_eq!(ids(TradeBmc::list_proposals(&mm, 100, 0, None, Some("")).await.unwrap()), all);
+        for id in [phone, radio, card, uncategorized] {
+            assert!(all.contains(&id));
+        }
+        assert!(!all.contains(&taken));
+
+        let categories = TradeBmc::list_categories(&mm).await.unwrap();
+        let count_of = |name: &str| categories.iter().find(|c| c.category == name).map(|c| c.count);
+        assert_eq!(count_of("Electronics"), Some(2));
+        assert_eq!(count_of("trading_cards"), Some(1));
+        assert!(categories.windows(2).all(|w| w[0].count >= w[1].count));
+    }
+
+    async fn search_ids(mm: &ModelManager, query: &str, sort: TradeSort) -> Vec<i64> {
+        let criteria = TradeSearchCriteria { query: Some(query.to_string()), ..Default::default() };
+        TradeBmc::search(mm, &criteria, sort, 50, 0).await.unwrap().iter().map(|t| t.id).collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_search_combines_criteria() {
+        let (mm, alice, _) = _dev_utils::init_test_with_users().await;
+        let marker = "zeiss";
+        let listing = |title: String, category: &str, value: f64, tags: &[&str]| TradeForCreate {
+            item_title: title,
+            item_category: Some(category.to_string()),
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            ..proposal_with_value(value)
+        };
+        let hit = TradeBmc::create(&alice, &mm, listing(format!("{} Planar 50mm", marker), "lenses", 300.0, &["manual"])).await.unwrap();
+        let cheap = TradeBmc::create(&alice, &mm, listing(format!("{} Tessar", marker), "lenses", 80.0, &["manual"])).await.unwrap();
+        let wrong_tag = TradeBmc::create(&alice, &mm, listing(format!("{} Sonnar", marker), "lenses", 400.0, &["autofocus"])).await.unwrap();
+        let wrong_category = TradeBmc::create(&alice, &mm, listing(format!("{} body", marker), "cameras", 350.0, &["manual"])).await.unwrap();
+
+        let criteria = TradeSearchCriteria {
+            query: Some(marker.to_uppercase()),
+            category: Some("Lenses".to_string()),
+            min_value_usd: Some(100.0),
+            max_value_usd: Some(1000.0),
+            tag: Some("Manual".to_string()),
+        };
+        let ids: Vec<i64> = TradeBmc::search(&mm, &criteria, TradeSort::Newest, 50, 0).await.unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![hit]);
+
+        let by_value = search_ids(&mm, marker, TradeSort::Value).await;
+        assert_eq!(by_value, vec![wrong_tag, wrong_category, hit, cheap]);
+
+        // LIKE wildcards in the query are matched literally
+        let wildcard = TradeSearchCriteria { query: Some(format!("{}%", marker)), ..Default::default() };
+        assert!(TradeBmc::search(&mm, &wildcard, TradeSort::Newest, 50, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_search_sorts_by_reputation() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let marker = "hasselblad";
+        let listing = || TradeForCreate { item_title: format!("{} 500C", marker), ..proposal_with_value(120.0) };
+        let from_bob = TradeBmc::create(&bob, &mm, listing()).await.unwrap();
+        let from_alice = TradeBmc::create(&alice, &mm, listing()).await.unwrap();
+
+        for (ctx, score) in [(&alice, 2.5), (&bob, 4.75)] {
+            sqlx::query("UPDATE users SET reputation_score = $2 WHERE id = $1")
+                .bind(ctx.user_id())
+                .bind(score)
+                .execute(mm.db())
+                .await
+                .unwrap();
+        }
+
+        // Newest puts alice's later proposal first; reputation puts bob's first
+        assert_eq!(search_ids(&mm, marker, TradeSort::Newest).await, vec![from_alice, from_bob]);
+        assert_eq!(search_ids(&mm, marker, TradeSort::Reputation).await, vec![from_bob, from_alice]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_search_query_fields_and_blank_query() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let by_title = TradeBmc::create(&alice, &mm, TradeForCreate {
+            item_title: "Leica M6".to_string(),
+            ..proposal_with_value(900.0)
+        }).await.unwrap();
+        let by_description = TradeBmc::create(&alice, &mm, TradeForCreate {
+            item_description: "Fits any LEICA M body".to_string(),
+            ..proposal_with_value(200.0)
+        }).await.unwrap();
+        let by_category = TradeBmc::create(&alice, &mm, TradeForCreate {
+            item_category: Some("leica-accessories".to_string()),
+            ..proposal_with_value(50.0)
+        }).await.unwrap();
+        let unrelated = TradeBmc::create(&alice, &mm, proposal_with_value(10.0)).await.unwrap();
+
+        // Matched trades drop out of the search
+        let matched = TradeBmc::create(&alice, &mm, TradeForCreate {
+            item_title: "Leica IIIf".to_string(),
+            ..proposal_with_value(400.0)
+        }).await.unwrap();
+        sqlx::query("UPDATE trades SET status = 'matched', acceptor_id = $2 WHERE id = $1")
+            .bind(matched)
+            .bind(bob.user_id())
+            .execute(mm.db())
+            .await
+            .unwrap();
+
+        let mut hits = search_ids(&mm, "lEiCa", TradeSort::Newest).await;
+        hits.sort_unstable();
+        assert_eq!(hits, vec![by_title, by_description, by_category]);
+
+        // A blank query doesn't filter: every open proposal comes back
+        let all = search_ids(&mm, "", TradeSort::Newest).await;
+        assert_eq!(search_ids(&mm, "   ", TradeSort::Newest).await, all);
+        for id in [by_title, by_description, by_category, unrelated] {
+            assert!(all.contains(&id));
+        }
+        assert!(!all.contains(&matched));
+    }
 }
@@ -0,0 +1,101 @@
+// ============================================
+// Trade Events (timeline)
+// ============================================
+
+use crate::ctx::Ctx;
+use super::ModelManager;
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeEvent {
+    pub id: i64,
+    pub trade_id: i64,
+    pub actor_id: Option<i64>,
+    pub event_type: String,
+    pub details: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Row shape as read from Postgres (details come back as JSON text)
+#[derive(FromRow)]
+struct TradeEventRow {
+    id: i64,
+    trade_id: i64,
+    actor_id: Option<i64>,
+    event_type: String,
+    details: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TradeEventRow> for TradeEvent {
+    fn from(row: TradeEventRow) -> Self {
+        Self {
+            id: row.id,
+            trade_id: row.trade_id,
+            actor_id: row.actor_id,
+            event_type: row.event_type,
+            details: serde_json::from_str(&row.details).unwrap_or(serde_json::Value::Null),
+            created_at: row.created_at,
+        }
+    }
+}
+
+pub struct TradeEventBmc;
+
+impl TradeEventBmc {
+    /// Record an event on a trade, inside the caller's transaction
+    /// (`actor_id` is None for system transitions such as a confirmed payment)
+    pub async fn record(
+        conn: &mut PgConnection,
+        trade_id: i64,
+        actor_id: Option<i64>,
+        event_type: &str,
+        details: serde_json::Value,
+    ) -> Result<i64> {
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO trade_events (trade_id, actor_id, event_type, details)
+             VALUES ($1, $2, $3, $4::jsonb)
+             RETURNING id"
+        )
+        .bind(trade_id)
+        .bind(actor_id)
+        .bind(event_type)
+        .bind(details.to_string())
+        .fetch_one(conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(id)
+    }
+    
+    /// Timeline for a trade, oldest first; `NotFound` for non-participants, as with `TradeBmc::get`
+    pub async fn list_for_trade(ctx: &Ctx, mm: &ModelManager, trade_id: i64) -> Result<Vec<TradeEvent>> {
+        let is_participant: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM trades WHERE id = $1 AND (proposer_id = $2 OR acceptor_id = $2)"
+        )
+        .bind(trade_id)
+        .bind(ctx.user_id())
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if is_participant.is_none() {
+            return Err(Error::NotFound);
+        }
+        
+        let rows: Vec<TradeEventRow> = sqlx::query_as(
+            "SELECT id, trade_id, actor_id, event_type, details::text AS details, created_at
+             FROM trade_events
+             WHERE trade_id = $1
+             ORDER BY created_at ASC, id ASC"
+        )
+        .bind(trade_id)
+        .fetch_all(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(rows.into_iter().map(TradeEvent::from).collect())
+    }
+}
@@ -3,10 +3,12 @@
 // ============================================
 
 use crate::ctx::Ctx;
-use super::ModelManager;
+use super::{AuditBmc, ConfigBmc, ExchangeSettingsForUpdate, FeeModel, ModelManager, SettingsBmc, TradeEventBmc, TradeStatus};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection, Postgres, QueryBuilder};
+use std::collections::BTreeSet;
 use crate::error::{Error, Result};
+use crate::blockchain::escrow::derive_escrow_puzzle_hash;
 
 // ============================================
 // Transaction Types
@@ -79,6 +81,62 @@ impl From<&str> for TxStatus {
     }
 }
 
+pub const MOJOS_PER_XCH: f64 = 1_000_000_000_000.0;
+
+const COMMITMENT_MEMO_PREFIX: &str = "DTREX-COMMIT-";
+
+/// Memo a participant attaches to their commitment fee payment
+pub fn commitment_memo(trade_id: i64, user_id: i64) -> String {
+    format!("{}{}-{}", COMMITMENT_MEMO_PREFIX, trade_id, user_id)
+}
+
+/// Inverse of `commitment_memo`: the (trade_id, user_id) a memo names, if it is one of ours
+pub fn parse_commitment_memo(memo: &str) -> Option<(i64, i64)> {
+    let (trade_id, user_id) = memo.trim().strip_prefix(COMMITMENT_MEMO_PREFIX)?.split_once('-')?;
+    let parse_id = |s: &str| {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse::<i64>().ok()
+    };
+    Some((parse_id(trade_id)?, parse_id(user_id)?))
+}
+
+/// How a USD commitment fee is rounded to whole mojos
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeRounding {
+    /// Round up so the exchange never receives less than the fee
+    Ceil,
+    Nearest,
+}
+
+impl FeeRounding {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ceil" => Some(FeeRounding::Ceil),
+            "nearest" => Some(FeeRounding::Nearest),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeRounding::Ceil => "ceil",
+            FeeRounding::Nearest => "nearest",
+        }
+    }
+
+    /// Convert a USD fee to mojos at the given XCH price
+    pub fn usd_to_mojos(&self, fee_usd: f64, xch_price_usd: f64) -> i64 {
+        let raw = fee_usd * MOJOS_PER_XCH / xch_price_usd;
+        match self {
+            // Ignore float noise so exact amounts don't gain a mojo
+            FeeRounding::Ceil => (raw - 1e-6).ceil() as i64,
+            FeeRounding::Nearest => raw.round() as i64,
+        }
+    }
+}
+
 // ============================================
 // Trade Transaction
 // ============================================
@@ -97,11 +155,14 @@ pub struct TradeTransaction {
     pub amount_mojos: i64,
     pub status: String,
     pub confirmations: Option<i32>,
+    pub confirmed_height: Option<i64>,
     pub error_message: Option<String>,
     pub retry_count: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub mempool_at: Option<chrono::DateTime<chrono::Utc>>,
     pub confirmed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set on a failed transaction once a retry has replaced it
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -114,18 +175,25 @@ pub struct TradeTransactionForCreate {
     pub amount_mojos: i64,
 }
 
-// ============================================
-// Exchange Config
-// ============================================
+/// One on-chain confirmation to apply (see `TransactionBmc::confirm_batch`)
+#[derive(Debug, Clone)]
+pub struct TxConfirmation {
+    pub tx_id: String,
+    pub coin_id: String,
+    pub confirmations: i32,
+    pub confirmed_height: Option<i64>,
+}
 
-#[derive(Debug, Clone, Serialize, FromRow)]
-pub struct ExchangeConfig {
-    pub id: i32,
-    pub key: String,
-    pub value: String,
-    pub description: Option<String>,
+/// Optional filters for `TransactionBmc::list_for_trade`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransactionListFilter {
+    pub status: Option<String>,
+    pub tx_type: Option<String>,
 }
 
+/// Rows per UPDATE in `confirm_batch` (4 binds each, well under Postgres' 65535 limit)
+const CONFIRM_BATCH_CHUNK: usize = 1000;
+
 // ============================================
 // Commitment Details Response
 // ============================================
@@ -136,11 +204,20 @@ pub struct CommitmentDetails {
     pub exchange_wallet_address: String,
     pub commitment_fee_usd: f64,  // Fee in USD - frontend calculates XCH dynamically
     pub user_role: String,  // "proposer" or "acceptor"
-    pub user_commit_status: String,
+    pub fee_model: String,
+    pub user_commit_status: String,  // "not_required" when the fee model exempts this side, "waived" when an admin waived the fee
     pub other_commit_status: String,
+    pub fee_waived: bool,
     pub memo: String,
 }
 
+/// Whether the caller can pay their commitment fee now, and why not
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CommitEligibility {
+    pub can_commit: bool,
+    pub reason: Option<String>,
+}
+
 // ============================================
 // Trade Transaction BMC
 // ============================================
@@ -148,48 +225,34 @@ pub struct CommitmentDetails {
 pub struct TransactionBmc;
 
 impl TransactionBmc {
-    /// Get the exchange wallet address from config
+    /// Get the exchange wallet address from settings
     pub async fn get_exchange_wallet(_ctx: &Ctx, mm: &ModelManager) -> Result<String> {
-        let config: Option<ExchangeConfig> = sqlx::query_as::<_, ExchangeConfig>(
-            "SELECT * FROM exchange_config WHERE key = 'exchange_wallet_address'"
-        )
-        .fetch_optional(mm.pool())
-        .await
-        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        let settings = SettingsBmc::get(mm).await?;
         
-        match config {
-            Some(c) if !c.value.is_empty() => Ok(c.value),
-            _ => Err(Error::Config("Exchange wallet address not configured".to_string())),
+        if settings.wallet_address.is_empty() {
+            return Err(Error::Config("Exchange wallet address not configured".to_string()));
         }
+        Ok(settings.wallet_address)
     }
     
-    /// Get the default commitment fee in USD
+    /// Get the commitment fee in USD (defaults to $1.00)
     pub async fn get_commitment_fee_usd(_ctx: &Ctx, mm: &ModelManager) -> Result<f64> {
-        let config: Option<ExchangeConfig> = sqlx::query_as::<_, ExchangeConfig>(
-            "SELECT * FROM exchange_config WHERE key = 'commitment_fee_usd'"
-        )
-        .fetch_optional(mm.pool())
-        .await
-        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
-        
-        match config {
-            Some(c) => c.value.parse::<f64>().map_err(|_| Error::Config("Invalid commitment fee value".to_string())),
-            None => Ok(1.0), // Default: $1.00 USD
+        Ok(SettingsBmc::get(mm).await?.commitment_fee_usd)
+    }
+    
+    /// Get the rounding mode for USD -> mojo fee conversion (default: ceil)
+    pub async fn get_commitment_fee_rounding(_ctx: &Ctx, mm: &ModelManager) -> Result<FeeRounding> {
+        match ConfigBmc::get(mm, "commitment_fee_rounding").await? {
+            Some(value) => FeeRounding::parse(&value)
+                .ok_or_else(|| Error::Config(format!("Invalid commitment fee rounding '{}'", value))),
+            None => Ok(FeeRounding::Ceil),
         }
     }
     
     /// Set the exchange wallet address
     pub async fn set_exchange_wallet(_ctx: &Ctx, mm: &ModelManager, address: &str) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO exchange_config (key, value, description, updated_at) 
-             VALUES ('exchange_wallet_address', $1, 'XCH address where commitment fees are sent', NOW())
-             ON CONFLICT (key) DO UPDATE SET value = $1, updated_at = NOW()"
-        )
-        .bind(address)
-        .execute(mm.pool())
-        .await
-        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
-        
+        let settings_u = ExchangeSettingsForUpdate { wallet_address: Some(address.to_string()), ..Default::default() };
+        SettingsBmc::update(mm, settings_u).await?;
         Ok(())
     }
     
@@ -198,8 +261,9 @@ impl TransactionBmc {
         let user_id = ctx.user_id();
         
         // Get the trade
-        let trade: Option<(i64, i64, Option<i64>, String, Option<String>, Option<String>)> = sqlx::query_as(
-            "SELECT id, proposer_id, acceptor_id, status, proposer_commit_status, acceptor_commit_status 
+        let trade: Option<(i64, i64, Option<i64>, String, Option<String>, Option<String>, String, bool)> = sqlx::query_as(
+            "SELECT id, proposer_id, acceptor_id, status, proposer_commit_status, acceptor_commit_status, fee_model,
+                    commitment_fee_waived
              FROM trades WHERE id = $1"
         )
         .bind(trade_id)
@@ -207,7 +271,7 @@ impl TransactionBmc {
         .await
         .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         
-        let (id, proposer_id, acceptor_id, status, prop_status, acc_status) = trade
+        let (id, proposer_id, acceptor_id, status, prop_status, acc_status, fee_model, fee_waived) = trade
             .ok_or_else(|| Error::NotFoundMsg("Trade not found".to_string()))?;
         
         // Verify user is a participant
@@ -218,39 +282,87 @@ impl TransactionBmc {
             return Err(Error::Auth("Not a participant in this trade".to_string()));
         }
         
-        // Check trade status allows commitment
-        if status != "matched" && status != "committed" {
+        // Check trade status allows commitment (a waiver moves the trade straight to escrow)
+        if status != "matched" && status != "committed" && !(fee_waived && status == "escrow") {
             return Err(Error::InvalidState(format!(
                 "Trade status '{}' does not allow commitment. Must be 'matched'.", status
             )));
         }
         
         let exchange_wallet = Self::get_exchange_wallet(ctx, mm).await?;
-        let fee_usd = Self::get_commitment_fee_usd(ctx, mm).await?;
+        let fee_usd = if fee_waived { 0.0 } else { Self::get_commitment_fee_usd(ctx, mm).await? };
         
-        let user_role = if is_proposer { "proposer" } else { "acceptor" };
-        let user_commit_status = if is_proposer { 
-            prop_status.clone().unwrap_or_else(|| "pending".to_string())
-        } else { 
-            acc_status.clone().unwrap_or_else(|| "pending".to_string())
-        };
-        let other_commit_status = if is_proposer {
-            acc_status.unwrap_or_else(|| "pending".to_string())
-        } else {
-            prop_status.unwrap_or_else(|| "pending".to_string())
+        let model = FeeModel::parse(&fee_model).unwrap_or(FeeModel::Both);
+        let commit_status = |for_proposer: bool, stored: Option<String>| {
+            if !model.requires(for_proposer) {
+                "not_required".to_string()
+            } else if fee_waived {
+                "waived".to_string()
+            } else {
+                stored.unwrap_or_else(|| "pending".to_string())
+            }
         };
         
+        let user_role = if is_proposer { "proposer" } else { "acceptor" };
+        let (user_stored, other_stored) = if is_proposer { (prop_status, acc_status) } else { (acc_status, prop_status) };
+        let user_commit_status = commit_status(is_proposer, user_stored);
+        let other_commit_status = commit_status(!is_proposer, other_stored);
+        
         Ok(CommitmentDetails {
             trade_id: id,
             exchange_wallet_address: exchange_wallet,
             commitment_fee_usd: fee_usd,
             user_role: user_role.to_string(),
+            fee_model: model.as_str().to_string(),
             user_commit_status,
             other_commit_status,
-            memo: format!("DTREX-COMMIT-{}-{}", trade_id, user_id),
+            fee_waived,
+            memo: commitment_memo(trade_id, user_id),
         })
     }
     
+    /// Cheap check for the commit UI: the trade's status, fee model, waiver and the
+    /// caller's own commit status, without the wallet and fee config lookups of
+    /// `get_commitment_details`
+    pub async fn can_commit(ctx: &Ctx, mm: &ModelManager, trade_id: i64) -> Result<CommitEligibility> {
+        let user_id = ctx.user_id();
+        
+        let trade: Option<(i64, Option<i64>, String, Option<String>, Option<String>, String, bool)> = sqlx::query_as(
+            "SELECT proposer_id, acceptor_id, status, proposer_commit_status, acceptor_commit_status, fee_model,
+                    commitment_fee_waived
+             FROM trades WHERE id = $1"
+        )
+        .bind(trade_id)
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let (proposer_id, acceptor_id, status, prop_status, acc_status, fee_model, fee_waived) = trade
+            .ok_or_else(|| Error::NotFoundMsg("Trade not found".to_string()))?;
+        
+        let is_proposer = user_id == proposer_id;
+        if !is_proposer && acceptor_id != Some(user_id) {
+            return Err(Error::Auth("Not a participant in this trade".to_string()));
+        }
+        
+        let model = FeeModel::parse(&fee_model).unwrap_or(FeeModel::Both);
+        let own_status = if is_proposer { prop_status } else { acc_status };
+        let reason = if status != "matched" {
+            Some(format!("Trade status '{}' does not allow commitment. Must be 'matched'.", status))
+        } else if fee_waived {
+            Some("The commitment fee was waived".to_string())
+        } else if !model.requires(is_proposer) {
+            let role = if is_proposer { "proposer" } else { "acceptor" };
+            Some(format!("No commitment fee required from the {} (fee model '{}')", role, model.as_str()))
+        } else if own_status.as_deref() == Some("confirmed") {
+            Some("Your commitment fee is already confirmed".to_string())
+        } else {
+            None
+        };
+        
+        Ok(CommitEligibility { can_commit: reason.is_none(), reason })
+    }
+    
     /// Create a pending transaction record
     pub async fn create(ctx: &Ctx, mm: &ModelManager, tx: TradeTransactionForCreate) -> Result<i64> {
         let user_id = ctx.user_id();
@@ -323,96 +435,725 @@ impl TransactionBmc {
         .await
         .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         
-        if result.rows_affected() == 0 {
-            return Err(Error::NotFoundMsg("Transaction not found or already submitted".to_string()));
+        if result.rows_affected() > 0 {
+            return Ok(());
         }
         
-        Ok(())
+        // A retried submit of the same tx_id succeeds; anything else explains why it can't be applied
+        let existing: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT status, tx_id FROM trade_transactions WHERE id = $1 AND user_id = $2"
+        )
+        .bind(transaction_id)
+        .bind(user_id)
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        match existing {
+            None => Err(Error::NotFoundMsg("Transaction not found".to_string())),
+            Some((status, Some(existing))) if existing == tx_id && status == "mempool" => Ok(()),
+            Some((_, Some(existing))) if existing != tx_id => Err(Error::Conflict(format!(
+                "Transaction was already submitted with a different tx_id ({})", existing
+            ))),
+            Some((status, _)) => Err(Error::InvalidState(format!(
+                "Transaction can no longer be submitted (status '{}')", status
+            ))),
+        }
     }
     
     /// Confirm a transaction (called after blockchain verification)
-    pub async fn confirm(_ctx: &Ctx, mm: &ModelManager, tx_id: &str, coin_id: &str, confirmations: i32) -> Result<()> {
+    pub async fn confirm(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        tx_id: &str,
+        coin_id: &str,
+        confirmations: i32,
+        confirmed_height: Option<i64>,
+    ) -> Result<()> {
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        Self::confirm_in(&mut tx, tx_id, coin_id, confirmations, confirmed_height).await?;
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Admin: confirm a pending or mempool transaction verified out-of-band. The
+    /// confirmation and its audit row (with the admin's reason) commit together.
+    pub async fn admin_confirm(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        tx_id: &str,
+        coin_id: &str,
+        confirmations: i32,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let (trade_id, status): (i64, String) = sqlx::query_as(
+            "SELECT trade_id, status FROM trade_transactions WHERE tx_id = $1 FOR UPDATE"
+        )
+        .bind(tx_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?
+        .ok_or_else(|| Error::NotFoundMsg("Transaction not found".to_string()))?;
+        
+        if status != "pending" && status != "mempool" {
+            return Err(Error::InvalidState(format!(
+                "Only pending or mempool transactions can be confirmed (status is '{}')", status
+            )));
+        }
+        
+        Self::confirm_in(&mut tx, tx_id, coin_id, confirmations, None).await?;
+        
+        let details = serde_json::json!({
+            "trade_id": trade_id,
+            "previous_status": status,
+            "coin_id": coin_id,
+            "confirmations": confirmations,
+            "reason": reason,
+        });
+        AuditBmc::record_in(&mut tx, ctx, "confirm_transaction", "trade_transaction", tx_id, details).await?;
+        
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Confirm a transaction and apply its effect on the trade, inside the caller's transaction
+    async fn confirm_in(
+        conn: &mut PgConnection,
+        tx_id: &str,
+        coin_id: &str,
+        confirmations: i32,
+        confirmed_height: Option<i64>,
+    ) -> Result<()> {
         // Update transaction status
-        let result = sqlx::query(
+        let (trade_id, tx_type, amount_mojos): (i64, String, i64) = sqlx::query_as(
             "UPDATE trade_transactions 
-             SET status = 'confirmed', coin_id = $1, confirmations = $2, confirmed_at = NOW()
-             WHERE tx_id = $3 AND status IN ('pending', 'mempool')"
+             SET status = 'confirmed', coin_id = $1, confirmations = $2, confirmed_height = $4, confirmed_at = NOW()
+             WHERE tx_id = $3 AND status IN ('pending', 'mempool')
+             RETURNING trade_id, tx_type, amount_mojos"
         )
         .bind(coin_id)
         .bind(confirmations)
         .bind(tx_id)
-        .execute(mm.pool())
+        .bind(confirmed_height)
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?
+        .ok_or_else(|| Error::NotFoundMsg("Transaction not found or already confirmed".to_string()))?;
         
-        if result.rows_affected() == 0 {
-            return Err(Error::NotFoundMsg("Transaction not found or already confirmed".to_string()));
+        Self::record_confirmed_event(conn, trade_id, tx_id, &tx_type, amount_mojos).await?;
+        if tx_type == "commitment_fee" {
+            // Check if both parties have confirmed commitment fees
+            Self::check_and_update_trade_commitment(conn, trade_id).await?;
+        } else if tx_type == "escrow_deposit" {
+            Self::record_escrow_coin(conn, trade_id, coin_id).await?;
+        } else if tx_type == "escrow_release" {
+            Self::complete_released_escrow(conn, trade_id).await?;
         }
         
-        // Get the trade_id for this transaction
-        let trade_info: Option<(i64, String)> = sqlx::query_as(
-            "SELECT trade_id, tx_type FROM trade_transactions WHERE tx_id = $1"
-        )
-        .bind(tx_id)
-        .fetch_optional(mm.pool())
-        .await
-        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        Ok(())
+    }
+    
+    /// Add a confirmed transaction to its trade's event log
+    async fn record_confirmed_event(
+        conn: &mut PgConnection,
+        trade_id: i64,
+        tx_id: &str,
+        tx_type: &str,
+        amount_mojos: i64,
+    ) -> Result<()> {
+        let details = serde_json::json!({ "tx_id": tx_id, "tx_type": tx_type, "amount_mojos": amount_mojos });
+        TradeEventBmc::record(conn, trade_id, None, "transaction_confirmed", details).await?;
+        Ok(())
+    }
+    
+    /// Confirm many transactions with one `UPDATE ... FROM (VALUES ...)` per chunk.
+    /// Rows that are unknown or no longer pending/mempool are skipped.
+    /// Returns the (tx_id, trade_id) of each row that was confirmed.
+    pub async fn confirm_batch(mm: &ModelManager, confirmations: &[TxConfirmation]) -> Result<Vec<(String, i64)>> {
+        let mut confirmed = Vec::new();
+        let mut commitment_trades = BTreeSet::new();
+        let mut escrow_coins = Vec::new();
+        let mut released_trades = BTreeSet::new();
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         
-        if let Some((trade_id, tx_type)) = trade_info {
-            // Only check for commitment fee transactions
-            if tx_type == "commitment_fee" {
-                // Check if both parties have confirmed commitment fees
-                Self::check_and_update_trade_commitment(mm, trade_id).await?;
+        for chunk in confirmations.chunks(CONFIRM_BATCH_CHUNK) {
+            let mut qb = QueryBuilder::<Postgres>::new(
+                "UPDATE trade_transactions t
+                 SET status = 'confirmed', coin_id = v.coin_id, confirmations = v.confirmations,
+                     confirmed_height = v.confirmed_height, confirmed_at = NOW()
+                 FROM ("
+            );
+            qb.push_values(chunk, |mut row, c| {
+                row.push_bind(&c.tx_id)
+                    .push_bind(&c.coin_id)
+                    .push_bind(c.confirmations)
+                    .push_bind(c.confirmed_height);
+            });
+            qb.push(
+                ") AS v(tx_id, coin_id, confirmations, confirmed_height)
+                 WHERE t.tx_id = v.tx_id AND t.status IN ('pending', 'mempool')
+                 RETURNING t.tx_id, t.trade_id, t.tx_type, t.coin_id, t.amount_mojos"
+            );
+            
+            let rows: Vec<(String, i64, String, Option<String>, i64)> = qb
+                .build_query_as()
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+            
+            for (tx_id, trade_id, tx_type, coin_id, amount_mojos) in rows {
+                Self::record_confirmed_event(&mut tx, trade_id, &tx_id, &tx_type, amount_mojos).await?;
+                if tx_type == "commitment_fee" {
+                    commitment_trades.insert(trade_id);
+                } else if let ("escrow_deposit", Some(coin_id)) = (tx_type.as_str(), coin_id) {
+                    escrow_coins.push((trade_id, coin_id));
+                } else if tx_type == "escrow_release" {
+                    released_trades.insert(trade_id);
+                }
+                confirmed.push((tx_id, trade_id));
             }
         }
         
-        Ok(())
+        for trade_id in commitment_trades {
+            Self::check_and_update_trade_commitment(&mut tx, trade_id).await?;
+        }
+        for (trade_id, coin_id) in escrow_coins {
+            Self::record_escrow_coin(&mut tx, trade_id, &coin_id).await?;
+        }
+        for trade_id in released_trades {
+            Self::complete_released_escrow(&mut tx, trade_id).await?;
+        }
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(confirmed)
     }
     
-    /// Check if both parties have confirmed commitment fees and update trade status
-    async fn check_and_update_trade_commitment(mm: &ModelManager, trade_id: i64) -> Result<()> {
-        // Count confirmed commitment fees for this trade
-        let confirmed_count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(DISTINCT user_id) FROM trade_transactions 
-             WHERE trade_id = $1 
-             AND tx_type = 'commitment_fee' 
-             AND status = 'confirmed'"
+    /// Check if every party required by the trade's fee model has a confirmed
+    /// commitment fee (or the fee was waived) and, if so, move the trade to committed/escrow.
+    /// Returns whether the fees were settled.
+    pub(crate) async fn check_and_update_trade_commitment(conn: &mut PgConnection, trade_id: i64) -> Result<bool> {
+        let paid: Option<(String, bool, bool, bool)> = sqlx::query_as(
+            "SELECT t.fee_model, t.commitment_fee_waived,
+                    EXISTS (SELECT 1 FROM trade_transactions tx WHERE tx.trade_id = t.id AND tx.user_id = t.proposer_id
+                            AND tx.tx_type = 'commitment_fee' AND tx.status = 'confirmed'),
+                    EXISTS (SELECT 1 FROM trade_transactions tx WHERE tx.trade_id = t.id AND tx.user_id = t.acceptor_id
+                            AND tx.tx_type = 'commitment_fee' AND tx.status = 'confirmed')
+             FROM trades t WHERE t.id = $1"
         )
         .bind(trade_id)
-        .fetch_one(mm.pool())
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         
-        // If both parties have confirmed commitment fees, update trade to committed and start escrow
-        if confirmed_count.0 >= 2 {
-            sqlx::query(
+        let Some((fee_model, fee_waived, proposer_paid, acceptor_paid)) = paid else {
+            return Ok(false);
+        };
+        let model = FeeModel::parse(&fee_model).unwrap_or(FeeModel::Both);
+        
+        // Once the required fees are confirmed (or waived), update trade to committed and start escrow
+        if fee_waived || model.is_satisfied(proposer_paid, acceptor_paid) {
+            let committed = sqlx::query(
                 "UPDATE trades 
                  SET status = 'committed', 
                      committed_at = NOW(),
+                     version = version + 1,
                      escrow_start_date = NOW(),
                      escrow_end_date = NOW() + INTERVAL '30 days'
                  WHERE id = $1 AND status = 'matched'"
             )
             .bind(trade_id)
-            .execute(mm.pool())
+            .execute(&mut *conn)
             .await
             .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+            if committed.rows_affected() > 0 {
+                let details = serde_json::json!({ "fee_waived": fee_waived });
+                TradeEventBmc::record(conn, trade_id, None, "trade_committed", details).await?;
+            }
             
             // Immediately move to escrow status
             sqlx::query(
                 "UPDATE trades 
-                 SET status = 'escrow'
+                 SET status = 'escrow', version = version + 1
                  WHERE id = $1 AND status = 'committed'"
             )
             .bind(trade_id)
-            .execute(mm.pool())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+            
+            Self::assign_escrow_puzzle_hash(conn, trade_id).await?;
+            return Ok(true);
+        }
+        
+        Ok(false)
+    }
+    
+    /// Derive and store the trade's escrow puzzle hash once all three signer keys are known.
+    /// Missing or invalid keys leave the hash unset (logged) rather than blocking escrow.
+    async fn assign_escrow_puzzle_hash(conn: &mut PgConnection, trade_id: i64) -> Result<()> {
+        let unassigned: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM trades WHERE id = $1 AND status = 'escrow' AND escrow_puzzle_hash IS NULL"
+        )
+        .bind(trade_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        // Already assigned (or not in escrow): nothing to do
+        if unassigned.is_none() {
+            return Ok(());
+        }
+        let Some(puzzle_hash) = Self::escrow_puzzle_hash_for(conn, trade_id).await? else {
+            return Ok(());
+        };
+        
+        sqlx::query("UPDATE trades SET escrow_puzzle_hash = $2, version = version + 1 WHERE id = $1")
+            .bind(trade_id)
+            .bind(&puzzle_hash)
+            .execute(conn)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Derive a matched trade's escrow puzzle hash from the three signer keys.
+    /// `None` (logged) when a key is missing or invalid.
+    async fn escrow_puzzle_hash_for(conn: &mut PgConnection, trade_id: i64) -> Result<Option<String>> {
+        let keys: Option<(Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT p.public_key, a.public_key,
+                    (SELECT value FROM exchange_config WHERE key = 'exchange_public_key'
+                     ORDER BY updated_at DESC, id DESC LIMIT 1)
+             FROM trades t
+             JOIN users p ON p.id = t.proposer_id
+             JOIN users a ON a.id = t.acceptor_id
+             WHERE t.id = $1"
+        )
+        .bind(trade_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let Some((Some(proposer_pk), Some(acceptor_pk), Some(exchange_pk))) = keys else {
+            tracing::warn!("Trade {} is missing signer public keys; escrow puzzle hash not set", trade_id);
+            return Ok(None);
+        };
+        
+        match derive_escrow_puzzle_hash(trade_id, &proposer_pk, &acceptor_pk, &exchange_pk) {
+            Ok(hash) => Ok(Some(hash)),
+            Err(e) => {
+                tracing::warn!("Could not derive escrow puzzle hash for trade {}: {}", trade_id, e);
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Create the caller's pending escrow deposit, paying the trade's escrow puzzle hash.
+    /// Every required commitment fee must be confirmed (or waived) first; a trade still
+    /// `committed` moves to `escrow` here, once its escrow address can be derived.
+    /// One live deposit per trade; the trade row is locked while it is checked.
+    pub async fn create_escrow_deposit(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        trade_id: i64,
+        amount_mojos: i64,
+        from_address: Option<String>,
+    ) -> Result<TradeTransaction> {
+        if amount_mojos <= 0 {
+            return Err(Error::InvalidState("Escrow deposit amount must be positive".to_string()));
+        }
+        
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        // Lock the trade so two deposits can't race past the duplicate check
+        let trade: Option<(String, String, bool, Option<String>, bool, bool)> = sqlx::query_as(
+            "SELECT t.status, t.fee_model, t.commitment_fee_waived, t.escrow_puzzle_hash,
+                    EXISTS (SELECT 1 FROM trade_transactions tx WHERE tx.trade_id = t.id AND tx.user_id = t.proposer_id
+                            AND tx.tx_type = 'commitment_fee' AND tx.status = 'confirmed'),
+                    EXISTS (SELECT 1 FROM trade_transactions tx WHERE tx.trade_id = t.id AND tx.user_id = t.acceptor_id
+                            AND tx.tx_type = 'commitment_fee' AND tx.status = 'confirmed')
+             FROM trades t WHERE t.id = $1 AND (t.proposer_id = $2 OR t.acceptor_id = $2)
+             FOR UPDATE"
+        )
+        .bind(trade_id)
+        .bind(ctx.user_id())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let (status, fee_model, fee_waived, puzzle_hash, proposer_paid, acceptor_paid) = trade
+            .ok_or_else(|| Error::NotFoundMsg("Trade not found".to_string()))?;
+        let model = FeeModel::parse(&fee_model).unwrap_or(FeeModel::Both);
+        let current = TradeStatus::parse(&status);
+        let in_escrow = current == Some(TradeStatus::Escrow);
+        
+        let can_fund = in_escrow || current.is_some_and(|s| s.can_transition_to(TradeStatus::Escrow));
+        if !can_fund || !(fee_waived || model.is_satisfied(proposer_paid, acceptor_paid)) {
+            return Err(Error::InvalidState(format!(
+                "Both parties must commit before escrow can be funded (trade status '{}')", status
+            )));
+        }
+        
+        let existing: Option<(String,)> = sqlx::query_as(
+            "SELECT status FROM trade_transactions
+             WHERE trade_id = $1 AND tx_type = 'escrow_deposit' AND status NOT IN ('failed', 'refunded')"
+        )
+        .bind(trade_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if let Some((status,)) = existing {
+            return Err(Error::InvalidState(format!("Escrow already has a deposit with status '{}'", status)));
+        }
+        
+        // The deposit needs somewhere to go before the trade enters escrow
+        let assigned = puzzle_hash.is_some();
+        let puzzle_hash = match puzzle_hash {
+            Some(hash) => hash,
+            None => Self::escrow_puzzle_hash_for(&mut tx, trade_id).await?.ok_or_else(|| Error::InvalidState(
+                "Escrow address not set: both parties and the exchange need signer public keys".to_string()
+            ))?,
+        };
+        
+        if !in_escrow || !assigned {
+            sqlx::query(
+                "UPDATE trades SET status = 'escrow', escrow_puzzle_hash = $2, version = version + 1, updated_at = NOW()
+                 WHERE id = $1"
+            )
+            .bind(trade_id)
+            .bind(&puzzle_hash)
+            .execute(&mut *tx)
             .await
             .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         }
+        if !in_escrow {
+            let details = serde_json::json!({ "escrow_puzzle_hash": puzzle_hash });
+            TradeEventBmc::record(&mut tx, trade_id, Some(ctx.user_id()), "escrow_started", details).await?;
+        }
+        
+        let deposit: TradeTransaction = sqlx::query_as(
+            "INSERT INTO trade_transactions (trade_id, user_id, tx_type, from_address, puzzle_hash, amount_mojos, status)
+             VALUES ($1, $2, $3, $4, $5, $6, 'pending')
+             RETURNING *"
+        )
+        .bind(trade_id)
+        .bind(ctx.user_id())
+        .bind(TxType::EscrowDeposit.to_string())
+        .bind(&from_address)
+        .bind(&puzzle_hash)
+        .bind(amount_mojos)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(deposit)
+    }
+    
+    /// Admin: record the spend releasing a trade's confirmed escrow deposit to one side
+    /// (`recipient` is `proposer` or `acceptor`). The release is already broadcast, so it
+    /// starts in `mempool`; the verifier confirms it once the escrow coin pays
+    /// `recipient_puzzle_hash` the deposited amount. The release and its audit row
+    /// commit together.
+    pub async fn admin_create_escrow_release(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        trade_id: i64,
+        recipient: &str,
+        recipient_puzzle_hash: &str,
+        tx_id: &str,
+    ) -> Result<TradeTransaction> {
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        // Lock the trade so two releases can't race past the duplicate check
+        let trade: Option<(String, i64, Option<i64>, Option<String>)> = sqlx::query_as(
+            "SELECT status, proposer_id, acceptor_id, escrow_coin_id FROM trades WHERE id = $1 FOR UPDATE"
+        )
+        .bind(trade_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let (status, proposer_id, acceptor_id, escrow_coin_id) = trade
+            .ok_or_else(|| Error::NotFoundMsg("Trade not found".to_string()))?;
+        
+        let recipient_id = match recipient {
+            "proposer" => Some(proposer_id),
+            "acceptor" => acceptor_id,
+            _ => return Err(Error::InvalidState(format!("Unknown escrow recipient '{}'", recipient))),
+        };
+        let (Some(recipient_id), Some(escrow_coin_id)) = (recipient_id, escrow_coin_id) else {
+            return Err(Error::InvalidState(format!(
+                "Trade has no confirmed escrow deposit to release (status '{}')", status
+            )));
+        };
+        if status != "escrow" {
+            return Err(Error::InvalidState(format!("Escrow can only be released from escrow (status '{}')", status)));
+        }
+        
+        let existing: Option<(String,)> = sqlx::query_as(
+            "SELECT status FROM trade_transactions
+             WHERE trade_id = $1 AND tx_type = 'escrow_release' AND status NOT IN ('failed', 'refunded')"
+        )
+        .bind(trade_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if let Some((status,)) = existing {
+            return Err(Error::InvalidState(format!("Escrow already has a release with status '{}'", status)));
+        }
+        
+        let release: TradeTransaction = sqlx::query_as(
+            "INSERT INTO trade_transactions
+                 (trade_id, user_id, tx_type, tx_id, coin_id, puzzle_hash, amount_mojos, status, mempool_at)
+             SELECT $1, $2, $3, $4, $5, $6, d.amount_mojos, 'mempool', NOW()
+             FROM trade_transactions d
+             WHERE d.trade_id = $1 AND d.tx_type = 'escrow_deposit' AND d.status = 'confirmed'
+             ORDER BY d.confirmed_at DESC LIMIT 1
+             RETURNING *"
+        )
+        .bind(trade_id)
+        .bind(recipient_id)
+        .bind(TxType::EscrowRelease.to_string())
+        .bind(tx_id)
+        .bind(&escrow_coin_id)
+        .bind(recipient_puzzle_hash)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?
+        .ok_or_else(|| Error::InvalidState("Trade has no confirmed escrow deposit to release".to_string()))?;
+        
+        let details = serde_json::json!({
+            "recipient": recipient,
+            "recipient_puzzle_hash": recipient_puzzle_hash,
+            "tx_id": tx_id,
+        });
+        AuditBmc::record_in(&mut tx, ctx, "release_escrow", "trade", &trade_id.to_string(), details).await?;
+        
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(release)
+    }
+    
+    /// A confirmed release pays out the escrow, which completes the trade
+    async fn complete_released_escrow(conn: &mut PgConnection, trade_id: i64) -> Result<()> {
+        let completed = sqlx::query(
+            "UPDATE trades
+             SET status = 'completed', completed_at = COALESCE(completed_at, NOW()), version = version + 1, updated_at = NOW()
+             WHERE id = $1 AND status = 'escrow'"
+        )
+        .bind(trade_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if completed.rows_affected() > 0 {
+            TradeEventBmc::record(conn, trade_id, None, "escrow_released", serde_json::json!({})).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Remember the coin a confirmed escrow deposit created, so it can be released later
+    async fn record_escrow_coin(conn: &mut PgConnection, trade_id: i64, coin_id: &str) -> Result<()> {
+        sqlx::query("UPDATE trades SET escrow_coin_id = $2, version = version + 1 WHERE id = $1")
+            .bind(trade_id)
+            .bind(coin_id)
+            .execute(conn)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
         
         Ok(())
     }
     
+    /// Replace one of the caller's failed commitment fee transactions with a fresh
+    /// pending row paying the current exchange wallet the same amount. The failed
+    /// row is archived. Returns the new row.
+    pub async fn retry(ctx: &Ctx, mm: &ModelManager, transaction_id: i64) -> Result<TradeTransaction> {
+        let user_id = ctx.user_id();
+        
+        let failed: TradeTransaction = sqlx::query_as(
+            "SELECT * FROM trade_transactions WHERE id = $1 AND user_id = $2"
+        )
+        .bind(transaction_id)
+        .bind(user_id)
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?
+        .ok_or_else(|| Error::NotFoundMsg("Transaction not found".to_string()))?;
+        
+        if failed.tx_type != "commitment_fee" {
+            return Err(Error::InvalidState(format!("Cannot retry a {} transaction", failed.tx_type)));
+        }
+        
+        // The trade must still take this side's commitment; also yields the current destination
+        let details = Self::get_commitment_details(ctx, mm, failed.trade_id).await?;
+        if details.user_commit_status == "not_required" {
+            return Err(Error::InvalidState(format!(
+                "No commitment fee required from the {} (fee model '{}')", details.user_role, details.fee_model
+            )));
+        }
+        if details.fee_waived {
+            return Err(Error::InvalidState("Commitment fee waived for this trade".to_string()));
+        }
+        
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        // Archive the failed row; nothing to archive means it is not failed or was already retried
+        let archived = sqlx::query(
+            "UPDATE trade_transactions SET archived_at = NOW()
+             WHERE id = $1 AND status = 'failed' AND archived_at IS NULL"
+        )
+        .bind(transaction_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if archived.rows_affected() == 0 {
+            return Err(Error::InvalidState(format!(
+                "Only failed transactions can be retried (status '{}'{})",
+                failed.status,
+                if failed.archived_at.is_some() { ", already retried" } else { "" }
+            )));
+        }
+        
+        let live: Option<(String,)> = sqlx::query_as(
+            "SELECT status FROM trade_transactions 
+             WHERE trade_id = $1 AND user_id = $2 AND tx_type = $3 
+             AND status NOT IN ('failed', 'refunded')"
+        )
+        .bind(failed.trade_id)
+        .bind(user_id)
+        .bind(&failed.tx_type)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if let Some((status,)) = live {
+            return Err(Error::InvalidState(format!(
+                "A {} transaction already exists with status '{}'", failed.tx_type, status
+            )));
+        }
+        
+        let fresh: TradeTransaction = sqlx::query_as(
+            "INSERT INTO trade_transactions (trade_id, user_id, tx_type, from_address, to_address, amount_mojos, status, retry_count)
+             VALUES ($1, $2, $3, $4, $5, $6, 'pending', $7)
+             RETURNING *"
+        )
+        .bind(failed.trade_id)
+        .bind(user_id)
+        .bind(&failed.tx_type)
+        .bind(&failed.from_address)
+        .bind(&details.exchange_wallet_address)
+        .bind(failed.amount_mojos)
+        .bind(failed.retry_count.unwrap_or(0) + 1)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(fresh)
+    }
+    
+    /// Delete one of the caller's pending transactions that was never submitted
+    /// (no tx_id), so the caller can create a fresh one. Submitted, confirmed or
+    /// failed rows are kept.
+    pub async fn cancel_pending(ctx: &Ctx, mm: &ModelManager, transaction_id: i64) -> Result<()> {
+        let user_id = ctx.user_id();
+        
+        let deleted = sqlx::query(
+            "DELETE FROM trade_transactions
+             WHERE id = $1 AND user_id = $2 AND status = 'pending' AND tx_id IS NULL"
+        )
+        .bind(transaction_id)
+        .bind(user_id)
+        .execute(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if deleted.rows_affected() > 0 {
+            return Ok(());
+        }
+        
+        let existing: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT status, tx_id FROM trade_transactions WHERE id = $1 AND user_id = $2"
+        )
+        .bind(transaction_id)
+        .bind(user_id)
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        match existing {
+            None => Err(Error::NotFoundMsg("Transaction not found".to_string())),
+            Some((status, Some(tx_id))) if status == "pending" => Err(Error::InvalidState(format!(
+                "Transaction was already submitted ({}); it can no longer be cancelled", tx_id
+            ))),
+            Some((status, _)) => Err(Error::InvalidState(format!(
+                "Only pending transactions can be cancelled (status '{}')", status
+            ))),
+        }
+    }
+    
+    /// Admin: waive (or reinstate) the commitment fee of a matched trade. Waiving
+    /// moves the trade to escrow right away. The change and its audit row commit
+    /// together. Returns the trade's status afterwards.
+    pub async fn admin_set_fee_waived(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        trade_id: i64,
+        waived: bool,
+        reason: Option<&str>,
+    ) -> Result<String> {
+        let mut tx = mm.pool().begin().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let status: Option<String> = sqlx::query_scalar("SELECT status FROM trades WHERE id = $1 FOR UPDATE")
+            .bind(trade_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        let status = status.ok_or_else(|| Error::NotFoundMsg("Trade not found".to_string()))?;
+        if status != "matched" {
+            return Err(Error::InvalidState(format!(
+                "Commitment fee can only be waived on a matched trade (status '{}')", status
+            )));
+        }
+        
+        sqlx::query(
+            "UPDATE trades SET commitment_fee_waived = $2, version = version + 1, updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(trade_id)
+        .bind(waived)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        if waived {
+            Self::check_and_update_trade_commitment(&mut tx, trade_id).await?;
+        }
+        
+        let status: String = sqlx::query_scalar("SELECT status FROM trades WHERE id = $1")
+            .bind(trade_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        let details = serde_json::json!({ "waived": waived, "status": status, "reason": reason });
+        AuditBmc::record_in(&mut tx, ctx, "waive_commitment_fee", "trade", &trade_id.to_string(), details).await?;
+        
+        tx.commit().await.map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        Ok(status)
+    }
+    
     /// Mark transaction as failed
     pub async fn fail(_ctx: &Ctx, mm: &ModelManager, tx_id: &str, error_message: &str) -> Result<()> {
         sqlx::query(
@@ -430,7 +1171,14 @@ impl TransactionBmc {
     }
     
     /// Get transactions for a trade
-    pub async fn list_for_trade(ctx: &Ctx, mm: &ModelManager, trade_id: i64) -> Result<Vec<TradeTransaction>> {
+    /// List a trade's transactions, optionally filtered by status and tx_type.
+    /// Unconfirmed transactions come first, newest first within each group.
+    pub async fn list_for_trade(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        trade_id: i64,
+        filter: &TransactionListFilter,
+    ) -> Result<Vec<TradeTransaction>> {
         let user_id = ctx.user_id();
         
         // Verify user is a participant
@@ -448,9 +1196,15 @@ impl TransactionBmc {
         }
         
         let transactions: Vec<TradeTransaction> = sqlx::query_as::<_, TradeTransaction>(
-            "SELECT * FROM trade_transactions WHERE trade_id = $1 ORDER BY created_at DESC"
+            "SELECT * FROM trade_transactions
+             WHERE trade_id = $1
+               AND ($2::TEXT IS NULL OR status = $2)
+               AND ($3::TEXT IS NULL OR tx_type = $3)
+             ORDER BY (status = 'confirmed') ASC, created_at DESC"
         )
         .bind(trade_id)
+        .bind(filter.status.as_deref())
+        .bind(filter.tx_type.as_deref())
         .fetch_all(mm.pool())
         .await
         .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
@@ -458,6 +1212,103 @@ impl TransactionBmc {
         Ok(transactions)
     }
     
+    /// Count confirmed transactions whose confirmation count the verifier keeps current
+    pub async fn count_tracked_confirmations(_ctx: &Ctx, mm: &ModelManager) -> Result<i64> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM trade_transactions WHERE status = 'confirmed' AND confirmed_height IS NOT NULL"
+        )
+        .fetch_one(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(count)
+    }
+    
+    /// Recompute `confirmations` for confirmed transactions from the current peak height
+    pub async fn refresh_confirmations(_ctx: &Ctx, mm: &ModelManager, current_height: i64) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE trade_transactions 
+             SET confirmations = GREATEST($1 - confirmed_height, 0)::INTEGER
+             WHERE status = 'confirmed' 
+             AND confirmed_height IS NOT NULL
+             AND confirmations IS DISTINCT FROM GREATEST($1 - confirmed_height, 0)::INTEGER"
+        )
+        .bind(current_height)
+        .execute(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    /// Hard-delete up to `limit` failed transactions created more than `older_than_days` ago,
+    /// archived or not; returns how many were deleted.
+    pub async fn prune_failed(mm: &ModelManager, older_than_days: i64, limit: i64) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM trade_transactions WHERE id IN (
+                 SELECT id FROM trade_transactions
+                 WHERE status = 'failed' AND created_at < NOW() - make_interval(days => $1::INT)
+                 LIMIT $2
+             )"
+        )
+        .bind(older_than_days)
+        .bind(limit)
+        .execute(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    /// Look up a transaction by its blockchain tx_id (system/admin use)
+    pub async fn get_by_tx_id(_ctx: &Ctx, mm: &ModelManager, tx_id: &str) -> Result<Option<TradeTransaction>> {
+        sqlx::query_as::<_, TradeTransaction>("SELECT * FROM trade_transactions WHERE tx_id = $1")
+            .bind(tx_id)
+            .fetch_optional(mm.pool())
+            .await
+            .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+    
+    /// Whether any commitment fee is still waiting for its payment to be reported
+    pub async fn has_unsubmitted_commitments(mm: &ModelManager) -> Result<bool> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM trade_transactions
+                            WHERE tx_type = 'commitment_fee' AND status = 'pending' AND tx_id IS NULL)"
+        )
+        .fetch_one(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+    
+    /// Attach an on-chain payment found by its commitment memo to the user's unsubmitted
+    /// commitment fee on the trade, as if they had submitted `tx_id` themselves. The payment
+    /// must cover the pending amount and not already belong to another row.
+    /// Returns the id of the row it was attached to.
+    pub async fn attach_memo_payment(
+        mm: &ModelManager,
+        trade_id: i64,
+        user_id: i64,
+        tx_id: &str,
+        amount_mojos: i64,
+    ) -> Result<Option<i64>> {
+        sqlx::query_scalar(
+            "UPDATE trade_transactions SET tx_id = $3, status = 'mempool', mempool_at = NOW()
+             WHERE id = (SELECT id FROM trade_transactions
+                         WHERE trade_id = $1 AND user_id = $2 AND tx_type = 'commitment_fee'
+                           AND status = 'pending' AND tx_id IS NULL AND amount_mojos <= $4
+                         ORDER BY created_at DESC LIMIT 1)
+               AND NOT EXISTS (SELECT 1 FROM trade_transactions WHERE tx_id = $3)
+             RETURNING id"
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .bind(tx_id)
+        .bind(amount_mojos)
+        .fetch_optional(mm.pool())
+        .await
+        .map_err(|e: sqlx::Error| Error::Database(e.to_string()))
+    }
+    
     /// Get pending transactions that need verification
     pub async fn list_pending_verification(_ctx: &Ctx, mm: &ModelManager) -> Result<Vec<TradeTransaction>> {
         let transactions: Vec<TradeTransaction> = sqlx::query_as::<_, TradeTransaction>(
@@ -470,3 +1321,444 @@ impl TransactionBmc {
         Ok(transactions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, commitment_fee, proposal_with_value, xch_offer};
+    use crate::model::{TradeBmc, TradeForCreate};
+
+    #[test]
+    fn test_commitment_memo_round_trip() {
+        for (trade_id, user_id) in [(1, 2), (42, 7), (i64::MAX, 0)] {
+            assert_eq!(parse_commitment_memo(&commitment_memo(trade_id, user_id)), Some((trade_id, user_id)));
+        }
+        assert_eq!(commitment_memo(42, 7), "DTREX-COMMIT-42-7");
+        assert_eq!(parse_commitment_memo(" DTREX-COMMIT-42-7\n"), Some((42, 7)));
+
+        for bad in ["", "DTREX-COMMIT-", "DTREX-COMMIT-42", "DTREX-COMMIT-42-", "DTREX-COMMIT--7",
+                    "DTREX-COMMIT-+42-7", "DTREX-COMMIT-42-7-1", "dtrex-commit-42-7", "DTREX-COMMIT-42-99999999999999999999"] {
+            assert_eq!(parse_commitment_memo(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_fee_rounding_at_boundaries() {
+        // $1 at $3/XCH is 333333333333.33 mojos
+        assert_eq!(FeeRounding::Ceil.usd_to_mojos(1.0, 3.0), 333_333_333_334);
+        assert_eq!(FeeRounding::Nearest.usd_to_mojos(1.0, 3.0), 333_333_333_333);
+        // $2 at $3/XCH is 666666666666.67 mojos: both round up
+        assert_eq!(FeeRounding::Ceil.usd_to_mojos(2.0, 3.0), 666_666_666_667);
+        assert_eq!(FeeRounding::Nearest.usd_to_mojos(2.0, 3.0), 666_666_666_667);
+        // Exact amounts are left alone
+        assert_eq!(FeeRounding::Ceil.usd_to_mojos(1.0, 8.0), 125_000_000_000);
+        assert_eq!(FeeRounding::Nearest.usd_to_mojos(1.0, 8.0), 125_000_000_000);
+        assert_eq!(FeeRounding::Ceil.usd_to_mojos(0.3, 3.0), 100_000_000_000);
+
+        assert_eq!(FeeRounding::parse("ceil"), Some(FeeRounding::Ceil));
+        assert_eq!(FeeRounding::parse("nearest"), Some(FeeRounding::Nearest));
+        assert_eq!(FeeRounding::parse("floor"), None);
+    }
+
+    /// Create a matched trade (alice proposes, bob offers XCH) with the given fee model
+    async fn matched_trade(mm: &ModelManager, alice: &Ctx, bob: &Ctx, fee_model: &str) -> i64 {
+        let trade_c = TradeForCreate { fee_model: Some(fee_model.to_string()), ..proposal_with_value(80.0) };
+        let trade_id = TradeBmc::create(alice, mm, trade_c).await.unwrap();
+        _dev_utils::match_trade(mm, bob, xch_offer(trade_id)).await;
+        trade_id
+    }
+
+    async fn pay_fee(mm: &ModelManager, ctx: &Ctx, trade_id: i64, tx_id: &str) {
+        let id = TransactionBmc::create(ctx, mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(ctx, mm, id, tx_id).await.unwrap();
+        TransactionBmc::confirm(ctx, mm, tx_id, "", 6, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_fee_models_commitment_requirements() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        TransactionBmc::set_exchange_wallet(&alice, &mm, "xch1exchange").await.unwrap();
+
+        // (fee model, proposer status, acceptor status) as seen by each side
+        let cases = [
+            ("both", "pending", "pending"),
+            ("proposer_only", "pending", "not_required"),
+            ("acceptor_only", "not_required", "pending"),
+        ];
+        for (model, proposer, acceptor) in cases {
+            let trade_id = matched_trade(&mm, &alice, &bob, model).await;
+            let mine = TransactionBmc::get_commitment_details(&alice, &mm, trade_id).await.unwrap();
+            assert_eq!(mine.fee_model, model);
+            assert_eq!((mine.user_commit_status.as_str(), mine.other_commit_status.as_str()), (proposer, acceptor));
+            let theirs = TransactionBmc::get_commitment_details(&bob, &mm, trade_id).await.unwrap();
+            assert_eq!((theirs.user_commit_status.as_str(), theirs.other_commit_status.as_str()), (acceptor, proposer));
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_auto_commit_honors_fee_model() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+
+        // Proposer-only: the proposer's fee alone commits the trade
+        let solo = matched_trade(&mm, &alice, &bob, "proposer_only").await;
+        pay_fee(&mm, &alice, solo, "0xsolo").await;
+        assert_eq!(TradeBmc::get(&alice, &mm, solo).await.unwrap().status, "escrow");
+
+        // Both: one fee is not enough
+        let shared = matched_trade(&mm, &alice, &bob, "both").await;
+        pay_fee(&mm, &alice, shared, "0xhalf").await;
+        assert_eq!(TradeBmc::get(&alice, &mm, shared).await.unwrap().status, "matched");
+        pay_fee(&mm, &bob, shared, "0xother").await;
+        assert_eq!(TradeBmc::get(&alice, &mm, shared).await.unwrap().status, "escrow");
+
+        // An unknown model is rejected at creation
+        let bad = TradeForCreate { fee_model: Some("nobody".to_string()), ..proposal_with_value(80.0) };
+        assert!(matches!(TradeBmc::create(&alice, &mm, bad).await, Err(Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_waived_fee_commits_without_payment() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        TransactionBmc::set_exchange_wallet(&alice, &mm, "xch1exchange").await.unwrap();
+
+        // Neither side has paid; the waiver alone commits the trade
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        assert_eq!(TransactionBmc::admin_set_fee_waived(&admin, &mm, trade_id, true, None).await.unwrap(), "escrow");
+        let trade = TradeBmc::get(&alice, &mm, trade_id).await.unwrap();
+        assert!(trade.commitment_fee_waived);
+        assert!(trade.committed_at.is_some());
+
+        let details = TransactionBmc::get_commitment_details(&bob, &mm, trade_id).await.unwrap();
+        assert!(details.fee_waived);
+        assert_eq!(details.commitment_fee_usd, 0.0);
+        assert_eq!((details.user_commit_status.as_str(), details.other_commit_status.as_str()), ("waived", "waived"));
+
+        // Too late to change once committed
+        let err = TransactionBmc::admin_set_fee_waived(&admin, &mm, trade_id, false, None).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+        let missing = TransactionBmc::admin_set_fee_waived(&admin, &mm, i64::MAX, true, None).await;
+        assert!(matches!(missing, Err(Error::NotFoundMsg(_))));
+        // Only the successful waiver was audited
+        assert_eq!(AuditBmc::list_for_target(&mm, "trade", &trade_id.to_string()).await.unwrap().len(), 1);
+
+        // Exempt sides stay not_required; reinstating before commit restores the fee
+        let solo = matched_trade(&mm, &alice, &bob, "proposer_only").await;
+        TransactionBmc::admin_set_fee_waived(&admin, &mm, solo, true, None).await.unwrap();
+        let theirs = TransactionBmc::get_commitment_details(&bob, &mm, solo).await.unwrap();
+        assert_eq!((theirs.user_commit_status.as_str(), theirs.other_commit_status.as_str()), ("not_required", "waived"));
+
+        let unwaived = matched_trade(&mm, &alice, &bob, "proposer_only").await;
+        assert_eq!(TransactionBmc::admin_set_fee_waived(&admin, &mm, unwaived, false, None).await.unwrap(), "matched");
+        let mine = TransactionBmc::get_commitment_details(&alice, &mm, unwaived).await.unwrap();
+        assert!(!mine.fee_waived);
+        assert!(mine.commitment_fee_usd > 0.0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_confirm_batch_updates_all() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+
+        let mut batch = Vec::new();
+        for (ctx, tx_id) in [(&alice, "0xb1"), (&bob, "0xb2")] {
+            let id = TransactionBmc::create(ctx, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+            TransactionBmc::submit_tx_id(ctx, &mm, id, tx_id).await.unwrap();
+            batch.push(TxConfirmation {
+                tx_id: tx_id.to_string(),
+                coin_id: format!("{}-coin", tx_id),
+                confirmations: 7,
+                confirmed_height: Some(500),
+            });
+        }
+        // Unknown tx_ids are skipped rather than failing the batch
+        batch.push(TxConfirmation { tx_id: "0xnope".to_string(), coin_id: String::new(), confirmations: 1, confirmed_height: None });
+
+        let confirmed = TransactionBmc::confirm_batch(&mm, &batch).await.unwrap();
+        assert_eq!(confirmed.len(), 2);
+
+        let txs = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &TransactionListFilter::default()).await.unwrap();
+        assert_eq!(txs.len(), 2);
+        for tx in &txs {
+            assert_eq!(tx.status, "confirmed");
+            assert_eq!(tx.confirmations, Some(7));
+            assert_eq!(tx.confirmed_height, Some(500));
+            assert_eq!(tx.coin_id.as_deref(), Some(format!("{}-coin", tx.tx_id.as_deref().unwrap()).as_str()));
+        }
+        // Both required fees landed in the batch, so the trade committed
+        assert_eq!(TradeBmc::get(&alice, &mm, trade_id).await.unwrap().status, "escrow");
+
+        // Re-applying is a no-op
+        assert!(TransactionBmc::confirm_batch(&mm, &batch).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_escrow_deposit_requires_commitment() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+
+        // Only one side has paid
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        pay_fee(&mm, &alice, trade_id, &format!("0xhalf-escrow-{}", trade_id)).await;
+        let err = TransactionBmc::create_escrow_deposit(&alice, &mm, trade_id, 5_000, None).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidState(msg) if msg == "Both parties must commit before escrow can be funded (trade status 'matched')"),
+            "{:?}", err
+        );
+
+        // A trade forced to committed without the fees doesn't count either
+        sqlx::query("UPDATE trades SET status = 'committed' WHERE id = $1").bind(trade_id).execute(mm.pool()).await.unwrap();
+        let err = TransactionBmc::create_escrow_deposit(&bob, &mm, trade_id, 5_000, None).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+
+        // Strangers can't see the trade at all
+        let carol = _dev_utils::seed_ctx(&mm, "carol_escrow").await;
+        let err = TransactionBmc::create_escrow_deposit(&carol, &mm, trade_id, 5_000, None).await.unwrap_err();
+        assert!(matches!(err, Error::NotFoundMsg(_)), "{:?}", err);
+        // The amount is checked before anything else
+        let err = TransactionBmc::create_escrow_deposit(&carol, &mm, trade_id, 0, None).await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg == "Escrow deposit amount must be positive"), "{:?}", err);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_escrow_deposit_without_signer_keys_stays_committed() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        pay_fee(&mm, &alice, trade_id, &format!("0xa-nokeys-{}", trade_id)).await;
+        pay_fee(&mm, &bob, trade_id, &format!("0xb-nokeys-{}", trade_id)).await;
+        sqlx::query("UPDATE trades SET status = 'committed', escrow_puzzle_hash = NULL WHERE id = $1")
+            .bind(trade_id)
+            .execute(mm.pool())
+            .await
+            .unwrap();
+
+        // No signer keys, no escrow address: the trade must not be left in escrow without a deposit
+        let err = TransactionBmc::create_escrow_deposit(&bob, &mm, trade_id, 5_000, None).await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg.starts_with("Escrow address not set")), "{:?}", err);
+        assert_eq!(TradeBmc::get(&alice, &mm, trade_id).await.unwrap().status, "committed");
+        let deposits: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trade_transactions WHERE trade_id = $1 AND tx_type = 'escrow_deposit'",
+        )
+        .bind(trade_id)
+        .fetch_one(mm.pool())
+        .await
+        .unwrap();
+        assert_eq!(deposits, 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_escrow_deposit_and_release() {
+        let (mm, alice, bob, admin) = _dev_utils::init_test_with_admin().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        pay_fee(&mm, &alice, trade_id, &format!("0xa-escrow-{}", trade_id)).await;
+        pay_fee(&mm, &bob, trade_id, &format!("0xb-escrow-{}", trade_id)).await;
+
+        // Committed but not yet in escrow, with the escrow address derived earlier
+        let puzzle_hash = format!("{:064x}", trade_id);
+        sqlx::query("UPDATE trades SET status = 'committed', escrow_puzzle_hash = $2 WHERE id = $1")
+            .bind(trade_id)
+            .bind(&puzzle_hash)
+            .execute(mm.pool())
+            .await
+            .unwrap();
+
+        // Nothing to release before a deposit
+        let err = TransactionBmc::admin_create_escrow_release(&admin, &mm, trade_id, "acceptor", &puzzle_hash, "0xearly").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+
+        let deposit = TransactionBmc::create_escrow_deposit(&bob, &mm, trade_id, 5_000, Some("xch1bob".to_string())).await.unwrap();
+        assert_eq!((deposit.tx_type.as_str(), deposit.status.as_str()), ("escrow_deposit", "pending"));
+        assert_eq!(deposit.puzzle_hash.as_deref(), Some(puzzle_hash.as_str()));
+        assert_eq!(deposit.amount_mojos, 5_000);
+        assert_eq!(TradeBmc::get(&alice, &mm, trade_id).await.unwrap().status, "escrow");
+        let started: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trade_events WHERE trade_id = $1 AND event_type = 'escrow_started'",
+        )
+        .bind(trade_id)
+        .fetch_one(mm.pool())
+        .await
+        .unwrap();
+        assert_eq!(started, 1);
+
+        // One live deposit per trade, whoever funds it
+        let err = TransactionBmc::create_escrow_deposit(&alice, &mm, trade_id, 5_000, None).await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg == "Escrow already has a deposit with status 'pending'"), "{:?}", err);
+
+        // pending -> mempool -> confirmed records the escrow coin on the trade
+        let deposit_tx = format!("0xdeposit-{}", trade_id);
+        TransactionBmc::submit_tx_id(&bob, &mm, deposit.id, &deposit_tx).await.unwrap();
+        let confirmation = TxConfirmation {
+            tx_id: deposit_tx,
+            coin_id: format!("0xescrowcoin-{}", trade_id),
+            confirmations: 6,
+            confirmed_height: Some(100),
+        };
+        TransactionBmc::confirm_batch(&mm, &[confirmation]).await.unwrap();
+        let trade = TradeBmc::get(&alice, &mm, trade_id).await.unwrap();
+        assert_eq!(trade.escrow_coin_id, Some(format!("0xescrowcoin-{}", trade_id)));
+
+        let release_tx = format!("0xrelease-{}", trade_id);
+        let release = TransactionBmc::admin_create_escrow_release(&admin, &mm, trade_id, "proposer", &puzzle_hash, &release_tx).await.unwrap();
+        assert_eq!((release.tx_type.as_str(), release.status.as_str()), ("escrow_release", "mempool"));
+        assert_eq!(release.user_id, alice.user_id());
+        assert_eq!(release.coin_id, trade.escrow_coin_id);
+        assert_eq!(release.amount_mojos, 5_000);
+
+        let err = TransactionBmc::admin_create_escrow_release(&admin, &mm, trade_id, "acceptor", &puzzle_hash, "0xagain").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+        let err = TransactionBmc::admin_create_escrow_release(&admin, &mm, trade_id, "exchange", &puzzle_hash, "0xwho").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)), "{:?}", err);
+
+        // The release was audited with it
+        let audited: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM admin_audit_log WHERE action = 'release_escrow' AND target_id = $1",
+        )
+        .bind(trade_id.to_string())
+        .fetch_one(mm.pool())
+        .await
+        .unwrap();
+        assert_eq!(audited, 1);
+
+        // Once the release confirms the escrow is paid out and the trade completes
+        let confirmation = TxConfirmation {
+            tx_id: release_tx,
+            coin_id: format!("0xpayout-{}", trade_id),
+            confirmations: 6,
+            confirmed_height: Some(110),
+        };
+        TransactionBmc::confirm_batch(&mm, &[confirmation]).await.unwrap();
+        let trade = TradeBmc::get(&alice, &mm, trade_id).await.unwrap();
+        assert_eq!(trade.status, "completed");
+        assert!(trade.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_retry_failed_commitment() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        TransactionBmc::set_exchange_wallet(&alice, &mm, "xch1old").await.unwrap();
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+
+        let tx = TradeTransactionForCreate {
+            from_address: Some("xch1alice".to_string()),
+            to_address: Some("xch1old".to_string()),
+            ..commitment_fee(trade_id, 5_000)
+        };
+        let id = TransactionBmc::create(&alice, &mm, tx).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xdropped").await.unwrap();
+
+        // Still live: nothing to retry
+        let early = TransactionBmc::retry(&alice, &mm, id).await;
+        assert!(matches!(early, Err(Error::InvalidState(_))));
+
+        TransactionBmc::fail(&alice, &mm, "0xdropped", "dropped from mempool").await.unwrap();
+        TransactionBmc::set_exchange_wallet(&alice, &mm, "xch1new").await.unwrap();
+
+        // Only the owner may retry
+        assert!(matches!(TransactionBmc::retry(&bob, &mm, id).await, Err(Error::NotFoundMsg(_))));
+
+        let fresh = TransactionBmc::retry(&alice, &mm, id).await.unwrap();
+        assert_ne!(fresh.id, id);
+        assert_eq!(fresh.status, "pending");
+        assert_eq!(fresh.trade_id, trade_id);
+        assert_eq!(fresh.amount_mojos, 5_000);
+        assert_eq!(fresh.to_address.as_deref(), Some("xch1new"));
+        assert_eq!(fresh.from_address.as_deref(), Some("xch1alice"));
+        assert_eq!(fresh.retry_count, Some(1));
+
+        let txs = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap();
+        let old = txs.iter().find(|t| t.id == id).unwrap();
+        assert_eq!(old.status, "failed");
+        assert!(old.archived_at.is_some());
+
+        // A failed row is retried at most once
+        assert!(matches!(TransactionBmc::retry(&alice, &mm, id).await, Err(Error::InvalidState(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_cancel_pending_commitment() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        let fee = || commitment_fee(trade_id, 1_000);
+
+        let id = TransactionBmc::create(&alice, &mm, fee()).await.unwrap();
+        // The duplicate guard blocks a second row until the first is cancelled
+        assert!(matches!(TransactionBmc::create(&alice, &mm, fee()).await, Err(Error::InvalidState(_))));
+        // Only the owner may cancel
+        assert!(matches!(TransactionBmc::cancel_pending(&bob, &mm, id).await, Err(Error::NotFoundMsg(_))));
+
+        TransactionBmc::cancel_pending(&alice, &mm, id).await.unwrap();
+        assert!(TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap().is_empty());
+        assert!(matches!(TransactionBmc::cancel_pending(&alice, &mm, id).await, Err(Error::NotFoundMsg(_))));
+
+        // Recreating works; once submitted the row stays
+        let id = TransactionBmc::create(&alice, &mm, fee()).await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xsent").await.unwrap();
+        let err = TransactionBmc::cancel_pending(&alice, &mm, id).await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg.contains("'mempool'")), "{:?}", err);
+        assert_eq!(TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_submit_tx_id_is_idempotent() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+        let id = TransactionBmc::create(&alice, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+
+        // First submit, then a retry with the same tx_id
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xretried").await.unwrap();
+        TransactionBmc::submit_tx_id(&alice, &mm, id, "0xretried").await.unwrap();
+        let txs = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &Default::default()).await.unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!((txs[0].status.as_str(), txs[0].tx_id.as_deref()), ("mempool", Some("0xretried")));
+
+        // A different tx_id, someone else's row or a missing row still fail
+        let err = TransactionBmc::submit_tx_id(&alice, &mm, id, "0xother").await.unwrap_err();
+        assert!(matches!(&err, Error::Conflict(msg) if msg.contains("0xretried")), "{:?}", err);
+        assert!(matches!(TransactionBmc::submit_tx_id(&bob, &mm, id, "0xretried").await, Err(Error::NotFoundMsg(_))));
+        assert!(matches!(TransactionBmc::submit_tx_id(&alice, &mm, i64::MAX, "0xretried").await, Err(Error::NotFoundMsg(_))));
+
+        // Once confirmed, the same tx_id is no longer a retry
+        TransactionBmc::confirm(&alice, &mm, "0xretried", "0xcoin", 1, Some(10)).await.unwrap();
+        let err = TransactionBmc::submit_tx_id(&alice, &mm, id, "0xretried").await.unwrap_err();
+        assert!(matches!(&err, Error::InvalidState(msg) if msg.contains("'confirmed'")), "{:?}", err);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_for_trade_filters() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+        let trade_id = matched_trade(&mm, &alice, &bob, "both").await;
+
+        pay_fee(&mm, &alice, trade_id, "0xdone").await;
+        let id = TransactionBmc::create(&bob, &mm, commitment_fee(trade_id, 1_000)).await.unwrap();
+        TransactionBmc::submit_tx_id(&bob, &mm, id, "0xwaiting").await.unwrap();
+
+        // Unfiltered: confirmed sorts last
+        let all = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &TransactionListFilter::default()).await.unwrap();
+        let statuses: Vec<&str> = all.iter().map(|t| t.status.as_str()).collect();
+        assert_eq!(statuses, ["mempool", "confirmed"]);
+
+        let mempool = TransactionListFilter { status: Some("mempool".to_string()), tx_type: None };
+        let waiting = TransactionBmc::list_for_trade(&alice, &mm, trade_id, &mempool).await.unwrap();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].tx_id.as_deref(), Some("0xwaiting"));
+
+        let refunds = TransactionListFilter { status: None, tx_type: Some("refund".to_string()) };
+        assert!(TransactionBmc::list_for_trade(&alice, &mm, trade_id, &refunds).await.unwrap().is_empty());
+
+        // Filters don't bypass the participant check
+        let carol = _dev_utils::seed_ctx(&mm, "carol_filter").await;
+        assert!(matches!(
+            TransactionBmc::list_for_trade(&carol, &mm, trade_id, &mempool).await,
+            Err(Error::Auth(_))
+        ));
+    }
+}
@@ -1,8 +1,9 @@
 use crate::store::Db;
 use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Row};
+use sqlx::{FromRow, PgExecutor, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // ============================================================================
@@ -15,12 +16,23 @@ pub struct User {
     pub username: String,
 }
 
+/// User info for display on trades
+#[derive(Debug, Clone, Serialize)]
+pub struct UserPublicInfo {
+    pub id: i64,
+    pub username: String,
+    pub verification_status: String,
+    pub reputation_score: f64,
+    pub total_trades: i32,
+}
+
 /// User info for admin dashboard
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct UserAdmin {
     pub id: i64,
     pub username: String,
     pub is_admin: bool,
+    pub trading_paused: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -105,17 +117,60 @@ impl UserBmc {
         Ok(user_id)
     }
     
-    /// List all users (admin only)
-    pub async fn list_all(db: &Db) -> Result<Vec<UserAdmin>, sqlx::Error> {
+    /// Replace a user's password hash with one made under the current Argon2 params
+    pub async fn rehash_password(db: &Db, user_id: i64, pwd_clear: &str, pwd_salt: &Uuid) -> Result<(), sqlx::Error> {
+        let pwd = hash_password(pwd_clear, pwd_salt)?;
+        sqlx::query("UPDATE users SET pwd = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(&pwd)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+    
+    /// List one page of users, newest first, plus the total user count (admin only)
+    pub async fn list_paginated(db: &Db, limit: i64, offset: i64) -> Result<(Vec<UserAdmin>, i64), sqlx::Error> {
         let users = sqlx::query_as::<_, UserAdmin>(
-            "SELECT id, username, COALESCE(is_admin, false) as is_admin, created_at 
+            "SELECT id, username, COALESCE(is_admin, false) as is_admin, trading_paused, created_at 
              FROM users 
-             ORDER BY created_at DESC"
+             ORDER BY created_at DESC, id DESC
+             LIMIT $1 OFFSET $2"
         )
+        .bind(limit)
+        .bind(offset)
         .fetch_all(db)
         .await?;
+
+        let (total,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
+            .fetch_one(db)
+            .await?;
         
-        Ok(users)
+        Ok((users, total))
+    }
+    
+    /// Pause or resume a user's trading; false when the user doesn't exist
+    pub async fn set_trading_paused<'e>(
+        executor: impl PgExecutor<'e>,
+        user_id: i64,
+        paused: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET trading_paused = $2, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(paused)
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Give the user a new token salt, invalidating every token issued so far
+    pub async fn rotate_token_salt(db: &Db, user_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET token_salt = $1 WHERE id = $2")
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .execute(db)
+            .await?;
+
+        Ok(())
     }
     
     /// Set user admin status (admin only)
@@ -129,26 +184,89 @@ impl UserBmc {
         Ok(())
     }
     
-    /// Get user trade stats
-    pub async fn get_user_stats(db: &Db, user_id: i64) -> Result<UserStats, sqlx::Error> {
-        // Count trades where user is proposer or acceptor
-        let trade_count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM trades WHERE proposer_id = $1 OR acceptor_id = $1"
+    /// Set the user's escrow signer public key (None clears it)
+    pub async fn set_public_key(db: &Db, user_id: i64, public_key: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET public_key = $1 WHERE id = $2")
+            .bind(public_key)
+            .bind(user_id)
+            .execute(db)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Public info for many users in one query, keyed by id. Unknown ids are
+    /// absent; missing columns fall back to unverified / 0.0 / 0.
+    pub async fn get_public_info_batch<'e>(
+        db: impl PgExecutor<'e>,
+        user_ids: &[i64],
+    ) -> Result<HashMap<i64, UserPublicInfo>, sqlx::Error> {
+        let rows: Vec<(i64, String, Option<String>, Option<f64>, Option<i32>)> = sqlx::query_as(
+            "SELECT id, username, verification_status, reputation_score::float8, total_trades
+             FROM users WHERE id = ANY($1)"
         )
-        .bind(user_id)
-        .fetch_one(db)
+        .bind(user_ids)
+        .fetch_all(db)
         .await?;
-        
-        let completed_count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM trades WHERE (proposer_id = $1 OR acceptor_id = $1) AND status = 'completed'"
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, username, verification_status, reputation_score, total_trades)| {
+                let info = UserPublicInfo {
+                    id,
+                    username,
+                    verification_status: verification_status.unwrap_or_else(|| "unverified".to_string()),
+                    reputation_score: reputation_score.unwrap_or(0.0),
+                    total_trades: total_trades.unwrap_or(0),
+                };
+                (id, info)
+            })
+            .collect())
+    }
+
+    /// Get user trade stats, reputation and last activity (`RowNotFound` for an unknown user)
+    pub async fn get_user_stats(db: &Db, user_id: i64) -> Result<UserStats, sqlx::Error> {
+        // Reputation, reviews received, and the last review the user wrote
+        let (reputation_score, review_count, last_review_at): (f64, i64, Option<chrono::DateTime<chrono::Utc>>) =
+            sqlx::query_as(
+                "SELECT COALESCE(u.reputation_score, 0)::float8,
+                        (SELECT COUNT(*) FROM trade_reviews WHERE reviewee_id = u.id),
+                        (SELECT MAX(created_at) FROM trade_reviews WHERE reviewer_id = u.id)
+                 FROM users u WHERE u.id = $1"
+            )
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        // Trades where user is proposer or acceptor, grouped by status
+        let by_status: Vec<(String, i64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            "SELECT status, COUNT(*), MAX(updated_at) FROM trades
+             WHERE proposer_id = $1 OR acceptor_id = $1
+             GROUP BY status"
         )
         .bind(user_id)
-        .fetch_one(db)
+        .fetch_all(db)
         .await?;
-        
+
+        let total_trades = by_status.iter().map(|(_, n, _)| n).sum();
+        let completed_trades = by_status
+            .iter()
+            .find(|(status, _, _)| status == "completed")
+            .map_or(0, |(_, n, _)| *n);
+        let last_active = by_status
+            .iter()
+            .map(|(_, _, at)| *at)
+            .chain(last_review_at)
+            .max();
+
         Ok(UserStats {
-            total_trades: trade_count.0,
-            completed_trades: completed_count.0,
+            total_trades,
+            completed_trades,
+            reputation_score,
+            review_count,
+            last_active,
+            trades_by_status: by_status.into_iter().map(|(status, n, _)| (status, n)).collect(),
         })
     }
 }
@@ -157,18 +275,49 @@ impl UserBmc {
 pub struct UserStats {
     pub total_trades: i64,
     pub completed_trades: i64,
+    pub reputation_score: f64,
+    /// Reviews received
+    pub review_count: i64,
+    /// Most recent trade update or review written, if any
+    pub last_active: Option<chrono::DateTime<chrono::Utc>>,
+    pub trades_by_status: std::collections::BTreeMap<String, i64>,
 }
 
 // ============================================================================
 // Password Hashing
 // ============================================================================
 
-/// Hash password using Argon2
+/// Argon2id costs for new hashes: `ARGON2_MEM_KIB`, `ARGON2_ITERS` and `ARGON2_PARALLELISM`,
+/// each defaulting to the argon2 crate's default. An invalid combination falls back to the defaults.
+fn argon2_params() -> Params {
+    let env = |key: &str, default: u32| {
+        std::env::var(key)
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(default)
+    };
+    Params::new(
+        env("ARGON2_MEM_KIB", Params::DEFAULT_M_COST),
+        env("ARGON2_ITERS", Params::DEFAULT_T_COST),
+        env("ARGON2_PARALLELISM", Params::DEFAULT_P_COST),
+        None,
+    )
+    .unwrap_or_else(|e| {
+        tracing::warn!("Invalid Argon2 params ({}), using defaults", e);
+        Params::default()
+    })
+}
+
+/// Hash password using Argon2 with the current params
 fn hash_password(pwd_clear: &str, salt_uuid: &Uuid) -> Result<String, sqlx::Error> {
+    hash_password_with(pwd_clear, salt_uuid, argon2_params())
+}
+
+fn hash_password_with(pwd_clear: &str, salt_uuid: &Uuid, params: Params) -> Result<String, sqlx::Error> {
     let salt = SaltString::encode_b64(salt_uuid.as_bytes())
         .map_err(|e| sqlx::Error::Protocol(format!("Salt encoding error: {}", e)))?;
 
-    let argon2 = Argon2::default();
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
     let pwd_hash = argon2
         .hash_password(pwd_clear.as_bytes(), &salt)
@@ -197,3 +346,137 @@ pub fn validate_password(pwd_clear: &str, pwd_hash: &str) -> Result<(), sqlx::Er
 
     Ok(())
 }
+
+/// Whether a stored hash used any Argon2 cost below the current params, so it
+/// should be replaced the next time the clear password is known (at login)
+pub fn password_needs_rehash(pwd_hash: &str) -> bool {
+    let Some(parsed) = pwd_hash.strip_prefix("#02#").and_then(|h| PasswordHash::new(h).ok()) else {
+        return false;
+    };
+    let Ok(stored) = Params::try_from(&parsed) else {
+        return false;
+    };
+    let current = argon2_params();
+    stored.m_cost() < current.m_cost() || stored.t_cost() < current.t_cost() || stored.p_cost() < current.p_cost()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils::{self, proposal_with_value};
+    use crate::model::{ReviewBmc, ReviewForCreate, TradeBmc, TradeForCreate};
+    use serde_json::json;
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_weak_hash_upgraded_on_login() {
+        _dev_utils::ensure_token_secret();
+        let mm = _dev_utils::init_test().await;
+        let user_c = UserForCreate { username: "rehash".to_string(), pwd_clear: "welcome123".to_string() };
+        let user_id = UserBmc::create(mm.db(), user_c).await.unwrap();
+        let user = UserBmc::first_by_username(mm.db(), "rehash").await.unwrap();
+        assert!(!password_needs_rehash(&user.pwd));
+
+        // A hash from before the costs were raised
+        let weak = hash_password_with("welcome123", &user.pwd_salt, Params::new(256, 1, 1, None).unwrap()).unwrap();
+        assert!(password_needs_rehash(&weak));
+        sqlx::query("UPDATE users SET pwd = $2 WHERE id = $1").bind(user_id).bind(&weak).execute(mm.db()).await.unwrap();
+        let stored_pwd = || async { UserBmc::first_by_username(mm.db(), "rehash").await.unwrap().pwd };
+
+        // A failed login leaves it alone
+        let wrong = json!({ "username": "rehash", "pwd": "wrong" });
+        assert!(crate::api::auth::rpc_login(mm.clone(), Some(wrong)).await.is_err());
+        assert_eq!(stored_pwd().await, weak);
+
+        let creds = json!({ "username": "rehash", "pwd": "welcome123" });
+        crate::api::auth::rpc_login(mm.clone(), Some(creds.clone())).await.unwrap();
+        let upgraded = stored_pwd().await;
+        assert!(upgraded.starts_with("#02#") && upgraded != weak);
+        assert!(!password_needs_rehash(&upgraded));
+
+        // The upgraded hash still logs in, and isn't rewritten again
+        crate::api::auth::rpc_login(mm.clone(), Some(creds)).await.unwrap();
+        assert_eq!(stored_pwd().await, upgraded);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_user_stats_enriched() {
+        let (mm, alice, bob) = _dev_utils::init_test_with_users().await;
+
+        let empty = UserBmc::get_user_stats(mm.db(), alice.user_id()).await.unwrap();
+        assert_eq!(empty.total_trades, 0);
+        assert_eq!(empty.review_count, 0);
+        assert!(empty.last_active.is_none());
+        assert!(empty.trades_by_status.is_empty());
+
+        let mut ids = Vec::new();
+        for title in ["Lamp", "Chair", "Desk"] {
+            let trade_c = TradeForCreate { item_title: title.to_string(), ..proposal_with_value(40.0) };
+            ids.push(TradeBmc::create(&alice, &mm, trade_c).await.unwrap());
+        }
+        // One completed with bob, one cancelled, one still a proposal
+        sqlx::query("UPDATE trades SET status = 'completed', acceptor_id = $2, completed_at = NOW() WHERE id = $1")
+            .bind(ids[0])
+            .bind(bob.user_id())
+            .execute(mm.db())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE trades SET status = 'cancelled' WHERE id = $1")
+            .bind(ids[1])
+            .execute(mm.db())
+            .await
+            .unwrap();
+        let review = ReviewForCreate {
+            trade_id: ids[0],
+            timeliness: 5,
+            packaging: 4,
+            value_honesty: 5,
+            state_accuracy: 4,
+            comment: None,
+        };
+        ReviewBmc::create(&bob, &mm, review).await.unwrap();
+
+        let stats = UserBmc::get_user_stats(mm.db(), alice.user_id()).await.unwrap();
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.completed_trades, 1);
+        assert_eq!(stats.review_count, 1);
+        assert!((stats.reputation_score - 4.5).abs() < 0.01);
+        assert!(stats.last_active.is_some());
+        assert_eq!(stats.trades_by_status.get("proposal"), Some(&1));
+        assert_eq!(stats.trades_by_status.get("completed"), Some(&1));
+        assert_eq!(stats.trades_by_status.get("cancelled"), Some(&1));
+
+        // Bob took part in one trade and wrote the review
+        let stats = UserBmc::get_user_stats(mm.db(), bob.user_id()).await.unwrap();
+        assert_eq!(stats.total_trades, 1);
+        assert_eq!(stats.review_count, 0);
+        assert!(stats.last_active.is_some());
+
+        assert!(matches!(UserBmc::get_user_stats(mm.db(), i64::MAX).await, Err(sqlx::Error::RowNotFound)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_list_paginated_window_and_total() {
+        let (mm, _, _) = _dev_utils::init_test_with_users().await;
+        let (_, before) = UserBmc::list_paginated(mm.db(), 1, 0).await.unwrap();
+        _dev_utils::seed_ctx(&mm, "carol").await;
+
+        let (all, total) = UserBmc::list_paginated(mm.db(), 1000, 0).await.unwrap();
+        assert_eq!(total, before + 1);
+        assert_eq!(all.len() as i64, total);
+
+        let (first, first_total) = UserBmc::list_paginated(mm.db(), 2, 0).await.unwrap();
+        let (second, second_total) = UserBmc::list_paginated(mm.db(), 2, 1).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+        let ids = |users: &[UserAdmin]| users.iter().map(|u| u.id).collect::<Vec<_>>();
+        assert_eq!(ids(&first), ids(&all[..2]));
+        assert_eq!(ids(&second), ids(&all[1..3]));
+
+        let (past_end, past_end_total) = UserBmc::list_paginated(mm.db(), 2, total).await.unwrap();
+        assert!(past_end.is_empty());
+        assert_eq!([first_total, second_total, past_end_total], [total; 3]);
+    }
+}
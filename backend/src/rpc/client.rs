@@ -20,15 +20,19 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 
-use std::process::Command;
 use std::path::Path;
+use std::time::Duration;
 
-use crate::app_state::AppState;
+use crate::app_state::{is_local_rpc_url, AppState};
+use crate::blockchain::hash::{CoinId, PuzzleHash};
+use crate::rpc::tls::{self, WALLET_CA_PATH, WALLET_CERT_PATH, WALLET_KEY_PATH};
+use crate::rpc::ChiaError;
 
 #[derive(Clone)]
 pub struct ChiaRpcClient {
     base_url: String,
     client: Client,
+    timeout: Duration,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,12 +46,54 @@ pub struct PushTxResponse {
     pub status: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// `PushTxResponse::status` when the node already held the same spend bundle
+pub const PUSH_TX_ALREADY_IN_MEMPOOL: &str = "ALREADY_IN_MEMPOOL";
+
+/// True when a push_tx rejection only means the bundle was submitted before.
+/// A `DOUBLE_SPEND` is a different bundle spending the same coins and stays an error.
+fn is_duplicate_submission(message: &str) -> bool {
+    !message.contains("DOUBLE_SPEND")
+        && (message.contains("ALREADY_INCLUDING_TRANSACTION") || message.contains("ALREADY_IN_MEMPOOL"))
+}
+
+/// Balance of one wallet, in mojos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WalletBalance {
+    pub wallet_id: u32,
+    pub confirmed_mojos: u64,
+    /// What can be sent right now (excludes coins locked in pending transactions)
+    pub spendable_mojos: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoinRecord {
-    pub coin_id: String,
-    pub puzzle_hash: String,
+    pub coin_id: CoinId,
+    pub puzzle_hash: PuzzleHash,
     pub amount: u64,
     pub spent: bool,
+    /// Height of the block that created the coin
+    pub confirmed_height: u64,
+}
+
+/// Default connect and per-request timeout for node/wallet RPC calls
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Whether `FEATURE_WALLET_PYTHON_PROXY` routes wallet calls through
+/// `ssl/wallet/wallet_rpc_proxy.py` instead of the native mTLS client
+pub fn python_wallet_proxy_enabled() -> bool {
+    std::env::var("FEATURE_WALLET_PYTHON_PROXY")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Connect and per-request timeout: `CHIA_RPC_TIMEOUT_SECS`, default 10 seconds
+pub fn rpc_timeout() -> Duration {
+    std::env::var("CHIA_RPC_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
 }
 
 impl ChiaRpcClient {
@@ -58,10 +104,11 @@ impl ChiaRpcClient {
     pub fn new_with_insecure(base_url: String, allow_insecure: bool) -> Self {
         let client = reqwest::Client::builder()
             .danger_accept_invalid_certs(allow_insecure)
+            .connect_timeout(rpc_timeout())
             .build()
             .unwrap();
 
-        Self { base_url, client }
+        Self::new_with_client(base_url, client)
     }
 
     pub fn from_env(base_url: String) -> Self {
@@ -72,41 +119,42 @@ impl ChiaRpcClient {
     }
 
     pub fn new_with_client(base_url: String, client: Client) -> Self {
-        Self { base_url, client }
+        Self { base_url, client, timeout: rpc_timeout() }
+    }
+
+    /// Override the per-request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
     }
 
     /// Construct client from AppState, wiring HTTPS client identity for the given mode (wallet/full_node)
-    pub async fn from_state(state: Arc<AppState>, mode: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Use correct default port and scheme for wallet/full_node if not specified
-        let mut base_url = state.rpc_url().await;
+    pub async fn from_state(state: Arc<AppState>, mode: &str) -> Result<Self, ChiaError> {
+        // Each mode keeps its own URL, already normalized when it was stored
+        let base_url = state.rpc_url_for_mode(mode).await;
         let needs_wallet = mode == "wallet";
-        let is_local = base_url.contains("localhost") || base_url.contains("127.0.0.1");
-        let has_port = base_url.contains(":9256") || base_url.contains(":8555");
-        // (removed unused needs_full_node)
-        if needs_wallet && (base_url.is_empty() || base_url.ends_with(":8555") || base_url == "http://localhost" || base_url == "https://localhost" || base_url == "http://127.0.0.1" || base_url == "https://127.0.0.1") {
-            base_url = "https://localhost:9256".to_string();
-        } else if base_url.is_empty() || base_url == "http://localhost" || base_url == "https://localhost" || base_url == "http://127.0.0.1" || base_url == "https://127.0.0.1" {
-            base_url = if needs_wallet {
-                "https://localhost:9256".to_string()
-            } else {
-                "https://localhost:8555".to_string()
-            };
-        } else if is_local && !has_port {
-            base_url = if needs_wallet {
-                base_url.replace("http://", "https://").replace("https://", "https://") + ":9256"
-            } else {
-                base_url.replace("http://", "https://").replace("https://", "https://") + ":8555"
-            };
-        } else if is_local && has_port {
-            base_url = base_url.replace("http://", "https://");
-        }
         tracing::info!("ChiaRpcClient: connection_mode = {}", mode);
         if needs_wallet {
-            // For wallet mode, we use the Python subprocess proxy
-            Ok(Self::new_with_client(base_url, Client::new()))
+            if python_wallet_proxy_enabled() {
+                // The proxy script does its own TLS
+                return Ok(Self::new_with_client(base_url, Client::new()));
+            }
+            let (cert_path, key_path) = state.get_ssl_paths_for_mode(mode).await;
+            let ca_path = state
+                .get_ssl_ca_path_for_mode(mode)
+                .await
+                .filter(|p| !p.is_empty())
+                .or_else(|| Path::new(WALLET_CA_PATH).exists().then(|| WALLET_CA_PATH.to_string()));
+            let client = tls::wallet_http_client(
+                cert_path.as_deref().unwrap_or(WALLET_CERT_PATH),
+                key_path.as_deref().unwrap_or(WALLET_KEY_PATH),
+                ca_path.as_deref(),
+                is_local_rpc_url(&base_url),
+            )?;
+            Ok(Self::new_with_client(base_url, client))
         } else {
             // For full_node, use reqwest as before
-            let mut builder = reqwest::Client::builder();
+            let mut builder = reqwest::Client::builder().connect_timeout(rpc_timeout());
             if let Some(ca_path) = state.get_ssl_ca_path_for_mode(mode).await {
                 if !ca_path.is_empty() {
                     tracing::info!("ChiaRpcClient: CA file path = {}", ca_path);
@@ -140,169 +188,203 @@ impl ChiaRpcClient {
                 .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
                 .unwrap_or(false);
             builder = builder.danger_accept_invalid_certs(allow_insecure);
-            let client = builder.build()?;
+            let client = builder
+                .build()
+                .map_err(|e| ChiaError::NotConfigured(format!("Failed to build HTTPS client: {}", e)))?;
             Ok(Self::new_with_client(base_url, client))
         }
     }
 
-    /// Push a spend bundle to the mempool
+    fn url(&self, endpoint: &str) -> Result<String, ChiaError> {
+        if self.base_url.trim().is_empty() {
+            return Err(ChiaError::NotConfigured("RPC URL is not set".to_string()));
+        }
+        Ok(format!("{}/{}", self.base_url, endpoint))
+    }
+
+    /// POST a JSON body to a node endpoint and return the decoded JSON reply.
+    /// Non-2xx statuses and `success: false` replies become `NodeRejected`.
+    async fn post_json(&self, endpoint: &str, body: Option<&serde_json::Value>) -> Result<serde_json::Value, ChiaError> {
+        let url = self.url(endpoint)?;
+        Self::log_request_details("POST", &url, body);
+        let mut request = self.client.post(&url).timeout(self.timeout);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        Self::log_response_details(response.status(), response.headers());
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(ChiaError::NodeRejected { status: status.as_u16(), message });
+        }
+
+        let result: serde_json::Value = response.json().await?;
+        if result.get("success").and_then(|v| v.as_bool()) == Some(false) {
+            let message = result.get("error").and_then(|v| v.as_str()).unwrap_or("request failed").to_string();
+            return Err(ChiaError::NodeRejected { status: status.as_u16(), message });
+        }
+        Ok(result)
+    }
+
+    /// Call a wallet RPC method. Goes over this client's mTLS connection, or through
+    /// the Python proxy (off the async runtime) when `FEATURE_WALLET_PYTHON_PROXY` is set.
+    pub async fn wallet_call(&self, method: &str, params: &serde_json::Value) -> Result<serde_json::Value, ChiaError> {
+        if !python_wallet_proxy_enabled() {
+            return self.post_json(method, Some(params)).await;
+        }
+        Self::call_wallet_proxy(&self.url(method)?, method, &params.to_string()).await
+    }
+
+    /// Call the wallet RPC through the Python proxy (handles the wallet's client-cert TLS)
+    async fn call_wallet_proxy(url: &str, method: &str, params: &str) -> Result<serde_json::Value, ChiaError> {
+        let cert_path = WALLET_CERT_PATH;
+        let key_path = WALLET_KEY_PATH;
+        let proxy_path = "ssl/wallet/wallet_rpc_proxy.py";
+        let mut cmd = tokio::process::Command::new("python3");
+        cmd.arg(proxy_path)
+            .arg(method)
+            .arg(params)
+            .env("CHIA_WALLET_RPC_URL", url)
+            .env("CHIA_WALLET_CERT", cert_path)
+            .env("CHIA_WALLET_KEY", key_path);
+        tracing::info!("[wallet_rpc_proxy] Running: python3 {} {} <params> (CHIA_WALLET_RPC_URL={}, CHIA_WALLET_CERT={}, CHIA_WALLET_KEY={})", proxy_path, method, url, cert_path, key_path);
+        Self::run_wallet_proxy(cmd).await
+    }
+
+    /// Run a proxy command without blocking the runtime and decode its JSON stdout.
+    /// A non-zero exit, non-JSON output or an `error` field is an error.
+    async fn run_wallet_proxy(mut cmd: tokio::process::Command) -> Result<serde_json::Value, ChiaError> {
+        let output = cmd
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| ChiaError::NotConfigured(format!("Failed to run wallet_rpc_proxy.py: {}", e)))?;
+        tracing::info!("[wallet_rpc_proxy] status: {:?}", output.status);
+        tracing::info!("[wallet_rpc_proxy] stdout: {}", String::from_utf8_lossy(&output.stdout));
+        tracing::info!("[wallet_rpc_proxy] stderr: {}", String::from_utf8_lossy(&output.stderr));
+        if !output.status.success() {
+            return Err(ChiaError::Network(format!(
+                "wallet_rpc_proxy.py failed\nstdout: {}\nstderr: {}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).map_err(|e| {
+            ChiaError::Parse(format!("wallet_rpc_proxy.py output is not JSON: {}\nRaw output: {}", e, stdout))
+        })?;
+        if let Some(error) = parsed.get("error") {
+            return Err(ChiaError::NodeRejected { status: 200, message: error.to_string() });
+        }
+        Ok(parsed)
+    }
+
+    /// Push a spend bundle to the mempool.
+    /// Re-submitting a bundle the node already holds succeeds with
+    /// status `ALREADY_IN_MEMPOOL`, so retries are safe.
     pub async fn push_tx(
         &self,
         spend_bundle_hex: &str,
-    ) -> Result<PushTxResponse, Box<dyn std::error::Error + Send + Sync>> {
-        // If wallet mode, use Python subprocess
-        if self.base_url.contains(":9256") {
-            // Find PEM paths
-            let cert_path = "ssl/wallet/private_wallet.crt";
-            let key_path = "ssl/wallet/private_wallet.key";
-            let proxy_path = "ssl/wallet/wallet_rpc_proxy.py";
-            let method = "push_tx";
-            let params = json!({ "spend_bundle": spend_bundle_hex }).to_string();
-            let mut cmd = Command::new("python3");
-            cmd.arg(proxy_path)
-                .arg(method)
-                .arg(&params)
-                .env("CHIA_WALLET_RPC_URL", format!("{}/{}", self.base_url, method))
-                .env("CHIA_WALLET_CERT", cert_path)
-                .env("CHIA_WALLET_KEY", key_path);
-            tracing::info!("[wallet_rpc_proxy] Running: python3 {} {} <params> (CHIA_WALLET_RPC_URL={}, CHIA_WALLET_CERT={}, CHIA_WALLET_KEY={})", proxy_path, method, format!("{}/{}", self.base_url, method), cert_path, key_path);
-            let output = cmd.output()?;
-            tracing::info!("[wallet_rpc_proxy] status: {:?}", output.status);
-            tracing::info!("[wallet_rpc_proxy] stdout: {}", String::from_utf8_lossy(&output.stdout));
-            tracing::info!("[wallet_rpc_proxy] stderr: {}", String::from_utf8_lossy(&output.stderr));
-            if !output.status.success() {
-                let err = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("wallet_rpc_proxy.py failed: {}\nstdout: {}\nstderr: {}", err, String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)).into());
-            }
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let parsed: serde_json::Value = serde_json::from_str(&stdout)
-                .map_err(|e| format!("Failed to parse wallet_rpc_proxy.py output as JSON: {}\nRaw output: {}", e, stdout))?;
-            if let Some(error) = parsed.get("error") {
-                return Err(format!("wallet_rpc_proxy.py error: {}\nRaw output: {}", error, stdout).into());
+    ) -> Result<PushTxResponse, ChiaError> {
+        let body = json!({ "spend_bundle": spend_bundle_hex });
+        let result = if self.base_url.contains(":9256") {
+            self.wallet_call("push_tx", &body).await
+        } else {
+            self.post_json("push_tx", Some(&body)).await
+        };
+        let result = match result {
+            Ok(result) => result,
+            Err(ChiaError::NodeRejected { message, .. }) if is_duplicate_submission(&message) => {
+                tracing::info!("Push TX: spend bundle already in mempool ({})", message);
+                return Ok(PushTxResponse { success: true, status: PUSH_TX_ALREADY_IN_MEMPOOL.to_string() });
             }
-            let result: PushTxResponse = serde_json::from_value(parsed)?;
-            Ok(result)
+            Err(e) => return Err(e),
+        };
+        let result: PushTxResponse = serde_json::from_value(result)?;
+        tracing::info!("Push TX result: {:?}", result);
+        Ok(result)
+    }
+
+    /// Balance of a wallet (`get_wallet_balance`)
+    pub async fn get_wallet_balance(&self, wallet_id: u32) -> Result<WalletBalance, ChiaError> {
+        let body = json!({ "wallet_id": wallet_id });
+        let result = if self.base_url.contains(":9256") {
+            self.wallet_call("get_wallet_balance", &body).await?
         } else {
-            // Full node: use reqwest
-            let url = format!("{}/push_tx", self.base_url);
-            let body = json!({ "spend_bundle": spend_bundle_hex });
-            Self::log_request_details("POST", &url, Some(&body));
-            let response = self.client.post(&url).json(&body).send().await?;
-            Self::log_response_details(response.status(), response.headers());
-            let result = response.json::<PushTxResponse>().await?;
-            tracing::info!("Push TX result: {:?}", result);
-            Ok(result)
-        }
+            self.post_json("get_wallet_balance", Some(&body)).await?
+        };
+
+        let balance = result
+            .get("wallet_balance")
+            .ok_or_else(|| ChiaError::Parse("get_wallet_balance response has no wallet_balance".to_string()))?;
+        let mojos = |field: &str| {
+            balance
+                .get(field)
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| ChiaError::Parse(format!("wallet_balance.{} missing or not a mojo amount", field)))
+        };
+        Ok(WalletBalance {
+            wallet_id,
+            confirmed_mojos: mojos("confirmed_wallet_balance")?,
+            spendable_mojos: mojos("spendable_balance")?,
+        })
     }
 
     /// Get coin records by puzzle hash
     pub async fn get_coin_records_by_puzzle_hash(
         &self,
-        puzzle_hash: &str,
-    ) -> Result<Vec<CoinRecord>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/get_coin_records_by_puzzle_hash", self.base_url);
-
+        puzzle_hash: &PuzzleHash,
+    ) -> Result<Vec<CoinRecord>, ChiaError> {
         let body = json!({
             "puzzle_hash": puzzle_hash,
             "include_spent_coins": false
         });
 
-        Self::log_request_details("POST", &url, Some(&body));
-        let response = self.client.post(&url).json(&body).send().await?;
-        Self::log_response_details(response.status(), response.headers());
-        let result: serde_json::Value = response.json().await?;
-        let mut out: Vec<CoinRecord> = Vec::new();
-
-        if let Some(arr) = result.get("coin_records").and_then(|v| v.as_array()) {
-            for cr in arr {
-                let coin = cr.get("coin").unwrap_or(&serde_json::Value::Null);
-                let coin_id = coin
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let ph = coin
-                    .get("puzzle_hash")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let amount = coin.get("amount").and_then(|v| v.as_u64()).unwrap_or(0);
-                let spent = cr.get("spent").and_then(|v| v.as_bool()).unwrap_or(false);
-
-                if !ph.is_empty() {
-                    out.push(CoinRecord {
-                        coin_id,
-                        puzzle_hash: ph,
-                        amount,
-                        spent,
-                    });
-                }
-            }
-        }
+        let result = self.post_json("get_coin_records_by_puzzle_hash", Some(&body)).await?;
+        Ok(coin_records_from(&result))
+    }
 
-        Ok(out)
+    /// Get the coins created by spending `parent_ids`. A spent escrow coin's
+    /// children show where its funds went.
+    pub async fn get_coin_records_by_parent_ids(
+        &self,
+        parent_ids: &[CoinId],
+    ) -> Result<Vec<CoinRecord>, ChiaError> {
+        let body = json!({
+            "parent_ids": parent_ids,
+            "include_spent_coins": true
+        });
+
+        let result = self.post_json("get_coin_records_by_parent_ids", Some(&body)).await?;
+        Ok(coin_records_from(&result))
     }
 
     /// Get puzzle and solution for a coin
     pub async fn get_puzzle_and_solution(
         &self,
-        coin_id: &str,
+        coin_id: &CoinId,
         height: u64,
-    ) -> Result<PuzzleAndSolution, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/get_puzzle_and_solution", self.base_url);
-
+    ) -> Result<PuzzleAndSolution, ChiaError> {
         let body = json!({
             "coin_id": coin_id,
             "height": height
         });
 
-        Self::log_request_details("POST", &url, Some(&body));
-        let response = self.client.post(&url).json(&body).send().await?;
-        Self::log_response_details(response.status(), response.headers());
-        let result = response.json::<PuzzleAndSolution>().await?;
-        Ok(result)
+        let result = self.post_json("get_puzzle_and_solution", Some(&body)).await?;
+        Ok(serde_json::from_value(result)?)
     }
 
     /// Get blockchain state
     pub async fn get_blockchain_state(
         &self,
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
-        // If wallet mode, use Python subprocess proxy to respect insecure mode
+    ) -> Result<serde_json::Value, ChiaError> {
+        // A wallet has no blockchain state; its sync status stands in
         if self.base_url.contains(":9256") {
-            let cert_path = "ssl/wallet/private_wallet.crt";
-            let key_path = "ssl/wallet/private_wallet.key";
-            let proxy_path = "ssl/wallet/wallet_rpc_proxy.py";
-            let method = "get_sync_status";
-            let params = "{}";
-            let mut cmd = std::process::Command::new("python3");
-            cmd.arg(proxy_path)
-                .arg(method)
-                .arg(params)
-                .env("CHIA_WALLET_RPC_URL", format!("{}/{}", self.base_url, method))
-                .env("CHIA_WALLET_CERT", cert_path)
-                .env("CHIA_WALLET_KEY", key_path);
-            tracing::info!("[wallet_rpc_proxy] Running: python3 {} {} <params> (CHIA_WALLET_RPC_URL={}, CHIA_WALLET_CERT={}, CHIA_WALLET_KEY={})", proxy_path, method, format!("{}/{}", self.base_url, method), cert_path, key_path);
-            let output = cmd.output()?;
-            tracing::info!("[wallet_rpc_proxy] status: {:?}", output.status);
-            tracing::info!("[wallet_rpc_proxy] stdout: {}", String::from_utf8_lossy(&output.stdout));
-            tracing::info!("[wallet_rpc_proxy] stderr: {}", String::from_utf8_lossy(&output.stderr));
-            if !output.status.success() {
-                let err = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("wallet_rpc_proxy.py failed: {}\nstdout: {}\nstderr: {}", err, String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)).into());
-            }
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let parsed: serde_json::Value = serde_json::from_str(&stdout)
-                .map_err(|e| format!("Failed to parse wallet_rpc_proxy.py output as JSON: {}\nRaw output: {}", e, stdout))?;
-            if let Some(error) = parsed.get("error") {
-                return Err(format!("wallet_rpc_proxy.py error: {}\nRaw output: {}", error, stdout).into());
-            }
-            Ok(parsed)
+            self.wallet_call("get_sync_status", &json!({})).await
         } else {
-            let url = format!("{}/get_blockchain_state", self.base_url);
-            Self::log_request_details("POST", &url, None);
-            let response = self.client.post(&url).send().await?;
-            Self::log_response_details(response.status(), response.headers());
-            let result = response.json::<serde_json::Value>().await?;
+            let result = self.post_json("get_blockchain_state", None).await?;
             // Extract the blockchain_state from the response
             if let Some(blockchain_state) = result.get("blockchain_state") {
                 Ok(blockchain_state.clone())
@@ -312,75 +394,62 @@ impl ChiaRpcClient {
         }
     }
 
-    /// Get a transaction record from the wallet by transaction ID
-    /// This is used to verify commitment fee payments
+    /// Get a transaction record from the wallet by transaction ID (wallet RPC client only,
+    /// see `from_state(state, "wallet")`). This is used to verify commitment fee payments
     pub async fn get_transaction(
         &self,
         transaction_id: &str,
-    ) -> Result<TransactionRecord, Box<dyn std::error::Error + Send + Sync>> {
-        // Use wallet RPC endpoint
-        let wallet_url = self.base_url.replace(":8555", ":9256");
-        let url = format!("{}/get_transaction", wallet_url);
-        
+    ) -> Result<TransactionRecord, ChiaError> {
         let body = json!({
             "transaction_id": transaction_id
         });
 
-        // Use wallet proxy for SSL
-        let cert_path = "ssl/wallet/private_wallet.crt";
-        let key_path = "ssl/wallet/private_wallet.key";
-        let proxy_path = "ssl/wallet/wallet_rpc_proxy.py";
-        
-        let mut cmd = Command::new("python3");
-        cmd.arg(proxy_path)
-            .arg("get_transaction")
-            .arg(body.to_string())
-            .env("CHIA_WALLET_RPC_URL", &url)
-            .env("CHIA_WALLET_CERT", cert_path)
-            .env("CHIA_WALLET_KEY", key_path);
-        
-        tracing::info!("[wallet_rpc_proxy] Getting transaction: {}", transaction_id);
-        let output = cmd.output()?;
-        
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Failed to get transaction: {}", err).into());
-        }
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let parsed: serde_json::Value = serde_json::from_str(&stdout)?;
-        
-        if let Some(error) = parsed.get("error") {
-            return Err(format!("Transaction lookup error: {}", error).into());
-        }
+        tracing::info!("Getting wallet transaction: {}", transaction_id);
+        let parsed = self.wallet_call("get_transaction", &body).await?;
         
         // Parse transaction from response
         let tx = parsed.get("transaction")
-            .ok_or("No transaction in response")?;
+            .ok_or_else(|| ChiaError::Parse("No transaction in response".to_string()))?;
         
-        Ok(TransactionRecord {
-            transaction_id: tx.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            confirmed: tx.get("confirmed").and_then(|v| v.as_bool()).unwrap_or(false),
-            confirmed_at_height: tx.get("confirmed_at_height").and_then(|v| v.as_u64()),
-            amount: tx.get("amount").and_then(|v| v.as_u64()).unwrap_or(0),
-            fee_amount: tx.get("fee_amount").and_then(|v| v.as_u64()).unwrap_or(0),
-            to_address: tx.get("to_address").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            sent_to: tx.get("sent_to").and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0) > 0,
-        })
+        Ok(transaction_record_from(tx))
+    }
+
+    /// The wallet's `limit` most recent transactions, newest first (wallet RPC client only).
+    /// Used to find payments that were never reported through the API.
+    pub async fn get_transactions(&self, wallet_id: u32, limit: u32) -> Result<Vec<TransactionRecord>, ChiaError> {
+        let body = json!({ "wallet_id": wallet_id, "start": 0, "end": limit, "reverse": true });
+        let parsed = self.wallet_call("get_transactions", &body).await?;
+
+        Ok(parsed
+            .get("transactions")
+            .and_then(|v| v.as_array())
+            .map(|txs| txs.iter().map(transaction_record_from).collect())
+            .unwrap_or_default())
+    }
+
+    /// Look up a single mempool item by transaction ID.
+    /// Returns `Ok(None)` when the node reports the tx is not in the mempool, and
+    /// an error if the node does not support the endpoint (callers fall back to `is_tx_in_mempool`).
+    pub async fn get_mempool_item_by_tx_id(
+        &self,
+        tx_id: &str,
+    ) -> Result<Option<serde_json::Value>, ChiaError> {
+        let body = json!({ "tx_id": tx_id });
+
+        match self.post_json("get_mempool_item_by_tx_id", Some(&body)).await {
+            Ok(result) => Ok(result.get("mempool_item").filter(|v| !v.is_null()).cloned()),
+            // The node answers a miss with success=false and a "not in the mempool" error
+            Err(ChiaError::NodeRejected { message, .. }) if message.contains("not in the mempool") => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
     /// Check if a transaction is in the mempool
     pub async fn is_tx_in_mempool(
         &self,
         transaction_id: &str,
-    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/get_all_mempool_tx_ids", self.base_url);
-        
-        Self::log_request_details("POST", &url, None);
-        let response = self.client.post(&url).json(&json!({})).send().await?;
-        Self::log_response_details(response.status(), response.headers());
-        
-        let result: serde_json::Value = response.json().await?;
+    ) -> Result<bool, ChiaError> {
+        let result = self.post_json("get_all_mempool_tx_ids", Some(&json!({}))).await?;
         
         if let Some(tx_ids) = result.get("tx_ids").and_then(|v| v.as_array()) {
             for tx_id in tx_ids {
@@ -396,10 +465,10 @@ impl ChiaRpcClient {
     }
 
     /// Check node health
-    pub async fn health_check(&self) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/healthz", self.base_url);
+    pub async fn health_check(&self) -> Result<bool, ChiaError> {
+        let url = self.url("healthz")?;
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
 
         Ok(response.status().is_success())
     }
@@ -420,6 +489,82 @@ pub struct TransactionRecord {
     pub fee_amount: u64,
     pub to_address: String,
     pub sent_to: bool,
+    /// Memos on the transaction's coins, decoded to text where they are UTF-8
+    pub memos: Vec<String>,
+}
+
+/// Parse one wallet transaction record
+fn transaction_record_from(tx: &serde_json::Value) -> TransactionRecord {
+    TransactionRecord {
+        transaction_id: tx.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        confirmed: tx.get("confirmed").and_then(|v| v.as_bool()).unwrap_or(false),
+        confirmed_at_height: tx.get("confirmed_at_height").and_then(|v| v.as_u64()),
+        amount: tx.get("amount").and_then(|v| v.as_u64()).unwrap_or(0),
+        fee_amount: tx.get("fee_amount").and_then(|v| v.as_u64()).unwrap_or(0),
+        to_address: tx.get("to_address").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        sent_to: tx.get("sent_to").and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0) > 0,
+        memos: memos_from(tx.get("memos")),
+    }
+}
+
+/// Parse the `coin_records` array of a full node response, skipping records
+/// without a valid puzzle hash. Nodes don't send the coin's name, so the id is
+/// computed from the parent, puzzle hash and amount when it is missing.
+fn coin_records_from(result: &serde_json::Value) -> Vec<CoinRecord> {
+    let mut out: Vec<CoinRecord> = Vec::new();
+
+    if let Some(arr) = result.get("coin_records").and_then(|v| v.as_array()) {
+        for cr in arr {
+            let coin = cr.get("coin").unwrap_or(&serde_json::Value::Null);
+            let hash_field = |key: &str| coin.get(key).and_then(|v| v.as_str()).unwrap_or("");
+            let amount = coin.get("amount").and_then(|v| v.as_u64()).unwrap_or(0);
+            let spent = cr.get("spent").and_then(|v| v.as_bool()).unwrap_or(false);
+            let confirmed_height = cr.get("confirmed_block_index").and_then(|v| v.as_u64()).unwrap_or(0);
+
+            let Ok(puzzle_hash) = hash_field("puzzle_hash").parse::<PuzzleHash>() else {
+                continue;
+            };
+            let coin_id = hash_field("name").parse::<CoinId>().ok().or_else(|| {
+                let parent = hash_field("parent_coin_info").parse::<CoinId>().ok()?;
+                Some(CoinId::compute(&parent, &puzzle_hash, amount))
+            });
+
+            if let Some(coin_id) = coin_id {
+                out.push(CoinRecord {
+                    coin_id,
+                    puzzle_hash,
+                    amount,
+                    spent,
+                    confirmed_height,
+                });
+            }
+        }
+    }
+
+    out
+}
+
+/// Flatten a wallet `memos` field. Newer wallets send `{coin_id: [memo, ...]}`,
+/// older ones `[[coin_id, [memo, ...]], ...]`; memos are hex-encoded bytes.
+fn memos_from(memos: Option<&serde_json::Value>) -> Vec<String> {
+    let lists: Vec<&serde_json::Value> = match memos {
+        Some(serde_json::Value::Object(by_coin)) => by_coin.values().collect(),
+        Some(serde_json::Value::Array(pairs)) => pairs.iter().filter_map(|p| p.get(1)).collect(),
+        _ => return Vec::new(),
+    };
+
+    lists
+        .into_iter()
+        .filter_map(|list| list.as_array())
+        .flatten()
+        .filter_map(|memo| memo.as_str())
+        .map(|memo| {
+            hex::decode(memo.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .unwrap_or_else(|| memo.to_string())
+        })
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -434,9 +579,253 @@ pub struct BlockchainState {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_memos_from_both_wallet_shapes() {
+        let memo_hex = hex::encode("DTREX-COMMIT-7-3");
+        let by_coin = json!({ "0xcoin": [format!("0x{}", memo_hex)] });
+        assert_eq!(memos_from(Some(&by_coin)), vec!["DTREX-COMMIT-7-3"]);
+
+        let pairs = json!([["0xcoin", [memo_hex, "0xff"]]]);
+        assert_eq!(memos_from(Some(&pairs)), vec!["DTREX-COMMIT-7-3", "0xff"]);
+
+        assert!(memos_from(None).is_empty());
+    }
+
+    #[test]
+    fn test_coin_records_from_normalizes_hashes() {
+        let parent = format!("0x{}", "ab".repeat(32));
+        let result = json!({ "coin_records": [
+            { "coin": { "parent_coin_info": parent, "puzzle_hash": "CD".repeat(32), "amount": 1_000 }, "confirmed_block_index": 9, "spent": true },
+            { "coin": { "name": "11".repeat(32), "parent_coin_info": parent, "puzzle_hash": format!("0x{}", "cd".repeat(32)), "amount": 5 } },
+            { "coin": { "parent_coin_info": parent, "puzzle_hash": "0xcd", "amount": 5 } },
+            { "coin": { "puzzle_hash": "cd".repeat(32), "amount": 5 } }
+        ]});
+
+        let records = coin_records_from(&result);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].puzzle_hash, records[1].puzzle_hash);
+        assert_eq!(records[0].puzzle_hash.to_string(), format!("0x{}", "cd".repeat(32)));
+        let parent: CoinId = parent.parse().unwrap();
+        assert_eq!(records[0].coin_id, CoinId::compute(&parent, &records[0].puzzle_hash, 1_000));
+        assert_eq!((records[0].confirmed_height, records[0].spent), (9, true));
+        assert_eq!(records[1].coin_id, CoinId::from([0x11; 32]));
+    }
+
     #[test]
     fn test_client_creation() {
         let client = ChiaRpcClient::new("http://localhost:8555".to_string());
         assert_eq!(client.base_url, "http://localhost:8555");
     }
+
+    #[tokio::test]
+    async fn test_from_state_uses_the_mode_url() {
+        let state = Arc::new(AppState::new("https://node.example.com:8555".to_string()));
+        state.set_rpc_url_for_mode("wallet", "https://wallet.example.com:9256").await;
+        let testdata = |file: &str| format!("{}/src/rpc/testdata/{}", env!("CARGO_MANIFEST_DIR"), file);
+        state.set_ssl_paths_for_mode("wallet", testdata("wallet.crt"), testdata("wallet.key")).await;
+
+        let node = ChiaRpcClient::from_state(state.clone(), "full_node").await.unwrap();
+        assert_eq!(node.base_url, "https://node.example.com:8555");
+        let wallet = ChiaRpcClient::from_state(state, "wallet").await.unwrap();
+        assert_eq!(wallet.base_url, "https://wallet.example.com:9256");
+    }
+
+    #[tokio::test]
+    async fn test_get_mempool_item_hit_and_miss() {
+        use axum::{routing::post, Json, Router};
+
+        let router = Router::new().route(
+            "/get_mempool_item_by_tx_id",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                if body["tx_id"] == "0xabc" {
+                    Json(json!({ "mempool_item": { "fee": 0 }, "success": true }))
+                } else {
+                    Json(json!({ "error": format!("Tx id {} not in the mempool", body["tx_id"]), "success": false }))
+                }
+            }),
+        );
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await);
+
+        let hit = client.get_mempool_item_by_tx_id("0xabc").await.unwrap();
+        assert_eq!(hit, Some(json!({ "fee": 0 })));
+
+        let miss = client.get_mempool_item_by_tx_id("0xdef").await.unwrap();
+        assert!(miss.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_mempool_item_unsupported_errors() {
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(axum::Router::new()).await);
+        assert!(client.get_mempool_item_by_tx_id("0xabc").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_error_network() {
+        // Nothing listens on port 1
+        let client = ChiaRpcClient::new("http://127.0.0.1:1".to_string());
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(matches!(err, ChiaError::Network(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_error_timeout() {
+        use axum::{routing::post, Router};
+
+        let router = Router::new().route(
+            "/get_blockchain_state",
+            post(|| async {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                "{}"
+            }),
+        );
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await)
+            .with_timeout(Duration::from_millis(50));
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(matches!(err, ChiaError::Timeout), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_unroutable_node_gives_up_within_timeout() {
+        // Nothing answers on this reserved address, so the connect attempt hangs until the timeout.
+        // Sandboxes without a route fail fast instead, which is just as good here.
+        let client = ChiaRpcClient::new("http://10.255.255.1:8555".to_string()).with_timeout(Duration::from_millis(300));
+        let started = std::time::Instant::now();
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(3), "took {:?}", started.elapsed());
+        assert!(matches!(err, ChiaError::Timeout | ChiaError::Network(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_error_node_rejected() {
+        use axum::{http::StatusCode, routing::post, Json, Router};
+
+        let router = Router::new()
+            .route("/get_blockchain_state", post(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "boom") }))
+            .route(
+                "/push_tx",
+                post(|| async { Json(json!({ "success": false, "error": "INVALID_SPEND_BUNDLE" })) }),
+            );
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await);
+
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(matches!(err, ChiaError::NodeRejected { status: 500, .. }), "{:?}", err);
+
+        match client.push_tx("00").await.unwrap_err() {
+            ChiaError::NodeRejected { status, message } => {
+                assert_eq!(status, 200);
+                assert_eq!(message, "INVALID_SPEND_BUNDLE");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_tx_duplicate_vs_double_spend() {
+        use axum::{routing::post, Json, Router};
+
+        let router = Router::new().route(
+            "/push_tx",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                match body["spend_bundle"].as_str() {
+                    Some("aa") => Json(json!({ "status": "SUCCESS", "success": true })),
+                    Some("bb") => Json(json!({
+                        "error": "Failed to include transaction 0xbb, error ALREADY_INCLUDING_TRANSACTION",
+                        "success": false
+                    })),
+                    _ => Json(json!({
+                        "error": "Failed to include transaction 0xcc, error DOUBLE_SPEND",
+                        "success": false
+                    })),
+                }
+            }),
+        );
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await);
+
+        let fresh = client.push_tx("aa").await.unwrap();
+        assert!(fresh.success);
+        assert_eq!(fresh.status, "SUCCESS");
+
+        let resubmitted = client.push_tx("bb").await.unwrap();
+        assert!(resubmitted.success);
+        assert_eq!(resubmitted.status, PUSH_TX_ALREADY_IN_MEMPOOL);
+
+        match client.push_tx("cc").await.unwrap_err() {
+            ChiaError::NodeRejected { message, .. } => assert!(message.contains("DOUBLE_SPEND"), "{}", message),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_parse() {
+        use axum::{routing::post, Json, Router};
+
+        let router = Router::new()
+            .route("/get_blockchain_state", post(|| async { "not json" }))
+            .route("/get_puzzle_and_solution", post(|| async { Json(json!({ "success": true })) }));
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await);
+
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(matches!(err, ChiaError::Parse(_)), "{:?}", err);
+
+        let err = client.get_puzzle_and_solution(&CoinId::from([7; 32]), 1).await.unwrap_err();
+        assert!(matches!(err, ChiaError::Parse(_)), "{:?}", err);
+    }
+
+    fn sh(script: &str) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(script);
+        cmd
+    }
+
+    // Runs on the single-threaded test runtime, so a blocking proxy call would stall the node call
+    #[tokio::test]
+    async fn test_slow_proxy_does_not_block_other_requests() {
+        use axum::{routing::post, Json, Router};
+
+        let router = Router::new().route("/get_blockchain_state", post(|| async {
+            Json(json!({ "blockchain_state": { "peak": { "height": 7 } }, "success": true }))
+        }));
+        let client = ChiaRpcClient::new(crate::_dev_utils::spawn_mock_node(router).await);
+        let started = std::time::Instant::now();
+
+        let slow = async {
+            let result = ChiaRpcClient::run_wallet_proxy(sh(r#"sleep 1; echo '{"wallets": []}'"#)).await;
+            (result, started.elapsed())
+        };
+        let fast = async {
+            let result = client.get_blockchain_state().await;
+            (result, started.elapsed())
+        };
+        let ((slow, slow_at), (fast, fast_at)) = tokio::join!(slow, fast);
+
+        assert_eq!(slow.unwrap(), json!({ "wallets": [] }));
+        assert_eq!(fast.unwrap()["peak"]["height"], 7);
+        assert!(fast_at < Duration::from_millis(500), "node call waited {:?} for the proxy", fast_at);
+        assert!(slow_at >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_errors_are_preserved() {
+        let err = ChiaRpcClient::run_wallet_proxy(sh("echo partial; echo boom >&2; exit 3")).await.unwrap_err();
+        match err {
+            ChiaError::Network(msg) => assert_eq!(msg, "wallet_rpc_proxy.py failed\nstdout: partial\n\nstderr: boom\n"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let err = ChiaRpcClient::run_wallet_proxy(sh("echo not json")).await.unwrap_err();
+        assert!(matches!(err, ChiaError::Parse(_)), "{:?}", err);
+
+        let err = ChiaRpcClient::run_wallet_proxy(sh(r#"echo '{"error": "wallet locked"}'"#)).await.unwrap_err();
+        assert!(matches!(&err, ChiaError::NodeRejected { status: 200, message } if message == "\"wallet locked\""), "{:?}", err);
+
+        let err = ChiaRpcClient::run_wallet_proxy(tokio::process::Command::new("/nonexistent/python3")).await.unwrap_err();
+        assert!(matches!(err, ChiaError::NotConfigured(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_error_not_configured() {
+        let client = ChiaRpcClient::new(String::new());
+        let err = client.get_blockchain_state().await.unwrap_err();
+        assert!(matches!(err, ChiaError::NotConfigured(_)), "{:?}", err);
+    }
 }
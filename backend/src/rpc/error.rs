@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// Errors from talking to a Chia node or wallet
+#[derive(Debug)]
+pub enum ChiaError {
+    /// Could not reach the node (connection refused, TLS failure, proxy failure, ...)
+    Network(String),
+    /// The node did not answer in time
+    Timeout,
+    /// The node answered but refused the request (non-2xx, or `success: false`)
+    NodeRejected { status: u16, message: String },
+    /// The node's response was not what we expected
+    Parse(String),
+    /// The client is missing configuration (RPC URL, SSL material, wallet proxy)
+    NotConfigured(String),
+}
+
+impl fmt::Display for ChiaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChiaError::Network(msg) => write!(f, "Chia node unreachable: {}", msg),
+            ChiaError::Timeout => write!(f, "Chia node timed out (no answer within CHIA_RPC_TIMEOUT_SECS)"),
+            ChiaError::NodeRejected { status, message } => {
+                write!(f, "Chia node rejected request (HTTP {}): {}", status, message)
+            }
+            ChiaError::Parse(msg) => write!(f, "Unexpected Chia node response: {}", msg),
+            ChiaError::NotConfigured(msg) => write!(f, "Chia RPC not configured: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ChiaError {}
+
+impl From<reqwest::Error> for ChiaError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ChiaError::Timeout
+        } else if e.is_builder() {
+            ChiaError::NotConfigured(e.to_string())
+        } else if e.is_decode() {
+            ChiaError::Parse(e.to_string())
+        } else if let Some(status) = e.status() {
+            ChiaError::NodeRejected { status: status.as_u16(), message: e.to_string() }
+        } else {
+            ChiaError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for ChiaError {
+    fn from(e: serde_json::Error) -> Self {
+        ChiaError::Parse(e.to_string())
+    }
+}
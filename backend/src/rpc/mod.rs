@@ -1,3 +1,6 @@
 pub mod client;
+pub mod error;
+pub mod tls;
 
 pub use client::ChiaRpcClient;
+pub use error::ChiaError;
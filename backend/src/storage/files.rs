@@ -92,6 +92,51 @@ pub fn load_contract_metadata(
     Ok(metadata)
 }
 
+/// Whether on-disk metadata was created by `owner_id`.
+/// Metadata written before `created_by` was recorded has no owner and matches nobody.
+pub fn metadata_owned_by(metadata: &serde_json::Value, owner_id: i64) -> bool {
+    metadata.get("created_by").and_then(|v| v.as_i64()) == Some(owner_id)
+}
+
+/// List contract metadata in `storage_dir` created by `owner_id`
+pub fn list_contract_metadata_in(
+    storage_dir: &Path,
+    owner_id: i64,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    if !storage_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut contracts = vec![];
+    for entry in fs::read_dir(storage_dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+
+        let metadata: serde_json::Value = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+        {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                tracing::warn!("Failed to load metadata {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if metadata_owned_by(&metadata, owner_id) {
+            contracts.push(metadata);
+        }
+    }
+
+    Ok(contracts)
+}
+
+/// List contract metadata created by `owner_id`
+pub fn list_contract_metadata(owner_id: i64) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    list_contract_metadata_in(Path::new("storage/metadata"), owner_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +157,24 @@ mod tests {
         // Cleanup
         let _ = delete_contract_file(&file_path);
     }
+
+    #[test]
+    fn test_metadata_listing_is_owner_scoped() {
+        let dir = std::env::temp_dir().join(format!("dtrex-metadata-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for (id, owner) in [("a", Some(1)), ("b", Some(2)), ("legacy", None)] {
+            let metadata = serde_json::json!({ "contract_id": id, "created_by": owner });
+            fs::write(dir.join(format!("{}.json", id)), metadata.to_string()).unwrap();
+        }
+
+        let mine = list_contract_metadata_in(&dir, 1).unwrap();
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0]["contract_id"], "a");
+
+        // No one sees another user's (or unowned) contracts
+        assert_eq!(list_contract_metadata_in(&dir, 2).unwrap()[0]["contract_id"], "b");
+        assert!(list_contract_metadata_in(&dir, 3).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
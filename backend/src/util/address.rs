@@ -0,0 +1,164 @@
+use std::fmt;
+
+/// Human-readable parts we accept: mainnet and testnet
+const XCH_HRPS: [&str; 2] = ["xch", "txch"];
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const CHECKSUM_LEN: usize = 6;
+/// Puzzle hashes are sha256 digests
+const PUZZLE_HASH_LEN: usize = 32;
+
+/// Why an address failed `validate_xch_address`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddrError {
+    /// Missing `1` separator, mixed case, or a character outside the bech32 alphabet
+    Malformed,
+    /// Prefix other than `xch`/`txch`
+    UnknownPrefix(String),
+    /// Checksum does not verify as bech32m (typo, or a legacy bech32 address)
+    BadChecksum,
+    /// Decoded payload is not a 32-byte puzzle hash
+    BadLength(usize),
+}
+
+impl fmt::Display for AddrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddrError::Malformed => write!(f, "malformed address"),
+            AddrError::UnknownPrefix(hrp) => write!(f, "unknown address prefix '{}'", hrp),
+            AddrError::BadChecksum => write!(f, "address checksum is invalid"),
+            AddrError::BadLength(len) => write!(f, "address encodes {} bytes, expected {}", len, PUZZLE_HASH_LEN),
+        }
+    }
+}
+
+impl std::error::Error for AddrError {}
+
+/// Check that `addr` is a bech32m `xch1…`/`txch1…` address carrying a 32-byte puzzle hash
+pub fn validate_xch_address(addr: &str) -> Result<(), AddrError> {
+    decode_puzzle_hash(addr).map(|_| ())
+}
+
+/// Decode an `xch`/`txch` address into its HRP (lowercase) and puzzle hash
+pub fn decode_puzzle_hash(addr: &str) -> Result<(String, Vec<u8>), AddrError> {
+    // bech32 allows either case, but not both
+    if addr.bytes().any(|b| b.is_ascii_lowercase()) && addr.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(AddrError::Malformed);
+    }
+    let addr = addr.to_ascii_lowercase();
+    let (hrp, data) = addr.rsplit_once('1').ok_or(AddrError::Malformed)?;
+    if !XCH_HRPS.contains(&hrp) {
+        return Err(AddrError::UnknownPrefix(hrp.to_string()));
+    }
+    if data.len() < CHECKSUM_LEN {
+        return Err(AddrError::Malformed);
+    }
+
+    let values = data
+        .bytes()
+        .map(|b| CHARSET.iter().position(|&c| c == b).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(AddrError::Malformed)?;
+
+    let mut checked = expand_hrp(hrp);
+    checked.extend_from_slice(&values);
+    if polymod(&checked) != BECH32M_CONST {
+        return Err(AddrError::BadChecksum);
+    }
+
+    let payload = from_5bit(&values[..values.len() - CHECKSUM_LEN]).ok_or(AddrError::Malformed)?;
+    if payload.len() != PUZZLE_HASH_LEN {
+        return Err(AddrError::BadLength(payload.len()));
+    }
+    Ok((hrp.to_string(), payload))
+}
+
+fn expand_hrp(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    let mut chk: u32 = 1;
+    for &v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ff_ffff) << 5) ^ u32::from(v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Regroup 5-bit values into bytes; leftover padding must be under 5 bits and all zero
+fn from_5bit(values: &[u8]) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut out = Vec::with_capacity(values.len() * 5 / 8);
+    for &v in values {
+        acc = (acc << 5) | u32::from(v);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if bits >= 5 || (acc & ((1 << bits) - 1)) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well-known burn address (puzzle hash 0x00…dead)
+    const MAINNET: &str = "xch1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqm6ks6e8mvy";
+    const TESTNET: &str = "txch1umpes4mkuj48yjdhxw6c34anla7vakaq20l2srhzwu3xa9a833as4hf6zj";
+
+    #[test]
+    fn test_valid_addresses() {
+        assert_eq!(validate_xch_address(MAINNET), Ok(()));
+        assert_eq!(validate_xch_address(TESTNET), Ok(()));
+        assert_eq!(validate_xch_address(&MAINNET.to_uppercase()), Ok(()));
+
+        let (hrp, puzzle_hash) = decode_puzzle_hash(MAINNET).unwrap();
+        assert_eq!(hrp, "xch");
+        assert_eq!(hex::encode(puzzle_hash), format!("{}dead", "0".repeat(60)));
+    }
+
+    #[test]
+    fn test_wrong_checksum() {
+        // Last character changed
+        let typo = format!("{}x", &MAINNET[..MAINNET.len() - 1]);
+        assert_eq!(validate_xch_address(&typo), Err(AddrError::BadChecksum));
+        // Right prefix and length, but not an address (the old check accepted this)
+        let garbage = format!("xch1{}", "q".repeat(58));
+        assert_eq!(validate_xch_address(&garbage), Err(AddrError::BadChecksum));
+    }
+
+    #[test]
+    fn test_truncated_and_malformed() {
+        let truncated = &MAINNET[..MAINNET.len() - 4];
+        assert!(validate_xch_address(truncated).is_err());
+        // A valid checksum over a short payload is still rejected
+        assert_eq!(
+            validate_xch_address("xch1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq0av9p9"),
+            Err(AddrError::BadLength(20))
+        );
+        assert_eq!(validate_xch_address("xch1bbbbbbbb"), Err(AddrError::Malformed));
+        assert_eq!(validate_xch_address(&MAINNET.replacen("xch1q", "xch1Q", 1)), Err(AddrError::Malformed));
+        assert_eq!(validate_xch_address(""), Err(AddrError::Malformed));
+        assert_eq!(
+            validate_xch_address(&MAINNET.replacen("xch", "bc", 1)),
+            Err(AddrError::UnknownPrefix("bc".to_string()))
+        );
+    }
+}
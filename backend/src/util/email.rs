@@ -0,0 +1,63 @@
+/// Basic email sanity check: one `@`, a non-empty local part and a dotted
+/// domain, no whitespace. Not a full RFC 5322 parser.
+pub fn validate(email: &str) -> bool {
+    if email.len() > 254 || email.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+
+    let (local, domain) = match email.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    if local.is_empty() || local.len() > 64 || domain.contains('@') {
+        return false;
+    }
+    if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        return false;
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|l| {
+            !l.is_empty()
+                && !l.starts_with('-')
+                && !l.ends_with('-')
+                && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Trim and lowercase an email for storage
+pub fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_emails() {
+        assert!(validate("alice@example.com"));
+        assert!(validate("bob.smith+trades@mail.example.co.uk"));
+        assert!(validate("x@a-b.io"));
+    }
+
+    #[test]
+    fn test_invalid_emails() {
+        assert!(!validate(""));
+        assert!(!validate("alice"));
+        assert!(!validate("@example.com"));
+        assert!(!validate("alice@"));
+        assert!(!validate("alice@localhost"));
+        assert!(!validate("alice@@example.com"));
+        assert!(!validate("al ice@example.com"));
+        assert!(!validate("alice@example..com"));
+        assert!(!validate(".alice@example.com"));
+        assert!(!validate("alice@-example.com"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  Alice@Example.COM "), "alice@example.com");
+    }
+}
@@ -1,2 +1,9 @@
+pub mod address;
+pub mod email;
 pub mod hashing;
 pub mod pem_to_pkcs12;
+pub mod price;
+pub mod rate_limit;
+pub mod text;
+pub mod validation;
+pub mod webhook;
@@ -0,0 +1,198 @@
+//! Server-side XCH/USD price, used to check commitment fee amounts.
+//!
+//! Clients used to convert the USD fee themselves and send the mojo amount,
+//! which let them underpay. The server now fetches its own quote from
+//! `XCH_PRICE_URL` (CoinGecko by default), caches it for
+//! `XCH_PRICE_CACHE_SECS`, and rejects amounts more than
+//! `XCH_PRICE_TOLERANCE_PCT` away from the fee at that price.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// CoinGecko's simple price endpoint for XCH in USD
+pub const DEFAULT_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price?ids=chia&vs_currencies=usd";
+const DEFAULT_CACHE_SECS: u64 = 60;
+const DEFAULT_TOLERANCE_PCT: f64 = 5.0;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why no price could be obtained
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceError {
+    /// The price source could not be reached or answered with an error status
+    Network(String),
+    /// The response carried no positive USD price
+    Parse(String),
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PriceError::Network(e) => write!(f, "price source unreachable: {}", e),
+            PriceError::Parse(e) => write!(f, "bad price response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PriceError {}
+
+/// One quote from the price source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XchPrice {
+    pub usd: f64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Fetches XCH/USD and keeps the last quote for the cache TTL
+pub struct XchPriceProvider {
+    url: String,
+    ttl: Duration,
+    tolerance_pct: f64,
+    client: reqwest::Client,
+    cache: Mutex<Option<(XchPrice, Instant)>>,
+}
+
+impl XchPriceProvider {
+    pub fn new(url: &str, ttl: Duration, tolerance_pct: f64) -> Self {
+        Self {
+            url: url.to_string(),
+            ttl,
+            tolerance_pct,
+            client: reqwest::Client::builder().timeout(FETCH_TIMEOUT).build().unwrap_or_default(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Build from `XCH_PRICE_URL`, `XCH_PRICE_CACHE_SECS` and `XCH_PRICE_TOLERANCE_PCT`,
+    /// falling back to the defaults when unset or invalid
+    pub fn from_env() -> Self {
+        let url = std::env::var("XCH_PRICE_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PRICE_URL.to_string());
+        let ttl = std::env::var("XCH_PRICE_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CACHE_SECS);
+        let tolerance = std::env::var("XCH_PRICE_TOLERANCE_PCT")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|pct| pct.is_finite() && *pct >= 0.0)
+            .unwrap_or(DEFAULT_TOLERANCE_PCT);
+        Self::new(url.trim(), Duration::from_secs(ttl), tolerance)
+    }
+
+    /// Largest accepted deviation from the server's fee amount, in percent
+    pub fn tolerance_pct(&self) -> f64 {
+        self.tolerance_pct
+    }
+
+    /// Current XCH/USD, from the cache while it is fresh
+    pub async fn xch_usd(&self) -> Result<XchPrice, PriceError> {
+        // Held across the fetch so concurrent callers share one request
+        let mut cache = self.cache.lock().await;
+        if let Some((price, at)) = *cache {
+            if at.elapsed() < self.ttl {
+                return Ok(price);
+            }
+        }
+        let price = XchPrice { usd: self.fetch().await?, fetched_at: Utc::now() };
+        *cache = Some((price, Instant::now()));
+        Ok(price)
+    }
+
+    async fn fetch(&self) -> Result<f64, PriceError> {
+        let res = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| PriceError::Network(e.to_string()))?;
+        let body: Value = res.json().await.map_err(|e| PriceError::Parse(e.to_string()))?;
+        parse_usd(&body).ok_or_else(|| PriceError::Parse(format!("no USD price in {}", body)))
+    }
+
+    /// Whether `amount` is within the tolerance of `expected`
+    pub fn within_tolerance(&self, amount: i64, expected: i64) -> bool {
+        within_tolerance(amount, expected, self.tolerance_pct)
+    }
+}
+
+/// USD price from a CoinGecko response (`{"chia":{"usd":…}}`) or a flat `{"usd":…}` / `{"price":…}`
+fn parse_usd(body: &Value) -> Option<f64> {
+    body.pointer("/chia/usd")
+        .or_else(|| body.get("usd"))
+        .or_else(|| body.get("price"))
+        .and_then(Value::as_f64)
+        .filter(|usd| usd.is_finite() && *usd > 0.0)
+}
+
+/// `|amount - expected| <= expected * pct / 100`
+pub fn within_tolerance(amount: i64, expected: i64, pct: f64) -> bool {
+    (amount as f64 - expected as f64).abs() <= expected as f64 * pct / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_price_shapes() {
+        assert_eq!(parse_usd(&json!({ "chia": { "usd": 12.5 } })), Some(12.5));
+        assert_eq!(parse_usd(&json!({ "usd": 7 })), Some(7.0));
+        assert_eq!(parse_usd(&json!({ "price": "7" })), None);
+        assert_eq!(parse_usd(&json!({ "chia": { "usd": 0 } })), None);
+        assert_eq!(parse_usd(&json!({})), None);
+    }
+
+    #[test]
+    fn test_tolerance_bounds() {
+        assert!(within_tolerance(1_000, 1_000, 5.0));
+        assert!(within_tolerance(1_050, 1_000, 5.0));
+        assert!(within_tolerance(950, 1_000, 5.0));
+        assert!(!within_tolerance(949, 1_000, 5.0));
+        assert!(!within_tolerance(1_051, 1_000, 5.0));
+        assert!(!within_tolerance(1_001, 1_000, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_quote_cached_for_ttl() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let router = Router::new().route(
+            "/price",
+            get(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move { Json(json!({ "chia": { "usd": 10.0 + n as f64 } })) }
+            }),
+        );
+        let url = format!("{}/price", crate::_dev_utils::spawn_mock_node(router).await);
+
+        let cached = XchPriceProvider::new(&url, Duration::from_secs(60), 5.0);
+        assert_eq!(cached.xch_usd().await.unwrap().usd, 10.0);
+        assert_eq!(cached.xch_usd().await.unwrap().usd, 10.0);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let uncached = XchPriceProvider::new(&url, Duration::ZERO, 5.0);
+        assert_eq!(uncached.xch_usd().await.unwrap().usd, 11.0);
+        assert_eq!(uncached.xch_usd().await.unwrap().usd, 12.0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_price_is_an_error() {
+        let router = Router::new().route("/price", get(|| async { Json(json!({ "chia": {} })) }));
+        let url = format!("{}/price", crate::_dev_utils::spawn_mock_node(router).await);
+        let provider = XchPriceProvider::new(&url, Duration::from_secs(60), 5.0);
+        assert!(matches!(provider.xch_usd().await, Err(PriceError::Parse(_))));
+
+        let provider = XchPriceProvider::new("http://127.0.0.1:1/price", Duration::from_secs(60), 5.0);
+        assert!(matches!(provider.xch_usd().await, Err(PriceError::Network(_))));
+    }
+}
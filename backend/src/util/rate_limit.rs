@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Per-user token bucket: up to `burst` requests at once, refilled at `per_minute`
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<i64, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, per_minute: u32) -> Self {
+        Self {
+            capacity: burst as f64,
+            refill_per_sec: per_minute as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from env vars, falling back to the defaults when unset or invalid
+    pub fn from_env(burst_var: &str, per_minute_var: &str, default_burst: u32, default_per_minute: u32) -> Self {
+        let read = |var: &str, default: u32| {
+            std::env::var(var).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        Self::new(read(burst_var, default_burst), read(per_minute_var, default_per_minute))
+    }
+
+    /// Take a token for `user_id`; false if the bucket is empty
+    pub fn try_acquire(&self, user_id: i64) -> bool {
+        self.try_acquire_at(user_id, Instant::now())
+    }
+
+    fn try_acquire_at(&self, user_id: i64, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(user_id).or_insert(Bucket { tokens: self.capacity, updated: now });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_drains_and_refills() {
+        let limiter = RateLimiter::new(3, 60); // one token per second
+        let start = Instant::now();
+
+        assert!((0..3).all(|_| limiter.try_acquire_at(1, start)));
+        assert!(!limiter.try_acquire_at(1, start));
+        // Other users have their own bucket
+        assert!(limiter.try_acquire_at(2, start));
+
+        assert!(!limiter.try_acquire_at(1, start + Duration::from_millis(500)));
+        assert!(limiter.try_acquire_at(1, start + Duration::from_millis(1100)));
+        assert!(!limiter.try_acquire_at(1, start + Duration::from_millis(1100)));
+
+        // Refill is capped at the burst size
+        let later = start + Duration::from_secs(3600);
+        assert!((0..3).all(|_| limiter.try_acquire_at(1, later)));
+        assert!(!limiter.try_acquire_at(1, later));
+    }
+}
@@ -0,0 +1,93 @@
+//! Write-time handling of user-supplied free text (item descriptions, review
+//! comments, contact notes, contract names and terms).
+//!
+//! The contract with clients: these fields are plain text and must be rendered
+//! as text, never as HTML. Writes go through three steps in this order:
+//! control characters are stripped (tabs and line breaks are kept), the text
+//! the user wrote is validated and length-checked, and only then is it
+//! escaped for storage. Contract names and terms reject control characters
+//! instead of stripping them, matching the REST contract API, since their text
+//! is hashed and signed as written. Deployments with clients that can't
+//! guarantee text rendering can set `TEXT_ESCAPE_HTML=true`, which HTML-escapes
+//! `& < > " '` before the text is stored; filters compared against stored text
+//! must be escaped the same way.
+
+/// Longest item description, review comment or contact note (characters)
+pub const MAX_LONG_TEXT_LEN: usize = 5000;
+
+/// Whether `TEXT_ESCAPE_HTML` asks for HTML escaping of stored text
+pub fn escape_html_enabled() -> bool {
+    std::env::var("TEXT_ESCAPE_HTML")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Whether `text` holds a control character other than `\n`, `\r` and `\t`
+pub fn has_control_chars(text: &str) -> bool {
+    text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+}
+
+/// Strip control characters other than `\n`, `\r` and `\t` (before validation)
+pub fn strip_controls(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t')).collect()
+}
+
+/// `strip_controls` for optional fields
+pub fn strip_controls_opt(text: Option<String>) -> Option<String> {
+    text.map(|t| strip_controls(&t))
+}
+
+/// Escape validated text for storage according to the configured policy
+pub fn escape(text: &str) -> String {
+    escape_with(text, escape_html_enabled())
+}
+
+/// `escape` for optional fields
+pub fn escape_opt(text: Option<String>) -> Option<String> {
+    text.map(|t| escape(&t))
+}
+
+/// HTML-escape `& < > " '` if `escape_html`, otherwise return the text unchanged
+pub fn escape_with(text: &str, escape_html: bool) -> String {
+    if !escape_html {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = "<script>alert('x')</script><img src=x onerror=\"steal()\">";
+
+    #[test]
+    fn test_escape_policy_neutralizes_markup() {
+        let escaped = escape_with(PAYLOAD, true);
+        assert!(!escaped.contains('<') && !escaped.contains('>') && !escaped.contains('"'));
+        assert_eq!(
+            escaped,
+            "&lt;script&gt;alert(&#x27;x&#x27;)&lt;/script&gt;&lt;img src=x onerror=&quot;steal()&quot;&gt;"
+        );
+        assert_eq!(escape_with("Tom & Jerry", true), "Tom &amp; Jerry");
+        assert_eq!(escape_with(PAYLOAD, false), PAYLOAD);
+    }
+
+    #[test]
+    fn test_strip_controls_keeps_whitespace() {
+        assert_eq!(strip_controls(PAYLOAD), PAYLOAD);
+        assert_eq!(strip_controls("line one\r\n\tline\u{0}\u{7} two\u{1b}[31m"), "line one\r\n\tline two[31m");
+        assert!(has_control_chars("terms\u{0}") && !has_control_chars("line one\r\n\tline two"));
+    }
+}
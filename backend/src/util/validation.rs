@@ -0,0 +1,75 @@
+//! Field-level validation error accumulator.
+//!
+//! DTOs collect every problem into a `ValidationErrors` so the frontend can
+//! highlight all offending inputs at once instead of parsing a single message.
+
+use crate::error::Error;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ValidationErrors {
+    pub fields: BTreeMap<String, String>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an error for a field (the first error per field wins)
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.fields.entry(field.to_string()).or_insert_with(|| message.into());
+    }
+
+    /// Record an error for a field unless `ok` holds
+    pub fn check(&mut self, ok: bool, field: &str, message: &str) {
+        if !ok {
+            self.add(field, message);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// `Ok(())` when nothing was recorded, otherwise `Error::Validation`
+    pub fn into_result(self) -> Result<(), Error> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(self))
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let parts: Vec<String> = self.fields.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_all_fields() {
+        let mut errs = ValidationErrors::new();
+        errs.check(false, "item_title", "must not be empty");
+        errs.check(true, "item_description", "must not be empty");
+        errs.add("item_value_usd", "must be positive");
+        errs.add("item_value_usd", "ignored second message");
+
+        assert_eq!(errs.fields.len(), 2);
+        assert_eq!(errs.fields["item_value_usd"], "must be positive");
+        assert_eq!(errs.to_string(), "item_title: must not be empty, item_value_usd: must be positive");
+        assert!(matches!(errs.into_result(), Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn test_empty_is_ok() {
+        assert!(ValidationErrors::new().into_result().is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+//! Outbound webhook for confirmed transactions.
+//!
+//! When `WEBHOOK_URL` is set, the verifier POSTs a JSON payload for every
+//! transaction it confirms. The body is signed with HMAC-SHA256 under
+//! `TOKEN_SECRET`; receivers recompute it over the raw body and compare with the
+//! `X-DTREX-Signature: sha256=<hex>` header. Non-2xx answers are retried with
+//! exponential backoff.
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use std::fmt;
+use std::time::Duration;
+
+/// Header carrying `sha256=<hex hmac of the body>`
+pub const SIGNATURE_HEADER: &str = "x-dtrex-signature";
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(1);
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a webhook was given up on
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookError {
+    /// Every attempt failed; holds the last failure
+    GaveUp { attempts: u32, last_error: String },
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WebhookError::GaveUp { attempts, last_error } => {
+                write!(f, "webhook failed after {} attempts: {}", attempts, last_error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+/// Signs and delivers webhook payloads to one URL
+pub struct WebhookNotifier {
+    url: String,
+    secret: String,
+    max_attempts: u32,
+    base_delay: Duration,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, secret: &str, max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            url: url.to_string(),
+            secret: secret.to_string(),
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            client: reqwest::Client::builder().timeout(SEND_TIMEOUT).build().unwrap_or_default(),
+        }
+    }
+
+    /// From `WEBHOOK_URL` and `TOKEN_SECRET`; `None` (webhooks off) unless both are set
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("WEBHOOK_URL").ok().filter(|u| !u.trim().is_empty())?;
+        let secret = std::env::var("TOKEN_SECRET").ok().filter(|s| !s.is_empty());
+        let Some(secret) = secret else {
+            tracing::warn!("WEBHOOK_URL is set but TOKEN_SECRET is not; webhooks are off");
+            return None;
+        };
+        Some(Self::new(url.trim(), &secret, DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_DELAY))
+    }
+
+    /// POST `payload`, retrying non-2xx answers and network errors after
+    /// `base_delay`, then twice that, and so on
+    pub async fn send(&self, payload: &Value) -> Result<(), WebhookError> {
+        let body = payload.to_string();
+        let signature = format!("sha256={}", sign(&self.secret, body.as_bytes()));
+
+        let mut last_error = String::new();
+        for attempt in 1..=self.max_attempts {
+            let res = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await;
+            match res {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) => last_error = format!("HTTP {}", res.status()),
+                Err(e) => last_error = e.to_string(),
+            }
+            if attempt < self.max_attempts {
+                tokio::time::sleep(self.base_delay * 2u32.pow(attempt - 1)).await;
+            }
+        }
+        Err(WebhookError::GaveUp { attempts: self.max_attempts, last_error })
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_signed_payload_retried_until_accepted() {
+        // Fails twice, then accepts; records what it was sent
+        let received: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let log = received.clone();
+        let router = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let log = log.clone();
+                async move {
+                    let signature = headers[SIGNATURE_HEADER].to_str().unwrap().to_string();
+                    let mut log = log.lock().unwrap();
+                    log.push((signature, body));
+                    if log.len() < 3 { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::OK }
+                }
+            }),
+        );
+        let url = format!("{}/hook", crate::_dev_utils::spawn_mock_node(router).await);
+
+        let notifier = WebhookNotifier::new(&url, "hook-secret", 4, Duration::from_millis(5));
+        let payload = json!({
+            "event": "transaction_confirmed", "trade_id": 7, "tx_id": "0xfeed", "status": "confirmed", "confirmations": 6
+        });
+        notifier.send(&payload).await.unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let (signature, body) = &received[2];
+        assert_eq!(serde_json::from_str::<Value>(body).unwrap(), payload);
+        assert_eq!(*signature, format!("sha256={}", sign("hook-secret", body.as_bytes())));
+        assert_ne!(*signature, format!("sha256={}", sign("other-secret", body.as_bytes())));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let router = Router::new().route("/hook", post(|| async { StatusCode::INTERNAL_SERVER_ERROR }));
+        let url = format!("{}/hook", crate::_dev_utils::spawn_mock_node(router).await);
+        let notifier = WebhookNotifier::new(&url, "hook-secret", 2, Duration::from_millis(1));
+        let err = notifier.send(&json!({})).await.unwrap_err();
+        assert_eq!(
+            err,
+            WebhookError::GaveUp { attempts: 2, last_error: "HTTP 500 Internal Server Error".to_string() }
+        );
+    }
+}
@@ -50,6 +50,22 @@ export async function rpcCall<T>(method: string, params?: any): Promise<T> {
   return response.data.result;
 }
 
+export interface RpcBatchResult<T = any> {
+  result?: T;
+  error?: { code: number; message: string; data?: any };
+}
+
+// Several JSON-RPC calls in one POST; results come back in call order, each with its own error
+export async function rpcBatch(calls: { method: string; params?: any }[]): Promise<RpcBatchResult[]> {
+  const response = await api.post('/api/rpc', calls.map((call, i) => ({
+    id: i,
+    method: call.method,
+    params: call.params || {}
+  })));
+
+  return (response.data as any[]).map(({ result, error }) => ({ result, error }));
+}
+
 // ============================================
 // Trade Types
 // ============================================
@@ -66,7 +82,7 @@ export interface Trade {
   id: number;
   proposer_id: number;
   acceptor_id?: number;
-  status: string; // 'proposal' | 'matched' | 'committed' | 'escrow' | 'completed' | 'disputed' | 'cancelled'
+  status: string; // 'proposal' | 'matched' | 'committed' | 'escrow' | 'completed' | 'disputed' | 'cancelled' | 'refunded'
   
   // Proposer info (enriched from backend)
   proposer?: UserPublicInfo;
@@ -99,6 +115,23 @@ export interface Trade {
   acceptor_tracking_number?: string;
   acceptor_shipped_at?: string;
   
+  // Admin-featured proposals are listed first
+  is_featured?: boolean;
+
+  // Normalized keywords (filled in on proposal listings)
+  tags?: string[];
+
+  // Offers far below the item value are rejected while this is on
+  lowball_guard?: boolean;
+
+  // An admin waived the commitment fee; the trade committed without payments
+  commitment_fee_waived?: boolean;
+
+  // Set when a participant disputes the trade, and when an admin resolves it
+  dispute_reason?: string;
+  disputed_at?: string;
+  dispute_resolution?: string;
+  
   // Timestamps
   committed_at?: string;
   escrow_start_date?: string;
@@ -122,6 +155,17 @@ export interface CreateTradeRequest {
   item_value_usd: number;
   item_category?: string;
   wishlist?: WishlistItem[];
+  tags?: string[]; // up to 10, each up to 32 characters; lowercased server-side
+  lowball_guard?: boolean; // default true
+}
+
+export interface TradeSearchCriteria {
+  query?: string; // matches item title or description, case-insensitive
+  category?: string;
+  min_value_usd?: number;
+  max_value_usd?: number;
+  tag?: string;
+  sort?: 'newest' | 'value' | 'reputation'; // default 'newest'
 }
 
 export interface AcceptTradeRequest {
@@ -131,7 +175,47 @@ export interface AcceptTradeRequest {
   item_description?: string;
   item_condition?: string;
   item_value_usd?: number;
+  xch_amount?: number; // valued at the server's XCH price for the lowball guard
+}
+
+// The proposer's single-shot accept: `offer_id`, or the one offer waiting on them
+export interface AcceptOwnTradeRequest {
+  trade_id: number;
+  offer_id?: number;
+}
+
+export interface CounterOfferRequest extends Omit<AcceptTradeRequest, 'trade_id'> {
+  offer_id: number; // the pending offer being answered
+}
+
+export interface TradeOffer {
+  id: number;
+  trade_id: number;
+  bidder_id: number;
+  created_by: number; // the bidder, or the proposer for counter-offers
+  parent_offer_id?: number;
+  offer_type: string;
+  item_title?: string;
+  item_description?: string;
+  item_condition?: string;
+  item_value_usd?: number;
   xch_amount?: number;
+  status: 'pending' | 'countered' | 'accepted' | 'rejected' | 'withdrawn';
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TradeImage {
+  id: number;
+  trade_id: number;
+  user_id: number;
+  photo_type: string;
+  file_path: string;
+  file_size: number;
+  mime_type?: string;
+  position: number; // 0-based gallery position
+  is_primary: boolean; // the cover image, listed first
+  created_at: string;
 }
 
 export interface TradeReview {
@@ -154,8 +238,9 @@ export interface CommitmentDetails {
   exchange_wallet_address: string;
   commitment_fee_usd: number;  // Fee in USD - calculate XCH dynamically
   user_role: 'proposer' | 'acceptor';
-  user_commit_status: string;
+  user_commit_status: string;  // 'waived' when an admin waived the fee
   other_commit_status: string;
+  fee_waived: boolean;
   memo: string;
 }
 
@@ -165,6 +250,13 @@ export interface PendingTransaction {
   amount_mojos: number;
   amount_xch: number;
   memo: string;
+  price: {
+    source: string;
+    timestamp: string;
+    xch_price_usd: number;
+    fee_usd: number;
+    rounding: string;
+  } | null;
 }
 
 export interface TradeTransaction {
@@ -183,6 +275,7 @@ export interface TradeTransaction {
   created_at: string;
   mempool_at?: string;
   confirmed_at?: string;
+  archived_at?: string; // set on a failed transaction once it has been retried
 }
 
 export interface CreateReviewRequest {
@@ -200,8 +293,18 @@ export interface CreateReviewRequest {
 
 export const tradeApi = {
   // Public methods
-  listProposals: async (limit = 50, offset = 0): Promise<Trade[]> => {
-    const result = await rpcCall<any>('trade_list_proposals', { limit, offset });
+  listProposals: async (limit = 50, offset = 0, tag?: string, category?: string): Promise<Trade[]> => {
+    const result = await rpcCall<any>('trade_list_proposals', { limit, offset, tag, category });
+    return result.trades || [];
+  },
+
+  listCategories: async (): Promise<{ category: string; count: number }[]> => {
+    const result = await rpcCall<any>('trade_list_categories');
+    return result.categories || [];
+  },
+
+  search: async (criteria: TradeSearchCriteria, limit = 50, offset = 0): Promise<Trade[]> => {
+    const result = await rpcCall<any>('trade_search', { ...criteria, limit, offset });
     return result.trades || [];
   },
 
@@ -221,19 +324,56 @@ export const tradeApi = {
     return result.trades || [];
   },
 
+  // Per-status counts of the user's trades (statuses without trades are omitted)
+  myTradeCounts: async (): Promise<{ counts: Record<string, number>; total: number }> => {
+    return rpcCall('user_trade_counts');
+  },
+
   get: async (id: number): Promise<Trade> => {
     const result = await rpcCall<any>('trade_get', { id });
     return result.trade;
   },
 
-  accept: async (data: AcceptTradeRequest): Promise<void> => {
-    await rpcCall('trade_accept', data);
+  // Makes a pending offer; the trade is matched once the proposer accepts it.
+  // Called by the proposer, accepts an offer right away (status 'matched').
+  accept: async (data: AcceptTradeRequest | AcceptOwnTradeRequest): Promise<{ offer_id: number; status: string }> => {
+    return rpcCall('trade_accept', data);
+  },
+
+  counterOffer: async (data: CounterOfferRequest): Promise<{ offer_id: number; status: string }> => {
+    return rpcCall('trade_counter_offer', data);
+  },
+
+  acceptOffer: async (offerId: number): Promise<{ trade_id: number; status: string }> => {
+    return rpcCall('trade_accept_offer', { offer_id: offerId });
+  },
+
+  listOffers: async (tradeId: number): Promise<TradeOffer[]> => {
+    const result = await rpcCall<{ offers: TradeOffer[] }>('trade_list_offers', { trade_id: tradeId });
+    return result.offers;
+  },
+
+  listImages: async (tradeId: number): Promise<TradeImage[]> => {
+    const result = await rpcCall<{ images: TradeImage[] }>('trade_list_images', { trade_id: tradeId });
+    return result.images;
+  },
+
+  setPrimaryImage: async (tradeId: number, imageId: number): Promise<void> => {
+    await rpcCall('trade_set_primary_image', { trade_id: tradeId, image_id: imageId });
+  },
+
+  reorderImages: async (tradeId: number, imageIds: number[]): Promise<void> => {
+    await rpcCall('trade_reorder_images', { trade_id: tradeId, image_ids: imageIds });
   },
 
   commit: async (tradeId: number): Promise<void> => {
     await rpcCall('trade_commit', { trade_id: tradeId });
   },
 
+  canCommit: async (tradeId: number): Promise<{ can_commit: boolean; reason: string | null }> => {
+    return rpcCall('trade_can_commit', { trade_id: tradeId });
+  },
+
   addTracking: async (tradeId: number, trackingNumber: string, carrier: string): Promise<void> => {
     await rpcCall('trade_add_tracking', { trade_id: tradeId, tracking_number: trackingNumber, carrier });
   },
@@ -246,6 +386,11 @@ export const tradeApi = {
     await rpcCall('trade_cancel', { trade_id: tradeId });
   },
 
+  // Committed or escrow trades only; an admin resolves the dispute
+  raiseDispute: async (tradeId: number, reason: string): Promise<{ status: string; disputed_at: string }> => {
+    return rpcCall('trade_raise_dispute', { trade_id: tradeId, reason });
+  },
+
   delete: async (id: number): Promise<void> => {
     await rpcCall('trade_delete', { id });
   },
@@ -256,19 +401,51 @@ export const tradeApi = {
     return result;
   },
 
-  createPendingCommitment: async (tradeId: number, amountMojos: number, fromAddress?: string): Promise<PendingTransaction> => {
+  // The server converts the USD fee to mojos at the given price
+  createPendingCommitment: async (tradeId: number, xchPriceUsd: number, priceTimestamp?: Date, fromAddress?: string): Promise<PendingTransaction> => {
     const result = await rpcCall<PendingTransaction>('commitment_create_pending', { 
       trade_id: tradeId,
-      amount_mojos: amountMojos,
+      xch_price_usd: xchPriceUsd,
+      price_source: 'coingecko',
+      price_timestamp: priceTimestamp?.toISOString(),
       from_address: fromAddress 
     });
     return result;
   },
 
+  // Compares the fee at the server's XCH price with the wallet's spendable (not confirmed) balance
+  checkAffordability: async (tradeId: number, walletId?: number): Promise<{
+    affordable: boolean;
+    fee_mojos: number;
+    spendable_mojos: number;
+    shortfall_mojos: number;
+  }> => {
+    return rpcCall('affordability_check', { trade_id: tradeId, wallet_id: walletId });
+  },
+
   submitCommitmentTx: async (transactionId: number, txId: string): Promise<void> => {
     await rpcCall('commitment_submit_tx', { transaction_id: transactionId, tx_id: txId });
   },
 
+  // Replace a failed commitment transaction with a fresh pending one
+  retryCommitment: async (transactionId: number): Promise<Omit<PendingTransaction, 'price'> & { retried_transaction_id: number }> => {
+    return rpcCall('commitment_retry', { transaction_id: transactionId });
+  },
+
+  // Drop a pending commitment that was never paid, so a new one can be created
+  cancelPendingCommitment: async (transactionId: number): Promise<void> => {
+    await rpcCall('commitment_cancel_pending', { transaction_id: transactionId });
+  },
+
+  // Pending escrow deposit once both sides committed; pay escrow_puzzle_hash, then submitCommitmentTx
+  createEscrowDeposit: async (
+    tradeId: number,
+    amountMojos: number,
+    fromAddress?: string
+  ): Promise<{ transaction_id: number; trade_id: number; escrow_puzzle_hash: string; amount_mojos: number; amount_xch: number; status: string }> => {
+    return rpcCall('commitment_create_escrow_deposit', { trade_id: tradeId, amount_mojos: amountMojos, from_address: fromAddress });
+  },
+
   listTransactions: async (tradeId: number): Promise<TradeTransaction[]> => {
     const result = await rpcCall<any>('commitment_list_transactions', { trade_id: tradeId });
     return result.transactions || [];
@@ -417,9 +594,11 @@ export interface UploadFileResponse {
 }
 
 export const fileApi = {
-  upload: async (file: File): Promise<UploadFileResponse> => {
+  // All files go in one request; the server stores all of them or none
+  upload: async (files: File[], contractId?: number): Promise<UploadFileResponse[]> => {
     const formData = new FormData();
-    formData.append("file", file);
+    if (contractId !== undefined) formData.append("contract_id", String(contractId));
+    files.forEach((file) => formData.append("file", file));
     const response = await api.post("/files", formData, {
       transformRequest: [(data) => data], // Pass FormData as-is
     });
@@ -439,6 +618,10 @@ export const fileApi = {
     return `/files/${fileId}`;
   },
 
+  getContractZipUrl: (contractId: number): string => {
+    return `/contracts/${contractId}/files.zip`;
+  },
+
   delete: async (fileId: number): Promise<void> => {
     await api.delete(`/files/${fileId}`);
   },
@@ -458,6 +641,10 @@ export interface ChiaNodeStatus {
   network?: string;
   peak_height?: number;
   sync_mode?: boolean;
+  synced?: boolean;
+  sync_tip_height?: number;
+  sync_progress_height?: number;
+  sync_progress_percent?: number; // 0-100
   error?: string;
   rpc_url?: string;
 }
@@ -480,6 +667,18 @@ export const getChiaNodeStatus = async (
   }
 };
 
+export interface ChiaModeCheck {
+  connected: boolean;
+  network: string | null;
+  error: string | null;
+  effective_url: string;
+}
+
+// Probe the full node and wallet together (JSON-RPC `chia_check_all`)
+export const checkAllChiaConnections = async (): Promise<{ full_node: ChiaModeCheck; wallet: ChiaModeCheck }> => {
+  return rpcCall('chia_check_all');
+};
+
 export const setChiaRpcConfig = async (rpc_url: string, mode?: string) => {
   try {
     const response = await api.post("/chia/config", { rpc_url, mode });
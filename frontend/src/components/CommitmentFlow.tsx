@@ -12,7 +12,7 @@ interface CommitmentFlowProps {
 type CommitmentStep = 'loading' | 'ready' | 'pending_wallet' | 'signing' | 'submitted' | 'confirmed' | 'error';
 
 export default function CommitmentFlow({ tradeId, onSuccess, onError }: CommitmentFlowProps) {
-  const { price: xchPrice } = useXchPrice();
+  const { price: xchPrice, lastUpdated: priceUpdated } = useXchPrice();
   const { isConnected, sendTransaction, walletAddress } = useWalletConnect();
   
   const [step, setStep] = useState<CommitmentStep>('loading');
@@ -21,10 +21,10 @@ export default function CommitmentFlow({ tradeId, onSuccess, onError }: Commitme
   const [transactions, setTransactions] = useState<TradeTransaction[]>([]);
   const [error, setError] = useState<string | null>(null);
 
-  // Calculate XCH amount dynamically from USD fee
+  // Estimate XCH amount from USD fee (the server computes the exact amount)
   const feeUsd = details?.commitment_fee_usd ?? 1.0;
   const feeXch = xchPrice > 0 ? feeUsd / xchPrice : 0;
-  const feeMojos = Math.floor(feeXch * 1_000_000_000_000);
+  const feeMojos = Math.ceil(feeXch * 1_000_000_000_000);
 
   // Load commitment details and existing transactions
   useEffect(() => {
@@ -87,8 +87,8 @@ export default function CommitmentFlow({ tradeId, onSuccess, onError }: Commitme
       setStep('pending_wallet');
       setError(null);
       
-      // Create pending transaction record with dynamically calculated fee
-      const pending = await tradeApi.createPendingCommitment(tradeId, feeMojos, walletAddress || undefined);
+      // Create pending transaction record; the server converts the fee at this price
+      const pending = await tradeApi.createPendingCommitment(tradeId, xchPrice, priceUpdated ?? undefined, walletAddress || undefined);
       setPendingTx(pending);
       
       setStep('signing');
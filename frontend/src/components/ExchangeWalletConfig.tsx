@@ -62,13 +62,9 @@ export default function ExchangeWalletConfig() {
       return;
     }
     
-    if (!walletAddress.startsWith("xch1")) {
-      setError("Invalid wallet address. Must start with 'xch1'");
-      return;
-    }
-    
-    if (walletAddress.length !== 62) {
-      setError("Invalid wallet address length. Chia addresses are 62 characters.");
+    // Quick prefix check; the server decodes the address and verifies its checksum
+    if (!walletAddress.startsWith("xch1") && !walletAddress.startsWith("txch1")) {
+      setError("Invalid wallet address. Must start with 'xch1' (or 'txch1' on testnet)");
       return;
     }
     
@@ -19,7 +19,7 @@ export default function FileUploader({ attachedFiles, onChange }: Props) {
     setError("");
 
     try {
-      const uploads: UploadFileResponse[] = [];
+      const accepted: File[] = [];
       
       for (let i = 0; i < files.length; i++) {
         const file = files[i];
@@ -30,10 +30,11 @@ export default function FileUploader({ attachedFiles, onChange }: Props) {
           continue;
         }
 
-        const result = await fileApi.upload(file);
-        uploads.push(result);
+        accepted.push(file);
       }
 
+      const uploads: UploadFileResponse[] = accepted.length > 0 ? await fileApi.upload(accepted) : [];
+
       if (uploads.length > 0) {
         const newFileIds = uploads.map((f) => parseInt(f.file_id));
         onChange([...attachedFiles, ...newFileIds]);
@@ -89,7 +89,10 @@ export default function Navbar() {
                   {!nodeStatus
                     ? "Checking Chia node status..."
                     : nodeStatus.connected
-                    ? `Chia node connected (${nodeStatus.network ?? "unknown"}), peak height: ${nodeStatus.peak_height ?? "n/a"}`
+                    ? `Chia node connected (${nodeStatus.network ?? "unknown"}), peak height: ${nodeStatus.peak_height ?? "n/a"}` +
+                      (nodeStatus.synced === false && nodeStatus.sync_progress_percent !== undefined
+                        ? `, syncing: ${nodeStatus.sync_progress_percent}% (${nodeStatus.sync_progress_height} / ${nodeStatus.sync_tip_height})`
+                        : "")
                     : `Chia node not connected${nodeStatus.error ? ": " + nodeStatus.error : ""}`}
                 </div>
               </div>
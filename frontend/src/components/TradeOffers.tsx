@@ -0,0 +1,202 @@
+import { useState, useEffect } from "react";
+import { tradeApi, TradeOffer } from "../api/client";
+import { formatXch } from "../hooks/useXchPrice";
+
+interface TradeOffersProps {
+  tradeId: number;
+  userId: number;
+  isProposer: boolean;
+  onMatched?: () => void;
+}
+
+type OfferType = "item" | "xch" | "mixed";
+
+const MOJOS_PER_XCH = 1_000_000_000_000;
+
+// Offers on an open proposal: the proposer sees every bidder's, a bidder their own thread.
+// Whoever an offer is waiting on can accept it (matching the trade) or counter it.
+export default function TradeOffers({ tradeId, userId, isProposer, onMatched }: TradeOffersProps) {
+  const [offers, setOffers] = useState<TradeOffer[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  // Counter-offer form, open for one offer at a time
+  const [counterFor, setCounterFor] = useState<number | null>(null);
+  const [offerType, setOfferType] = useState<OfferType>("xch");
+  const [itemTitle, setItemTitle] = useState("");
+  const [itemDescription, setItemDescription] = useState("");
+  const [itemValueUsd, setItemValueUsd] = useState("");
+  const [xchAmount, setXchAmount] = useState(""); // in XCH, not mojos
+
+  useEffect(() => {
+    loadOffers();
+  }, [tradeId]);
+
+  const loadOffers = async () => {
+    try {
+      setLoading(true);
+      setOffers(await tradeApi.listOffers(tradeId));
+    } catch (err: any) {
+      setError(err.message || "Failed to load offers");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const acceptOffer = async (offerId: number) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await tradeApi.acceptOffer(offerId);
+      onMatched?.();
+    } catch (err: any) {
+      setError(err.message || "Failed to accept offer");
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const submitCounter = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (counterFor === null) return;
+    setBusy(true);
+    setError(null);
+    try {
+      await tradeApi.counterOffer({
+        offer_id: counterFor,
+        offer_type: offerType,
+        item_title: offerType !== "xch" ? itemTitle : undefined,
+        item_description: offerType !== "xch" && itemDescription.trim() ? itemDescription.trim() : undefined,
+        item_value_usd: offerType !== "xch" && itemValueUsd ? parseFloat(itemValueUsd) : undefined,
+        xch_amount: offerType !== "item" && xchAmount ? Math.round(parseFloat(xchAmount) * MOJOS_PER_XCH) : undefined,
+      });
+      setCounterFor(null);
+      await loadOffers();
+    } catch (err: any) {
+      setError(err.message || "Failed to send counter-offer");
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const describe = (offer: TradeOffer) => {
+    const parts: string[] = [];
+    if (offer.item_title) {
+      parts.push(offer.item_value_usd ? `${offer.item_title} (~$${offer.item_value_usd.toFixed(2)})` : offer.item_title);
+    }
+    if (offer.xch_amount) {
+      parts.push(`${formatXch(offer.xch_amount / MOJOS_PER_XCH)} XCH`);
+    }
+    return parts.join(" + ") || offer.offer_type;
+  };
+
+  const pending = offers.filter((o) => o.status === "pending");
+
+  return (
+    <div className="card">
+      <h2 className="text-xl font-semibold mb-4">💬 {isProposer ? "Offers" : "Your Offer"}</h2>
+
+      {error && <p className="text-sm text-red-600 mb-3">{error}</p>}
+
+      {loading ? (
+        <p className="text-gray-500 text-sm">Loading offers...</p>
+      ) : pending.length === 0 ? (
+        <p className="text-gray-500 text-sm">
+          {isProposer ? "No offers yet." : "No pending offer. Submit one to start negotiating."}
+        </p>
+      ) : (
+        <ul className="space-y-3">
+          {pending.map((offer) => {
+            const awaitingMe = offer.created_by !== userId;
+            return (
+              <li key={offer.id} className="border border-gray-200 rounded-lg p-3">
+                <div className="flex items-center justify-between gap-3">
+                  <div>
+                    <p className="font-medium text-gray-800">{describe(offer)}</p>
+                    <p className="text-xs text-gray-500">
+                      {offer.parent_offer_id ? "Counter-offer" : "Offer"} #{offer.id}
+                      {isProposer && ` from user #${offer.bidder_id}`} ·{" "}
+                      {awaitingMe ? "waiting for you" : "waiting for the other party"}
+                    </p>
+                  </div>
+                  {awaitingMe && (
+                    <div className="flex gap-2 shrink-0">
+                      <button className="btn-primary" disabled={busy} onClick={() => acceptOffer(offer.id)}>
+                        Accept
+                      </button>
+                      <button
+                        className="btn-secondary"
+                        disabled={busy}
+                        onClick={() => setCounterFor(counterFor === offer.id ? null : offer.id)}
+                      >
+                        Counter
+                      </button>
+                    </div>
+                  )}
+                </div>
+
+                {counterFor === offer.id && (
+                  <form onSubmit={submitCounter} className="mt-3 space-y-2">
+                    <select
+                      className="input-field"
+                      value={offerType}
+                      onChange={(e) => setOfferType(e.target.value as OfferType)}
+                    >
+                      <option value="xch">XCH</option>
+                      <option value="item">Item</option>
+                      <option value="mixed">Item + XCH</option>
+                    </select>
+                    {offerType !== "xch" && (
+                      <>
+                        <input
+                          className="input-field"
+                          placeholder="Item"
+                          value={itemTitle}
+                          onChange={(e) => setItemTitle(e.target.value)}
+                          required
+                        />
+                        <textarea
+                          className="input-field"
+                          rows={2}
+                          placeholder="Description (optional)"
+                          value={itemDescription}
+                          onChange={(e) => setItemDescription(e.target.value)}
+                        />
+                        <input
+                          className="input-field"
+                          type="number"
+                          min="0"
+                          step="0.01"
+                          placeholder="Item value (USD)"
+                          value={itemValueUsd}
+                          onChange={(e) => setItemValueUsd(e.target.value)}
+                          required
+                        />
+                      </>
+                    )}
+                    {offerType !== "item" && (
+                      <input
+                        className="input-field"
+                        type="number"
+                        min="0"
+                        step="0.000000000001"
+                        placeholder="XCH amount"
+                        value={xchAmount}
+                        onChange={(e) => setXchAmount(e.target.value)}
+                        required
+                      />
+                    )}
+                    <button type="submit" className="btn-primary w-full" disabled={busy}>
+                      {busy ? "Sending..." : "Send Counter-Offer"}
+                    </button>
+                  </form>
+                )}
+              </li>
+            );
+          })}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -24,6 +24,7 @@ interface AdminUser {
   id: number;
   username: string;
   is_admin: boolean;
+  trading_paused: boolean;
   created_at: string;
 }
 
@@ -45,6 +46,8 @@ interface AdminTrade {
   created_at: string;
 }
 
+const USERS_PAGE_SIZE = 50;
+
 export default function Admin() {
   const navigate = useNavigate();
   const { isAuthenticated } = useAuth();
@@ -53,6 +56,8 @@ export default function Admin() {
   const [userInfo, setUserInfo] = useState<UserInfo | null>(null);
   const [config, setConfig] = useState<ExchangeConfig | null>(null);
   const [users, setUsers] = useState<AdminUser[]>([]);
+  const [usersTotal, setUsersTotal] = useState(0);
+  const [usersOffset, setUsersOffset] = useState(0);
   const [stats, setStats] = useState<PlatformStats | null>(null);
   const [trades, setTrades] = useState<AdminTrade[]>([]);
   const [tradeStatusFilter, setTradeStatusFilter] = useState<string>('all');
@@ -130,10 +135,15 @@ export default function Admin() {
     }
   };
 
-  const loadUsers = async () => {
+  const loadUsers = async (offset = usersOffset) => {
     try {
-      const result = await rpcCall<{ users: AdminUser[] }>("admin_list_users", {});
+      const result = await rpcCall<{ users: AdminUser[]; total: number }>("admin_list_users", {
+        limit: USERS_PAGE_SIZE,
+        offset,
+      });
       setUsers(result.users);
+      setUsersTotal(result.total);
+      setUsersOffset(offset);
     } catch (err: any) {
       console.error("Failed to load users:", err);
     }
@@ -223,6 +233,24 @@ export default function Admin() {
     }
   };
 
+  const toggleTradingPaused = async (userId: number, currentlyPaused: boolean) => {
+    try {
+      setUpdatingUser(userId);
+      await rpcCall("admin_set_trading_paused", {
+        user_id: userId,
+        paused: !currentlyPaused,
+      });
+      
+      await loadUsers();
+      setSuccess(`Trading ${currentlyPaused ? 'resumed' : 'paused'} for user #${userId}`);
+      setTimeout(() => setSuccess(null), 3000);
+    } catch (err: any) {
+      setError(err.message || "Failed to update user");
+    } finally {
+      setUpdatingUser(null);
+    }
+  };
+
   const handleSave = async () => {
     // Validate wallet address
     if (!walletAddress.trim()) {
@@ -230,13 +258,9 @@ export default function Admin() {
       return;
     }
     
-    if (!walletAddress.startsWith("xch1")) {
-      setError("Invalid wallet address. Must start with 'xch1'");
-      return;
-    }
-    
-    if (walletAddress.length !== 62) {
-      setError("Invalid wallet address length. Chia addresses are 62 characters.");
+    // Quick prefix check; the server decodes the address and verifies its checksum
+    if (!walletAddress.startsWith("xch1") && !walletAddress.startsWith("txch1")) {
+      setError("Invalid wallet address. Must start with 'xch1' (or 'txch1' on testnet)");
       return;
     }
     
@@ -569,7 +593,7 @@ export default function Admin() {
                 <h2 className="text-lg font-semibold">User Management</h2>
               </div>
               <button
-                onClick={loadUsers}
+                onClick={() => loadUsers()}
                 className="text-sm text-gray-500 hover:text-gray-700"
               >
                 🔄 Refresh
@@ -610,9 +634,22 @@ export default function Admin() {
                         ) : (
                           <span className="text-xs text-gray-500">User</span>
                         )}
+                        {user.trading_paused && (
+                          <span className="ml-2 text-xs font-medium bg-amber-100 text-amber-700 px-2 py-1 rounded-full">
+                            Trading paused
+                          </span>
+                        )}
                       </td>
                       <td className="py-3 px-2 text-right">
                         {user.id !== userInfo?.id ? (
+                          <div className="flex justify-end gap-2">
+                          <button
+                            onClick={() => toggleTradingPaused(user.id, user.trading_paused)}
+                            disabled={updatingUser === user.id}
+                            className="text-xs px-3 py-1.5 rounded-lg font-medium transition bg-amber-50 text-amber-700 hover:bg-amber-100 disabled:opacity-50"
+                          >
+                            {user.trading_paused ? 'Resume Trading' : 'Pause Trading'}
+                          </button>
                           <button
                             onClick={() => toggleUserAdmin(user.id, user.is_admin)}
                             disabled={updatingUser === user.id}
@@ -630,6 +667,7 @@ export default function Admin() {
                               'Make Admin'
                             )}
                           </button>
+                          </div>
                         ) : (
                           <span className="text-xs text-gray-400">—</span>
                         )}
@@ -646,6 +684,30 @@ export default function Admin() {
               </div>
             )}
             
+            {usersTotal > USERS_PAGE_SIZE && (
+              <div className="flex items-center justify-between mt-4 text-sm text-gray-500">
+                <span>
+                  {usersOffset + 1}–{usersOffset + users.length} of {usersTotal}
+                </span>
+                <div className="flex gap-2">
+                  <button
+                    onClick={() => loadUsers(Math.max(0, usersOffset - USERS_PAGE_SIZE))}
+                    disabled={usersOffset === 0}
+                    className="px-3 py-1 rounded border border-gray-200 hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Previous
+                  </button>
+                  <button
+                    onClick={() => loadUsers(usersOffset + USERS_PAGE_SIZE)}
+                    disabled={usersOffset + USERS_PAGE_SIZE >= usersTotal}
+                    className="px-3 py-1 rounded border border-gray-200 hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Next
+                  </button>
+                </div>
+              </div>
+            )}
+            
             <div className="mt-4 text-xs text-gray-500 border-t pt-4">
               <p><strong>Note:</strong> Admin users can access this dashboard and configure platform settings. Only grant admin access to trusted users.</p>
             </div>
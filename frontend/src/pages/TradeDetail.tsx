@@ -4,6 +4,7 @@ import { tradeApi, type Trade } from "../api/client";
 import { useAuth } from "../contexts/AuthContext";
 import { useXchPrice, formatXch } from "../hooks/useXchPrice";
 import CommitmentFlow from "../components/CommitmentFlow";
+import TradeOffers from "../components/TradeOffers";
 
 export default function TradeDetail() {
   const { id } = useParams<{ id: string }>();
@@ -238,6 +239,16 @@ export default function TradeDetail() {
               </div>
             )}
 
+            {/* Offers - the proposer answers bids, a bidder follows their own thread */}
+            {trade.status === "proposal" && isAuthenticated && user && (
+              <TradeOffers
+                tradeId={trade.id}
+                userId={user.id}
+                isProposer={isProposer}
+                onMatched={() => window.location.reload()}
+              />
+            )}
+
             {/* Commitment Fee Section - Show when trade is matched and user is participant */}
             {isParticipant && trade.status === "matched" && (
               <CommitmentFlow 